@@ -0,0 +1,389 @@
+//! Per-request variation for benchmarks
+//!
+//! Identical requests mostly measure caches. With `--bench-data` each request
+//! takes a row from a CSV or NDJSON file and fills `{{column}}` placeholders
+//! in the URL and request items with it. Magic values such as `{uuid}` are
+//! expanded again for every request, and `{seq}` is the request's number.
+//!
+//! Items are parsed once from the template, so row values only ever fill the
+//! values of items: a cell such as `@/etc/passwd` cannot turn a header into a
+//! file read, and magic values inside row values are left as they are.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use once_cell::sync::Lazy;
+use rand::Rng;
+use regex::{Captures, Regex};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::cli::args::BenchDataOrder;
+use crate::errors::QuicpulseError;
+use crate::input::InputItem;
+use crate::magic::{expand_magic_values, has_magic_values};
+
+static PLACEHOLDER_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\{\{\s*([A-Za-z0-9_.-]+)\s*\}\}").unwrap()
+});
+static SEQ_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\{seq(?::(-?\d+))?\}").unwrap()
+});
+
+/// Rows of a `--bench-data` file, each mapping column names to values
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct DataSet {
+    rows: Vec<HashMap<String, String>>,
+}
+
+impl DataSet {
+    /// Load a CSV file (`.csv`, with a header row) or NDJSON file (anything else,
+    /// one object per line; non-string values are used as JSON text)
+    pub fn load(path: &Path) -> Result<Self, QuicpulseError> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| QuicpulseError::Config(format!("Cannot read bench data {}: {}", path.display(), e)))?;
+        let is_csv = path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("csv"));
+        let rows = if is_csv { parse_csv(&content) } else { parse_ndjson(&content) }
+            .map_err(|e| QuicpulseError::Parse(format!("Invalid bench data {}: {}", path.display(), e)))?;
+
+        if rows.is_empty() {
+            return Err(QuicpulseError::Parse(format!("Bench data {} has no rows", path.display())));
+        }
+        Ok(Self { rows })
+    }
+
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    pub fn row(&self, index: usize) -> &HashMap<String, String> {
+        &self.rows[index % self.rows.len()]
+    }
+}
+
+fn parse_csv(content: &str) -> Result<Vec<HashMap<String, String>>, String> {
+    let mut reader = csv::Reader::from_reader(content.as_bytes());
+    let columns: Vec<String> = reader.headers()
+        .map_err(|e| e.to_string())?
+        .iter()
+        .map(|column| column.trim().to_string())
+        .collect();
+
+    reader.records()
+        .map(|record| {
+            let record = record.map_err(|e| e.to_string())?;
+            Ok(columns.iter().cloned().zip(record.iter().map(str::to_string)).collect())
+        })
+        .collect()
+}
+
+fn parse_ndjson(content: &str) -> Result<Vec<HashMap<String, String>>, String> {
+    content.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(number, line)| {
+            let value: serde_json::Value = serde_json::from_str(line)
+                .map_err(|e| format!("line {}: {}", number + 1, e))?;
+            let serde_json::Value::Object(object) = value else {
+                return Err(format!("line {}: expected a JSON object", number + 1));
+            };
+            Ok(object.into_iter()
+                .map(|(key, value)| match value {
+                    serde_json::Value::String(text) => (key, text),
+                    other => (key, other.to_string()),
+                })
+                .collect())
+        })
+        .collect()
+}
+
+/// Fill `{{column}}` placeholders with the row's values, leaving unknown columns
+/// alone, and expand `{seq}` and magic values in the rest of `template` only
+pub fn fill_template(template: &str, row: Option<&HashMap<String, String>>, seq: u64) -> String {
+    let expand = |text: &str| expand_magic_values(&fill_seq(text, seq)).value;
+    let mut filled = String::with_capacity(template.len());
+    let mut last = 0;
+    for caps in PLACEHOLDER_RE.captures_iter(template) {
+        let placeholder = caps.get(0).unwrap();
+        filled.push_str(&expand(&template[last..placeholder.start()]));
+        match row.and_then(|row| row.get(&caps[1])) {
+            Some(value) => filled.push_str(value),
+            None => filled.push_str(placeholder.as_str()),
+        }
+        last = placeholder.end();
+    }
+    filled.push_str(&expand(&template[last..]));
+    filled
+}
+
+/// Replace `{seq}` and `{seq:START}` with `START + seq`
+fn fill_seq(template: &str, seq: u64) -> String {
+    SEQ_RE.replace_all(template, |caps: &Captures| {
+        let start: i64 = caps.get(1).and_then(|m| m.as_str().parse().ok()).unwrap_or(0);
+        (start + seq as i64).to_string()
+    }).into_owned()
+}
+
+/// A request ready to send
+pub struct RenderedRequest {
+    pub url: Url,
+    pub headers: HeaderMap,
+    pub body: Option<Vec<u8>>,
+}
+
+/// A request item parsed from its template, with the text its values are filled from
+enum ItemTemplate {
+    Header { name: String, value: String },
+    EmptyHeader { name: String },
+    QueryParam { name: String, value: String },
+    DataField { key: String, value: String },
+    /// The JSON text is filled first and parsed afterwards
+    JsonField { key: String, value: String },
+    /// File items are used as typed
+    Fixed(InputItem),
+}
+
+impl ItemTemplate {
+    fn parse(template: &str) -> Result<Self, QuicpulseError> {
+        // Placeholders hold no separators, so masking them only keeps
+        // `key:={{value}}` from failing as JSON before it is filled
+        let masked = PLACEHOLDER_RE.replace_all(template, "null");
+        let split = |separator: &str| {
+            let (key, value) = template.split_once(separator).unwrap_or((template, ""));
+            (key.to_string(), value.to_string())
+        };
+        Ok(match InputItem::parse(&masked)? {
+            InputItem::Header { .. } => {
+                let (name, value) = split(":");
+                Self::Header { name, value }
+            }
+            InputItem::EmptyHeader { .. } => Self::EmptyHeader { name: split(";").0 },
+            InputItem::QueryParam { .. } => {
+                let (name, value) = split("==");
+                Self::QueryParam { name, value }
+            }
+            InputItem::DataField { .. } => {
+                let (key, value) = split("=");
+                Self::DataField { key, value }
+            }
+            InputItem::JsonField { .. } => {
+                let (key, value) = split(":=");
+                Self::JsonField { key, value }
+            }
+            _ => Self::Fixed(InputItem::parse(template)?),
+        })
+    }
+
+    fn render(&self, row: Option<&HashMap<String, String>>, seq: u64) -> Result<InputItem, String> {
+        let fill = |text: &str| fill_template(text, row, seq);
+        Ok(match self {
+            Self::Header { name, value } => InputItem::Header { name: fill(name), value: fill(value) },
+            Self::EmptyHeader { name } => InputItem::EmptyHeader { name: fill(name) },
+            Self::QueryParam { name, value } => InputItem::QueryParam { name: fill(name), value: fill(value) },
+            Self::DataField { key, value } => InputItem::DataField { key: fill(key), value: fill(value) },
+            Self::JsonField { key, value } => {
+                let json = fill(value);
+                let value = serde_json::from_str(&json)
+                    .map_err(|e| format!("Invalid JSON in '{}:={}': {}", key, json, e))?;
+                InputItem::JsonField { key: fill(key), value }
+            }
+            Self::Fixed(item) => item.clone(),
+        })
+    }
+}
+
+/// URL and request items as typed, rendered afresh for every request
+pub struct RequestVariation {
+    url: String,
+    items: Vec<String>,
+    templates: Vec<ItemTemplate>,
+    has_data: bool,
+    data: Option<DataSet>,
+    order: BenchDataOrder,
+    next: AtomicU64,
+}
+
+impl RequestVariation {
+    pub fn new(url: String, items: Vec<String>, has_data: bool) -> Result<Self, QuicpulseError> {
+        let templates = items.iter()
+            .map(|item| ItemTemplate::parse(item))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self {
+            url,
+            items,
+            templates,
+            has_data,
+            data: None,
+            order: BenchDataOrder::RoundRobin,
+            next: AtomicU64::new(0),
+        })
+    }
+
+    /// Take placeholder values from `data`, one row per request
+    pub fn with_data(mut self, data: DataSet, order: BenchDataOrder) -> Self {
+        self.data = Some(data);
+        self.order = order;
+        self
+    }
+
+    /// Whether requests can differ at all; if not, the request is built once
+    pub fn varies(&self) -> bool {
+        self.data.is_some()
+            || has_magic_values(&self.url)
+            || self.items.iter().any(|item| has_magic_values(item))
+    }
+
+    /// Build the next request
+    pub fn render(&self) -> Result<RenderedRequest, String> {
+        let seq = self.next.fetch_add(1, Ordering::Relaxed);
+        let row = self.data.as_ref().map(|data| match self.order {
+            BenchDataOrder::RoundRobin => data.row(seq as usize),
+            BenchDataOrder::Random => data.row(rand::rng().random_range(0..data.len())),
+        });
+
+        let url = fill_template(&self.url, row, seq);
+        let url = Url::parse(&url).map_err(|e| format!("Invalid URL '{}': {}", url, e))?;
+        let items = self.templates.iter()
+            .map(|template| template.render(row, seq))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(RenderedRequest {
+            url,
+            headers: request_headers(&items),
+            body: self.has_data.then(|| request_body(&items)),
+        })
+    }
+}
+
+/// JSON body from the data and JSON fields among `items`
+pub fn request_body(items: &[InputItem]) -> Vec<u8> {
+    let mut body = serde_json::Map::new();
+    for item in items {
+        match item {
+            InputItem::DataField { key, value } => {
+                body.insert(key.clone(), serde_json::Value::String(value.clone()));
+            }
+            InputItem::JsonField { key, value } => {
+                body.insert(key.clone(), value.clone());
+            }
+            _ => {}
+        }
+    }
+    serde_json::Value::Object(body).to_string().into_bytes()
+}
+
+/// Headers from the header items among `items`; invalid ones are skipped
+pub fn request_headers(items: &[InputItem]) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    for item in items {
+        let (name, value) = match item {
+            InputItem::Header { name, value } => (name, value.clone()),
+            InputItem::EmptyHeader { name } => (name, String::new()),
+            InputItem::HeaderFile { name, path } => match std::fs::read_to_string(path) {
+                Ok(content) => (name, content.trim().to_string()),
+                Err(_) => continue,
+            },
+            _ => continue,
+        };
+        if let (Ok(name), Ok(value)) = (HeaderName::try_from(name.as_str()), HeaderValue::from_str(&value)) {
+            headers.insert(name, value);
+        }
+    }
+    headers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(dir: &tempfile::TempDir, name: &str, content: &str) -> std::path::PathBuf {
+        let path = dir.path().join(name);
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_load_csv_and_ndjson() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let csv = DataSet::load(&write(&dir, "users.csv", "id,name\n1,ann\n2,bob\n")).unwrap();
+        assert_eq!(csv.len(), 2);
+        assert_eq!(csv.row(1)["name"], "bob");
+        assert_eq!(csv.row(2)["id"], "1");
+
+        let ndjson = DataSet::load(&write(&dir, "users.ndjson", "{\"id\": 7, \"name\": \"cy\"}\n\n{\"id\": 8}\n")).unwrap();
+        assert_eq!(ndjson.len(), 2);
+        assert_eq!(ndjson.row(0)["id"], "7");
+        assert_eq!(ndjson.row(0)["name"], "cy");
+
+        assert!(DataSet::load(&write(&dir, "bad.ndjson", "[1, 2]\n")).is_err());
+        assert!(DataSet::load(&write(&dir, "empty.csv", "id,name\n")).is_err());
+    }
+
+    #[test]
+    fn test_fill_placeholders() {
+        let row: HashMap<String, String> = [("id".to_string(), "42".to_string())].into_iter().collect();
+        assert_eq!(fill_template("/users/{{id}}?x={{ id }}", Some(&row), 0), "/users/42?x=42");
+        assert_eq!(fill_template("{{missing}}", Some(&row), 0), "{{missing}}");
+        assert_eq!(fill_template("{{id}}", None, 0), "{{id}}");
+        assert_eq!(fill_seq("n={seq}&m={seq:100}", 5), "n=5&m=105");
+
+        // Row values are inserted as they are
+        let row: HashMap<String, String> = [("id".to_string(), "{seq}{env:HOME}".to_string())].into_iter().collect();
+        assert_eq!(fill_template("{seq}/{{id}}", Some(&row), 3), "3/{seq}{env:HOME}");
+    }
+
+    #[test]
+    fn test_render_round_robin() {
+        let dir = tempfile::tempdir().unwrap();
+        let data = DataSet::load(&write(&dir, "users.csv", "id,token\n1,a\n2,b\n")).unwrap();
+        let variation = RequestVariation::new(
+            "http://localhost/users/{{id}}?n={seq}".to_string(),
+            vec!["Authorization:Bearer {{token}}".to_string(), "request={uuid}".to_string()],
+            true,
+        ).unwrap().with_data(data, BenchDataOrder::RoundRobin);
+        assert!(variation.varies());
+
+        let first = variation.render().unwrap();
+        let second = variation.render().unwrap();
+        let third = variation.render().unwrap();
+        assert_eq!(first.url.as_str(), "http://localhost/users/1?n=0");
+        assert_eq!(second.url.as_str(), "http://localhost/users/2?n=1");
+        assert_eq!(third.url.as_str(), "http://localhost/users/1?n=2");
+        assert_eq!(second.headers["authorization"], "Bearer b");
+
+        let body = |request: &RenderedRequest| -> serde_json::Value {
+            serde_json::from_slice(request.body.as_ref().unwrap()).unwrap()
+        };
+        assert_ne!(body(&first)["request"], body(&second)["request"]);
+    }
+
+    #[test]
+    fn test_static_request_does_not_vary() {
+        let variation = RequestVariation::new("http://localhost/".to_string(), vec!["name=x".to_string()], true).unwrap();
+        assert!(!variation.varies());
+    }
+
+    #[test]
+    fn test_row_values_only_fill_item_values() {
+        let dir = tempfile::tempdir().unwrap();
+        let data = DataSet::load(&write(&dir, "rows.ndjson", "{\"tok\": \"@/etc/passwd\", \"n\": 5, \"name\": \"{env:HOME}\"}\n")).unwrap();
+        let variation = RequestVariation::new(
+            "http://localhost/".to_string(),
+            vec!["X-Token:{{tok}}".to_string(), "count:={{n}}".to_string(), "name={{name}}".to_string()],
+            true,
+        ).unwrap().with_data(data, BenchDataOrder::RoundRobin);
+
+        let request = variation.render().unwrap();
+        assert_eq!(request.headers["x-token"], "@/etc/passwd");
+        let body: serde_json::Value = serde_json::from_slice(request.body.as_ref().unwrap()).unwrap();
+        assert_eq!(body, serde_json::json!({"count": 5, "name": "{env:HOME}"}));
+    }
+}
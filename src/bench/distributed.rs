@@ -0,0 +1,731 @@
+//! Benchmarks spread across several machines
+//!
+//! `--bench-worker` turns quicpulse into an agent that waits for jobs on
+//! `--listen`. A controller started with `--bench-workers` sends each worker
+//! the benchmark's effective command line (config file defaults and `{{VAR}}`
+//! expansion applied), the `--bench-data` rows and its share of the
+//! load: part of `--requests`, and an equal fraction of `--rate` and
+//! `--bench-stages`. Every worker runs the benchmark as if started locally and
+//! streams a sample per interval and its final statistics back, HDR histograms
+//! included, as newline-delimited JSON. The controller merges the samples for
+//! its live output and the final statistics into one [`BenchmarkStats`].
+//!
+//! Jobs carry the `--bench-token` shared by controller and workers, and their
+//! command line may only use the options in [`JOB_OPTIONS`] and no `{env:NAME}`
+//! magic values, so a worker never reads its own files or environment on
+//! behalf of a controller.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use clap::{ArgMatches, CommandFactory, FromArgMatches, ValueEnum};
+use clap::parser::ValueSource;
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::tcp::OwnedWriteHalf;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
+
+use super::data::DataSet;
+use super::live::{LiveReporter, Sample};
+use super::runner::{BenchmarkConfig, BenchmarkResult};
+use super::stats::{BenchmarkStats, StatsCollector};
+use crate::cli::Args;
+use crate::errors::QuicpulseError;
+use crate::input::InputItem;
+use crate::magic::expand_magic_values;
+use crate::output::terminal;
+use crate::status::ExitStatus;
+
+/// Where a worker listens without `--listen`
+pub const DEFAULT_LISTEN: &str = "127.0.0.1:7000";
+
+/// How long a worker waits for the job after a controller connects
+pub const JOB_READ_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Options a job's command line may set; everything else is rejected by the worker.
+/// The `--bench-*` reporting options only matter to the controller.
+pub const JOB_OPTIONS: &[&str] = &[
+    "method", "url", "request_items",
+    "json", "form", "multipart", "raw",
+    "timeout", "follow", "max_redirects", "default_scheme",
+    "http_version", "http3", "verify", "ssl", "ciphers",
+    "grpc", "grpc_plaintext", "ws", "ws_send", "ws_subprotocol", "ws_binary",
+    "verbose", "quiet",
+    "bench", "bench_requests", "bench_concurrency", "bench_rate", "bench_stages",
+    "bench_duration", "bench_data", "bench_data_order", "bench_ws_mode", "bench_no_keepalive",
+    "bench_workers", "bench_token", "bench_output", "bench_baseline", "bench_threshold",
+    "bench_timeseries",
+];
+
+/// Options the controller applies itself before building the job
+pub const CONTROLLER_OPTIONS: &[&str] = &["env_file", "no_env"];
+
+/// Benchmark a controller asks a worker to run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Job {
+    /// Version of the controller; a worker only runs jobs from its own version
+    pub version: String,
+    /// Must equal the worker's `--bench-token`
+    pub token: String,
+    /// The controller's command line, program name first
+    pub argv: Vec<String>,
+    /// This worker's part of `--requests`
+    pub requests: u32,
+    /// This worker's fraction of the arrival rate
+    pub rate_share: f64,
+    /// Rows of the controller's `--bench-data` file
+    #[serde(default)]
+    pub data: Option<DataSet>,
+}
+
+/// What a worker sends back while it runs a job
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum Message {
+    /// Statistics of one interval
+    Sample {
+        index: u64,
+        elapsed_s: f64,
+        /// Length of the interval; the last one is usually shorter
+        interval_s: f64,
+        stats: BenchmarkStats,
+    },
+    /// The job finished
+    Done {
+        requests: u64,
+        duration_s: f64,
+        stats: BenchmarkStats,
+    },
+    /// The job could not run or stopped early
+    Error { message: String },
+}
+
+/// Parse `host1:7000,host2:7000`
+pub fn parse_workers(text: &str) -> Result<Vec<String>, QuicpulseError> {
+    let workers: Vec<String> = text.split(',')
+        .map(str::trim)
+        .filter(|worker| !worker.is_empty())
+        .map(str::to_string)
+        .collect();
+    if workers.is_empty() {
+        return Err(QuicpulseError::Argument("--bench-workers needs at least one HOST:PORT".to_string()));
+    }
+    if let Some(worker) = workers.iter().find(|worker| !worker.contains(':')) {
+        return Err(QuicpulseError::Argument(format!("Invalid worker '{}': expected HOST:PORT", worker)));
+    }
+    Ok(workers)
+}
+
+/// `:7000` listens on every interface
+fn listen_addr(listen: &str) -> String {
+    if listen.starts_with(':') {
+        format!("0.0.0.0{}", listen)
+    } else {
+        listen.to_string()
+    }
+}
+
+/// Reject a controller command line that sets options outside [`JOB_OPTIONS`]
+/// and [`CONTROLLER_OPTIONS`]
+pub fn check_job_options(argv: &[String]) -> Result<(), QuicpulseError> {
+    job_matches(argv, CONTROLLER_OPTIONS).map(drop)
+}
+
+fn job_matches(argv: &[String], also_allowed: &[&str]) -> Result<ArgMatches, QuicpulseError> {
+    let command = Args::command();
+    let matches = command.clone().try_get_matches_from(argv)
+        .map_err(|e| QuicpulseError::Argument(e.to_string()))?;
+    let mut rejected: Vec<String> = command.get_arguments()
+        .filter(|arg| {
+            let id = arg.get_id().as_str();
+            matches.value_source(id) == Some(ValueSource::CommandLine)
+                && !JOB_OPTIONS.contains(&id)
+                && !also_allowed.contains(&id)
+        })
+        .map(|arg| arg.get_long().map_or_else(|| arg.get_id().to_string(), |long| format!("--{}", long)))
+        .collect();
+    if !rejected.is_empty() {
+        rejected.sort_unstable();
+        return Err(QuicpulseError::Argument(format!(
+            "Options not allowed in distributed benchmarks: {}", rejected.join(", ")
+        )));
+    }
+    Ok(matches)
+}
+
+/// Parse a job's command line, rejecting options outside [`JOB_OPTIONS`],
+/// request items that read files and `{env:NAME}` magic values
+pub fn parse_job_args(argv: &[String]) -> Result<Args, QuicpulseError> {
+    let matches = job_matches(argv, &[])?;
+    let args = Args::from_arg_matches(&matches)
+        .map_err(|e| QuicpulseError::Argument(e.to_string()))?;
+    if !matches!(args.verify.to_lowercase().as_str(), "yes" | "no" | "true" | "false") {
+        return Err(QuicpulseError::Argument(
+            "--verify with a CA bundle is not allowed in distributed benchmarks".to_string()
+        ));
+    }
+    for item in &args.request_items {
+        let reads_file = matches!(
+            InputItem::parse(item),
+            Ok(InputItem::HeaderFile { .. }
+                | InputItem::QueryParamFile { .. }
+                | InputItem::DataFieldFile { .. }
+                | InputItem::JsonFieldFile { .. }
+                | InputItem::FileUpload { .. })
+        );
+        if reads_file {
+            return Err(QuicpulseError::Argument(format!(
+                "Request items that read files are not allowed in distributed benchmarks: {}", item
+            )));
+        }
+    }
+    let mut expanded = args.method.iter().chain(&args.url).chain(&args.request_items);
+    if let Some(text) = expanded.find(|text| reads_env(text)) {
+        return Err(QuicpulseError::Argument(format!(
+            "{{env:NAME}} values are not allowed in distributed benchmarks: {}", text
+        )));
+    }
+    Ok(args)
+}
+
+/// Whether magic-value expansion of `text` would read an environment variable.
+/// Expanding is the only reliable check, as one value can produce another
+/// (`{now:{}env:NAME}` turns into `{env:NAME}`).
+fn reads_env(text: &str) -> bool {
+    text.contains("{env:")
+        || expand_magic_values(text).generated.keys().any(|pattern| pattern.starts_with("{env:"))
+}
+
+/// The command line a worker runs for `args`, holding the options in
+/// [`JOB_OPTIONS`] that affect the requests
+pub fn job_argv(args: &Args) -> Vec<String> {
+    let mut argv = vec!["quicpulse".to_string(), "--bench".to_string()];
+
+    let flags = [
+        ("--json", args.json),
+        ("--form", args.form),
+        ("--multipart", args.multipart),
+        ("--follow", args.follow),
+        ("--http3", args.http3),
+        ("--grpc", args.grpc),
+        ("--grpc-plaintext", args.grpc_plaintext),
+        ("--ws", args.ws),
+        ("--bench-no-keepalive", args.bench_no_keepalive),
+    ];
+    argv.extend(flags.into_iter().filter(|(_, set)| *set).map(|(flag, _)| flag.to_string()));
+    argv.extend((0..args.verbose).map(|_| "--verbose".to_string()));
+    argv.extend((0..args.quiet).map(|_| "--quiet".to_string()));
+
+    // `--name=value` keeps values that start with a dash from reading as options
+    let values = [
+        ("--raw", args.raw.clone()),
+        ("--timeout", args.timeout.map(|timeout| timeout.to_string())),
+        ("--max-redirects", Some(args.max_redirects.to_string())),
+        ("--default-scheme", Some(args.default_scheme.clone())),
+        ("--http-version", args.http_version.clone()),
+        ("--verify", Some(args.verify.clone())),
+        ("--ssl", args.ssl.clone()),
+        ("--ciphers", args.ciphers.clone()),
+        ("--ws-send", args.ws_send.clone()),
+        ("--ws-subprotocol", args.ws_subprotocol.clone()),
+        ("--ws-binary", args.ws_binary.clone()),
+        ("--requests", Some(args.bench_requests.to_string())),
+        ("--concurrency", Some(args.bench_concurrency.to_string())),
+        ("--rate", args.bench_rate.clone()),
+        ("--bench-stages", args.bench_stages.clone()),
+        ("--bench-duration", args.bench_duration.clone()),
+        ("--bench-data", args.bench_data.as_ref().map(|path| path.display().to_string())),
+        ("--bench-data-order", value_name(&args.bench_data_order)),
+        ("--bench-ws-mode", value_name(&args.bench_ws_mode)),
+    ];
+    argv.extend(values.into_iter().filter_map(|(option, value)| value.map(|value| format!("{}={}", option, value))));
+
+    argv.push("--".to_string());
+    argv.extend(args.method.iter().chain(&args.url).chain(&args.request_items).cloned());
+    argv
+}
+
+fn value_name<T: ValueEnum>(value: &T) -> Option<String> {
+    value.to_possible_value().map(|value| value.get_name().to_string())
+}
+
+/// Compare tokens without stopping at the first difference
+fn token_matches(expected: &str, given: &str) -> bool {
+    expected.len() == given.len()
+        && expected.bytes().zip(given.bytes()).fold(0, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+/// Split `total` requests as evenly as possible, the first workers taking the remainder
+fn split_requests(total: u32, workers: usize) -> Vec<u32> {
+    let workers = workers as u32;
+    (0..workers)
+        .map(|index| total / workers + u32::from(index < total % workers))
+        .collect()
+}
+
+// =============================================================================
+// Worker
+// =============================================================================
+
+/// Run as a worker until interrupted
+pub async fn run_worker(args: &Args) -> Result<ExitStatus, QuicpulseError> {
+    let token = required_token(args, "--bench-worker")?;
+    let addr = listen_addr(args.listen.as_deref().unwrap_or(DEFAULT_LISTEN));
+    let listener = TcpListener::bind(&addr).await
+        .map_err(|e| QuicpulseError::Connection(format!("Cannot listen on {}: {}", addr, e)))?;
+    eprintln!("{} {}", terminal::info("Benchmark worker listening on"), listener.local_addr()?);
+    serve(listener, &token).await
+}
+
+/// The shared `--bench-token`, which workers and controllers cannot run without
+pub fn required_token(args: &Args, mode: &str) -> Result<String, QuicpulseError> {
+    match args.bench_token.as_deref().map(str::trim) {
+        Some(token) if !token.is_empty() => Ok(token.to_string()),
+        _ => Err(QuicpulseError::Argument(format!(
+            "{} requires --bench-token or QUICPULSE_BENCH_TOKEN", mode
+        ))),
+    }
+}
+
+/// Take jobs one at a time, so two controllers never share a worker's capacity
+async fn serve(listener: TcpListener, token: &str) -> Result<ExitStatus, QuicpulseError> {
+    loop {
+        let (socket, peer) = listener.accept().await?;
+        eprintln!("{} {}", terminal::info("Job from"), peer);
+        if let Err(e) = handle(socket, token, JOB_READ_TIMEOUT).await {
+            eprintln!("{}", terminal::warning(&format!("Job from {} failed: {}", peer, e)));
+        }
+    }
+}
+
+async fn handle(socket: TcpStream, token: &str, read_timeout: Duration) -> Result<(), QuicpulseError> {
+    let (read, mut write) = socket.into_split();
+    let line = tokio::time::timeout(read_timeout, BufReader::new(read).lines().next_line()).await
+        .map_err(|_| QuicpulseError::Timeout(read_timeout.as_secs_f64()))??;
+    let Some(line) = line else {
+        return Ok(());
+    };
+
+    let outcome = match serde_json::from_str::<Job>(&line) {
+        Ok(job) if !token_matches(token, &job.token) => {
+            Err(QuicpulseError::Argument("Invalid benchmark token".to_string()))
+        }
+        Ok(job) => run_job(job, &mut write).await,
+        Err(e) => Err(QuicpulseError::Parse(format!("Invalid job: {}", e))),
+    };
+    if let Err(ref e) = outcome {
+        send(&mut write, &Message::Error { message: e.to_string() }).await?;
+    }
+    outcome
+}
+
+async fn run_job(job: Job, write: &mut OwnedWriteHalf) -> Result<(), QuicpulseError> {
+    if job.version != env!("CARGO_PKG_VERSION") {
+        return Err(QuicpulseError::Argument(format!(
+            "Controller runs quicpulse {}, this worker {}", job.version, env!("CARGO_PKG_VERSION")
+        )));
+    }
+
+    let mut args = parse_job_args(&job.argv)?;
+    args.bench_workers = None;
+    args.bench_requests = job.requests;
+    // Rows come with the job; the worker's own files are never read
+    args.bench_data = None;
+    if job.data.as_ref().is_some_and(DataSet::is_empty) {
+        return Err(QuicpulseError::Argument("Job has empty bench data".to_string()));
+    }
+
+    let processed = crate::cli::process_args(&args)?;
+    let mut config = BenchmarkConfig::from_args(&args, &processed)?;
+    config.rate = config.rate.map(|rate| rate.scaled(job.rate_share));
+
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let runner = super::prepare_runner(&args, &processed, config, job.data, LiveReporter::new().with_forward(tx)).await?;
+
+    // Samples stop when the runner drops its reporter at the end of the run
+    let forward = async {
+        let mut last = Duration::ZERO;
+        while let Some(sample) = rx.recv().await {
+            let message = Message::Sample {
+                index: sample.index,
+                elapsed_s: sample.elapsed.as_secs_f64(),
+                interval_s: sample.elapsed.saturating_sub(last).as_secs_f64(),
+                stats: sample.stats,
+            };
+            last = sample.elapsed;
+            send(write, &message).await?;
+        }
+        Ok::<(), QuicpulseError>(())
+    };
+    let (result, forwarded) = tokio::join!(runner.run(), forward);
+    let result = result?;
+    forwarded?;
+
+    send(write, &Message::Done {
+        requests: result.total_requests as u64,
+        duration_s: result.duration.as_secs_f64(),
+        stats: result.stats,
+    }).await
+}
+
+async fn send(write: &mut OwnedWriteHalf, message: &Message) -> Result<(), QuicpulseError> {
+    let mut line = serde_json::to_vec(message)?;
+    line.push(b'\n');
+    write.write_all(&line).await?;
+    Ok(())
+}
+
+// =============================================================================
+// Controller
+// =============================================================================
+
+/// Samples of one interval, merged once every running worker sent theirs
+struct PendingSample {
+    workers: usize,
+    elapsed: Duration,
+    interval: Duration,
+    stats: StatsCollector,
+}
+
+/// Run the benchmark described by `args` on `workers` and merge their results
+pub async fn run_controller(
+    args: &Args,
+    workers: &[String],
+    token: &str,
+    config: BenchmarkConfig,
+    data: Option<DataSet>,
+    mut reporter: LiveReporter,
+) -> Result<BenchmarkResult, QuicpulseError> {
+    // Fail before connecting when the workers would reject the command line
+    let argv = job_argv(args);
+    parse_job_args(&argv)?;
+
+    let shares = split_requests(config.total_requests, workers.len());
+    let (tx, mut rx) = mpsc::unbounded_channel::<(usize, Result<Message, String>)>();
+
+    // Connect to every worker before any starts, so one unreachable worker stops the run
+    let mut sockets = Vec::new();
+    for worker in workers {
+        let socket = TcpStream::connect(worker.as_str()).await
+            .map_err(|e| QuicpulseError::Connection(format!("Cannot reach worker {}: {}", worker, e)))?;
+        sockets.push(socket);
+    }
+
+    for (index, (socket, requests)) in sockets.into_iter().zip(shares).enumerate() {
+        let (read, mut write) = socket.into_split();
+        let job = Job {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            token: token.to_string(),
+            argv: argv.clone(),
+            requests,
+            rate_share: 1.0 / workers.len() as f64,
+            data: data.clone(),
+        };
+        let mut line = serde_json::to_vec(&job)?;
+        line.push(b'\n');
+        write.write_all(&line).await
+            .map_err(|e| QuicpulseError::Connection(format!("Cannot send job to worker {}: {}", workers[index], e)))?;
+
+        let tx = tx.clone();
+        tokio::spawn(async move {
+            // Keep the write half open until the worker is done
+            let _write = write;
+            let mut lines = BufReader::new(read).lines();
+            loop {
+                let message = match lines.next_line().await {
+                    Ok(Some(line)) => serde_json::from_str(&line).map_err(|e| format!("invalid message: {}", e)),
+                    Ok(None) => Err("connection closed".to_string()),
+                    Err(e) => Err(e.to_string()),
+                };
+                let finished = !matches!(message, Ok(Message::Sample { .. }));
+                if tx.send((index, message)).is_err() || finished {
+                    break;
+                }
+            }
+        });
+    }
+    drop(tx);
+
+    let mut running = workers.len();
+    let mut pending: BTreeMap<u64, PendingSample> = BTreeMap::new();
+    let mut total = StatsCollector::new();
+    let mut requests = 0;
+    let mut duration = Duration::ZERO;
+
+    while running > 0 {
+        let Some((index, message)) = rx.recv().await else {
+            break;
+        };
+        let message = message.map_err(|e| QuicpulseError::Connection(format!("Worker {} failed: {}", workers[index], e)))?;
+        match message {
+            Message::Sample { index, elapsed_s, interval_s, stats } => {
+                let sample = pending.entry(index).or_insert_with(|| PendingSample {
+                    workers: 0,
+                    elapsed: Duration::ZERO,
+                    interval: Duration::ZERO,
+                    stats: StatsCollector::new(),
+                });
+                sample.workers += 1;
+                sample.elapsed = sample.elapsed.max(Duration::from_secs_f64(elapsed_s));
+                sample.interval = sample.interval.max(Duration::from_secs_f64(interval_s));
+                sample.stats.merge(StatsCollector::from(stats));
+            }
+            Message::Done { requests: sent, duration_s, stats } => {
+                requests += sent;
+                duration = duration.max(Duration::from_secs_f64(duration_s));
+                total.merge(StatsCollector::from(stats));
+                running -= 1;
+            }
+            Message::Error { message } => {
+                return Err(QuicpulseError::Connection(format!("Worker {} failed: {}", workers[index], message)));
+            }
+        }
+        report_ready(&mut pending, running, &mut reporter);
+    }
+    reporter.finish();
+
+    Ok(BenchmarkResult {
+        url: config.url,
+        method: config.method,
+        total_requests: requests as u32,
+        // Every worker runs with the full concurrency
+        concurrency: config.concurrency * workers.len() as u32,
+        duration,
+        stats: total.finalize(duration),
+        rate: config.rate,
+        protocol: config.protocol,
+    })
+}
+
+/// Report the intervals every running worker has sent, in order
+fn report_ready(pending: &mut BTreeMap<u64, PendingSample>, running: usize, reporter: &mut LiveReporter) {
+    while let Some(entry) = pending.first_entry() {
+        if entry.get().workers < running {
+            break;
+        }
+        let (index, sample) = entry.remove_entry();
+        reporter.report(&Sample {
+            index,
+            elapsed: sample.elapsed,
+            stats: sample.stats.finalize(sample.interval),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::args::BenchDataOrder;
+    use clap::Parser;
+
+    #[test]
+    fn test_parse_workers() {
+        assert_eq!(parse_workers("a:7000, b:7001,").unwrap(), vec!["a:7000", "b:7001"]);
+        assert!(parse_workers(" , ").is_err());
+        assert!(parse_workers("a:7000,b").is_err());
+        assert_eq!(listen_addr(":7000"), "0.0.0.0:7000");
+        assert_eq!(listen_addr("127.0.0.1:0"), "127.0.0.1:0");
+    }
+
+    #[test]
+    fn test_parse_job_args() {
+        let argv = |args: &[&str]| -> Vec<String> {
+            ["quicpulse", "--bench"].iter().chain(args).map(|arg| arg.to_string()).collect()
+        };
+
+        let args = parse_job_args(&argv(&["--requests", "5", "--bench-data", "rows.csv", "POST", "http://x/{{id}}", "name=a", "X-Id:1"])).unwrap();
+        assert_eq!(args.bench_requests, 5);
+        assert!(parse_job_args(&argv(&["--verify", "no", "https://x/"])).is_ok());
+
+        for rejected in [
+            &["--cert", "client.pem", "https://x/"][..],
+            &["--session", "s", "http://x/"],
+            &["--output", "out", "http://x/"],
+            &["--verify", "/etc/ssl/ca.pem", "https://x/"],
+            &["POST", "http://x/", "X-Key:@/etc/passwd"],
+            &["POST", "http://x/", "key=@secret.txt"],
+            &["POST", "http://x/", "file@/etc/passwd"],
+            &["http://x/{env:HOME}"],
+            &["POST", "http://x/", "X-Key:{env:HOME}"],
+            &["POST", "http://x/", "key={now:{}env:PATH}"],
+        ] {
+            assert!(parse_job_args(&argv(rejected)).is_err(), "{:?} should be rejected", rejected);
+        }
+    }
+
+    #[test]
+    fn test_job_argv_uses_effective_args() {
+        let mut args = Args::try_parse_from([
+            "quicpulse", "--bench", "--requests", "50", "--rate", "10/s", "--bench-data-order", "random",
+            "--timeout", "2.5", "-vv", "--bench-workers", "w:7000", "POST", "{{BASE_URL}}/users", "name=a",
+        ]).unwrap();
+        // As after config defaults and `{{VAR}}` expansion
+        args.url = Some("http://api.test/users".to_string());
+        args.form = true;
+        args.request_items.push("--x=1".to_string());
+
+        let job = parse_job_args(&job_argv(&args)).unwrap();
+        assert_eq!(job.method.as_deref(), Some("POST"));
+        assert_eq!(job.url.as_deref(), Some("http://api.test/users"));
+        assert_eq!(job.request_items, vec!["name=a", "--x=1"]);
+        assert!(job.form);
+        assert_eq!(job.bench_requests, 50);
+        assert_eq!(job.bench_rate.as_deref(), Some("10/s"));
+        assert_eq!(job.bench_data_order, BenchDataOrder::Random);
+        assert_eq!(job.timeout, Some(2.5));
+        assert_eq!(job.verbose, 2);
+        assert!(job.bench_workers.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_worker_checks_token_and_times_out() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        // A client that never sends its job does not hold the worker
+        let _idle = TcpStream::connect(addr).await.unwrap();
+        let (socket, _) = listener.accept().await.unwrap();
+        let outcome = handle(socket, "secret", Duration::from_millis(50)).await;
+        assert!(matches!(outcome, Err(QuicpulseError::Timeout(_))), "{:?}", outcome);
+
+        let mut client = TcpStream::connect(addr).await.unwrap();
+        let (socket, _) = listener.accept().await.unwrap();
+        let job = Job {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            token: "guess".to_string(),
+            argv: vec!["quicpulse".to_string(), "--bench".to_string(), "http://127.0.0.1:1/".to_string()],
+            requests: 1,
+            rate_share: 1.0,
+            data: None,
+        };
+        let mut line = serde_json::to_vec(&job).unwrap();
+        line.push(b'\n');
+        client.write_all(&line).await.unwrap();
+        assert!(handle(socket, "secret", Duration::from_secs(5)).await.is_err());
+
+        let mut reply = String::new();
+        BufReader::new(client).read_line(&mut reply).await.unwrap();
+        let message: Message = serde_json::from_str(&reply).unwrap();
+        assert!(matches!(message, Message::Error { ref message } if message.contains("token")), "{:?}", message);
+    }
+
+    #[test]
+    fn test_split_requests() {
+        assert_eq!(split_requests(10, 3), vec![4, 3, 3]);
+        assert_eq!(split_requests(2, 3), vec![1, 1, 0]);
+        assert_eq!(split_requests(9, 1), vec![9]);
+    }
+
+    #[test]
+    fn test_message_round_trip() {
+        let mut collector = StatsCollector::new();
+        collector.record(Some(200), Duration::from_millis(3), 2, None);
+        let message = Message::Done { requests: 1, duration_s: 0.5, stats: collector.finalize(Duration::from_millis(500)) };
+
+        let line = serde_json::to_string(&message).unwrap();
+        assert!(line.starts_with("{\"type\":\"done\""));
+        let Message::Done { stats, .. } = serde_json::from_str(&line).unwrap() else {
+            panic!("expected done: {}", line);
+        };
+        assert_eq!(stats.histogram.len(), 1);
+    }
+
+    /// HTTP server answering every request with 200, returning its address and the request lines seen
+    async fn ok_server() -> (String, std::sync::Arc<std::sync::Mutex<Vec<String>>>) {
+        use tokio::io::AsyncReadExt;
+
+        let server = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = server.local_addr().unwrap().to_string();
+        let seen = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let lines = seen.clone();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = server.accept().await {
+                let lines = lines.clone();
+                tokio::spawn(async move {
+                    let mut buf = [0u8; 1024];
+                    while let Ok(n) = socket.read(&mut buf).await {
+                        if n == 0 {
+                            break;
+                        }
+                        let request = String::from_utf8_lossy(&buf[..n]);
+                        lines.lock().unwrap().push(request.lines().next().unwrap_or_default().to_string());
+                        if socket.write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nok").await.is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+        });
+        (addr, seen)
+    }
+
+    /// Listeners for `count` local workers and their addresses
+    async fn local_workers(count: usize) -> (Vec<TcpListener>, Vec<String>) {
+        let mut workers = Vec::new();
+        let mut listeners = Vec::new();
+        for _ in 0..count {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            workers.push(listener.local_addr().unwrap().to_string());
+            listeners.push(listener);
+        }
+        (listeners, workers)
+    }
+
+    #[tokio::test]
+    async fn test_controller_merges_local_workers() {
+        let (addr, _) = ok_server().await;
+        let url = format!("http://{}/", addr);
+
+        let (listeners, workers) = local_workers(2).await;
+        let serving = futures::future::join_all(listeners.into_iter().map(|listener| serve(listener, "secret")));
+
+        let argv: Vec<String> = ["quicpulse", "--bench", "--requests", "21", "--concurrency", "2", "GET", &url]
+            .iter().map(|arg| arg.to_string()).collect();
+        let args = Args::try_parse_from(&argv).unwrap();
+        let processed = crate::cli::process_args(&args).unwrap();
+        let config = BenchmarkConfig::from_args(&args, &processed).unwrap();
+
+        let result = tokio::select! {
+            result = run_controller(&args, &workers, "secret", config, None, LiveReporter::new()) => result.unwrap(),
+            _ = serving => unreachable!("workers only stop when accepting fails"),
+        };
+        assert_eq!(result.total_requests, 21);
+        assert_eq!(result.concurrency, 4);
+        assert_eq!(result.stats.successful_requests, 21);
+        assert_eq!(result.stats.status_codes[&200], 21);
+        assert_eq!(result.stats.histogram.len(), 21);
+        assert_eq!(result.stats.total_bytes, 42);
+
+        // A worker that is not there stops the run
+        let config = BenchmarkConfig::from_args(&args, &processed).unwrap();
+        let missing = vec!["127.0.0.1:1".to_string()];
+        assert!(run_controller(&args, &missing, "secret", config, None, LiveReporter::new()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_controller_sends_data_rows() {
+        let (addr, seen) = ok_server().await;
+        let url = format!("http://{}/users/{{{{id}}}}", addr);
+
+        let (listeners, workers) = local_workers(1).await;
+        let serving = futures::future::join_all(listeners.into_iter().map(|listener| serve(listener, "secret")));
+
+        // The path does not exist on the worker; only the rows in the job are used
+        let argv: Vec<String> = ["quicpulse", "--bench", "--requests", "2", "--concurrency", "1", "--bench-data", "/nonexistent/rows.csv", "GET", &url]
+            .iter().map(|arg| arg.to_string()).collect();
+        let args = Args::try_parse_from(&argv).unwrap();
+        let processed = crate::cli::process_args(&args).unwrap();
+        let config = BenchmarkConfig::from_args(&args, &processed).unwrap();
+        let data: DataSet = serde_json::from_str(r#"[{"id": "7"}, {"id": "8"}]"#).unwrap();
+
+        let result = tokio::select! {
+            result = run_controller(&args, &workers, "secret", config, Some(data), LiveReporter::new()) => result.unwrap(),
+            _ = serving => unreachable!("workers only stop when accepting fails"),
+        };
+        assert_eq!(result.stats.successful_requests, 2);
+        let mut lines = seen.lock().unwrap().clone();
+        lines.sort();
+        assert_eq!(lines, vec!["GET /users/7 HTTP/1.1", "GET /users/8 HTTP/1.1"]);
+    }
+}
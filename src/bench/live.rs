@@ -0,0 +1,303 @@
+//! Per-interval benchmark statistics
+//!
+//! While a benchmark runs, results are also summarised once per
+//! [`SAMPLE_INTERVAL`] with a fresh [`StatsCollector`](super::stats::StatsCollector).
+//! Each [`Sample`] can be drawn in a live terminal panel and appended to a
+//! CSV or NDJSON time-series file, so latency drift over a long run is visible
+//! rather than averaged away. A benchmark worker forwards its samples to the
+//! controller instead.
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::Serialize;
+use tokio::sync::mpsc;
+
+use super::stats::BenchmarkStats;
+use crate::errors::QuicpulseError;
+use crate::output::terminal::{self, colors};
+
+/// Length of one sample
+pub const SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Statistics for one interval of a run
+#[derive(Debug, Clone)]
+pub struct Sample {
+    /// Position of the interval, counting from 1
+    pub index: u64,
+    /// Time from the start of the run to the end of the interval
+    pub elapsed: Duration,
+    pub stats: BenchmarkStats,
+}
+
+impl Sample {
+    /// Share of completed requests in the interval that failed
+    pub fn error_rate(&self) -> f64 {
+        let total = self.stats.successful_requests + self.stats.failed_requests;
+        if total == 0 { 0.0 } else { self.stats.failed_requests as f64 / total as f64 }
+    }
+}
+
+/// One line of the time-series file
+#[derive(Debug, Serialize)]
+struct TimeSeriesRow {
+    interval: u64,
+    elapsed_s: f64,
+    requests: u64,
+    successful: u64,
+    failed: u64,
+    dropped: u64,
+    late: u64,
+    rps: f64,
+    error_rate: f64,
+    bytes: u64,
+    min_ms: f64,
+    mean_ms: f64,
+    p50_ms: f64,
+    p75_ms: f64,
+    p90_ms: f64,
+    p95_ms: f64,
+    p99_ms: f64,
+    max_ms: f64,
+}
+
+impl From<&Sample> for TimeSeriesRow {
+    fn from(sample: &Sample) -> Self {
+        let stats = &sample.stats;
+        Self {
+            interval: sample.index,
+            elapsed_s: sample.elapsed.as_secs_f64(),
+            requests: stats.successful_requests + stats.failed_requests,
+            successful: stats.successful_requests,
+            failed: stats.failed_requests,
+            dropped: stats.dropped_requests,
+            late: stats.late_requests,
+            rps: stats.requests_per_second,
+            error_rate: sample.error_rate(),
+            bytes: stats.total_bytes,
+            min_ms: stats.latency.min_ms,
+            mean_ms: stats.latency.mean_ms,
+            p50_ms: stats.latency.p50_ms,
+            p75_ms: stats.latency.p75_ms,
+            p90_ms: stats.latency.p90_ms,
+            p95_ms: stats.latency.p95_ms,
+            p99_ms: stats.latency.p99_ms,
+            max_ms: stats.latency.max_ms,
+        }
+    }
+}
+
+enum Sink {
+    Csv(Box<csv::Writer<File>>),
+    Ndjson(BufWriter<File>),
+}
+
+/// Appends one row per sample to a CSV (`.csv`) or NDJSON (any other extension) file
+pub struct TimeSeriesWriter {
+    path: PathBuf,
+    sink: Sink,
+}
+
+impl TimeSeriesWriter {
+    pub fn create(path: &Path) -> Result<Self, QuicpulseError> {
+        let file = File::create(path)?;
+        let is_csv = path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("csv"));
+        let sink = if is_csv {
+            Sink::Csv(Box::new(csv::Writer::from_writer(file)))
+        } else {
+            Sink::Ndjson(BufWriter::new(file))
+        };
+        Ok(Self { path: path.to_path_buf(), sink })
+    }
+
+    /// Write a row and flush it, so the file can be followed during the run
+    pub fn write(&mut self, sample: &Sample) -> io::Result<()> {
+        let row = TimeSeriesRow::from(sample);
+        match self.sink {
+            Sink::Csv(ref mut writer) => {
+                writer.serialize(&row)?;
+                writer.flush()
+            }
+            Sink::Ndjson(ref mut writer) => {
+                serde_json::to_writer(&mut *writer, &row)?;
+                writer.write_all(b"\n")?;
+                writer.flush()
+            }
+        }
+    }
+}
+
+/// Table of samples on stderr with a status line below it that is redrawn in place
+pub struct LivePanel {
+    duration: Option<Duration>,
+    requests: u64,
+    failed: u64,
+}
+
+impl LivePanel {
+    pub fn new(duration: Option<Duration>) -> Self {
+        eprintln!("{}", terminal::muted(&format!(
+            "{:>8}  {:>9}  {:>9}  {:>10}  {:>10}  {:>7}",
+            "elapsed", "requests", "rps", "p50", "p99", "errors"
+        )));
+        Self { duration, requests: 0, failed: 0 }
+    }
+
+    pub fn update(&mut self, sample: &Sample) {
+        let stats = &sample.stats;
+        self.requests += stats.successful_requests + stats.failed_requests;
+        self.failed += stats.failed_requests;
+
+        let error_rate = sample.error_rate();
+        let error_color = if error_rate == 0.0 { colors::GREEN } else { colors::RED };
+        clear_line();
+        eprintln!(
+            "{}  {}  {}  {}  {}  {}",
+            terminal::muted(&format!("{:>7.0}s", sample.elapsed.as_secs_f64())),
+            terminal::number(&format!("{:>9}", stats.successful_requests + stats.failed_requests)),
+            terminal::number(&format!("{:>9.1}", stats.requests_per_second)),
+            terminal::number(&format!("{:>8.2}ms", stats.latency.p50_ms)),
+            terminal::colorize(&format!("{:>8.2}ms", stats.latency.p99_ms), colors::ORANGE),
+            terminal::colorize(&format!("{:>6.1}%", error_rate * 100.0), error_color),
+        );
+
+        let elapsed = humantime::format_duration(Duration::from_secs(sample.elapsed.as_secs()));
+        let progress = match self.duration {
+            Some(total) => format!("{} / {}", elapsed, humantime::format_duration(total)),
+            None => elapsed.to_string(),
+        };
+        eprint!("{}", terminal::muted(&format!("  {} · {} requests · {} failed", progress, self.requests, self.failed)));
+        let _ = io::stderr().flush();
+    }
+
+    /// Remove the status line
+    pub fn finish(&self) {
+        clear_line();
+    }
+}
+
+fn clear_line() {
+    let _ = crossterm::execute!(
+        io::stderr(),
+        crossterm::terminal::Clear(crossterm::terminal::ClearType::CurrentLine),
+        crossterm::cursor::MoveToColumn(0)
+    );
+}
+
+/// Sends samples to the live panel, the time-series file and a channel
+#[derive(Default)]
+pub struct LiveReporter {
+    panel: Option<LivePanel>,
+    timeseries: Option<TimeSeriesWriter>,
+    forward: Option<mpsc::UnboundedSender<Sample>>,
+}
+
+impl LiveReporter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Draw samples on stderr
+    pub fn with_panel(mut self, duration: Option<Duration>) -> Self {
+        self.panel = Some(LivePanel::new(duration));
+        self
+    }
+
+    /// Append samples to a time-series file
+    pub fn with_timeseries(mut self, writer: TimeSeriesWriter) -> Self {
+        self.timeseries = Some(writer);
+        self
+    }
+
+    /// Send every sample to `sender`
+    pub fn with_forward(mut self, sender: mpsc::UnboundedSender<Sample>) -> Self {
+        self.forward = Some(sender);
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.panel.is_none() && self.timeseries.is_none() && self.forward.is_none()
+    }
+
+    pub fn report(&mut self, sample: &Sample) {
+        if let Some(ref mut panel) = self.panel {
+            panel.update(sample);
+        }
+        if let Some(ref mut writer) = self.timeseries {
+            if let Err(e) = writer.write(sample) {
+                eprintln!("{}", terminal::warning(&format!(
+                    "Failed to write {}: {}; time series disabled", writer.path.display(), e
+                )));
+                self.timeseries = None;
+            }
+        }
+        if let Some(ref sender) = self.forward {
+            let _ = sender.send(sample.clone());
+        }
+    }
+
+    pub fn finish(&mut self) {
+        if let Some(ref panel) = self.panel {
+            panel.finish();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bench::stats::StatsCollector;
+
+    fn sample(index: u64) -> Sample {
+        let mut collector = StatsCollector::new();
+        collector.record(Some(200), Duration::from_millis(10), 100, None);
+        collector.record(Some(200), Duration::from_millis(30), 100, None);
+        collector.record(Some(503), Duration::from_millis(50), 0, None);
+        collector.record(None, Duration::from_millis(90), 0, Some("Timeout".to_string()));
+        Sample {
+            index,
+            elapsed: SAMPLE_INTERVAL * index as u32,
+            stats: collector.finalize(SAMPLE_INTERVAL),
+        }
+    }
+
+    #[test]
+    fn test_error_rate() {
+        assert_eq!(sample(1).error_rate(), 0.5);
+    }
+
+    #[test]
+    fn test_timeseries_ndjson() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("series.ndjson");
+        let mut writer = TimeSeriesWriter::create(&path).unwrap();
+        writer.write(&sample(1)).unwrap();
+        writer.write(&sample(2)).unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        let rows: Vec<serde_json::Value> = content.lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[1]["interval"], 2);
+        assert_eq!(rows[1]["elapsed_s"], 2.0);
+        assert_eq!(rows[0]["requests"], 4);
+        assert_eq!(rows[0]["rps"], 4.0);
+        assert_eq!(rows[0]["error_rate"], 0.5);
+    }
+
+    #[test]
+    fn test_timeseries_csv() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("series.csv");
+        let mut writer = TimeSeriesWriter::create(&path).unwrap();
+        writer.write(&sample(1)).unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        let mut lines = content.lines();
+        assert!(lines.next().unwrap().starts_with("interval,elapsed_s,requests,"));
+        assert!(lines.next().unwrap().starts_with("1,1.0,4,"));
+    }
+}
@@ -1,16 +1,29 @@
 //! Benchmarking module for load testing
 
+pub mod data;
+pub mod distributed;
+pub mod live;
+pub mod protocol;
+pub mod rate;
+pub mod report;
 pub mod runner;
 pub mod stats;
 
+pub use data::{DataSet, RequestVariation};
+pub use live::{LiveReporter, TimeSeriesWriter};
+pub use protocol::{GrpcTarget, Protocol, Target, WsTarget};
+pub use rate::RateProfile;
+pub use report::{BenchmarkReport, Comparison, Thresholds, format_comparison};
 pub use runner::{BenchmarkRunner, BenchmarkConfig, BenchmarkResult};
 
 use crate::cli::Args;
+use crate::cli::args::{BenchDataOrder, BenchOutput, BenchWsMode};
 use crate::cli::parser::ProcessedArgs;
 use crate::context::Environment;
 use crate::errors::QuicpulseError;
 use crate::status::ExitStatus;
 use crate::output::terminal::{self, colors, RESET};
+use stats::ConnectionStats;
 
 /// Check if benchmark mode is enabled
 pub fn is_benchmark_mode(args: &Args) -> bool {
@@ -35,10 +48,18 @@ pub fn format_results(result: &BenchmarkResult) -> String {
 
     // Summary
     output.push_str(&format!("  {}              {}\n", terminal::label("URL:"), terminal::colorize(&result.url, colors::AQUA)));
-    output.push_str(&format!("  {}           {}{}{}\n", terminal::label("Method:"), http_method(&result.method), result.method, RESET));
+    if result.protocol != Protocol::Http {
+        output.push_str(&format!("  {}         {}\n", terminal::label("Protocol:"), terminal::label(result.protocol.name())));
+    }
+    if matches!(result.protocol, Protocol::Http | Protocol::Http3) {
+        output.push_str(&format!("  {}           {}{}{}\n", terminal::label("Method:"), http_method(&result.method), result.method, RESET));
+    }
     output.push_str(&format!("  {}         {}\n", terminal::label("Requests:"), terminal::number(&result.total_requests.to_string())));
     output.push_str(&format!("  {}      {}\n", terminal::label("Concurrency:"), terminal::number(&result.concurrency.to_string())));
     output.push_str(&format!("  {}         {}\n", terminal::label("Duration:"), terminal::number(&format!("{:.2}s", result.duration.as_secs_f64()))));
+    if let Some(ref rate) = result.rate {
+        output.push_str(&format!("  {}      {}\n", terminal::label("Target rate:"), terminal::number(&rate.describe())));
+    }
     output.push_str("\n");
 
     // Throughput
@@ -50,6 +71,12 @@ pub fn format_results(result: &BenchmarkResult) -> String {
     output.push_str(&format!("  {}     {}\n", terminal::label("Requests/sec:"), terminal::number(&format!("{:.2}", result.stats.requests_per_second))));
     output.push_str(&format!("  {}        {}\n", terminal::label("Bytes/sec:"), terminal::number(&crate::utils::format_bytes(result.stats.bytes_per_second as u64, 2))));
     output.push_str(&format!("  {}      {}\n", terminal::label("Total bytes:"), terminal::number(&crate::utils::format_bytes(result.stats.total_bytes, 2))));
+    if result.rate.is_some() {
+        let dropped_color = if result.stats.dropped_requests == 0 { colors::GREEN } else { colors::RED };
+        let late_color = if result.stats.late_requests == 0 { colors::GREEN } else { colors::YELLOW };
+        output.push_str(&format!("  {}          {}\n", terminal::label("Dropped:"), terminal::colorize(&result.stats.dropped_requests.to_string(), dropped_color)));
+        output.push_str(&format!("  {}             {}\n", terminal::label("Late:"), terminal::colorize(&result.stats.late_requests.to_string(), late_color)));
+    }
     output.push_str("\n");
 
     // Latency
@@ -71,6 +98,10 @@ pub fn format_results(result: &BenchmarkResult) -> String {
     output.push_str(&format!("    {}            {}\n", terminal::muted("p99:"), terminal::number(&format!("{:.2}ms", result.stats.latency.p99_ms))));
     output.push_str("\n");
 
+    if let Some(ref connections) = result.stats.connections {
+        output.push_str(&format_connections(connections, &section_line));
+    }
+
     // Status codes
     output.push_str(&section_line);
     output.push_str("\n");
@@ -107,6 +138,21 @@ pub fn format_results(result: &BenchmarkResult) -> String {
         }
     }
 
+    if !result.stats.grpc_status.is_empty() {
+        output.push_str(&format!("\n  {}:\n", terminal::label("gRPC status breakdown")));
+        let mut statuses: Vec<_> = result.stats.grpc_status.iter().collect();
+        statuses.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+        for (status, count) in statuses {
+            let pct = (*count as f64 / result.total_requests as f64) * 100.0;
+            let status_color = if status == "Ok" { colors::GREEN } else { colors::RED };
+            output.push_str(&format!("    {}: {} ({})\n",
+                terminal::colorize(status, status_color),
+                terminal::number(&count.to_string()),
+                terminal::muted(&format!("{:.1}%", pct))
+            ));
+        }
+    }
+
     if !result.stats.errors.is_empty() {
         output.push_str(&format!("\n  {}:\n", terminal::error("Error breakdown")));
         for (error, count) in &result.stats.errors {
@@ -121,103 +167,222 @@ pub fn format_results(result: &BenchmarkResult) -> String {
     output
 }
 
+fn format_connections(connections: &ConnectionStats, section_line: &str) -> String {
+    let mut output = String::new();
+
+    output.push_str(section_line);
+    output.push('\n');
+    output.push_str(&format!("  {}\n", terminal::bold("CONNECTIONS", colors::WHITE)));
+    output.push_str(section_line);
+    output.push('\n');
+
+    let total = connections.opened + connections.reused;
+    let reuse_pct = if total > 0 { connections.reused as f64 / total as f64 * 100.0 } else { 0.0 };
+    output.push_str(&format!("  {}           {}\n", terminal::label("Opened:"), terminal::number(&connections.opened.to_string())));
+    output.push_str(&format!("  {}           {} ({})\n",
+        terminal::label("Reused:"),
+        terminal::number(&connections.reused.to_string()),
+        terminal::muted(&format!("{:.1}%", reuse_pct))
+    ));
+    output.push('\n');
+    output.push_str(&format!("  {}\n", terminal::muted(&format!(
+        "{:<8}  {:>7}  {:>9}  {:>9}  {:>9}  {:>9}", "Phase", "Count", "p50", "p90", "p99", "Max"
+    ))));
+
+    let phases = [
+        ("DNS", &connections.dns),
+        ("Connect", &connections.connect),
+        ("TLS", &connections.tls),
+        ("TTFB", &connections.ttfb),
+    ];
+    for (name, phase) in phases.into_iter().filter(|(_, phase)| phase.count > 0) {
+        output.push_str(&format!(
+            "  {}  {}  {}  {}  {}  {}\n",
+            terminal::label(&format!("{:<8}", name)),
+            terminal::number(&format!("{:>7}", phase.count)),
+            terminal::number(&format!("{:>7.2}ms", phase.latency.p50_ms)),
+            terminal::number(&format!("{:>7.2}ms", phase.latency.p90_ms)),
+            terminal::number(&format!("{:>7.2}ms", phase.latency.p99_ms)),
+            terminal::colorize(&format!("{:>7.2}ms", phase.latency.max_ms), colors::ORANGE),
+        ));
+    }
+    output.push('\n');
+
+    output
+}
+
 // Local humanize_bytes removed in favor of crate::utils::format_bytes
 
 pub async fn run_benchmark(
     args: Args,
     processed: ProcessedArgs,
-    _env: Environment,
+    env: Environment,
 ) -> Result<ExitStatus, QuicpulseError> {
-    use crate::input::InputItem;
+    let config = BenchmarkConfig::from_args(&args, &processed)?;
 
-    let config = BenchmarkConfig::from_args(&args, &processed);
+    // Check the baseline before spending time on the run
+    let thresholds = args.bench_threshold.as_deref()
+        .map(Thresholds::parse)
+        .transpose()
+        .map_err(QuicpulseError::Argument)?
+        .unwrap_or_default();
+    let baseline = args.bench_baseline.as_deref().map(BenchmarkReport::load).transpose()?;
+    if args.bench_data.is_some() && matches!(config.protocol, Protocol::Grpc | Protocol::WebSocket) {
+        return Err(QuicpulseError::Argument(format!(
+            "--bench-data is not supported for {} benchmarks", config.protocol.name()
+        )));
+    }
+    let workers = match args.bench_workers {
+        Some(ref list) => {
+            // Workers only get the allowed options, so anything else the user set would be dropped
+            let config = crate::config::Config::load(&env).unwrap_or_default();
+            distributed::check_job_options(&crate::core::merge_default_options(std::env::args().collect(), &config))?;
+            Some((distributed::parse_workers(list)?, distributed::required_token(&args, "--bench-workers")?))
+        }
+        None => None,
+    };
+    // Workers get the rows with their job
+    let data = args.bench_data.as_deref().map(DataSet::load).transpose()?;
 
     use terminal::protocol::http_method;
+    let load = match (&config.rate, config.duration) {
+        (Some(rate), _) => format!("rate: {}", terminal::number(&rate.describe())),
+        (None, Some(duration)) => format!("duration: {}", terminal::number(&humantime::format_duration(duration).to_string())),
+        (None, None) => format!("requests: {}", terminal::number(&config.total_requests.to_string())),
+    };
+    let limit = if config.rate.is_some() { "max in flight" } else { "concurrency" };
+    let what = match config.protocol {
+        Protocol::Http => format!("{}{}{}", http_method(&config.method), config.method, RESET),
+        Protocol::Http3 => format!("{} {}{}{}", terminal::label("HTTP/3"), http_method(&config.method), config.method, RESET),
+        Protocol::Grpc => terminal::label("gRPC"),
+        Protocol::WebSocket => match args.bench_ws_mode {
+            BenchWsMode::RoundTrip => terminal::label("WebSocket round-trip"),
+            BenchWsMode::Throughput => terminal::label("WebSocket throughput"),
+        },
+    };
+    let spread = match workers {
+        Some((ref workers, _)) => format!(", workers: {}", terminal::number(&workers.len().to_string())),
+        None => String::new(),
+    };
     eprintln!(
-        "{} {}{}{} {} (requests: {}, concurrency: {})",
+        "{} {} {} ({}, {}: {}{})",
         terminal::info("Benchmarking"),
-        http_method(&config.method), config.method, RESET,
+        what,
         terminal::colorize(&config.url, colors::AQUA),
-        terminal::number(&config.total_requests.to_string()),
-        terminal::number(&config.concurrency.to_string())
+        load,
+        limit,
+        terminal::number(&config.concurrency.to_string()),
+        spread
     );
+    if let (Some(path), Some(data)) = (&args.bench_data, &data) {
+        let order = match args.bench_data_order {
+            BenchDataOrder::RoundRobin => "round-robin",
+            BenchDataOrder::Random => "random",
+        };
+        eprintln!("{} {} ({} rows, {})", terminal::info("Data:"), path.display(), terminal::number(&data.len().to_string()), order);
+    }
     eprintln!("{}\n", terminal::muted("Running..."));
 
-    let runner = BenchmarkRunner::new(config, &args)?;
+    // The live panel needs a terminal; the time series works anywhere
+    let mut reporter = LiveReporter::new();
+    if env.stderr_isatty {
+        reporter = reporter.with_panel(config.duration.or_else(|| config.rate.as_ref().and_then(RateProfile::duration)));
+    }
+    if let Some(ref path) = args.bench_timeseries {
+        reporter = reporter.with_timeseries(TimeSeriesWriter::create(path)?);
+    }
 
-    let runner = if processed.has_data {
-        let mut body = serde_json::json!({});
-        for item in &processed.items {
-            match item {
-                InputItem::DataField { key, value } => {
-                    if let Some(map) = body.as_object_mut() {
-                        map.insert(key.clone(), serde_json::Value::String(value.clone()));
-                    }
-                }
-                InputItem::JsonField { key, value } => {
-                    if let Some(map) = body.as_object_mut() {
-                        map.insert(key.clone(), value.clone());
-                    }
-                }
-                _ => {}
-            }
+    let result = match workers {
+        Some((workers, token)) => {
+            distributed::run_controller(&args, &workers, &token, config, data, reporter).await?
         }
-        runner.with_body(body.to_string().into_bytes())
-    } else {
-        runner
+        None => prepare_runner(&args, &processed, config, data, reporter).await?.run().await?,
     };
+    let comparison = baseline.map(|baseline| Comparison::new(&baseline.stats, &result.stats, &thresholds));
 
-    let mut headers = reqwest::header::HeaderMap::new();
-    for item in &processed.items {
-        match item {
-            InputItem::Header { name, value } => {
-                if let Ok(header_name) = reqwest::header::HeaderName::try_from(name.as_str()) {
-                    if let Ok(header_value) = reqwest::header::HeaderValue::from_str(value) {
-                        headers.insert(header_name, header_value);
-                    }
-                }
+    match args.bench_output {
+        BenchOutput::Text => {
+            print!("{}", format_results(&result));
+            if let Some(ref comparison) = comparison {
+                print!("{}", format_comparison(comparison));
             }
-            InputItem::EmptyHeader { name } => {
-                if let Ok(header_name) = reqwest::header::HeaderName::try_from(name.as_str()) {
-                    if let Ok(header_value) = reqwest::header::HeaderValue::from_str("") {
-                        headers.insert(header_name, header_value);
-                    }
-                }
-            }
-            InputItem::HeaderFile { name, path } => {
-                if let Ok(content) = std::fs::read_to_string(path) {
-                    if let Ok(header_name) = reqwest::header::HeaderName::try_from(name.as_str()) {
-                        if let Ok(header_value) = reqwest::header::HeaderValue::from_str(content.trim()) {
-                            headers.insert(header_name, header_value);
-                        }
-                    }
-                }
-            }
-            _ => {}
+        }
+        BenchOutput::Json => {
+            let mut report = BenchmarkReport::new(&result);
+            report.baseline = comparison.clone();
+            println!("{}", serde_json::to_string_pretty(&report)?);
         }
     }
 
-    let runner = if !headers.is_empty() {
-        runner.with_headers(headers)
+    if let Some(ref comparison) = comparison {
+        for delta in comparison.regressions() {
+            eprintln!("{}", terminal::error(&format!(
+                "Regression: {} {:+.1}% (limit {}%)", delta.metric, delta.change_pct, delta.threshold_pct
+            )));
+        }
+        if comparison.regressed {
+            return Ok(ExitStatus::Error);
+        }
+    }
+
+    if result.stats.success_rate >= 0.5 {
+        Ok(ExitStatus::Success)
     } else {
-        runner
+        Ok(ExitStatus::Error)
+    }
+}
+
+/// Set up the runner for a benchmark on this machine: connect gRPC and
+/// WebSocket targets, and attach the request body, headers and variation
+pub async fn prepare_runner(
+    args: &Args,
+    processed: &ProcessedArgs,
+    config: BenchmarkConfig,
+    data: Option<DataSet>,
+    reporter: LiveReporter,
+) -> Result<BenchmarkRunner, QuicpulseError> {
+    // gRPC and WebSocket connect before the clock starts
+    let target = match config.protocol {
+        Protocol::Grpc => Some(Target::Grpc(GrpcTarget::connect(args, processed).await?)),
+        Protocol::WebSocket => Some(Target::WebSocket(WsTarget::connect(args, processed, config.concurrency).await?)),
+        Protocol::Http | Protocol::Http3 => None,
     };
 
-    let result = runner.run().await?;
+    let runner = BenchmarkRunner::new(config, args)?;
+    let runner = match target {
+        Some(target) => runner.with_target(target),
+        None => runner,
+    };
+    let runner = if reporter.is_empty() { runner } else { runner.with_reporter(reporter) };
 
-    print!("{}", format_results(&result));
+    let runner = if processed.has_data {
+        runner.with_body(data::request_body(&processed.items))
+    } else {
+        runner
+    };
 
-    if result.stats.success_rate >= 0.5 {
-        Ok(ExitStatus::Success)
+    let headers = data::request_headers(&processed.items);
+    let runner = if !headers.is_empty() {
+        runner.with_headers(headers)
     } else {
-        Ok(ExitStatus::Error)
+        runner
+    };
+
+    // Requests that use data rows or magic values are rendered one by one
+    let mut variation = RequestVariation::new(processed.raw_url.clone(), processed.raw_items.clone(), processed.has_data)?;
+    if let Some(data) = data {
+        variation = variation.with_data(data, args.bench_data_order);
     }
+    let runner = if variation.varies() { runner.with_variation(variation) } else { runner };
+
+    Ok(runner)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use super::stats::{BenchmarkStats, LatencyStats};
+    use crate::client::timing::{ConnectTiming, ConnectionInfo};
     use std::time::Duration;
     use std::collections::HashMap;
 
@@ -258,7 +423,10 @@ mod tests {
                     p95_ms: 300.0,
                     p99_ms: 450.0,
                 },
+                ..Default::default()
             },
+            rate: None,
+            protocol: Protocol::Http,
         };
 
         let output = format_results(&result);
@@ -269,5 +437,85 @@ mod tests {
         assert!(output.contains("STATUS CODES"));
         assert!(output.contains("example.com"));
         assert!(output.contains("p95"));
+        assert!(!output.contains("Dropped"));
+    }
+
+    #[test]
+    fn test_format_results_open_loop() {
+        let result = BenchmarkResult {
+            url: "https://example.com".to_string(),
+            method: "GET".to_string(),
+            total_requests: 500,
+            concurrency: 50,
+            duration: Duration::from_secs(1),
+            stats: BenchmarkStats {
+                dropped_requests: 7,
+                late_requests: 3,
+                ..Default::default()
+            },
+            rate: Some(RateProfile::constant(500.0)),
+            protocol: Protocol::Http,
+        };
+
+        let output = format_results(&result);
+        assert!(output.contains("500/s"));
+        assert!(output.contains("Dropped"));
+        assert!(output.contains("Late"));
+    }
+
+    #[test]
+    fn test_format_results_connections() {
+        let mut collector = stats::StatsCollector::new();
+        collector.record(Some(200), Duration::from_millis(12), 2, None);
+        collector.record_connection(&ConnectionInfo {
+            opened: Some(ConnectTiming {
+                dns: None,
+                connect: Duration::from_millis(1),
+                tls: Some(Duration::from_millis(4)),
+                session: None,
+            }),
+            ttfb: Duration::from_millis(10),
+        });
+        let result = BenchmarkResult {
+            url: "https://example.com".to_string(),
+            method: "GET".to_string(),
+            total_requests: 1,
+            concurrency: 1,
+            duration: Duration::from_secs(1),
+            stats: collector.finalize(Duration::from_secs(1)),
+            rate: None,
+            protocol: Protocol::Http,
+        };
+
+        let output = format_results(&result);
+        assert!(output.contains("CONNECTIONS"));
+        assert!(output.contains("TTFB"));
+        assert!(output.contains("TLS"));
+        // Phases nothing was recorded for are left out
+        assert!(!output.contains("DNS"));
+    }
+
+    #[test]
+    fn test_format_results_grpc() {
+        let result = BenchmarkResult {
+            url: "grpc://localhost:50051/echo.Echo/Say".to_string(),
+            method: "GET".to_string(),
+            total_requests: 10,
+            concurrency: 2,
+            duration: Duration::from_secs(1),
+            stats: BenchmarkStats {
+                successful_requests: 8,
+                failed_requests: 2,
+                grpc_status: HashMap::from([("Ok".to_string(), 8), ("Unavailable".to_string(), 2)]),
+                ..Default::default()
+            },
+            rate: None,
+            protocol: Protocol::Grpc,
+        };
+
+        let output = format_results(&result);
+        assert!(output.contains("gRPC status breakdown"));
+        assert!(output.contains("Unavailable"));
+        assert!(!output.contains("Method:"));
     }
 }
@@ -0,0 +1,316 @@
+//! Benchmark targets besides plain HTTP
+//!
+//! HTTP/3 only changes how the runner's client is built. gRPC and WebSocket
+//! benchmarks need their own connections: a gRPC target encodes the unary
+//! call once and sends it over a shared channel, a WebSocket target keeps one
+//! connection per concurrent request and exchanges text messages over it.
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use futures::{FutureExt, StreamExt};
+use serde::{Deserialize, Serialize};
+
+use crate::cli::Args;
+use crate::cli::args::BenchWsMode;
+use crate::cli::parser::ProcessedArgs;
+use crate::errors::QuicpulseError;
+use crate::grpc::client::{GrpcClient, UnaryCall};
+use crate::websocket::client::WsClient;
+use crate::websocket::{WsEndpoint, WsMessage, WsOptions};
+
+/// Protocol a benchmark speaks
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Protocol {
+    /// HTTP/1.1 or HTTP/2 (default)
+    #[default]
+    Http,
+    Http3,
+    Grpc,
+    #[serde(rename = "websocket")]
+    WebSocket,
+}
+
+impl Protocol {
+    pub fn from_args(args: &Args) -> Self {
+        if args.grpc {
+            Protocol::Grpc
+        } else if crate::websocket::is_ws_request(args) {
+            Protocol::WebSocket
+        } else if args.http3 || args.http_version.as_deref() == Some("3") {
+            Protocol::Http3
+        } else {
+            Protocol::Http
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Protocol::Http => "HTTP",
+            Protocol::Http3 => "HTTP/3",
+            Protocol::Grpc => "gRPC",
+            Protocol::WebSocket => "WebSocket",
+        }
+    }
+}
+
+/// What the server answered, in the terms of its protocol
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Reply {
+    /// HTTP status code
+    Http(u16),
+    /// gRPC status
+    Grpc(tonic::Code),
+    /// WebSocket message sent, and answered in round-trip mode
+    Message,
+}
+
+/// Benchmark target that needs its own connections; HTTP requests go through the runner's client
+pub enum Target {
+    Grpc(GrpcTarget),
+    WebSocket(WsTarget),
+}
+
+impl Target {
+    /// Send one call or message, returning the reply and the bytes it carried
+    pub async fn send(&self) -> Result<(Reply, usize), String> {
+        match self {
+            Target::Grpc(target) => target.send().await,
+            Target::WebSocket(target) => target.send().await,
+        }
+    }
+}
+
+/// Unary gRPC call sent over one shared channel
+pub struct GrpcTarget {
+    call: UnaryCall,
+}
+
+impl GrpcTarget {
+    /// Connect and encode the call from the URL's service and method and the request items
+    pub async fn connect(args: &Args, processed: &ProcessedArgs) -> Result<Self, QuicpulseError> {
+        let endpoint = crate::grpc::parse_grpc_endpoint(&processed.url)?;
+        let (service, method) = endpoint.service.clone()
+            .zip(endpoint.method.clone())
+            .ok_or_else(|| QuicpulseError::Argument(
+                "gRPC benchmarks need a service and method: grpc://host:port/package.Service/Method".to_string()
+            ))?;
+
+        let timeout = args.timeout.map(Duration::from_secs_f64);
+        let ssl_config = crate::grpc::ssl_config(args);
+        let headers = crate::grpc::request_metadata(&processed.items);
+        let mut client = GrpcClient::connect_with_options(endpoint, timeout, Some(headers), Some(&ssl_config)).await?;
+        if let Some(ref proto_path) = args.proto {
+            client.load_proto(proto_path)?;
+        }
+
+        let request_json = crate::grpc::request_json(args, &processed.items)?;
+        let call = client.prepare_unary(&service, &method, &request_json).await?;
+        Ok(Self { call })
+    }
+
+    async fn send(&self) -> Result<(Reply, usize), String> {
+        match self.call.send().await {
+            Ok(bytes) => Ok((Reply::Grpc(tonic::Code::Ok), bytes)),
+            Err(status) => Ok((Reply::Grpc(status.code()), 0)),
+        }
+    }
+}
+
+/// Text message exchanged over a pool of WebSocket connections
+pub struct WsTarget {
+    endpoint: WsEndpoint,
+    options: WsOptions,
+    skip_tls_verify: bool,
+    message: String,
+    mode: BenchWsMode,
+    /// Connections not in use; a request takes one and returns it unless it broke
+    idle: Mutex<Vec<WsClient>>,
+}
+
+impl WsTarget {
+    /// Open `connections` connections up front so handshakes stay out of the latencies.
+    ///
+    /// The message is `--ws-send`, or else JSON built from the data items.
+    pub async fn connect(args: &Args, processed: &ProcessedArgs, connections: u32) -> Result<Self, QuicpulseError> {
+        let message = match args.ws_send {
+            Some(ref message) => message.clone(),
+            None => crate::websocket::json_message(&processed.items)
+                .map(|json| json.to_string())
+                .ok_or_else(|| QuicpulseError::Argument(
+                    "WebSocket benchmarks need a message: use --ws-send or data items".to_string()
+                ))?,
+        };
+
+        let verify = args.verify.to_lowercase();
+        let target = Self {
+            endpoint: crate::websocket::parse_ws_endpoint(&processed.url, args)?,
+            options: crate::websocket::ws_options(args, &processed.items)?,
+            skip_tls_verify: verify == "no" || verify == "false" || verify == "0",
+            message,
+            mode: args.bench_ws_mode,
+            idle: Mutex::new(Vec::new()),
+        };
+
+        let mut idle = Vec::new();
+        for _ in 0..connections.max(1) {
+            idle.push(target.open().await?);
+        }
+        *target.idle.lock().unwrap() = idle;
+        Ok(target)
+    }
+
+    async fn open(&self) -> Result<WsClient, QuicpulseError> {
+        WsClient::connect_simple(&self.endpoint, &self.options, self.skip_tls_verify).await
+    }
+
+    async fn send(&self) -> Result<(Reply, usize), String> {
+        // A connection that broke earlier is replaced here
+        let idle = self.idle.lock().unwrap().pop();
+        let mut client = match idle {
+            Some(client) => client,
+            None => self.open().await.map_err(|e| e.to_string())?,
+        };
+
+        let exchange = async {
+            match self.mode {
+                BenchWsMode::RoundTrip => round_trip(&mut client, &self.message).await,
+                BenchWsMode::Throughput => send_only(&mut client, &self.message).await,
+            }
+        };
+        let result = match self.options.timeout {
+            Some(timeout) => tokio::time::timeout(timeout, exchange).await
+                .unwrap_or_else(|_| Err("Timeout".to_string())),
+            None => exchange.await,
+        };
+
+        if result.is_ok() {
+            self.idle.lock().unwrap().push(client);
+        }
+        result.map(|bytes| (Reply::Message, bytes))
+    }
+}
+
+/// Send the message and wait for the next data message; returns the reply's size
+async fn round_trip(client: &mut WsClient, message: &str) -> Result<usize, String> {
+    client.send_text(message).await.map_err(|e| e.to_string())?;
+    loop {
+        match client.receive().await.map_err(|e| e.to_string())? {
+            Some(WsMessage::Text(text)) => return Ok(text.len()),
+            Some(WsMessage::Binary(data)) => return Ok(data.len()),
+            Some(WsMessage::Close(_, _)) | None => return Err("Connection closed".to_string()),
+            Some(WsMessage::Ping(_)) | Some(WsMessage::Pong(_)) => continue,
+        }
+    }
+}
+
+/// Send the message and discard replies that have already arrived; returns the message's size
+async fn send_only(client: &mut WsClient, message: &str) -> Result<usize, String> {
+    client.send_text(message).await.map_err(|e| e.to_string())?;
+    // Unread replies would eventually stall the server's writes
+    while let Some(Some(Ok(_))) = client.stream_mut().next().now_or_never() {}
+    Ok(message.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_protocol_from_args() {
+        let mut args = Args::default();
+        assert_eq!(Protocol::from_args(&args), Protocol::Http);
+
+        args.http_version = Some("3".to_string());
+        assert_eq!(Protocol::from_args(&args), Protocol::Http3);
+
+        args.url = Some("ws://localhost:9000/echo".to_string());
+        assert_eq!(Protocol::from_args(&args), Protocol::WebSocket);
+
+        args.grpc = true;
+        assert_eq!(Protocol::from_args(&args), Protocol::Grpc);
+        assert_eq!(serde_json::to_string(&Protocol::WebSocket).unwrap(), "\"websocket\"");
+    }
+
+    /// WebSocket server echoing every text message
+    async fn serve_echo() -> std::net::SocketAddr {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((socket, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let Ok(mut ws) = tokio_tungstenite::accept_async(socket).await else {
+                        return;
+                    };
+                    while let Some(Ok(message)) = ws.next().await {
+                        if message.is_text() && futures::SinkExt::send(&mut ws, message).await.is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+        });
+        addr
+    }
+
+    fn echo_target(addr: std::net::SocketAddr, mode: BenchWsMode) -> WsTarget {
+        WsTarget {
+            endpoint: WsEndpoint {
+                host: "127.0.0.1".to_string(),
+                port: addr.port(),
+                path: "/".to_string(),
+                use_tls: false,
+                subprotocol: None,
+            },
+            options: WsOptions::default(),
+            skip_tls_verify: false,
+            message: "hello".to_string(),
+            mode,
+            idle: Mutex::new(Vec::new()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_websocket_reuses_connection() {
+        let addr = serve_echo().await;
+        for mode in [BenchWsMode::RoundTrip, BenchWsMode::Throughput] {
+            let target = echo_target(addr, mode);
+            for _ in 0..3 {
+                assert_eq!(target.send().await, Ok((Reply::Message, 5)));
+            }
+            assert_eq!(target.idle.lock().unwrap().len(), 1);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_websocket_benchmark() {
+        use crate::bench::runner::{BenchmarkConfig, BenchmarkRunner};
+
+        let addr = serve_echo().await;
+        let runner = BenchmarkRunner {
+            config: BenchmarkConfig {
+                total_requests: 20,
+                concurrency: 2,
+                url: format!("ws://{}/", addr),
+                method: "GET".to_string(),
+                rate: None,
+                duration: None,
+                protocol: Protocol::WebSocket,
+            },
+            client: reqwest::Client::new(),
+            body: None,
+            headers: reqwest::header::HeaderMap::new(),
+            reporter: None,
+            variation: None,
+            target: None,
+            track_connections: false,
+        }.with_target(Target::WebSocket(echo_target(addr, BenchWsMode::RoundTrip)));
+
+        let result = runner.run().await.unwrap();
+        assert_eq!(result.stats.successful_requests, 20);
+        assert_eq!(result.stats.total_bytes, 100);
+        assert!(result.stats.status_codes.is_empty());
+        assert_eq!(result.protocol, Protocol::WebSocket);
+    }
+}
@@ -0,0 +1,224 @@
+//! Arrival-rate profiles for open-loop benchmarks
+//!
+//! A profile says when each request is due, independent of how fast the
+//! server answers. It starts at a base rate and moves through stages; each
+//! stage changes the rate linearly to its target over its duration, which
+//! gives ramp-up, hold and ramp-down phases.
+
+use std::time::Duration;
+
+/// One stage of a rate profile
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stage {
+    pub duration: Duration,
+    /// Rate in requests per second at the end of the stage
+    pub target: f64,
+}
+
+/// When requests are due during an open-loop run
+#[derive(Debug, Clone, PartialEq)]
+pub struct RateProfile {
+    /// Rate in requests per second at the start
+    pub start_rate: f64,
+    /// Stages run in order; without stages the start rate is held indefinitely
+    pub stages: Vec<Stage>,
+}
+
+impl RateProfile {
+    /// Hold one rate for as long as the run lasts
+    pub fn constant(rate: f64) -> Self {
+        Self { start_rate: rate, stages: Vec::new() }
+    }
+
+    /// Whether the profile ends on its own
+    pub fn is_finite(&self) -> bool {
+        !self.stages.is_empty()
+    }
+
+    /// Number of requests the stages schedule; `None` for a constant rate
+    pub fn total_requests(&self) -> Option<u64> {
+        if !self.is_finite() {
+            return None;
+        }
+        let mut rate = self.start_rate;
+        let mut total = 0.0;
+        for stage in &self.stages {
+            total += (rate + stage.target) / 2.0 * stage.duration.as_secs_f64();
+            rate = stage.target;
+        }
+        Some(total.ceil() as u64)
+    }
+
+    /// Total length of the stages; `None` for a constant rate
+    pub fn duration(&self) -> Option<Duration> {
+        self.is_finite().then(|| self.stages.iter().map(|stage| stage.duration).sum())
+    }
+
+    /// Offset from the start at which request `n` (counting from 0) is due.
+    ///
+    /// Returns `None` once the profile has ended.
+    pub fn send_offset(&self, n: u64) -> Option<Duration> {
+        let n = n as f64;
+        let mut rate = self.start_rate;
+        let mut elapsed = 0.0;
+        let mut scheduled = 0.0;
+
+        for stage in &self.stages {
+            let length = stage.duration.as_secs_f64();
+            let count = (rate + stage.target) / 2.0 * length;
+            if n < scheduled + count {
+                // Solve rate * t + slope * t^2 / 2 = remaining for t
+                let remaining = n - scheduled;
+                let slope = (stage.target - rate) / length;
+                let discriminant = (rate * rate + 2.0 * slope * remaining).max(0.0);
+                let denominator = rate + discriminant.sqrt();
+                let t = if denominator > 0.0 { 2.0 * remaining / denominator } else { 0.0 };
+                return Some(Duration::from_secs_f64(elapsed + t.min(length)));
+            }
+            elapsed += length;
+            scheduled += count;
+            rate = stage.target;
+        }
+
+        if self.is_finite() || rate <= 0.0 {
+            return None;
+        }
+        Some(Duration::from_secs_f64(elapsed + (n - scheduled) / rate))
+    }
+
+    /// The same profile at `factor` times the rate, e.g. one worker's share
+    pub fn scaled(&self, factor: f64) -> Self {
+        Self {
+            start_rate: self.start_rate * factor,
+            stages: self.stages.iter()
+                .map(|stage| Stage { duration: stage.duration, target: stage.target * factor })
+                .collect(),
+        }
+    }
+
+    /// Short description such as `500/s` or `0/s → 500/s (10s) → 0/s (5s)`
+    pub fn describe(&self) -> String {
+        let mut text = format_rate(self.start_rate);
+        for stage in &self.stages {
+            text.push_str(&format!(" → {} ({})", format_rate(stage.target), humantime::format_duration(stage.duration)));
+        }
+        text
+    }
+}
+
+fn format_rate(rate: f64) -> String {
+    if rate.fract() == 0.0 {
+        format!("{}/s", rate)
+    } else {
+        format!("{:.2}/s", rate)
+    }
+}
+
+/// Parse a rate such as `500/s`, `3000/m`, `50/100ms` or a bare `500` (per second)
+pub fn parse_rate(text: &str) -> Result<f64, String> {
+    let text = text.trim();
+    let (count, per) = match text.split_once('/') {
+        Some((count, per)) => (count.trim(), per.trim()),
+        None => (text, "s"),
+    };
+
+    let count: f64 = count.parse()
+        .map_err(|_| format!("Invalid rate '{}': expected a number of requests like 500/s", text))?;
+    if !count.is_finite() || count < 0.0 {
+        return Err(format!("Invalid rate '{}': must not be negative", text));
+    }
+
+    // A bare unit means one of it: `/s` is `/1s`
+    let per = if per.starts_with(|c: char| c.is_ascii_digit()) { per.to_string() } else { format!("1{}", per) };
+    let period = humantime::parse_duration(&per)
+        .map_err(|e| format!("Invalid rate '{}': {}", text, e))?;
+    if period.is_zero() {
+        return Err(format!("Invalid rate '{}': period must not be zero", text));
+    }
+
+    Ok(count / period.as_secs_f64())
+}
+
+/// Parse stages such as `10s:500/s,30s:500/s,10s:0`
+pub fn parse_stages(text: &str) -> Result<Vec<Stage>, String> {
+    text.split(',')
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let (duration, target) = part.split_once(':')
+                .ok_or_else(|| format!("Invalid stage '{}': expected DURATION:RATE like 30s:500/s", part))?;
+            let duration = humantime::parse_duration(duration.trim())
+                .map_err(|e| format!("Invalid stage '{}': {}", part, e))?;
+            Ok(Stage { duration, target: parse_rate(target)? })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stage(secs: u64, target: f64) -> Stage {
+        Stage { duration: Duration::from_secs(secs), target }
+    }
+
+    #[test]
+    fn test_parse_rate() {
+        assert_eq!(parse_rate("500/s").unwrap(), 500.0);
+        assert_eq!(parse_rate("500").unwrap(), 500.0);
+        assert_eq!(parse_rate("600/m").unwrap(), 10.0);
+        assert_eq!(parse_rate("5/100ms").unwrap(), 50.0);
+        assert!(parse_rate("fast").is_err());
+        assert!(parse_rate("-1/s").is_err());
+        assert!(parse_rate("5/0s").is_err());
+    }
+
+    #[test]
+    fn test_parse_stages() {
+        let stages = parse_stages("10s:500/s, 30s:500, 5s:0").unwrap();
+        assert_eq!(stages, vec![stage(10, 500.0), stage(30, 500.0), stage(5, 0.0)]);
+        assert!(parse_stages("10s").is_err());
+        assert!(parse_stages("soon:10").is_err());
+    }
+
+    #[test]
+    fn test_constant_schedule() {
+        let profile = RateProfile::constant(100.0);
+        assert_eq!(profile.send_offset(0), Some(Duration::ZERO));
+        assert_eq!(profile.send_offset(50), Some(Duration::from_millis(500)));
+        assert_eq!(profile.total_requests(), None);
+        assert_eq!(RateProfile::constant(0.0).send_offset(0), None);
+    }
+
+    #[test]
+    fn test_scaled() {
+        let profile = RateProfile { start_rate: 100.0, stages: vec![stage(10, 300.0)] };
+        let share = profile.scaled(0.5);
+        assert_eq!(share, RateProfile { start_rate: 50.0, stages: vec![stage(10, 150.0)] });
+        assert_eq!(share.total_requests(), Some(1000));
+    }
+
+    #[test]
+    fn test_staged_schedule() {
+        // Ramp 0 → 100/s over 10s (500 requests), hold 10s (1000), ramp down over 10s (500)
+        let profile = RateProfile {
+            start_rate: 0.0,
+            stages: vec![stage(10, 100.0), stage(10, 100.0), stage(10, 0.0)],
+        };
+        assert_eq!(profile.total_requests(), Some(2000));
+        assert_eq!(profile.duration(), Some(Duration::from_secs(30)));
+
+        // Halfway through the ramp-up a quarter of its requests are due
+        let offset = profile.send_offset(125).unwrap().as_secs_f64();
+        assert!((offset - 5.0).abs() < 0.01, "offset {}", offset);
+
+        let offset = profile.send_offset(1000).unwrap().as_secs_f64();
+        assert!((offset - 15.0).abs() < 0.01, "offset {}", offset);
+
+        // Offsets never go backwards, even while ramping down
+        let offsets: Vec<Duration> = (0..2000).map(|n| profile.send_offset(n).unwrap()).collect();
+        assert!(offsets.windows(2).all(|pair| pair[0] <= pair[1]));
+        assert!(offsets[1999] <= Duration::from_secs(30));
+        assert_eq!(profile.send_offset(2000), None);
+    }
+}
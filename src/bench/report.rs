@@ -0,0 +1,367 @@
+//! Machine-readable benchmark results and baseline comparison
+//!
+//! `--bench-output json` prints a [`BenchmarkReport`], which carries the full
+//! latency histogram. A saved report can be passed back as `--bench-baseline`;
+//! the new run is then compared metric by metric and any metric that got worse
+//! by more than its threshold counts as a regression. Throughput and latencies
+//! are compared by relative change, the error rate by percentage points, as a
+//! relative change of a rate that was 0 means nothing.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use super::protocol::Protocol;
+use super::runner::BenchmarkResult;
+use super::stats::BenchmarkStats;
+use crate::errors::QuicpulseError;
+use crate::output::terminal::{self, colors};
+
+/// Version of the report format
+pub const REPORT_VERSION: u32 = 1;
+
+/// Allowed change in percent for metrics without their own threshold
+pub const DEFAULT_THRESHOLD_PCT: f64 = 10.0;
+
+/// Allowed rise of the error rate, in percentage points, unless set with `errors=`
+pub const DEFAULT_ERROR_THRESHOLD_PCT: f64 = 1.0;
+
+/// Metrics compared against a baseline; `rps` regresses when it drops,
+/// latencies and the error rate (`errors`) when they rise
+pub const METRICS: [&str; 8] = ["rps", "mean", "p50", "p75", "p90", "p95", "p99", "errors"];
+
+/// Everything about one benchmark run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchmarkReport {
+    pub version: u32,
+    pub url: String,
+    pub method: String,
+    /// Reports from before protocols were recorded are HTTP
+    #[serde(default)]
+    pub protocol: Protocol,
+    pub total_requests: u32,
+    pub concurrency: u32,
+    pub duration_s: f64,
+    /// Target rate of an open-loop run
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rate: Option<String>,
+    pub stats: BenchmarkStats,
+    /// Comparison with `--bench-baseline`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub baseline: Option<Comparison>,
+}
+
+impl BenchmarkReport {
+    pub fn new(result: &BenchmarkResult) -> Self {
+        Self {
+            version: REPORT_VERSION,
+            url: result.url.clone(),
+            method: result.method.clone(),
+            protocol: result.protocol,
+            total_requests: result.total_requests,
+            concurrency: result.concurrency,
+            duration_s: result.duration.as_secs_f64(),
+            rate: result.rate.as_ref().map(|rate| rate.describe()),
+            stats: result.stats.clone(),
+            baseline: None,
+        }
+    }
+
+    /// Read a report written by `--bench-output json`
+    pub fn load(path: &Path) -> Result<Self, QuicpulseError> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| QuicpulseError::Config(format!("Cannot read baseline {}: {}", path.display(), e)))?;
+        let report: Self = serde_json::from_str(&content)
+            .map_err(|e| QuicpulseError::Parse(format!("Invalid baseline {}: {}", path.display(), e)))?;
+        if report.version > REPORT_VERSION {
+            return Err(QuicpulseError::Parse(format!(
+                "Baseline {} has report version {}, newer than the supported {}",
+                path.display(), report.version, REPORT_VERSION
+            )));
+        }
+        Ok(report)
+    }
+}
+
+/// Allowed change per metric, in percent; for `errors`, in percentage points
+#[derive(Debug, Clone, PartialEq)]
+pub struct Thresholds {
+    default_pct: f64,
+    metrics: HashMap<String, f64>,
+}
+
+impl Default for Thresholds {
+    fn default() -> Self {
+        Self { default_pct: DEFAULT_THRESHOLD_PCT, metrics: HashMap::new() }
+    }
+}
+
+impl Thresholds {
+    /// Parse `10%` (every metric but `errors`) or `p99=5%,rps=15` (per metric); `%` is optional
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut thresholds = Self::default();
+        for part in spec.split(',').map(str::trim).filter(|part| !part.is_empty()) {
+            match part.split_once('=') {
+                Some((metric, value)) => {
+                    let metric = metric.trim().to_lowercase();
+                    if !METRICS.contains(&metric.as_str()) {
+                        return Err(format!("Unknown metric '{}' in threshold; expected one of {}", metric, METRICS.join(", ")));
+                    }
+                    thresholds.metrics.insert(metric, parse_percent(value)?);
+                }
+                None => thresholds.default_pct = parse_percent(part)?,
+            }
+        }
+        Ok(thresholds)
+    }
+
+    pub fn for_metric(&self, metric: &str) -> f64 {
+        let default = if metric == "errors" { DEFAULT_ERROR_THRESHOLD_PCT } else { self.default_pct };
+        self.metrics.get(metric).copied().unwrap_or(default)
+    }
+}
+
+fn parse_percent(text: &str) -> Result<f64, String> {
+    let number = text.trim().trim_end_matches('%').trim();
+    match number.parse::<f64>() {
+        Ok(value) if value.is_finite() && value >= 0.0 => Ok(value),
+        _ => Err(format!("Invalid threshold '{}': expected a percentage like 10%", text.trim())),
+    }
+}
+
+/// Change in one metric between the baseline and the current run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricDelta {
+    pub metric: String,
+    pub baseline: f64,
+    pub current: f64,
+    /// Relative change in percent, 0 when the baseline value is 0; for
+    /// `errors`, the change in percentage points
+    pub change_pct: f64,
+    pub threshold_pct: f64,
+    pub regressed: bool,
+}
+
+/// Current run compared with a baseline
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Comparison {
+    pub metrics: Vec<MetricDelta>,
+    pub regressed: bool,
+}
+
+impl Comparison {
+    pub fn new(baseline: &BenchmarkStats, current: &BenchmarkStats, thresholds: &Thresholds) -> Self {
+        let metrics: Vec<MetricDelta> = METRICS.iter()
+            .map(|&metric| {
+                let (old, new) = (metric_value(baseline, metric), metric_value(current, metric));
+                let change_pct = if metric == "errors" {
+                    new - old
+                } else if old > 0.0 {
+                    (new - old) / old * 100.0
+                } else {
+                    0.0
+                };
+                let threshold_pct = thresholds.for_metric(metric);
+                // Throughput regresses when it falls, latency when it rises
+                let worse_pct = if metric == "rps" { -change_pct } else { change_pct };
+                MetricDelta {
+                    metric: metric.to_string(),
+                    baseline: old,
+                    current: new,
+                    change_pct,
+                    threshold_pct,
+                    regressed: worse_pct > threshold_pct,
+                }
+            })
+            .collect();
+
+        let regressed = metrics.iter().any(|delta| delta.regressed);
+        Self { metrics, regressed }
+    }
+
+    pub fn regressions(&self) -> impl Iterator<Item = &MetricDelta> {
+        self.metrics.iter().filter(|delta| delta.regressed)
+    }
+}
+
+fn metric_value(stats: &BenchmarkStats, metric: &str) -> f64 {
+    match metric {
+        "rps" => stats.requests_per_second,
+        "mean" => stats.latency.mean_ms,
+        "p50" => stats.latency.p50_ms,
+        "p75" => stats.latency.p75_ms,
+        "p90" => stats.latency.p90_ms,
+        "p95" => stats.latency.p95_ms,
+        "p99" => stats.latency.p99_ms,
+        "errors" => error_rate(stats),
+        _ => 0.0,
+    }
+}
+
+/// Failed requests in percent of all completed requests
+fn error_rate(stats: &BenchmarkStats) -> f64 {
+    let total = stats.successful_requests + stats.failed_requests;
+    if total > 0 { stats.failed_requests as f64 / total as f64 * 100.0 } else { 0.0 }
+}
+
+/// Format a comparison for display after the results
+pub fn format_comparison(comparison: &Comparison) -> String {
+    let mut output = String::new();
+
+    let header_line = terminal::colorize("═══════════════════════════════════════════════════════════════════", colors::GREY);
+    let section_line = terminal::colorize("───────────────────────────────────────────────────────────────────", colors::GREY);
+
+    output.push_str(&section_line);
+    output.push('\n');
+    output.push_str(&format!("  {}\n", terminal::bold("BASELINE COMPARISON", colors::WHITE)));
+    output.push_str(&section_line);
+    output.push('\n');
+    output.push_str(&format!("  {}\n", terminal::muted(&format!(
+        "{:<8}  {:>12}  {:>12}  {:>9}", "Metric", "Baseline", "Current", "Change"
+    ))));
+
+    for delta in &comparison.metrics {
+        let (baseline, current, change) = match delta.metric.as_str() {
+            "rps" => (format!("{:.2}/s", delta.baseline), format!("{:.2}/s", delta.current), format!("{:>+8.1}%", delta.change_pct)),
+            "errors" => (format!("{:.2}%", delta.baseline), format!("{:.2}%", delta.current), format!("{:>+7.1}pp", delta.change_pct)),
+            _ => (format!("{:.2}ms", delta.baseline), format!("{:.2}ms", delta.current), format!("{:>+8.1}%", delta.change_pct)),
+        };
+        let better = if delta.metric == "rps" { delta.change_pct > 0.0 } else { delta.change_pct < 0.0 };
+        let change_color = if delta.regressed {
+            colors::RED
+        } else if better && delta.change_pct != 0.0 {
+            colors::GREEN
+        } else {
+            colors::GREY
+        };
+
+        output.push_str(&format!(
+            "  {}  {}  {}  {}",
+            terminal::label(&format!("{:<8}", delta.metric)),
+            terminal::number(&format!("{:>12}", baseline)),
+            terminal::number(&format!("{:>12}", current)),
+            terminal::colorize(&change, change_color),
+        ));
+        if delta.regressed {
+            let unit = if delta.metric == "errors" { "pp" } else { "%" };
+            output.push_str(&format!("  {}", terminal::error(&format!("regression (limit {}{})", delta.threshold_pct, unit))));
+        }
+        output.push('\n');
+    }
+
+    output.push('\n');
+    output.push_str(&header_line);
+    output.push('\n');
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bench::stats::LatencyStats;
+
+    fn stats(rps: f64, p50: f64, p99: f64) -> BenchmarkStats {
+        BenchmarkStats {
+            requests_per_second: rps,
+            latency: LatencyStats {
+                mean_ms: p50,
+                p50_ms: p50,
+                p75_ms: p50,
+                p90_ms: p99,
+                p95_ms: p99,
+                p99_ms: p99,
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_thresholds() {
+        let thresholds = Thresholds::parse("5%, p99=20, rps=2.5%").unwrap();
+        assert_eq!(thresholds.for_metric("p50"), 5.0);
+        assert_eq!(thresholds.for_metric("p99"), 20.0);
+        assert_eq!(thresholds.for_metric("rps"), 2.5);
+
+        assert_eq!(Thresholds::parse("").unwrap(), Thresholds::default());
+        assert!(Thresholds::parse("p42=5").is_err());
+        assert!(Thresholds::parse("lots").is_err());
+    }
+
+    #[test]
+    fn test_comparison() {
+        let baseline = stats(1000.0, 10.0, 50.0);
+
+        let same = Comparison::new(&baseline, &stats(980.0, 10.5, 52.0), &Thresholds::default());
+        assert!(!same.regressed);
+
+        let slower = Comparison::new(&baseline, &stats(1000.0, 10.0, 60.0), &Thresholds::default());
+        assert!(slower.regressed);
+        let regressions: Vec<&str> = slower.regressions().map(|d| d.metric.as_str()).collect();
+        assert_eq!(regressions, vec!["p90", "p95", "p99"]);
+        assert_eq!(slower.metrics.iter().find(|d| d.metric == "p99").unwrap().change_pct, 20.0);
+
+        // Throughput only regresses when it drops
+        let faster = Comparison::new(&baseline, &stats(2000.0, 5.0, 25.0), &Thresholds::default());
+        assert!(!faster.regressed);
+        let fewer = Comparison::new(&baseline, &stats(800.0, 10.0, 50.0), &Thresholds::default());
+        assert_eq!(fewer.regressions().map(|d| d.metric.as_str()).collect::<Vec<_>>(), vec!["rps"]);
+
+        // Per-metric thresholds
+        let lenient = Thresholds::parse("p90=50,p95=50,p99=50").unwrap();
+        assert!(!Comparison::new(&baseline, &stats(1000.0, 10.0, 60.0), &lenient).regressed);
+    }
+
+    #[test]
+    fn test_error_rate_comparison() {
+        let with_errors = |failed: u64| BenchmarkStats {
+            successful_requests: 1000 - failed,
+            failed_requests: failed,
+            ..stats(1000.0, 10.0, 50.0)
+        };
+        let baseline = with_errors(0);
+
+        // Percentage points, so a rise from no errors at all counts
+        let failing = Comparison::new(&baseline, &with_errors(20), &Thresholds::default());
+        let errors = failing.metrics.iter().find(|d| d.metric == "errors").unwrap();
+        assert_eq!((errors.current, errors.change_pct), (2.0, 2.0));
+        assert_eq!(failing.regressions().map(|d| d.metric.as_str()).collect::<Vec<_>>(), vec!["errors"]);
+        assert!(!Comparison::new(&baseline, &with_errors(5), &Thresholds::default()).regressed);
+
+        // A blanket threshold leaves the error rate's own
+        assert_eq!(Thresholds::parse("50%").unwrap().for_metric("errors"), DEFAULT_ERROR_THRESHOLD_PCT);
+        assert!(!Comparison::new(&baseline, &with_errors(20), &Thresholds::parse("errors=5").unwrap()).regressed);
+    }
+
+    #[test]
+    fn test_report_round_trip() {
+        let mut collector = crate::bench::stats::StatsCollector::new();
+        collector.record(Some(200), std::time::Duration::from_millis(12), 100, None);
+        let result = BenchmarkResult {
+            url: "https://example.com".to_string(),
+            method: "GET".to_string(),
+            total_requests: 1,
+            concurrency: 1,
+            duration: std::time::Duration::from_secs(1),
+            stats: collector.finalize(std::time::Duration::from_secs(1)),
+            rate: None,
+            protocol: Protocol::Http,
+        };
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("baseline.json");
+        std::fs::write(&path, serde_json::to_string_pretty(&BenchmarkReport::new(&result)).unwrap()).unwrap();
+
+        let report = BenchmarkReport::load(&path).unwrap();
+        assert_eq!(report.version, REPORT_VERSION);
+        assert_eq!(report.stats.histogram.len(), 1);
+        assert!(report.rate.is_none());
+        assert_eq!(report.protocol, Protocol::Http);
+        assert!(report.baseline.is_none());
+
+        std::fs::write(&path, "{}").unwrap();
+        assert!(BenchmarkReport::load(&path).is_err());
+    }
+}
@@ -1,8 +1,18 @@
 //! Benchmark runner implementation
 //!
-//! Handles concurrent HTTP request execution and result collection.
+//! Handles concurrent request execution and result collection. Requests go
+//! through a reqwest client (HTTP/1.1, HTTP/2 or HTTP/3) unless a gRPC or
+//! WebSocket [`Target`] is attached. The HTTP/1.1 and HTTP/2 client is
+//! instrumented to report how each request got its connection.
+//!
+//! Without a rate the runner is closed-loop: a fixed number of workers send
+//! requests back to back. With a [`RateProfile`] it is open-loop: requests
+//! go out on schedule whether or not earlier ones have finished, and latency
+//! is measured from the scheduled send time so a stalled server cannot hide
+//! its queueing delay.
 
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::{Duration, Instant};
 use tokio::sync::{mpsc, Semaphore};
 use reqwest::{Client, Method};
@@ -10,9 +20,18 @@ use url::Url;
 
 use crate::cli::Args;
 use crate::cli::parser::ProcessedArgs;
+use crate::client::ssl::SslConfig;
+use crate::client::timing::{self, ConnectionInfo};
 use crate::errors::QuicpulseError;
+use super::data::RequestVariation;
+use super::live::{LiveReporter, Sample, SAMPLE_INTERVAL};
+use super::protocol::{Protocol, Reply, Target};
+use super::rate::{self, RateProfile};
 use super::stats::{BenchmarkStats, StatsCollector};
 
+/// How far behind schedule an open-loop request may go out before it counts as late
+pub const LATE_THRESHOLD: Duration = Duration::from_millis(10);
+
 /// Configuration for a benchmark run
 #[derive(Debug, Clone)]
 pub struct BenchmarkConfig {
@@ -20,26 +39,82 @@ pub struct BenchmarkConfig {
     pub concurrency: u32,
     pub url: String,
     pub method: String,
+    /// Arrival rate for open-loop runs; `concurrency` then caps requests in flight
+    pub rate: Option<RateProfile>,
+    /// Run for this long instead of sending `total_requests`
+    pub duration: Option<Duration>,
+    /// gRPC and WebSocket runs also need a [`Target`]
+    pub protocol: Protocol,
 }
 
 impl BenchmarkConfig {
-    pub fn from_args(args: &Args, processed: &ProcessedArgs) -> Self {
-        Self {
+    pub fn from_args(args: &Args, processed: &ProcessedArgs) -> Result<Self, QuicpulseError> {
+        let start_rate = args.bench_rate.as_deref()
+            .map(rate::parse_rate)
+            .transpose()
+            .map_err(QuicpulseError::Argument)?;
+        let stages = args.bench_stages.as_deref()
+            .map(rate::parse_stages)
+            .transpose()
+            .map_err(QuicpulseError::Argument)?;
+
+        let rate = match (start_rate, stages) {
+            (None, None) => None,
+            (start_rate, stages) => Some(RateProfile {
+                start_rate: start_rate.unwrap_or(0.0),
+                stages: stages.unwrap_or_default(),
+            }),
+        };
+
+        let duration = args.bench_duration.as_deref()
+            .map(|text| humantime::parse_duration(text)
+                .map_err(|e| QuicpulseError::Argument(format!("Invalid --bench-duration '{}': {}", text, e))))
+            .transpose()?;
+        if duration.is_some_and(|d| d.is_zero()) {
+            return Err(QuicpulseError::Argument("--bench-duration must be greater than zero".to_string()));
+        }
+        if duration.is_some() && args.bench_stages.is_some() {
+            return Err(QuicpulseError::Argument(
+                "--bench-duration cannot be combined with --bench-stages, which set their own length".to_string()
+            ));
+        }
+
+        let protocol = Protocol::from_args(args);
+        if protocol == Protocol::Http3 && !processed.url.starts_with("https://") {
+            return Err(QuicpulseError::Argument("HTTP/3 benchmarks need an https:// URL".to_string()));
+        }
+
+        Ok(Self {
             total_requests: args.bench_requests,
             concurrency: args.bench_concurrency,
             url: processed.url.clone(),
             method: processed.method.clone(),
-        }
+            rate,
+            duration,
+            protocol,
+        })
     }
 }
 
 /// Result of a single request
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct RequestResult {
-    status_code: Option<u16>,
+    /// `None` when the request failed before the server answered
+    reply: Option<Reply>,
     latency: Duration,
     bytes: usize,
     error: Option<String>,
+    late: bool,
+    /// Connection use of an HTTP request that got a response, when tracked
+    connection: Option<ConnectionInfo>,
+}
+
+/// What became of one request
+#[derive(Debug, Clone)]
+enum Outcome {
+    Completed(RequestResult),
+    /// Open-loop request skipped because the in-flight limit was reached
+    Dropped,
 }
 
 /// Final benchmark results
@@ -51,14 +126,23 @@ pub struct BenchmarkResult {
     pub concurrency: u32,
     pub duration: Duration,
     pub stats: BenchmarkStats,
+    pub rate: Option<RateProfile>,
+    pub protocol: Protocol,
 }
 
-/// Runs HTTP benchmarks with concurrent requests
+/// Runs benchmarks with concurrent requests
 pub struct BenchmarkRunner {
     pub config: BenchmarkConfig,
     pub client: Client,
     pub body: Option<Vec<u8>>,
     pub headers: reqwest::header::HeaderMap,
+    pub reporter: Option<LiveReporter>,
+    /// Builds each request afresh instead of sending `body` and `headers`
+    pub variation: Option<RequestVariation>,
+    /// Sends gRPC calls or WebSocket messages instead of HTTP requests
+    pub target: Option<Target>,
+    /// `client` was built with [`timing::instrument`], so connection use is recorded
+    pub track_connections: bool,
 }
 
 impl BenchmarkRunner {
@@ -67,13 +151,18 @@ impl BenchmarkRunner {
         config: BenchmarkConfig,
         args: &Args,
     ) -> Result<Self, QuicpulseError> {
-        let client = build_client(args)?;
+        let client = build_client(args, config.protocol)?;
+        let track_connections = config.protocol == Protocol::Http;
 
         Ok(Self {
             config,
             client,
             body: None,
             headers: reqwest::header::HeaderMap::new(),
+            reporter: None,
+            variation: None,
+            target: None,
+            track_connections,
         })
     }
 
@@ -89,66 +178,64 @@ impl BenchmarkRunner {
         self
     }
 
-    /// Run the benchmark
-    pub async fn run(self) -> Result<BenchmarkResult, QuicpulseError> {
-        let config = self.config.clone();
-        let start = Instant::now();
-
-        // Parse URL
-        let url: Url = config.url.parse()
-            .map_err(|e| QuicpulseError::Argument(format!("Invalid URL: {}", e)))?;
-
-        // Parse method
-        let method: Method = config.method.parse()
-            .map_err(|e| QuicpulseError::Argument(format!("Invalid method: {}", e)))?;
-
-        // Create channel for collecting results
-        let (tx, mut rx) = mpsc::channel::<RequestResult>(config.concurrency as usize * 2);
-
-        // Semaphore to limit concurrency
-        let semaphore = Arc::new(Semaphore::new(config.concurrency as usize));
-
-        // Spawn request tasks
-        let client = Arc::new(self.client);
-        let body = Arc::new(self.body);
-        let headers = Arc::new(self.headers);
-
-        let mut handles = Vec::new();
-
-        for _ in 0..config.total_requests {
-            let semaphore = semaphore.clone();
-            let tx = tx.clone();
-            let client = client.clone();
-            let url = url.clone();
-            let method = method.clone();
-            let body = body.clone();
-            let headers = headers.clone();
-
-            let handle = tokio::spawn(async move {
-                // Acquire permit inside the task to avoid blocking the spawn loop
-                let _permit = semaphore.acquire().await.ok();
-                let result = Self::execute_request(&client, url, method, &body, &headers).await;
-                let _ = tx.send(result).await;
-                // _permit drops here, releasing the semaphore
-            });
-
-            handles.push(handle);
-        }
+    /// Report per-interval samples while the benchmark runs
+    pub fn with_reporter(mut self, reporter: LiveReporter) -> Self {
+        self.reporter = Some(reporter);
+        self
+    }
 
-        // Drop the original sender so the receiver knows when all senders are done
-        drop(tx);
+    /// Render every request from templates and data rows
+    pub fn with_variation(mut self, variation: RequestVariation) -> Self {
+        self.variation = Some(variation);
+        self
+    }
 
-        // Collect results
-        let mut collector = StatsCollector::new();
+    /// Send gRPC calls or WebSocket messages to `target`
+    pub fn with_target(mut self, target: Target) -> Self {
+        self.target = Some(target);
+        self
+    }
 
-        while let Some(result) = rx.recv().await {
-            collector.record(result.status_code, result.latency, result.bytes, result.error);
-        }
+    /// Run the benchmark
+    pub async fn run(self) -> Result<BenchmarkResult, QuicpulseError> {
+        let config = self.config;
+
+        let request = match self.target {
+            Some(target) => Arc::new(Sender::Target(target)),
+            None => {
+                // Parse URL
+                let url: Url = config.url.parse()
+                    .map_err(|e| QuicpulseError::Argument(format!("Invalid URL: {}", e)))?;
+
+                // Parse method
+                let method: Method = config.method.parse()
+                    .map_err(|e| QuicpulseError::Argument(format!("Invalid method: {}", e)))?;
+
+                Arc::new(Sender::Http(RequestTemplate {
+                    client: self.client,
+                    url,
+                    method,
+                    body: self.body,
+                    headers: self.headers,
+                    variation: self.variation,
+                    http3: config.protocol == Protocol::Http3,
+                    track_connections: self.track_connections,
+                }))
+            }
+        };
 
-        // Wait for all tasks to complete
-        for handle in handles {
-            let _ = handle.await;
-        }
+        let start = Instant::now();
+        let (tx, rx) = mpsc::unbounded_channel::<Outcome>();
+        let (sent, collector) = match config.rate {
+            Some(ref profile) => tokio::join!(
+                run_open_loop(&config, profile, request, tx),
+                collect(rx, self.reporter)
+            ),
+            None => tokio::join!(
+                run_closed_loop(&config, request, tx),
+                collect(rx, self.reporter)
+            ),
+        };
 
         let duration = start.elapsed();
         let stats = collector.finalize(duration);
@@ -156,24 +243,79 @@ impl BenchmarkRunner {
         Ok(BenchmarkResult {
             url: config.url,
             method: config.method,
-            total_requests: config.total_requests,
+            total_requests: sent as u32,
             concurrency: config.concurrency,
             duration,
             stats,
+            rate: config.rate,
+            protocol: config.protocol,
         })
     }
+}
 
-    /// Execute a single request
-    async fn execute_request(
-        client: &Client,
-        url: Url,
-        method: Method,
-        body: &Option<Vec<u8>>,
-        headers: &reqwest::header::HeaderMap,
-    ) -> RequestResult {
-        let start = Instant::now();
+/// Sends one request of whichever protocol is benchmarked
+enum Sender {
+    Http(RequestTemplate),
+    Target(Target),
+}
+
+impl Sender {
+    /// Send one request, measuring latency from `start`
+    async fn send(&self, start: Instant) -> RequestResult {
+        let exchange = match self {
+            Sender::Http(template) => template.send().await,
+            Sender::Target(target) => target.send().await
+                .map(|(reply, bytes)| Exchange { reply, bytes, connection: None }),
+        };
+        let latency = start.elapsed();
+
+        match exchange {
+            Ok(exchange) => RequestResult {
+                reply: Some(exchange.reply),
+                latency,
+                bytes: exchange.bytes,
+                error: None,
+                late: false,
+                connection: exchange.connection,
+            },
+            Err(error) => RequestResult {
+                reply: None,
+                latency,
+                bytes: 0,
+                error: Some(error),
+                late: false,
+                connection: None,
+            },
+        }
+    }
+}
 
-        let mut request = client.request(method, url);
+/// A request that got a response
+struct Exchange {
+    reply: Reply,
+    /// Size of the response body
+    bytes: usize,
+    connection: Option<ConnectionInfo>,
+}
+
+/// Everything needed to send the benchmarked request
+struct RequestTemplate {
+    client: Client,
+    url: Url,
+    method: Method,
+    body: Option<Vec<u8>>,
+    headers: reqwest::header::HeaderMap,
+    variation: Option<RequestVariation>,
+    http3: bool,
+    track_connections: bool,
+}
+
+impl RequestTemplate {
+    fn build(&self, url: Url, headers: &reqwest::header::HeaderMap, body: Option<Vec<u8>>) -> reqwest::RequestBuilder {
+        let mut request = self.client.request(self.method.clone(), url);
+        if self.http3 {
+            request = request.version(reqwest::Version::HTTP_3);
+        }
 
         // Add headers
         for (key, value) in headers.iter() {
@@ -181,28 +323,45 @@ impl BenchmarkRunner {
         }
 
         // Add body if present
-        if let Some(ref body_bytes) = body {
-            request = request.body(body_bytes.clone());
+        if let Some(body_bytes) = body {
+            request = request.body(body_bytes);
         }
 
-        match request.send().await {
+        request
+    }
+
+    /// Send one request and read the response body
+    async fn send(&self) -> Result<Exchange, String> {
+        let request = match self.variation {
+            Some(ref variation) => {
+                let rendered = variation.render()?;
+                self.build(rendered.url, &rendered.headers, rendered.body)
+            }
+            None => self.build(self.url.clone(), &self.headers, self.body.clone()),
+        };
+
+        let sent = Instant::now();
+        let (response, opened) = if self.track_connections {
+            timing::track(request.send()).await
+        } else {
+            (request.send().await, None)
+        };
+        let ttfb = sent.elapsed();
+
+        match response {
             Ok(response) => {
                 let status_code = response.status().as_u16();
                 let bytes = match response.bytes().await {
                     Ok(b) => b.len(),
                     Err(_) => 0,
                 };
-                let latency = start.elapsed();
-
-                RequestResult {
-                    status_code: Some(status_code),
-                    latency,
+                Ok(Exchange {
+                    reply: Reply::Http(status_code),
                     bytes,
-                    error: None,
-                }
+                    connection: self.track_connections.then_some(ConnectionInfo { opened, ttfb }),
+                })
             }
             Err(e) => {
-                let latency = start.elapsed();
                 let error_msg = if e.is_timeout() {
                     "Timeout".to_string()
                 } else if e.is_connect() {
@@ -210,23 +369,192 @@ impl BenchmarkRunner {
                 } else {
                     format!("{}", e)
                 };
+                Err(error_msg)
+            }
+        }
+    }
+}
 
-                RequestResult {
-                    status_code: None,
-                    latency,
-                    bytes: 0,
-                    error: Some(error_msg),
+/// `concurrency` workers send requests back to back until the request
+/// budget or the duration runs out. Returns the number of requests sent.
+async fn run_closed_loop(
+    config: &BenchmarkConfig,
+    request: Arc<Sender>,
+    tx: mpsc::UnboundedSender<Outcome>,
+) -> u64 {
+    let deadline = config.duration.map(|duration| Instant::now() + duration);
+    let remaining = Arc::new(AtomicU64::new(config.total_requests as u64));
+
+    let workers: Vec<_> = (0..config.concurrency.max(1))
+        .map(|_| {
+            let request = request.clone();
+            let remaining = remaining.clone();
+            let tx = tx.clone();
+
+            tokio::spawn(async move {
+                let mut sent = 0;
+                loop {
+                    let more = match deadline {
+                        Some(deadline) => Instant::now() < deadline,
+                        None => remaining.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| n.checked_sub(1)).is_ok(),
+                    };
+                    if !more {
+                        break;
+                    }
+
+                    let result = request.send(Instant::now()).await;
+                    sent += 1;
+                    if tx.send(Outcome::Completed(result)).is_err() {
+                        break;
+                    }
+                }
+                sent
+            })
+        })
+        .collect();
+
+    // Workers hold their own senders; the collector stops when the last one exits
+    drop(tx);
+
+    let mut sent = 0;
+    for worker in workers {
+        sent += worker.await.unwrap_or(0);
+    }
+    sent
+}
+
+/// Send requests on the profile's schedule, dropping any that would exceed
+/// the in-flight limit. Returns the number of requests scheduled.
+async fn run_open_loop(
+    config: &BenchmarkConfig,
+    profile: &RateProfile,
+    request: Arc<Sender>,
+    tx: mpsc::UnboundedSender<Outcome>,
+) -> u64 {
+    // Stages end on their own; a constant rate runs for the duration or the request count
+    let limit = match (profile.total_requests(), config.duration) {
+        (Some(total), _) => total,
+        (None, Some(_)) => u64::MAX,
+        (None, None) => config.total_requests as u64,
+    };
+
+    let in_flight = Arc::new(Semaphore::new(config.concurrency.max(1) as usize));
+    let mut scheduled: u64 = 0;
+    let start = tokio::time::Instant::now();
+
+    while scheduled < limit {
+        let Some(offset) = profile.send_offset(scheduled) else {
+            break;
+        };
+        if config.duration.is_some_and(|duration| offset >= duration) {
+            break;
+        }
+        scheduled += 1;
+
+        let due = start + offset;
+        tokio::time::sleep_until(due).await;
+
+        let Ok(permit) = in_flight.clone().try_acquire_owned() else {
+            let _ = tx.send(Outcome::Dropped);
+            continue;
+        };
+
+        let late = due.elapsed() > LATE_THRESHOLD;
+        let request = request.clone();
+        let tx = tx.clone();
+
+        tokio::spawn(async move {
+            let mut result = request.send(due.into_std()).await;
+            result.late = late;
+            let _ = tx.send(Outcome::Completed(result));
+            drop(permit);
+        });
+    }
+
+    scheduled
+}
+
+/// Record outcomes until every sender is gone, sampling each interval
+/// when there is a reporter
+async fn collect(mut rx: mpsc::UnboundedReceiver<Outcome>, mut reporter: Option<LiveReporter>) -> StatsCollector {
+    let mut total = StatsCollector::new();
+    let mut interval = StatsCollector::new();
+    let mut index = 0;
+
+    let start = tokio::time::Instant::now();
+    let mut interval_start = start;
+    let mut ticker = tokio::time::interval_at(start + SAMPLE_INTERVAL, SAMPLE_INTERVAL);
+
+    loop {
+        tokio::select! {
+            outcome = rx.recv() => {
+                let Some(outcome) = outcome else {
+                    break;
+                };
+                if reporter.is_some() {
+                    record(&mut interval, outcome.clone());
+                }
+                record(&mut total, outcome);
+            }
+            now = ticker.tick(), if reporter.is_some() => {
+                index += 1;
+                let sample = Sample {
+                    index,
+                    elapsed: now - start,
+                    stats: std::mem::take(&mut interval).finalize(now - interval_start),
+                };
+                interval_start = now;
+                if let Some(ref mut reporter) = reporter {
+                    reporter.report(&sample);
                 }
             }
         }
     }
+
+    if let Some(ref mut reporter) = reporter {
+        // The last, partial interval
+        let now = tokio::time::Instant::now();
+        if now > interval_start {
+            let sample = Sample {
+                index: index + 1,
+                elapsed: now - start,
+                stats: interval.finalize(now - interval_start),
+            };
+            reporter.report(&sample);
+        }
+        reporter.finish();
+    }
+
+    total
+}
+
+fn record(collector: &mut StatsCollector, outcome: Outcome) {
+    match outcome {
+        Outcome::Completed(result) => {
+            if result.late {
+                collector.record_late();
+            }
+            if let Some(ref connection) = result.connection {
+                collector.record_connection(connection);
+            }
+            match result.reply {
+                Some(Reply::Http(code)) => collector.record(Some(code), result.latency, result.bytes, None),
+                Some(Reply::Grpc(code)) => collector.record_grpc(code, result.latency, result.bytes),
+                Some(Reply::Message) => collector.record_message(result.latency, result.bytes),
+                None => collector.record(None, result.latency, 0, result.error),
+            }
+        }
+        Outcome::Dropped => collector.record_dropped(),
+    }
 }
 
 /// Build the HTTP client for benchmarking
-fn build_client(args: &Args) -> Result<Client, QuicpulseError> {
+fn build_client(args: &Args, protocol: Protocol) -> Result<Client, QuicpulseError> {
+    // An empty pool makes every request open its own connection
+    let max_idle = if args.bench_no_keepalive { 0 } else { 100 };
     let mut builder = Client::builder()
         .user_agent(concat!("QuicPulse-Bench/", env!("CARGO_PKG_VERSION")))
-        .pool_max_idle_per_host(100)
+        .pool_max_idle_per_host(max_idle)
         .pool_idle_timeout(Duration::from_secs(30));
 
     // Set timeout if specified
@@ -241,9 +569,18 @@ fn build_client(args: &Args) -> Result<Client, QuicpulseError> {
         builder = builder.redirect(reqwest::redirect::Policy::none());
     }
 
-    // SSL verification
-    if args.verify == "no" {
-        builder = builder.danger_accept_invalid_certs(true);
+    // QUIC from the first request; there is no Alt-Svc upgrade to wait for.
+    // Other HTTP gets the instrumented connector, which brings its own TLS setup.
+    let accept_invalid_certs = args.verify == "no";
+    if protocol == Protocol::Http3 {
+        builder = builder.http3_prior_knowledge();
+        if accept_invalid_certs {
+            builder = builder.danger_accept_invalid_certs(true);
+        }
+    } else {
+        let mut ssl = SslConfig::new();
+        ssl.verify = !accept_invalid_certs;
+        builder = timing::instrument(builder, &ssl, timing::ALPN_DEFAULT)?;
     }
 
     builder.build()
@@ -261,9 +598,157 @@ mod tests {
             concurrency: 10,
             url: "https://example.com".to_string(),
             method: "GET".to_string(),
+            rate: None,
+            duration: None,
+            protocol: Protocol::Http,
         };
 
         assert_eq!(config.total_requests, 100);
         assert_eq!(config.concurrency, 10);
     }
+
+    #[tokio::test]
+    async fn test_open_loop_drops_when_saturated() {
+        // A server that never answers holds the only slot until the client
+        // times out, so every later request at 1000/s is dropped
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let mut held = Vec::new();
+            while let Ok((socket, _)) = listener.accept().await {
+                held.push(socket);
+            }
+        });
+
+        let config = BenchmarkConfig {
+            total_requests: 20,
+            concurrency: 1,
+            url: format!("http://{}/", addr),
+            method: "GET".to_string(),
+            rate: Some(RateProfile::constant(1000.0)),
+            duration: None,
+            protocol: Protocol::Http,
+        };
+        let runner = BenchmarkRunner {
+            config,
+            client: Client::builder().timeout(Duration::from_millis(200)).build().unwrap(),
+            body: None,
+            headers: reqwest::header::HeaderMap::new(),
+            reporter: None,
+            variation: None,
+            target: None,
+            track_connections: false,
+        };
+
+        let result = runner.run().await.unwrap();
+        assert_eq!(result.total_requests, 20);
+        assert_eq!(result.stats.failed_requests, 1);
+        assert_eq!(result.stats.dropped_requests, 19);
+        // Latency counts from the scheduled time, so it covers the whole timeout
+        assert!(result.stats.latency.max_ms >= 200.0);
+    }
+
+    /// Minimal keep-alive HTTP server answering every request with 200
+    async fn serve_ok() -> std::net::SocketAddr {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let mut buf = Vec::new();
+                    let mut chunk = [0u8; 1024];
+                    while let Ok(n) = socket.read(&mut chunk).await {
+                        if n == 0 {
+                            break;
+                        }
+                        buf.extend_from_slice(&chunk[..n]);
+                        while let Some(end) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+                            buf.drain(..end + 4);
+                            let response = b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nok";
+                            if socket.write_all(response).await.is_err() {
+                                return;
+                            }
+                        }
+                    }
+                });
+            }
+        });
+        addr
+    }
+
+    #[tokio::test]
+    async fn test_duration_run_writes_timeseries() {
+        let addr = serve_ok().await;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("series.ndjson");
+
+        let config = BenchmarkConfig {
+            total_requests: 1,
+            concurrency: 2,
+            url: format!("http://{}/", addr),
+            method: "GET".to_string(),
+            rate: None,
+            duration: Some(Duration::from_millis(1500)),
+            protocol: Protocol::Http,
+        };
+        let reporter = LiveReporter::new()
+            .with_timeseries(crate::bench::live::TimeSeriesWriter::create(&path).unwrap());
+        let runner = BenchmarkRunner {
+            config,
+            client: Client::new(),
+            body: None,
+            headers: reqwest::header::HeaderMap::new(),
+            reporter: None,
+            variation: None,
+            target: None,
+            track_connections: false,
+        }.with_reporter(reporter);
+
+        let result = runner.run().await.unwrap();
+        assert!(result.duration >= Duration::from_millis(1500));
+        // The duration, not the request count, ends the run
+        assert!(result.total_requests > 1);
+        assert_eq!(result.stats.successful_requests, result.total_requests as u64);
+
+        // One full interval plus the partial one at the end
+        let rows: Vec<serde_json::Value> = std::fs::read_to_string(&path).unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(rows.len(), 2);
+        let sampled: u64 = rows.iter().map(|row| row["requests"].as_u64().unwrap()).sum();
+        assert_eq!(sampled, result.total_requests as u64);
+    }
+
+    #[tokio::test]
+    async fn test_connection_reuse_and_no_keepalive() {
+        let addr = serve_ok().await;
+        let config = BenchmarkConfig {
+            total_requests: 20,
+            concurrency: 2,
+            url: format!("http://{}/", addr),
+            method: "GET".to_string(),
+            rate: None,
+            duration: None,
+            protocol: Protocol::Http,
+        };
+
+        let mut args = Args::default();
+        let result = BenchmarkRunner::new(config.clone(), &args).unwrap().run().await.unwrap();
+        let connections = result.stats.connections.unwrap();
+        assert_eq!(connections.opened + connections.reused, 20);
+        assert!(connections.opened <= 2, "{} connections opened", connections.opened);
+        assert_eq!(connections.ttfb.count, 20);
+        assert_eq!(connections.dns.count, 0);
+        assert_eq!(connections.tls.count, 0);
+
+        args.bench_no_keepalive = true;
+        let result = BenchmarkRunner::new(config, &args).unwrap().run().await.unwrap();
+        let connections = result.stats.connections.unwrap();
+        assert_eq!(connections.opened, 20);
+        assert_eq!(connections.reused, 0);
+        assert_eq!(connections.connect.count, 20);
+    }
 }
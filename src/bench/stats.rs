@@ -1,13 +1,18 @@
 //! Statistics collection and computation for benchmarks
 //!
 //! Uses HDR Histogram for accurate latency percentile calculation.
+//! The histogram travels with the statistics; serialized it is the standard
+//! compressed HdrHistogram V2 format in base64, so runs can be merged later.
 
 use std::collections::HashMap;
 use std::time::Duration;
 use hdrhistogram::Histogram;
+use serde::{Deserialize, Serialize};
+
+use crate::client::timing::ConnectionInfo;
 
 /// Latency statistics in milliseconds
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LatencyStats {
     pub min_ms: f64,
     pub max_ms: f64,
@@ -36,8 +41,28 @@ impl Default for LatencyStats {
     }
 }
 
+impl LatencyStats {
+    /// Summarise a histogram of latencies in microseconds
+    pub fn from_histogram(histogram: &Histogram<u64>) -> Self {
+        if histogram.is_empty() {
+            return Self::default();
+        }
+        Self {
+            min_ms: histogram.min() as f64 / 1000.0,
+            max_ms: histogram.max() as f64 / 1000.0,
+            mean_ms: histogram.mean() / 1000.0,
+            stddev_ms: histogram.stdev() / 1000.0,
+            p50_ms: histogram.value_at_percentile(50.0) as f64 / 1000.0,
+            p75_ms: histogram.value_at_percentile(75.0) as f64 / 1000.0,
+            p90_ms: histogram.value_at_percentile(90.0) as f64 / 1000.0,
+            p95_ms: histogram.value_at_percentile(95.0) as f64 / 1000.0,
+            p99_ms: histogram.value_at_percentile(99.0) as f64 / 1000.0,
+        }
+    }
+}
+
 /// Complete benchmark statistics
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BenchmarkStats {
     pub successful_requests: u64,
     pub failed_requests: u64,
@@ -45,9 +70,23 @@ pub struct BenchmarkStats {
     pub requests_per_second: f64,
     pub bytes_per_second: f64,
     pub total_bytes: u64,
+    #[serde(deserialize_with = "status_code_keys::deserialize")]
     pub status_codes: HashMap<u16, u64>,
     pub errors: HashMap<String, u64>,
     pub latency: LatencyStats,
+    /// Open-loop requests skipped because the in-flight limit was reached
+    pub dropped_requests: u64,
+    /// Open-loop requests sent noticeably after their scheduled time
+    pub late_requests: u64,
+    /// Calls per gRPC status, such as `Ok` or `Unavailable`
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub grpc_status: HashMap<String, u64>,
+    /// Connection reuse and setup phases, for HTTP benchmarks
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub connections: Option<ConnectionStats>,
+    /// Every recorded latency, in microseconds
+    #[serde(with = "histogram_base64")]
+    pub histogram: Histogram<u64>,
 }
 
 impl Default for BenchmarkStats {
@@ -62,10 +101,93 @@ impl Default for BenchmarkStats {
             status_codes: HashMap::new(),
             errors: HashMap::new(),
             latency: LatencyStats::default(),
+            dropped_requests: 0,
+            late_requests: 0,
+            grpc_status: HashMap::new(),
+            connections: None,
+            histogram: new_histogram(),
         }
     }
 }
 
+/// Durations of one connection phase
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PhaseStats {
+    pub count: u64,
+    #[serde(flatten)]
+    pub latency: LatencyStats,
+    /// Every recorded duration, in microseconds
+    #[serde(with = "histogram_base64")]
+    pub histogram: Histogram<u64>,
+}
+
+impl PhaseStats {
+    fn from_histogram(histogram: Histogram<u64>) -> Self {
+        Self { count: histogram.len(), latency: LatencyStats::from_histogram(&histogram), histogram }
+    }
+}
+
+/// How requests used their connections, counted over requests that got a response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConnectionStats {
+    /// Requests that opened a new connection
+    pub opened: u64,
+    /// Requests that used a pooled connection
+    pub reused: u64,
+    /// DNS lookups of new connections; empty for IP addresses
+    pub dns: PhaseStats,
+    /// TCP connects of new connections
+    pub connect: PhaseStats,
+    /// TLS handshakes of new connections; empty for plain HTTP
+    pub tls: PhaseStats,
+    /// Time to first byte of every request
+    pub ttfb: PhaseStats,
+}
+
+/// Empty latency histogram covering 1µs to five minutes at 3 significant digits
+pub fn new_histogram() -> Histogram<u64> {
+    Histogram::new_with_bounds(1, MAX_LATENCY_US, 3)
+        .expect("Failed to create histogram")
+}
+
+/// Serde for histograms as base64 of the compressed V2 encoding
+mod histogram_base64 {
+    use base64::Engine;
+    use base64::engine::general_purpose::STANDARD;
+    use hdrhistogram::Histogram;
+    use hdrhistogram::serialization::{Deserializer as HistogramDeserializer, Serializer as _, V2DeflateSerializer};
+    use serde::{de, ser, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(histogram: &Histogram<u64>, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut bytes = Vec::new();
+        V2DeflateSerializer::new()
+            .serialize(histogram, &mut bytes)
+            .map_err(|e| ser::Error::custom(format!("{:?}", e)))?;
+        serializer.serialize_str(&STANDARD.encode(bytes))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Histogram<u64>, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        let bytes = STANDARD.decode(text.trim()).map_err(de::Error::custom)?;
+        HistogramDeserializer::new()
+            .deserialize(&mut bytes.as_slice())
+            .map_err(|e| de::Error::custom(format!("invalid histogram: {:?}", e)))
+    }
+}
+
+/// Status code maps read through string keys, which tagged worker messages buffer them as
+mod status_code_keys {
+    use std::collections::HashMap;
+    use serde::{de, Deserialize, Deserializer};
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<HashMap<u16, u64>, D::Error> {
+        HashMap::<String, u64>::deserialize(deserializer)?
+            .into_iter()
+            .map(|(code, count)| code.parse().map(|code| (code, count)).map_err(de::Error::custom))
+            .collect()
+    }
+}
+
 /// Collects statistics during benchmark execution
 pub struct StatsCollector {
     /// HDR Histogram for latency tracking (in microseconds)
@@ -74,12 +196,68 @@ pub struct StatsCollector {
     status_codes: HashMap<u16, u64>,
     /// Error counts
     errors: HashMap<String, u64>,
+    /// gRPC status counts
+    grpc_status: HashMap<String, u64>,
     /// Total successful requests
     successful: u64,
     /// Total failed requests
     failed: u64,
     /// Total bytes received
     total_bytes: u64,
+    /// Requests never sent because the in-flight limit was reached
+    dropped: u64,
+    /// Requests sent after their scheduled time
+    late: u64,
+    /// Connection phases, once a request reported them
+    connections: Option<ConnectionCollector>,
+}
+
+/// Connection counts and phase histograms, in microseconds
+struct ConnectionCollector {
+    opened: u64,
+    reused: u64,
+    dns: Histogram<u64>,
+    connect: Histogram<u64>,
+    tls: Histogram<u64>,
+    ttfb: Histogram<u64>,
+}
+
+impl ConnectionCollector {
+    fn new() -> Self {
+        Self {
+            opened: 0,
+            reused: 0,
+            dns: new_histogram(),
+            connect: new_histogram(),
+            tls: new_histogram(),
+            ttfb: new_histogram(),
+        }
+    }
+
+    fn merge(&mut self, other: ConnectionCollector) {
+        self.opened += other.opened;
+        self.reused += other.reused;
+        let _ = self.dns.add(&other.dns);
+        let _ = self.connect.add(&other.connect);
+        let _ = self.tls.add(&other.tls);
+        let _ = self.ttfb.add(&other.ttfb);
+    }
+
+    fn finalize(self) -> ConnectionStats {
+        ConnectionStats {
+            opened: self.opened,
+            reused: self.reused,
+            dns: PhaseStats::from_histogram(self.dns),
+            connect: PhaseStats::from_histogram(self.connect),
+            tls: PhaseStats::from_histogram(self.tls),
+            ttfb: PhaseStats::from_histogram(self.ttfb),
+        }
+    }
+}
+
+fn record_duration(histogram: &mut Histogram<u64>, duration: Duration) {
+    let micros = (duration.as_micros() as u64).clamp(1, MAX_LATENCY_US);
+    let _ = histogram.record(micros);
 }
 
 const MAX_LATENCY_US: u64 = 300_000_000; // 5 minutes
@@ -87,16 +265,17 @@ const MAX_LATENCY_US: u64 = 300_000_000; // 5 minutes
 impl StatsCollector {
     /// Create a new stats collector
     pub fn new() -> Self {
-        let histogram = Histogram::new_with_bounds(1, MAX_LATENCY_US, 3)
-            .expect("Failed to create histogram");
-
         Self {
-            histogram,
+            histogram: new_histogram(),
             status_codes: HashMap::new(),
             errors: HashMap::new(),
+            grpc_status: HashMap::new(),
             successful: 0,
             failed: 0,
             total_bytes: 0,
+            dropped: 0,
+            late: 0,
+            connections: None,
         }
     }
 
@@ -108,9 +287,7 @@ impl StatsCollector {
         bytes: usize,
         error: Option<String>,
     ) {
-        let latency_us = latency.as_micros() as u64;
-        let clamped_latency = latency_us.max(1).min(MAX_LATENCY_US);
-        let _ = self.histogram.record(clamped_latency);
+        self.record_latency(latency);
 
         if let Some(code) = status_code {
             *self.status_codes.entry(code).or_insert(0) += 1;
@@ -131,26 +308,115 @@ impl StatsCollector {
         }
     }
 
+    /// Record a request whose success the caller decided, such as a workflow
+    /// step that passes or fails on its assertions
+    pub fn record_outcome(
+        &mut self,
+        status_code: Option<u16>,
+        latency: Duration,
+        bytes: usize,
+        passed: bool,
+        error: Option<String>,
+    ) {
+        self.record_latency(latency);
+
+        if let Some(code) = status_code {
+            *self.status_codes.entry(code).or_insert(0) += 1;
+        }
+        if let Some(err) = error {
+            *self.errors.entry(err).or_insert(0) += 1;
+        }
+        if passed {
+            self.successful += 1;
+        } else {
+            self.failed += 1;
+        }
+        self.total_bytes += bytes as u64;
+    }
+
+    /// Record a gRPC call; only `Ok` counts as success
+    pub fn record_grpc(&mut self, code: tonic::Code, latency: Duration, bytes: usize) {
+        self.record_latency(latency);
+        *self.grpc_status.entry(format!("{:?}", code)).or_insert(0) += 1;
+
+        if code == tonic::Code::Ok {
+            self.successful += 1;
+        } else {
+            self.failed += 1;
+        }
+        self.total_bytes += bytes as u64;
+    }
+
+    /// Record a completed exchange of a protocol without status codes, such as a WebSocket message
+    pub fn record_message(&mut self, latency: Duration, bytes: usize) {
+        self.record_latency(latency);
+        self.successful += 1;
+        self.total_bytes += bytes as u64;
+    }
+
+    fn record_latency(&mut self, latency: Duration) {
+        let latency_us = latency.as_micros() as u64;
+        let clamped_latency = latency_us.max(1).min(MAX_LATENCY_US);
+        let _ = self.histogram.record(clamped_latency);
+    }
+
+    /// Record whether a request opened a connection, the connection's setup, and the request's TTFB
+    pub fn record_connection(&mut self, info: &ConnectionInfo) {
+        let connections = self.connections.get_or_insert_with(ConnectionCollector::new);
+        match info.opened {
+            Some(timing) => {
+                connections.opened += 1;
+                if let Some(dns) = timing.dns {
+                    record_duration(&mut connections.dns, dns);
+                }
+                record_duration(&mut connections.connect, timing.connect);
+                if let Some(tls) = timing.tls {
+                    record_duration(&mut connections.tls, tls);
+                }
+            }
+            None => connections.reused += 1,
+        }
+        record_duration(&mut connections.ttfb, info.ttfb);
+    }
+
+    /// Count a scheduled request that was never sent
+    pub fn record_dropped(&mut self) {
+        self.dropped += 1;
+    }
+
+    /// Count a request that was sent after its scheduled time
+    pub fn record_late(&mut self) {
+        self.late += 1;
+    }
+
+    /// Fold another collector's counts and latencies into this one
+    pub fn merge(&mut self, other: StatsCollector) {
+        let _ = self.histogram.add(&other.histogram);
+        for (code, count) in other.status_codes {
+            *self.status_codes.entry(code).or_insert(0) += count;
+        }
+        for (error, count) in other.errors {
+            *self.errors.entry(error).or_insert(0) += count;
+        }
+        for (status, count) in other.grpc_status {
+            *self.grpc_status.entry(status).or_insert(0) += count;
+        }
+        self.successful += other.successful;
+        self.failed += other.failed;
+        self.total_bytes += other.total_bytes;
+        self.dropped += other.dropped;
+        self.late += other.late;
+        if let Some(connections) = other.connections {
+            self.connections.get_or_insert_with(ConnectionCollector::new).merge(connections);
+        }
+    }
+
     /// Finalize and compute statistics
     pub fn finalize(self, duration: Duration) -> BenchmarkStats {
         let total = self.successful + self.failed;
         let duration_secs = duration.as_secs_f64();
 
-        let latency = if self.histogram.len() > 0 {
-            LatencyStats {
-                min_ms: self.histogram.min() as f64 / 1000.0,
-                max_ms: self.histogram.max() as f64 / 1000.0,
-                mean_ms: self.histogram.mean() / 1000.0,
-                stddev_ms: self.histogram.stdev() / 1000.0,
-                p50_ms: self.histogram.value_at_percentile(50.0) as f64 / 1000.0,
-                p75_ms: self.histogram.value_at_percentile(75.0) as f64 / 1000.0,
-                p90_ms: self.histogram.value_at_percentile(90.0) as f64 / 1000.0,
-                p95_ms: self.histogram.value_at_percentile(95.0) as f64 / 1000.0,
-                p99_ms: self.histogram.value_at_percentile(99.0) as f64 / 1000.0,
-            }
-        } else {
-            LatencyStats::default()
-        };
+        let latency = LatencyStats::from_histogram(&self.histogram);
 
         BenchmarkStats {
             successful_requests: self.successful,
@@ -162,6 +428,11 @@ impl StatsCollector {
             status_codes: self.status_codes,
             errors: self.errors,
             latency,
+            dropped_requests: self.dropped,
+            late_requests: self.late,
+            grpc_status: self.grpc_status,
+            connections: self.connections.map(ConnectionCollector::finalize),
+            histogram: self.histogram,
         }
     }
 }
@@ -172,6 +443,32 @@ impl Default for StatsCollector {
     }
 }
 
+/// Turn finished statistics back into counts and histograms, so statistics
+/// from elsewhere, such as benchmark workers, can be merged
+impl From<BenchmarkStats> for StatsCollector {
+    fn from(stats: BenchmarkStats) -> Self {
+        Self {
+            histogram: stats.histogram,
+            status_codes: stats.status_codes,
+            errors: stats.errors,
+            grpc_status: stats.grpc_status,
+            successful: stats.successful_requests,
+            failed: stats.failed_requests,
+            total_bytes: stats.total_bytes,
+            dropped: stats.dropped_requests,
+            late: stats.late_requests,
+            connections: stats.connections.map(|connections| ConnectionCollector {
+                opened: connections.opened,
+                reused: connections.reused,
+                dns: connections.dns.histogram,
+                connect: connections.connect.histogram,
+                tls: connections.tls.histogram,
+                ttfb: connections.ttfb.histogram,
+            }),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -226,4 +523,150 @@ mod tests {
         assert_eq!(stats.failed_requests, 0);
         assert_eq!(stats.latency.mean_ms, 0.0);
     }
+
+    #[test]
+    fn test_merge_collectors() {
+        let mut first = StatsCollector::new();
+        first.record(Some(200), Duration::from_millis(10), 100, None);
+        first.record(None, Duration::from_millis(30), 0, Some("Timeout".to_string()));
+
+        let mut second = StatsCollector::new();
+        second.record(Some(200), Duration::from_millis(20), 50, None);
+        second.record(None, Duration::from_millis(40), 0, Some("Timeout".to_string()));
+        second.record_dropped();
+
+        first.merge(second);
+        let stats = first.finalize(Duration::from_secs(1));
+
+        assert_eq!(stats.successful_requests, 2);
+        assert_eq!(stats.failed_requests, 2);
+        assert_eq!(stats.total_bytes, 150);
+        assert_eq!(stats.status_codes[&200], 2);
+        assert_eq!(stats.errors["Timeout"], 2);
+        assert_eq!(stats.dropped_requests, 1);
+        assert_eq!(stats.histogram.len(), 4);
+    }
+
+    #[test]
+    fn test_dropped_and_late() {
+        let mut collector = StatsCollector::new();
+        collector.record(Some(200), Duration::from_millis(10), 10, None);
+        collector.record_late();
+        collector.record_dropped();
+        collector.record_dropped();
+
+        let stats = collector.finalize(Duration::from_secs(1));
+
+        // Dropped requests were never sent, so they count neither way
+        assert_eq!(stats.successful_requests, 1);
+        assert_eq!(stats.failed_requests, 0);
+        assert_eq!(stats.dropped_requests, 2);
+        assert_eq!(stats.late_requests, 1);
+    }
+
+    #[test]
+    fn test_grpc_and_message_results() {
+        let mut collector = StatsCollector::new();
+        collector.record_grpc(tonic::Code::Ok, Duration::from_millis(5), 20);
+        collector.record_grpc(tonic::Code::Ok, Duration::from_millis(6), 20);
+        collector.record_grpc(tonic::Code::Unavailable, Duration::from_millis(7), 0);
+        collector.record_message(Duration::from_millis(2), 11);
+
+        let stats = collector.finalize(Duration::from_secs(1));
+
+        assert_eq!(stats.successful_requests, 3);
+        assert_eq!(stats.failed_requests, 1);
+        assert_eq!(stats.total_bytes, 51);
+        assert_eq!(stats.grpc_status["Ok"], 2);
+        assert_eq!(stats.grpc_status["Unavailable"], 1);
+        assert!(stats.status_codes.is_empty());
+        assert_eq!(stats.histogram.len(), 4);
+    }
+
+    #[test]
+    fn test_connection_stats() {
+        use crate::client::timing::ConnectTiming;
+
+        let opened = ConnectionInfo {
+            opened: Some(ConnectTiming {
+                dns: None,
+                connect: Duration::from_millis(2),
+                tls: Some(Duration::from_millis(8)),
+                session: None,
+            }),
+            ttfb: Duration::from_millis(20),
+        };
+        let reused = ConnectionInfo { opened: None, ttfb: Duration::from_millis(5) };
+
+        let mut first = StatsCollector::new();
+        first.record_connection(&opened);
+        first.record_connection(&reused);
+        let mut second = StatsCollector::new();
+        second.record_connection(&reused);
+        first.merge(second);
+
+        let stats = first.finalize(Duration::from_secs(1));
+        let connections = stats.connections.as_ref().unwrap();
+        assert_eq!(connections.opened, 1);
+        assert_eq!(connections.reused, 2);
+        assert_eq!(connections.dns.count, 0);
+        assert_eq!(connections.tls.count, 1);
+        assert_eq!(connections.ttfb.count, 3);
+        assert!(connections.tls.latency.max_ms >= 7.9);
+
+        let json = serde_json::to_value(&stats).unwrap();
+        assert_eq!(json["connections"]["ttfb"]["count"], 3);
+        assert!(json["connections"]["ttfb"]["p50_ms"].is_number());
+        let restored: BenchmarkStats = serde_json::from_value(json).unwrap();
+        assert_eq!(restored.connections.unwrap().connect.histogram.len(), 1);
+
+        // Protocols without connection timings leave the section out
+        assert!(StatsCollector::new().finalize(Duration::from_secs(1)).connections.is_none());
+    }
+
+    #[test]
+    fn test_merge_finished_stats() {
+        let mut first = StatsCollector::new();
+        first.record(Some(200), Duration::from_millis(10), 100, None);
+        first.record_connection(&ConnectionInfo { opened: None, ttfb: Duration::from_millis(8) });
+        let mut second = StatsCollector::new();
+        second.record(Some(503), Duration::from_millis(40), 0, None);
+        second.record_dropped();
+
+        // As if each had come from a worker
+        let mut merged = StatsCollector::from(first.finalize(Duration::from_secs(1)));
+        merged.merge(StatsCollector::from(second.finalize(Duration::from_secs(2))));
+        let stats = merged.finalize(Duration::from_secs(2));
+
+        assert_eq!(stats.successful_requests, 1);
+        assert_eq!(stats.failed_requests, 1);
+        assert_eq!(stats.requests_per_second, 1.0);
+        assert_eq!(stats.dropped_requests, 1);
+        assert_eq!(stats.histogram.len(), 2);
+        assert!(stats.latency.max_ms >= 39.9);
+        assert_eq!(stats.connections.unwrap().reused, 1);
+    }
+
+    #[test]
+    fn test_stats_round_trip_with_histogram() {
+        let mut collector = StatsCollector::new();
+        for i in 1..=100 {
+            collector.record(Some(200), Duration::from_millis(i), 10, None);
+        }
+        let stats = collector.finalize(Duration::from_secs(1));
+
+        let json = serde_json::to_value(&stats).unwrap();
+        assert!(json["histogram"].is_string());
+        assert_eq!(json["status_codes"]["200"], 100);
+
+        let restored: BenchmarkStats = serde_json::from_value(json).unwrap();
+        assert_eq!(restored.histogram.len(), 100);
+        assert_eq!(restored.histogram.max(), stats.histogram.max());
+
+        // Serialized histograms can be merged
+        let mut merged = restored.histogram.clone();
+        merged.add(&stats.histogram).unwrap();
+        assert_eq!(merged.len(), 200);
+        assert_eq!(LatencyStats::from_histogram(&merged).p50_ms, stats.latency.p50_ms);
+    }
 }
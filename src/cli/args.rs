@@ -293,6 +293,14 @@ pub struct Args {
     #[arg(short = 'q', long = "quiet", action = ArgAction::Count)]
     pub quiet: u8,
 
+    /// Print a template after the response, e.g. '{status} {time_total}\n' (@FILE reads it from a file)
+    #[arg(short = 'w', long = "write-out", value_name = "TEMPLATE")]
+    pub write_out: Option<String>,
+
+    /// Append --write-out output to FILE instead of stdout
+    #[arg(long = "write-out-file", value_name = "FILE", requires = "write_out")]
+    pub write_out_file: Option<PathBuf>,
+
     // =========================================================================
     // SESSIONS
     // =========================================================================
@@ -624,6 +632,71 @@ pub struct Args {
     #[arg(long = "concurrency", value_name = "NUM", default_value = "10")]
     pub bench_concurrency: u32,
 
+    /// Open-loop arrival rate for benchmarks, e.g. 500/s or 3000/m
+    /// (--concurrency then caps requests in flight)
+    #[arg(long = "rate", value_name = "RATE")]
+    pub bench_rate: Option<String>,
+
+    /// Ramp profile as DURATION:RATE stages, e.g. 10s:500/s,30s:500/s,10s:0
+    /// (starts from --rate, or 0)
+    #[arg(long = "bench-stages", value_name = "STAGES")]
+    pub bench_stages: Option<String>,
+
+    /// Run the benchmark for a fixed time, e.g. 60s or 5m, instead of --requests
+    #[arg(long = "bench-duration", value_name = "DURATION")]
+    pub bench_duration: Option<String>,
+
+    /// Write per-second statistics to FILE (CSV for .csv, otherwise NDJSON)
+    #[arg(long = "bench-timeseries", value_name = "FILE")]
+    pub bench_timeseries: Option<PathBuf>,
+
+    /// Benchmark result format: text (default) or json, which includes the full latency histogram
+    #[arg(long = "bench-output", value_name = "FORMAT", value_enum, default_value = "text")]
+    pub bench_output: BenchOutput,
+
+    /// Compare against a previous --bench-output json result and fail on regressions
+    #[arg(long = "bench-baseline", value_name = "FILE")]
+    pub bench_baseline: Option<PathBuf>,
+
+    /// Allowed regression against the baseline: 10% for every metric, or per metric
+    /// like p99=5%,rps=10% (metrics: rps, mean, p50, p75, p90, p95, p99, errors);
+    /// errors is the error rate's rise in percentage points, 1 unless set
+    #[arg(long = "bench-threshold", value_name = "SPEC")]
+    pub bench_threshold: Option<String>,
+
+    /// CSV or NDJSON file whose rows fill {{column}} placeholders in the URL, headers and body
+    #[arg(long = "bench-data", value_name = "FILE")]
+    pub bench_data: Option<PathBuf>,
+
+    /// Order in which --bench-data rows are used: round-robin (default) or random
+    #[arg(long = "bench-data-order", value_name = "ORDER", value_enum, default_value = "round-robin")]
+    pub bench_data_order: BenchDataOrder,
+
+    /// What a WebSocket benchmark measures: round-trip (default) waits for each reply,
+    /// throughput sends messages back to back
+    #[arg(long = "bench-ws-mode", value_name = "MODE", value_enum, default_value = "round-trip")]
+    pub bench_ws_mode: BenchWsMode,
+
+    /// Open a new connection for every benchmark request to measure cold-connection cost
+    #[arg(long = "bench-no-keepalive", action = ArgAction::SetTrue)]
+    pub bench_no_keepalive: bool,
+
+    /// Run as a benchmark worker, taking jobs from a --bench-workers controller
+    #[arg(long = "bench-worker", action = ArgAction::SetTrue)]
+    pub bench_worker: bool,
+
+    /// Address a benchmark worker listens on: 127.0.0.1:7000 (default), 10.0.0.5:7000, or :7000 for every interface
+    #[arg(long = "listen", value_name = "ADDR")]
+    pub listen: Option<String>,
+
+    /// Split the benchmark across workers, e.g. host1:7000,host2:7000
+    #[arg(long = "bench-workers", value_name = "ADDRS")]
+    pub bench_workers: Option<String>,
+
+    /// Shared secret a benchmark worker requires from its controller
+    #[arg(long = "bench-token", value_name = "TOKEN", env = "QUICPULSE_BENCH_TOKEN", hide_env_values = true)]
+    pub bench_token: Option<String>,
+
     // =========================================================================
     // DATA FILTERING & FORMATTING (Phase 11)
     // =========================================================================
@@ -716,6 +789,10 @@ pub struct Args {
     #[arg(long = "save-responses", value_name = "DIR")]
     pub save_responses: Option<PathBuf>,
 
+    /// Overwrite snapshot files that no longer match the response
+    #[arg(long = "update-snapshots", action = ArgAction::SetTrue)]
+    pub update_snapshots: bool,
+
     /// Generate JUnit XML report (for CI/CD integration)
     #[arg(long = "report-junit", value_name = "FILE")]
     pub report_junit: Option<PathBuf>,
@@ -940,10 +1017,26 @@ pub struct Args {
     #[arg(long = "mock-log", action = ArgAction::SetTrue)]
     pub mock_log: bool,
 
-    /// Record requests to HAR file
+    /// Record requests and responses to a file (HAR; NDJSON for .ndjson/.jsonl; proxied routes for .yaml)
     #[arg(long = "mock-record", value_name = "FILE")]
     pub mock_record: Option<PathBuf>,
 
+    /// Most recent requests kept for the admin API (default 1000, 0 disables the journal)
+    #[arg(long = "mock-journal-limit", value_name = "N")]
+    pub mock_journal_limit: Option<usize>,
+
+    /// Largest request body the mock server reads, in bytes; larger ones get a 413 (default 10 MiB)
+    #[arg(long = "mock-max-body", value_name = "BYTES")]
+    pub mock_max_body: Option<usize>,
+
+    /// Serve the mock admin API under /__admin
+    #[arg(long = "mock-admin", action = ArgAction::SetTrue)]
+    pub mock_admin: bool,
+
+    /// Bearer token required by the mock admin API (implies --mock-admin)
+    #[arg(long = "mock-admin-token", value_name = "TOKEN", env = "QUICPULSE_MOCK_ADMIN_TOKEN", hide_env_values = true)]
+    pub mock_admin_token: Option<String>,
+
     /// TLS certificate for HTTPS mock server
     #[arg(long = "mock-tls-cert", value_name = "FILE")]
     pub mock_tls_cert: Option<PathBuf>,
@@ -952,10 +1045,26 @@ pub struct Args {
     #[arg(long = "mock-tls-key", value_name = "FILE")]
     pub mock_tls_key: Option<PathBuf>,
 
+    /// Serve HTTPS with a throwaway self-signed certificate
+    #[arg(long = "mock-tls-self-signed", action = ArgAction::SetTrue)]
+    pub mock_tls_self_signed: bool,
+
+    /// Advertise HTTP/2 via ALPN on the HTTPS mock server
+    #[arg(long = "mock-tls-h2", action = ArgAction::SetTrue)]
+    pub mock_tls_h2: bool,
+
     /// Proxy unmatched requests to another server
     #[arg(long = "mock-proxy", value_name = "URL")]
     pub mock_proxy: Option<String>,
 
+    /// Generate mock routes from an OpenAPI/Swagger spec
+    #[arg(long = "mock-openapi", value_name = "FILE")]
+    pub mock_openapi: Option<PathBuf>,
+
+    /// Reject request bodies that do not match the OpenAPI schema with a 400
+    #[arg(long = "mock-validate", action = ArgAction::SetTrue)]
+    pub mock_validate: bool,
+
     // =========================================================================
     // PLUGIN ECOSYSTEM
     // =========================================================================
@@ -1021,6 +1130,36 @@ pub enum LogFormat {
     Json,
 }
 
+/// Benchmark result format
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum BenchOutput {
+    /// Coloured summary (default)
+    #[default]
+    Text,
+    /// JSON report, usable as a later --bench-baseline
+    Json,
+}
+
+/// Order in which benchmark data rows are used
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum BenchDataOrder {
+    /// Each request takes the next row, wrapping around (default)
+    #[default]
+    RoundRobin,
+    /// Each request takes a random row
+    Random,
+}
+
+/// What a WebSocket benchmark measures
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum BenchWsMode {
+    /// Time from sending a message to receiving the reply (default)
+    #[default]
+    RoundTrip,
+    /// Time to send each message, without waiting for replies
+    Throughput,
+}
+
 // Note: PrettyOption is defined in output::options and re-exported from output module
 pub use crate::output::PrettyOption;
 
@@ -1100,6 +1239,8 @@ impl Default for Args {
             download: false,
             continue_download: false,
             quiet: 0,
+            write_out: None,
+            write_out_file: None,
             session: None,
             session_read_only: None,
             auth: None,
@@ -1168,6 +1309,21 @@ impl Default for Args {
             bench: false,
             bench_requests: 100,
             bench_concurrency: 10,
+            bench_rate: None,
+            bench_stages: None,
+            bench_duration: None,
+            bench_timeseries: None,
+            bench_output: BenchOutput::Text,
+            bench_baseline: None,
+            bench_threshold: None,
+            bench_data: None,
+            bench_data_order: BenchDataOrder::RoundRobin,
+            bench_ws_mode: BenchWsMode::RoundTrip,
+            bench_no_keepalive: false,
+            bench_worker: false,
+            listen: None,
+            bench_workers: None,
+            bench_token: None,
             filter: None,
             table: false,
             csv: false,
@@ -1188,6 +1344,7 @@ impl Default for Args {
             workflow_include: Vec::new(),
             workflow_exclude: Vec::new(),
             save_responses: None,
+            update_snapshots: false,
             report_junit: None,
             report_json: None,
             report_tap: None,
@@ -1239,9 +1396,17 @@ impl Default for Args {
             mock_host: None,
             mock_log: false,
             mock_record: None,
+            mock_journal_limit: None,
+            mock_max_body: None,
+            mock_admin: false,
+            mock_admin_token: None,
             mock_tls_cert: None,
             mock_tls_key: None,
+            mock_tls_self_signed: false,
+            mock_tls_h2: false,
             mock_proxy: None,
+            mock_openapi: None,
+            mock_validate: false,
             plugin_list: false,
             plugin_install: None,
             plugin_uninstall: None,
@@ -75,6 +75,10 @@ pub struct ProcessedArgs {
     pub request_type: RequestType,
     /// Whether the request has data
     pub has_data: bool,
+    /// URL before magic value expansion
+    pub raw_url: String,
+    /// Request items before magic value expansion
+    pub raw_items: Vec<String>,
 }
 
 /// Process raw CLI arguments into a usable form
@@ -156,7 +160,8 @@ pub fn process_args(args: &Args) -> Result<ProcessedArgs, QuicpulseError> {
     let url = process_url(&actual_url, &args.default_scheme)?;
 
     // 6. Expand magic values in URL
-    let url = expand_magic_values(&url).value;
+    let raw_url = url;
+    let url = expand_magic_values(&raw_url).value;
 
     Ok(ProcessedArgs {
         method,
@@ -164,6 +169,8 @@ pub fn process_args(args: &Args) -> Result<ProcessedArgs, QuicpulseError> {
         items,
         request_type,
         has_data,
+        raw_url,
+        raw_items: all_items,
     })
 }
 
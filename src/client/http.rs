@@ -6,7 +6,9 @@ use reqwest::header::{HeaderMap, HeaderName, HeaderValue, ACCEPT, CONTENT_LENGTH
 use reqwest::{Client, Method, Response};
 use serde_json::{json, Value as JsonValue};
 use std::collections::HashMap;
-use std::time::Duration;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use url::Url;
 
 use crate::auth::{AwsSigV4Config, OAuth2Config, get_token, sign_request as aws_sign_request, sha256_hex};
@@ -15,6 +17,7 @@ use crate::cli::parser::ProcessedArgs;
 use crate::input::InputItem;
 use crate::models::types::RequestType;
 use crate::client::ssl::SslConfig;
+use crate::client::timing::{self, RequestTiming};
 use crate::context::Environment;
 use crate::errors::QuicpulseError;
 use crate::graphql;
@@ -84,6 +87,20 @@ pub struct HttpResult {
     pub url: String,
     /// Intermediate responses from redirects (populated when --all is used)
     pub intermediate_responses: Vec<IntermediateResponse>,
+    /// Redirects followed to reach `response`
+    pub num_redirects: usize,
+    /// Phases of the request that produced `response`; the caller adds the body transfer
+    pub timing: RequestTiming,
+}
+
+/// Send a request, timing it and the connection it opened; connection setup
+/// is only seen by clients built with [`timing::instrument`]
+async fn send_timed(request: reqwest::RequestBuilder) -> Result<(Response, RequestTiming), QuicpulseError> {
+    let sent = Instant::now();
+    let (response, opened) = timing::track(request.send()).await;
+    let response = response.map_err(QuicpulseError::Request)?;
+    let timing = RequestTiming::new(opened, sent.elapsed(), &response);
+    Ok((response, timing))
 }
 
 /// Build and send an HTTP request
@@ -106,7 +123,8 @@ pub async fn send_request_with_session(
 ) -> Result<HttpResult, QuicpulseError> {
     debug!("Building HTTP client");
     // Build the client (pass URL for HTTP version configuration)
-    let client = build_client(args, &processed.url)?;
+    let followed = Arc::new(AtomicUsize::new(0));
+    let client = build_client(args, &processed.url, &followed)?;
 
     // Parse the method
     let method: Method = processed.method.parse()
@@ -502,8 +520,7 @@ pub async fn send_request_with_session(
 
     // Send the request
     debug!(method = %method, url = %url, "Sending HTTP request");
-    let mut response = request_builder.send().await
-        .map_err(QuicpulseError::Request)?;
+    let (mut response, mut timing) = send_timed(request_builder).await?;
 
     // Log response details
     let version = match response.version() {
@@ -591,9 +608,9 @@ pub async fn send_request_with_session(
                                     }
                                 }
 
-                                // Send retry request
-                                response = retry_builder.send().await
-                                    .map_err(QuicpulseError::Request)?;
+                                // Send retry request; it follows the redirects again
+                                followed.store(0, Ordering::Relaxed);
+                                (response, timing) = send_timed(retry_builder).await?;
                             }
                         }
                     }
@@ -607,9 +624,9 @@ pub async fn send_request_with_session(
     // 2. Using AWS SigV4 (must re-sign for each redirect URL)
     let mut intermediate_responses = Vec::new();
     let handle_redirects_manually = (args.all && args.follow) || (use_aws_sigv4 && args.follow);
-    
+    let mut redirect_count = 0;
+
     if handle_redirects_manually {
-        let mut redirect_count = 0;
         let mut current_url = url.clone();
         let mut current_method = method.clone();
 
@@ -714,8 +731,7 @@ pub async fn send_request_with_session(
             }
 
             // Send redirect request
-            response = redirect_request.send().await
-                .map_err(QuicpulseError::Request)?;
+            (response, timing) = send_timed(redirect_request).await?;
 
             current_url = next_url;
             current_method = next_method;
@@ -728,6 +744,8 @@ pub async fn send_request_with_session(
         method: method.to_string(),
         url: url.to_string(),
         intermediate_responses,
+        num_redirects: redirect_count + followed.load(Ordering::Relaxed),
+        timing,
     })
 }
 
@@ -773,9 +791,19 @@ fn infer_aws_service(url: &Url) -> Option<String> {
     None
 }
 
+/// Whether the output shows connection timings (`--meta`, `--print=m`, `--write-out`),
+/// which need an instrumented client
+fn wants_timing(args: &Args) -> bool {
+    args.meta
+        || args.print.as_deref().is_some_and(|print| print.contains('m'))
+        || args.write_out.is_some()
+}
+
 /// Build the HTTP client with appropriate configuration
-#[instrument(skip(args))]
-fn build_client(args: &Args, url: &str) -> Result<Client, QuicpulseError> {
+///
+/// Redirects the client follows by itself are counted in `followed`.
+#[instrument(skip(args, followed))]
+fn build_client(args: &Args, url: &str, followed: &Arc<AtomicUsize>) -> Result<Client, QuicpulseError> {
     debug!("Configuring HTTP client");
     let mut builder = Client::builder()
         .user_agent(USER_AGENT_STRING);
@@ -798,7 +826,16 @@ fn build_client(args: &Args, url: &str) -> Result<Client, QuicpulseError> {
         args.cert_key.as_ref().and_then(|p| p.to_str()),
         args.cert_key_pass.as_deref(),
     );
-    builder = ssl_config.apply_to_builder(builder)?;
+    if wants_timing(args) {
+        // rustls is set up here rather than by reqwest so every phase can be timed
+        let alpn = match args.http_version.as_deref() {
+            Some("1" | "1.0" | "1.1") => &["http/1.1"][..],
+            _ => timing::ALPN_DEFAULT,
+        };
+        builder = timing::instrument(builder, &ssl_config, alpn)?;
+    } else {
+        builder = ssl_config.apply_to_builder(builder)?;
+    }
 
     // Handle redirects
     // When --all is used, we disable automatic redirects to capture intermediate responses
@@ -807,7 +844,12 @@ fn build_client(args: &Args, url: &str) -> Result<Client, QuicpulseError> {
         // Always disable auto-redirects when --all is specified so we can capture intermediates
         builder = builder.redirect(reqwest::redirect::Policy::none());
     } else if args.follow {
-        builder = builder.redirect(reqwest::redirect::Policy::limited(args.max_redirects as usize));
+        let limited = reqwest::redirect::Policy::limited(args.max_redirects as usize);
+        let followed = followed.clone();
+        builder = builder.redirect(reqwest::redirect::Policy::custom(move |attempt| {
+            followed.fetch_add(1, Ordering::Relaxed);
+            limited.redirect(attempt)
+        }));
     } else {
         builder = builder.redirect(reqwest::redirect::Policy::none());
     }
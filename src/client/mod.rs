@@ -4,6 +4,7 @@ pub mod adapters;
 pub mod http;
 pub mod http3;
 pub mod ssl;
+pub mod timing;
 
 #[cfg(unix)]
 pub mod unix_socket;
@@ -29,9 +29,16 @@
 //! Note: Custom cipher suite selection (`--ciphers`) has limited support with rustls.
 //! The default cipher suites provided by rustls are secure and recommended for most use cases.
 
+use std::collections::HashMap;
 use std::fs;
 use std::io::{self, Write};
 use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+
+use rustls::client::danger::ServerCertVerifier;
+use rustls::crypto::CryptoProvider;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use rustls::{ClientConfig, SupportedProtocolVersion};
 
 use crate::errors::QuicpulseError;
 use tracing::{info, debug, instrument};
@@ -167,6 +174,53 @@ impl Certificate {
             .map_err(|e| QuicpulseError::Ssl(format!("Failed to load PEM certificate: {}", e)))?;
         Ok(Some(identity))
     }
+
+    /// Load the certificate chain and private key for rustls
+    ///
+    /// Same files and password handling as [`load_identity`](Self::load_identity).
+    pub fn load_chain(&self) -> Result<Option<(Vec<CertificateDer<'static>>, PrivateKeyDer<'static>)>, QuicpulseError> {
+        let cert_path = match &self.cert_file {
+            Some(path) => path,
+            None => return Ok(None),
+        };
+
+        let cert_data = fs::read(cert_path)
+            .map_err(|e| QuicpulseError::Ssl(format!("Failed to read certificate '{}': {}", cert_path.display(), e)))?;
+
+        if is_pkcs12(&cert_data) || cert_path.extension().map(|e| e == "p12" || e == "pfx").unwrap_or(false) {
+            return Err(QuicpulseError::Ssl(
+                "PKCS#12 certificates are not supported. Use PEM format instead.".to_string()
+            ));
+        }
+
+        let certs = rustls_pemfile::certs(&mut cert_data.as_slice())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| QuicpulseError::Ssl(format!("Failed to load PEM certificate: {}", e)))?;
+
+        // The key is either in its own file or next to the certificate
+        let key_data = match &self.key_file {
+            Some(key_path) => {
+                let key_data = fs::read(key_path)
+                    .map_err(|e| QuicpulseError::Ssl(format!("Failed to read key file '{}': {}", key_path.display(), e)))?;
+                if is_key_encrypted(&key_data) {
+                    let password = match &self.key_password {
+                        Some(p) => p.clone(),
+                        None => prompt_password(&format!("Enter passphrase for '{}': ", key_path.display()))?,
+                    };
+                    decrypt_pem_key(&key_data, &password)?
+                } else {
+                    key_data
+                }
+            }
+            None => cert_data,
+        };
+
+        let key = rustls_pemfile::private_key(&mut key_data.as_slice())
+            .map_err(|e| QuicpulseError::Ssl(format!("Failed to load PEM identity: {}", e)))?
+            .ok_or_else(|| QuicpulseError::Ssl(format!("No private key found for '{}'", cert_path.display())))?;
+
+        Ok(Some((certs, key)))
+    }
 }
 
 /// Check if data appears to be PKCS#12 format
@@ -220,6 +274,49 @@ fn decrypt_pem_key(_encrypted_data: &[u8], _password: &str) -> Result<Vec<u8>, Q
     ))
 }
 
+/// Load the certificates of a PEM bundle file for rustls
+fn load_ca_certs(path: &Path) -> Result<Vec<CertificateDer<'static>>, QuicpulseError> {
+    let ca_data = fs::read(path)
+        .map_err(|e| QuicpulseError::Ssl(format!("Failed to read CA bundle '{}': {}", path.display(), e)))?;
+
+    let certs = rustls_pemfile::certs(&mut ca_data.as_slice())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| QuicpulseError::Ssl(format!("Failed to parse CA bundle: {}", e)))?;
+
+    if certs.is_empty() {
+        return Err(QuicpulseError::Ssl(format!("No certificates found in CA bundle '{}'", path.display())));
+    }
+    Ok(certs)
+}
+
+/// The verifier reqwest sets up for clients that verify certificates: the
+/// platform's verifier, with the certificates of `ca_bundle` as extra roots
+///
+/// The platform verifier loads the system roots once, so verifiers are
+/// shared by every client with the same CA bundle.
+fn platform_verifier(ca_bundle: Option<&Path>) -> Result<Arc<dyn ServerCertVerifier>, QuicpulseError> {
+    type Verifiers = HashMap<Option<PathBuf>, Arc<dyn ServerCertVerifier>>;
+    static VERIFIERS: OnceLock<Mutex<Verifiers>> = OnceLock::new();
+    let mut verifiers = VERIFIERS.get_or_init(Default::default).lock().unwrap_or_else(|p| p.into_inner());
+    let key = ca_bundle.map(Path::to_path_buf);
+    if let Some(verifier) = verifiers.get(&key) {
+        return Ok(verifier.clone());
+    }
+
+    let provider = CryptoProvider::get_default()
+        .cloned()
+        .unwrap_or_else(|| Arc::new(rustls::crypto::aws_lc_rs::default_provider()));
+    let verifier = match ca_bundle {
+        Some(path) => rustls_platform_verifier::Verifier::new_with_extra_roots(load_ca_certs(path)?, provider),
+        None => rustls_platform_verifier::Verifier::new(provider),
+    }
+    .map_err(|e| QuicpulseError::Ssl(format!("Failed to set up certificate verification: {}", e)))?;
+
+    let verifier: Arc<dyn ServerCertVerifier> = Arc::new(verifier);
+    verifiers.insert(key, verifier.clone());
+    Ok(verifier)
+}
+
 /// Load multiple CA certificates from a PEM bundle file
 fn load_ca_bundle(path: &Path) -> Result<Vec<reqwest::Certificate>, QuicpulseError> {
     let ca_data = fs::read(path)
@@ -375,6 +472,56 @@ impl SslConfig {
 
         Ok(builder)
     }
+
+    /// Build the rustls configuration that [`apply_to_builder`](Self::apply_to_builder)
+    /// stands for, for clients that hook into the handshake
+    ///
+    /// `wrap_verifier` gets the certificate verifier, and ALPN is left to the caller.
+    pub fn rustls_config(
+        &self,
+        provider: Arc<CryptoProvider>,
+        wrap_verifier: impl FnOnce(Arc<dyn ServerCertVerifier>) -> Arc<dyn ServerCertVerifier>,
+    ) -> Result<ClientConfig, QuicpulseError> {
+        // The verifiers below use the process-wide provider
+        let _ = rustls::crypto::aws_lc_rs::default_provider().install_default();
+
+        let verifier: Arc<dyn ServerCertVerifier> = if self.verify {
+            platform_verifier(self.ca_bundle.as_deref())?
+        } else {
+            Arc::new(crate::websocket::client::NoVerifier)
+        };
+
+        let builder = ClientConfig::builder_with_provider(provider)
+            .with_protocol_versions(self.protocol_versions())
+            .map_err(|e| QuicpulseError::Ssl(format!("Unsupported TLS versions: {}", e)))?
+            .dangerous()
+            .with_custom_certificate_verifier(wrap_verifier(verifier));
+
+        let config = match self.client_cert.load_chain()? {
+            Some((certs, key)) => builder.with_client_auth_cert(certs, key)
+                .map_err(|e| QuicpulseError::Ssl(format!("Invalid client certificate or key: {}", e)))?,
+            None => builder.with_no_client_auth(),
+        };
+
+        if let Some(ciphers) = &self.ciphers {
+            eprintln!(
+                "Warning: Custom cipher suite '{}' specified, but rustls uses its own \
+                 secure defaults. Custom ciphers are ignored.",
+                ciphers
+            );
+        }
+
+        Ok(config)
+    }
+
+    /// TLS versions allowed by the minimum version
+    fn protocol_versions(&self) -> &'static [&'static SupportedProtocolVersion] {
+        static TLS13_ONLY: &[&SupportedProtocolVersion] = &[&rustls::version::TLS13];
+        match self.version {
+            Some(TlsVersion::Tls1_3) => TLS13_ONLY,
+            _ => rustls::ALL_VERSIONS,
+        }
+    }
 }
 
 /// Get the default cipher suites used by rustls
@@ -577,4 +724,13 @@ mod tests {
         assert!(config.ciphers.is_none());
         assert!(!config.client_cert.is_configured());
     }
+
+    #[test]
+    fn test_platform_verifier_shared() {
+        let _ = rustls::crypto::aws_lc_rs::default_provider().install_default();
+        let first = platform_verifier(None).unwrap();
+        let second = platform_verifier(None).unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+        assert!(platform_verifier(Some(Path::new("/nonexistent/ca.pem"))).is_err());
+    }
 }
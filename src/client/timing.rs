@@ -0,0 +1,603 @@
+//! Connection and phase timings of HTTP requests
+//!
+//! reqwest hides connection setup, so clients are instrumented: a connector
+//! layer times every new connection, the DNS resolver times lookups, and the
+//! TLS verifier notes when the ClientHello goes out, which splits the rest of
+//! the setup into TCP connect and TLS handshake. The cipher suites report
+//! themselves once the handshake settled on one, which gives the negotiated
+//! TLS version and cipher, and the finished connection tells whether ALPN
+//! settled on HTTP/2. A request during which no connection was opened reused
+//! one from the pool.
+//!
+//! reqwest keeps the rustls connection to itself; the connector layer only
+//! sees its own connection type, which is why the handshake is observed
+//! through the verifier and the cipher suites rather than read off the
+//! connection.
+//!
+//! Instrumented clients set up rustls themselves, with the certificate
+//! verifier reqwest would use, so only clients whose output asks for timings
+//! are instrumented. HTTP/3 connections bypass the connector and are not
+//! timed.
+
+use std::future::Future;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use hyper_util::client::legacy::connect::Connection;
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::crypto::cipher::{
+    AeadKey, Iv, KeyBlockShape, MessageDecrypter, MessageEncrypter, Tls12AeadAlgorithm,
+    Tls13AeadAlgorithm, UnsupportedOperationError,
+};
+use rustls::crypto::CryptoProvider;
+use rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use rustls::{
+    CipherSuiteCommon, ConnectionTrafficSecrets, DigitallySignedStruct, SignatureScheme,
+    SupportedCipherSuite, Tls12CipherSuite, Tls13CipherSuite,
+};
+use serde_json::{json, Value as JsonValue};
+
+use super::ssl::SslConfig;
+use crate::errors::QuicpulseError;
+
+/// ALPN offer of a client that speaks HTTP/2 and HTTP/1.1
+pub const ALPN_DEFAULT: &[&str] = &["h2", "http/1.1"];
+
+/// Names understood by [`RequestTiming::variable`], after curl's `--write-out`
+pub const VARIABLES: &[&str] = &[
+    "time_namelookup",
+    "time_connect",
+    "time_appconnect",
+    "time_starttransfer",
+    "time_total",
+    "remote_ip",
+    "remote_port",
+    "num_connects",
+    "tls_version",
+    "tls_cipher",
+    "alpn",
+];
+
+tokio::task_local! {
+    /// Filled with the connection the current request opened, if any
+    static REQUEST: Arc<Mutex<Option<ConnectTiming>>>;
+    /// Progress of the connection being set up
+    static CONNECTING: Arc<Marks>;
+}
+
+/// Negotiated parameters of a TLS connection
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TlsSession {
+    /// `TLSv1.2` or `TLSv1.3`
+    pub version: &'static str,
+    /// IANA name of the cipher suite
+    pub cipher: &'static str,
+    /// `h2` when the handshake agreed on HTTP/2 via ALPN, else `http/1.1`
+    /// if the client offered it, as the connection then speaks HTTP/1.1
+    pub alpn: Option<&'static str>,
+}
+
+/// How long the setup of one new connection took
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ConnectTiming {
+    /// `None` when the host is an IP address
+    pub dns: Option<Duration>,
+    /// TCP connect
+    pub connect: Duration,
+    /// `None` for plain HTTP
+    pub tls: Option<Duration>,
+    /// `None` for plain HTTP
+    pub session: Option<TlsSession>,
+}
+
+/// What one request saw of its connection
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ConnectionInfo {
+    /// Setup of the connection the request opened; `None` if it reused one
+    pub opened: Option<ConnectTiming>,
+    /// Time from sending the request to receiving the response headers
+    pub ttfb: Duration,
+}
+
+/// Phases and connection details of one request and its response
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RequestTiming {
+    /// Setup of the connection the request opened; `None` if it reused one
+    pub opened: Option<ConnectTiming>,
+    /// Sending the request until the response headers arrived, connection setup included
+    pub ttfb: Duration,
+    /// Reading the response body
+    pub transfer: Duration,
+    pub remote_addr: Option<SocketAddr>,
+}
+
+impl RequestTiming {
+    /// Timing of `response`, whose headers arrived `ttfb` after sending
+    pub fn new(opened: Option<ConnectTiming>, ttfb: Duration, response: &reqwest::Response) -> Self {
+        Self {
+            opened,
+            ttfb,
+            transfer: Duration::ZERO,
+            remote_addr: response.remote_addr(),
+        }
+    }
+
+    pub fn reused(&self) -> bool {
+        self.opened.is_none()
+    }
+
+    /// Sending the request until the body was read
+    pub fn total(&self) -> Duration {
+        self.ttfb + self.transfer
+    }
+
+    /// Time spent setting up the connection
+    pub fn setup(&self) -> Duration {
+        self.opened.map_or(Duration::ZERO, |opened| {
+            opened.dns.unwrap_or_default() + opened.connect + opened.tls.unwrap_or_default()
+        })
+    }
+
+    /// Waiting for the response headers once the connection was up
+    pub fn wait(&self) -> Duration {
+        self.ttfb.saturating_sub(self.setup())
+    }
+
+    pub fn tls_session(&self) -> Option<TlsSession> {
+        self.opened.and_then(|opened| opened.session)
+    }
+
+    /// Value of one of [`VARIABLES`]; times are cumulative seconds, as in curl,
+    /// and details that do not apply are null
+    pub fn variable(&self, name: &str) -> Option<JsonValue> {
+        let opened = self.opened.unwrap_or_default();
+        let namelookup = opened.dns.unwrap_or_default();
+        let connect = if self.opened.is_some() { namelookup + opened.connect } else { Duration::ZERO };
+        let appconnect = opened.tls.map_or(Duration::ZERO, |tls| connect + tls);
+
+        let value = match name {
+            "time_namelookup" => seconds(namelookup),
+            "time_connect" => seconds(connect),
+            "time_appconnect" => seconds(appconnect),
+            "time_starttransfer" => seconds(self.ttfb),
+            "time_total" => seconds(self.total()),
+            "remote_ip" => json!(self.remote_addr.map(|addr| addr.ip().to_string())),
+            "remote_port" => json!(self.remote_addr.map(|addr| addr.port())),
+            "num_connects" => json!(u8::from(self.opened.is_some())),
+            "tls_version" => json!(self.tls_session().map(|session| session.version)),
+            "tls_cipher" => json!(self.tls_session().map(|session| session.cipher)),
+            "alpn" => json!(self.tls_session().and_then(|session| session.alpn)),
+            _ => return None,
+        };
+        Some(value)
+    }
+
+    /// Every variable as a JSON object, for reports
+    pub fn to_json(&self) -> JsonValue {
+        VARIABLES.iter()
+            .filter_map(|name| Some((name.to_string(), self.variable(name)?)))
+            .collect::<serde_json::Map<_, _>>()
+            .into()
+    }
+}
+
+/// Seconds with microsecond precision
+fn seconds(duration: Duration) -> JsonValue {
+    json!((duration.as_secs_f64() * 1e6).round() / 1e6)
+}
+
+struct Marks {
+    start: Instant,
+    dns: Mutex<Option<Duration>>,
+    client_hello: Mutex<Option<Instant>>,
+    session: Mutex<Option<TlsSession>>,
+}
+
+impl Marks {
+    fn new() -> Self {
+        Self {
+            start: Instant::now(),
+            dns: Mutex::new(None),
+            client_hello: Mutex::new(None),
+            session: Mutex::new(None),
+        }
+    }
+
+    fn finish(&self) -> ConnectTiming {
+        let dns = *self.dns.lock().unwrap();
+        let session = *self.session.lock().unwrap();
+        let after_dns = self.start.elapsed().saturating_sub(dns.unwrap_or_default());
+        match *self.client_hello.lock().unwrap() {
+            Some(client_hello) => {
+                let tls = client_hello.elapsed();
+                ConnectTiming { dns, connect: after_dns.saturating_sub(tls), tls: Some(tls), session }
+            }
+            None => ConnectTiming { dns, connect: after_dns, tls: None, session },
+        }
+    }
+}
+
+/// Await `future` as one request of an instrumented client, returning the
+/// setup of the connection it opened, if it opened one
+pub async fn track<F: Future>(future: F) -> (F::Output, Option<ConnectTiming>) {
+    let slot = Arc::new(Mutex::new(None));
+    let output = REQUEST.scope(slot.clone(), future).await;
+    let opened = slot.lock().unwrap().take();
+    (output, opened)
+}
+
+/// Instrument a client builder for [`track`]. Replaces the TLS configuration,
+/// so certificates are set through `ssl` rather than on the builder.
+pub fn instrument(
+    builder: reqwest::ClientBuilder,
+    ssl: &SslConfig,
+    alpn: &[&str],
+) -> Result<reqwest::ClientBuilder, QuicpulseError> {
+    let mut config = ssl.rustls_config(recording_provider(), |inner| Arc::new(TimedVerifier { inner }))?;
+    config.alpn_protocols = alpn.iter().map(|protocol| protocol.as_bytes().to_vec()).collect();
+
+    Ok(builder
+        .dns_resolver(TimedResolver)
+        .connector_layer(TimingLayer { offers_http1: alpn.contains(&"http/1.1") })
+        .tls_backend_preconfigured(config))
+}
+
+/// Connector layer timing each new connection
+#[derive(Clone)]
+struct TimingLayer {
+    /// Whether ALPN offers HTTP/1.1
+    offers_http1: bool,
+}
+
+impl<S> tower::Layer<S> for TimingLayer {
+    type Service = TimedConnector<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        TimedConnector { inner, offers_http1: self.offers_http1 }
+    }
+}
+
+#[derive(Clone)]
+struct TimedConnector<S> {
+    inner: S,
+    offers_http1: bool,
+}
+
+impl<S, R> tower::Service<R> for TimedConnector<S>
+where
+    S: tower::Service<R>,
+    S::Future: Send + 'static,
+    S::Response: Connection + Send + 'static,
+    S::Error: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<S::Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: R) -> Self::Future {
+        // The request asks for the connection, but it may be finished by
+        // another task, so the marks travel with the connecting future
+        let slot = REQUEST.try_with(Arc::clone).ok();
+        let marks = Arc::new(Marks::new());
+        let offers_http1 = self.offers_http1;
+        let connecting = CONNECTING.sync_scope(marks.clone(), || self.inner.call(request));
+
+        Box::pin(CONNECTING.scope(marks.clone(), async move {
+            let connection = connecting.await?;
+            if let Some(slot) = slot {
+                let mut opened = marks.finish();
+                if let Some(session) = opened.session.as_mut() {
+                    session.alpn = if connection.connected().is_negotiated_h2() {
+                        Some("h2")
+                    } else {
+                        offers_http1.then_some("http/1.1")
+                    };
+                }
+                *slot.lock().unwrap() = Some(opened);
+            }
+            Ok(connection)
+        }))
+    }
+}
+
+/// System resolver that records lookup time
+struct TimedResolver;
+
+impl Resolve for TimedResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let marks = CONNECTING.try_with(Arc::clone).ok();
+        let host = name.as_str().to_string();
+        Box::pin(async move {
+            let start = Instant::now();
+            let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host.as_str(), 0)).await?.collect();
+            if let Some(marks) = marks {
+                *marks.dns.lock().unwrap() = Some(start.elapsed());
+            }
+            Ok::<Addrs, Box<dyn std::error::Error + Send + Sync>>(Box::new(addrs.into_iter()))
+        })
+    }
+}
+
+/// Verifier that notes when the ClientHello is built, which rustls does as
+/// soon as the TCP connection is up, and otherwise defers to `inner`
+#[derive(Debug)]
+struct TimedVerifier {
+    inner: Arc<dyn ServerCertVerifier>,
+}
+
+impl ServerCertVerifier for TimedVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        intermediates: &[CertificateDer<'_>],
+        server_name: &ServerName<'_>,
+        ocsp_response: &[u8],
+        now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        self.inner.verify_server_cert(end_entity, intermediates, server_name, ocsp_response, now)
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.inner.verify_tls12_signature(message, cert, dss)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.inner.verify_tls13_signature(message, cert, dss)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        // Only the first call counts; a HelloRetryRequest builds another ClientHello
+        let _ = CONNECTING.try_with(|marks| {
+            marks.client_hello.lock().unwrap().get_or_insert_with(Instant::now);
+        });
+        self.inner.supported_verify_schemes()
+    }
+}
+
+/// The aws-lc-rs provider with every cipher suite wrapped in [`Recorder`]
+fn recording_provider() -> Arc<CryptoProvider> {
+    static PROVIDER: OnceLock<Arc<CryptoProvider>> = OnceLock::new();
+    PROVIDER.get_or_init(|| {
+        let mut provider = rustls::crypto::aws_lc_rs::default_provider();
+        provider.cipher_suites = provider.cipher_suites.iter().map(|suite| recording(*suite)).collect();
+        Arc::new(provider)
+    }).clone()
+}
+
+/// Copy of `suite` whose AEAD records the suite; built once per process
+fn recording(suite: SupportedCipherSuite) -> SupportedCipherSuite {
+    let common = |common: &CipherSuiteCommon| CipherSuiteCommon {
+        suite: common.suite,
+        hash_provider: common.hash_provider,
+        confidentiality_limit: common.confidentiality_limit,
+    };
+    let cipher = suite.suite().as_str().unwrap_or("unknown");
+
+    match suite {
+        SupportedCipherSuite::Tls13(inner) => {
+            let session = TlsSession { version: "TLSv1.3", cipher, alpn: None };
+            SupportedCipherSuite::Tls13(Box::leak(Box::new(Tls13CipherSuite {
+                common: common(&inner.common),
+                hkdf_provider: inner.hkdf_provider,
+                aead_alg: Box::leak(Box::new(Recorder { inner: inner.aead_alg, session })),
+                quic: inner.quic,
+            })))
+        }
+        SupportedCipherSuite::Tls12(inner) => {
+            let session = TlsSession { version: "TLSv1.2", cipher, alpn: None };
+            SupportedCipherSuite::Tls12(Box::leak(Box::new(Tls12CipherSuite {
+                common: common(&inner.common),
+                prf_provider: inner.prf_provider,
+                kx: inner.kx,
+                sign: inner.sign,
+                aead_alg: Box::leak(Box::new(Recorder { inner: inner.aead_alg, session })),
+            })))
+        }
+    }
+}
+
+/// AEAD that tells the connection being set up which suite it belongs to;
+/// rustls only asks the negotiated suite for keys
+struct Recorder<A: ?Sized + 'static> {
+    inner: &'static A,
+    session: TlsSession,
+}
+
+impl<A: ?Sized + 'static> Recorder<A> {
+    fn record(&self) {
+        let _ = CONNECTING.try_with(|marks| *marks.session.lock().unwrap() = Some(self.session));
+    }
+}
+
+impl Tls13AeadAlgorithm for Recorder<dyn Tls13AeadAlgorithm> {
+    fn encrypter(&self, key: AeadKey, iv: Iv) -> Box<dyn MessageEncrypter> {
+        self.record();
+        self.inner.encrypter(key, iv)
+    }
+
+    fn decrypter(&self, key: AeadKey, iv: Iv) -> Box<dyn MessageDecrypter> {
+        self.record();
+        self.inner.decrypter(key, iv)
+    }
+
+    fn key_len(&self) -> usize {
+        self.inner.key_len()
+    }
+
+    fn extract_keys(&self, key: AeadKey, iv: Iv) -> Result<ConnectionTrafficSecrets, UnsupportedOperationError> {
+        self.inner.extract_keys(key, iv)
+    }
+
+    fn fips(&self) -> bool {
+        self.inner.fips()
+    }
+}
+
+impl Tls12AeadAlgorithm for Recorder<dyn Tls12AeadAlgorithm> {
+    fn encrypter(&self, key: AeadKey, iv: &[u8], extra: &[u8]) -> Box<dyn MessageEncrypter> {
+        self.record();
+        self.inner.encrypter(key, iv, extra)
+    }
+
+    fn decrypter(&self, key: AeadKey, iv: &[u8]) -> Box<dyn MessageDecrypter> {
+        self.record();
+        self.inner.decrypter(key, iv)
+    }
+
+    fn key_block_shape(&self) -> KeyBlockShape {
+        self.inner.key_block_shape()
+    }
+
+    fn extract_keys(
+        &self,
+        key: AeadKey,
+        iv: &[u8],
+        explicit: &[u8],
+    ) -> Result<ConnectionTrafficSecrets, UnsupportedOperationError> {
+        self.inner.extract_keys(key, iv, explicit)
+    }
+
+    fn fips(&self) -> bool {
+        self.inner.fips()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_marks_split_phases() {
+        let marks = Marks::new();
+        *marks.dns.lock().unwrap() = Some(Duration::from_millis(5));
+        std::thread::sleep(Duration::from_millis(20));
+        *marks.client_hello.lock().unwrap() = Some(Instant::now());
+        std::thread::sleep(Duration::from_millis(20));
+
+        let timing = marks.finish();
+        assert_eq!(timing.dns, Some(Duration::from_millis(5)));
+        assert!(timing.connect >= Duration::from_millis(15), "{:?}", timing);
+        assert!(timing.tls.unwrap() >= Duration::from_millis(20), "{:?}", timing);
+    }
+
+    #[test]
+    fn test_variables() {
+        let timing = RequestTiming {
+            opened: Some(ConnectTiming {
+                dns: Some(Duration::from_millis(2)),
+                connect: Duration::from_millis(3),
+                tls: Some(Duration::from_millis(5)),
+                session: Some(TlsSession { version: "TLSv1.3", cipher: "TLS13_AES_128_GCM_SHA256", alpn: Some("h2") }),
+            }),
+            ttfb: Duration::from_millis(30),
+            transfer: Duration::from_millis(4),
+            remote_addr: Some("10.0.0.1:443".parse().unwrap()),
+        };
+        assert_eq!(timing.variable("time_namelookup"), Some(json!(0.002)));
+        assert_eq!(timing.variable("time_connect"), Some(json!(0.005)));
+        assert_eq!(timing.variable("time_appconnect"), Some(json!(0.01)));
+        assert_eq!(timing.variable("time_total"), Some(json!(0.034)));
+        assert_eq!(timing.variable("remote_ip"), Some(json!("10.0.0.1")));
+        assert_eq!(timing.variable("tls_cipher"), Some(json!("TLS13_AES_128_GCM_SHA256")));
+        assert_eq!(timing.variable("alpn"), Some(json!("h2")));
+        assert_eq!(timing.variable("nope"), None);
+        assert_eq!(timing.wait(), Duration::from_millis(20));
+        assert_eq!(timing.to_json().as_object().unwrap().len(), VARIABLES.len());
+
+        let reused = RequestTiming { ttfb: Duration::from_millis(7), ..Default::default() };
+        assert!(reused.reused());
+        assert_eq!(reused.variable("time_connect"), Some(json!(0.0)));
+        assert_eq!(reused.variable("num_connects"), Some(json!(0)));
+        assert_eq!(reused.variable("tls_version"), Some(JsonValue::Null));
+    }
+
+    #[tokio::test]
+    async fn test_track_counts_new_connections() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let mut buf = [0u8; 1024];
+                    while let Ok(n) = socket.read(&mut buf).await {
+                        if n == 0 || socket.write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n").await.is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+        });
+
+        let client = instrument(reqwest::Client::builder(), &SslConfig::new(), ALPN_DEFAULT).unwrap().build().unwrap();
+        let url = format!("http://{}/", addr);
+
+        let (response, opened) = track(client.get(&url).send()).await;
+        assert!(response.unwrap().status().is_success());
+        let opened = opened.expect("first request opens a connection");
+        assert_eq!(opened.dns, None);
+        assert_eq!(opened.tls, None);
+
+        let (response, opened) = track(client.get(&url).send()).await;
+        assert!(response.unwrap().status().is_success());
+        assert_eq!(opened, None);
+    }
+
+    #[tokio::test]
+    async fn test_track_tls_session() {
+        use crate::mock::config::TlsConfig;
+        use crate::mock::routes::RouteConfig;
+        use crate::mock::{MockServer, MockServerConfig};
+
+        let port = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+        let mut config = MockServerConfig::new()
+            .with_port(port)
+            .add_route(RouteConfig::get("/secure", "secret"));
+        config.tls = Some(TlsConfig { self_signed: true, http2: true, ..Default::default() });
+        let server = MockServer::new(config).unwrap();
+        let mut ssl = SslConfig::new();
+        ssl.ca_bundle = server.ca_cert_path().map(|path| path.to_path_buf());
+        tokio::spawn(async move {
+            let _ = server.run().await;
+        });
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        let client = instrument(reqwest::Client::builder(), &ssl, ALPN_DEFAULT).unwrap().build().unwrap();
+        let sent = Instant::now();
+        let (response, opened) = track(client.get(format!("https://localhost:{}/secure", port)).send()).await;
+        let response = response.unwrap();
+        let timing = RequestTiming::new(opened, sent.elapsed(), &response);
+
+        let opened = timing.opened.expect("first request opens a connection");
+        assert!(opened.dns.is_some());
+        assert!(opened.tls.is_some());
+        assert_eq!(opened.session.unwrap().version, "TLSv1.3");
+        assert!(opened.session.unwrap().cipher.starts_with("TLS13_"));
+        assert_eq!(opened.session.unwrap().alpn, Some("h2"));
+        assert_eq!(timing.remote_addr.unwrap().port(), port);
+
+        let client = instrument(reqwest::Client::builder(), &ssl, &["http/1.1"]).unwrap().build().unwrap();
+        let (response, opened) = track(client.get(format!("https://localhost:{}/secure", port)).send()).await;
+        assert!(response.unwrap().status().is_success());
+        assert_eq!(opened.unwrap().session.unwrap().alpn, Some("http/1.1"));
+    }
+}
@@ -29,6 +29,7 @@ fn json_to_deterministic_format(value: &serde_json::Value) -> String {
 use std::time::Instant;
 
 use crate::bench::run_benchmark;
+use crate::bench::distributed::run_worker;
 use crate::cli::{Args, process_args};
 use crate::devexp::{generate_code, generate_curl_command, format_curl_pretty, import_curl, EnvVars};
 use crate::fuzz::run_fuzz;
@@ -39,6 +40,7 @@ use crate::pipeline::{run_workflow, handle_workflow_commands};
 use crate::websocket::{is_ws_request, run_websocket};
 // PrettyOption is now shared between cli::args and output::writer
 use crate::client::{send_request_with_session, check_status, IntermediateResponse, run_http3};
+use crate::client::timing::RequestTiming;
 use crate::config::Config;
 use crate::context::Environment;
 use crate::downloads::Downloader;
@@ -46,6 +48,8 @@ use crate::errors::QuicpulseError;
 use crate::filter;
 use crate::internal;
 use crate::output::formatters::{ColorFormatter, ColorStyle, format_json, JsonFormatterOptions};
+use crate::output::lexers::metadata::format_metadata;
+use crate::output::write_out::{WriteOut, WriteOutContext};
 use crate::output::pager::{PagerConfig, write_with_pager};
 use crate::output::writer::{OutputOptions, ProcessingOptions, PrettyOption};
 use crate::pipeline;
@@ -214,6 +218,11 @@ pub async fn program(args: Args, env: Environment) -> Result<ExitStatus, Quicpul
         return run_mock_server(&args).await;
     }
 
+    // Handle benchmark worker
+    if args.bench_worker {
+        return run_worker(&args).await;
+    }
+
     // Handle plugin commands
     if args.plugin_list {
         return handle_plugin_list(&args).await;
@@ -319,6 +328,8 @@ pub async fn program(args: Args, env: Environment) -> Result<ExitStatus, Quicpul
 
     let proc_opts = build_processing_options(&args, &env);
 
+    let write_out = args.write_out.as_deref().map(WriteOut::parse).transpose()?;
+
     if args.verbose > 0 {
         print_request(&processed, &args, &env, &proc_opts);
     }
@@ -355,8 +366,11 @@ pub async fn program(args: Args, env: Environment) -> Result<ExitStatus, Quicpul
 
     let response_time = request_start.elapsed();
 
-    let status_code = result.response.status().as_u16();
+    let response_status = result.response.status();
+    let status_code = response_status.as_u16();
     let response_headers = result.response.headers().clone();
+    let response_version = result.response.version();
+    let response_url = result.response.url().to_string();
 
     if let Some(ref mut sess) = session {
         update_session_from_response(sess, &response_headers, &host);
@@ -374,13 +388,33 @@ pub async fn program(args: Args, env: Environment) -> Result<ExitStatus, Quicpul
         }
     }
 
+    let mut timing = result.timing;
     let response_body = if let Some(ref mut dl) = downloader {
+        let transfer_start = Instant::now();
         download_response(result.response, dl, &args, &env).await?;
+        timing.transfer = transfer_start.elapsed();
+        if build_output_options(&args, &env).metadata && args.quiet == 0 {
+            println!("{}", format_metadata(&timing));
+        }
         String::new()
     } else {
-        print_response_with_body(result.response, &args, &env).await?
+        print_response_with_body(result.response, &mut timing, &args, &env).await?
     };
 
+    if let Some(ref write_out) = write_out {
+        let ctx = WriteOutContext {
+            status: response_status,
+            version: response_version,
+            method: &processed.method,
+            url: &response_url,
+            headers: &response_headers,
+            body: &response_body,
+            num_redirects: result.num_redirects,
+            timing: &timing,
+        };
+        write_out.write(&ctx, args.write_out_file.as_deref())?;
+    }
+
     save_session(&args, session, &host, &config)?;
 
     if pipeline::has_assertions(&args) {
@@ -1216,6 +1250,10 @@ fn build_output_options(args: &Args, env: &Environment) -> OutputOptions {
         return OutputOptions::from_str(print_str);
     }
 
+    if args.meta {
+        return OutputOptions::from_str("m");
+    }
+
     if args.headers_only {
         return OutputOptions::headers_only();
     }
@@ -1338,7 +1376,7 @@ fn format_response_body(
     }
 }
 
-fn merge_default_options(args: Vec<String>, config: &Config) -> Vec<String> {
+pub(crate) fn merge_default_options(args: Vec<String>, config: &Config) -> Vec<String> {
     if config.default_options.is_empty() {
         return args;
     }
@@ -1597,6 +1635,7 @@ fn process_response_body(
 
 async fn print_response_with_body(
     response: reqwest::Response,
+    timing: &mut RequestTiming,
     args: &Args,
     env: &Environment,
 ) -> Result<String, QuicpulseError> {
@@ -1661,7 +1700,9 @@ async fn print_response_with_body(
     }
 
     const MAX_BODY_SIZE: u64 = 100 * 1024 * 1024;
+    let transfer_start = Instant::now();
     let body = read_body_with_limit(response, MAX_BODY_SIZE, env.stdout_isatty).await?;
+    timing.transfer = transfer_start.elapsed();
 
     if output_opts.response_body && args.quiet < 2 {
         if !body.is_empty() {
@@ -1681,6 +1722,18 @@ async fn print_response_with_body(
         }
     }
 
+    if output_opts.metadata && args.quiet == 0 {
+        let metadata = format_metadata(timing);
+        let separator = if output_opts.response_headers || output_opts.response_body { "\n" } else { "" };
+        if pager_config.enabled {
+            output_buffer.push_str(separator);
+            output_buffer.push_str(&metadata);
+            output_buffer.push('\n');
+        } else {
+            println!("{}{}", separator, metadata);
+        }
+    }
+
     // Write through pager if enabled
     if pager_config.enabled && !output_buffer.is_empty() {
         let mut stdout = std::io::stdout();
@@ -2078,8 +2131,14 @@ fn merge_curl_args(cli_args: Args, curl_args: Args) -> Args {
 }
 
 fn expand_args_variables(mut args: Args, env_vars: &EnvVars) -> Result<Args, QuicpulseError> {
+    // With --bench-data, undefined names are column placeholders filled per request
+    let templated = args.bench && args.bench_data.is_some();
+    let expand_template = |input: &str| -> Result<String, QuicpulseError> {
+        if templated { Ok(env_vars.expand_known(input)) } else { env_vars.expand(input) }
+    };
+
     if let Some(ref url) = args.url {
-        args.url = Some(env_vars.expand(url)?);
+        args.url = Some(expand_template(url)?);
     }
 
     if let Some(ref method) = args.method {
@@ -2090,7 +2149,7 @@ fn expand_args_variables(mut args: Args, env_vars: &EnvVars) -> Result<Args, Qui
 
     let mut expanded_items = Vec::with_capacity(args.request_items.len());
     for item in &args.request_items {
-        expanded_items.push(env_vars.expand(item)?);
+        expanded_items.push(expand_template(item)?);
     }
     args.request_items = expanded_items;
 
@@ -2142,6 +2201,64 @@ async fn run_mock_server(args: &Args) -> Result<ExitStatus, QuicpulseError> {
         config.cors = true;
     }
 
+    // Record requests to file if specified
+    if let Some(ref record_path) = args.mock_record {
+        config.record_to = Some(record_path.to_string_lossy().to_string());
+    }
+
+    // Bound the admin API's request journal if specified
+    if let Some(limit) = args.mock_journal_limit {
+        config.journal_limit = limit;
+    }
+
+    // Cap request bodies if specified
+    if let Some(bytes) = args.mock_max_body {
+        config.max_body_bytes = bytes;
+    }
+
+    // Serve the admin API if requested; a token implies it
+    if args.mock_admin {
+        config.admin = true;
+    }
+    if let Some(ref token) = args.mock_admin_token {
+        config.admin = true;
+        config.admin_token = Some(token.clone());
+    }
+
+    // Serve HTTPS if a certificate was given or requested
+    match (&args.mock_tls_cert, &args.mock_tls_key) {
+        (Some(cert), Some(key)) => {
+            let tls = config.tls.get_or_insert_with(Default::default);
+            tls.cert = cert.to_string_lossy().to_string();
+            tls.key = key.to_string_lossy().to_string();
+            tls.self_signed = false;
+        }
+        (None, None) => {}
+        _ => {
+            return Err(QuicpulseError::Argument(
+                "--mock-tls-cert and --mock-tls-key must be used together".to_string()
+            ));
+        }
+    }
+    if args.mock_tls_self_signed {
+        config.tls.get_or_insert_with(Default::default).self_signed = true;
+    }
+    if args.mock_tls_h2 {
+        match config.tls {
+            Some(ref mut tls) => tls.http2 = true,
+            None => {
+                return Err(QuicpulseError::Argument(
+                    "--mock-tls-h2 requires --mock-tls-cert/--mock-tls-key or --mock-tls-self-signed".to_string()
+                ));
+            }
+        }
+    }
+
+    // Forward unmatched requests upstream if specified
+    if let Some(ref upstream) = args.mock_proxy {
+        config.proxy_to = Some(upstream.clone());
+    }
+
     // Parse latency if specified
     if let Some(ref latency) = args.mock_latency {
         if let Some((min_str, max_str)) = latency.split_once('-') {
@@ -2206,11 +2323,22 @@ async fn run_mock_server(args: &Args) -> Result<ExitStatus, QuicpulseError> {
             priority: 0,
             enabled: true,
             name: None,
+            ..Default::default()
         });
     }
 
+    // Generate routes from an OpenAPI spec; added last so explicit routes win ties
+    if let Some(ref spec_path) = args.mock_openapi {
+        let spec = crate::openapi::parse_spec(spec_path)?;
+        let routes = crate::mock::openapi::routes_from_spec(&spec, args.mock_validate);
+        eprintln!("Loaded {} routes from {} v{}", routes.len(), spec.title, spec.version);
+        config.routes.extend(routes);
+    } else if args.mock_validate {
+        return Err(QuicpulseError::Argument("--mock-validate requires --mock-openapi".to_string()));
+    }
+
     // If no routes configured, add a default echo endpoint
-    if config.routes.is_empty() {
+    if config.routes.is_empty() && config.proxy_to.is_none() {
         eprintln!("No routes configured. Use --mock-route to add routes.");
         eprintln!("Example: --mock-route 'GET:/api/hello:{{\"message\":\"Hello, World!\"}}'");
         eprintln!("         --mock-route 'POST:/api/echo:{{{{body}}}}'");
@@ -124,6 +124,11 @@ impl EnvVars {
     pub fn expand(&self, input: &str) -> Result<String, QuicpulseError> {
         expand_variables(input, &self.vars)
     }
+
+    /// Expand {{variable}} syntax, leaving undefined variables in place
+    pub fn expand_known(&self, input: &str) -> String {
+        substitute_variables(input, &self.vars).0
+    }
 }
 
 /// Parse a single .env line into key-value pair
@@ -189,6 +194,21 @@ fn unescape_double_quoted(s: &str) -> String {
 /// Expand {{variable}} syntax in a string
 /// Uses SIMD-optimized cached regex for performance
 pub fn expand_variables(input: &str, vars: &HashMap<String, String>) -> Result<String, QuicpulseError> {
+    let (result, missing) = substitute_variables(input, vars);
+
+    // If there are missing variables without defaults, return error
+    if !missing.is_empty() {
+        return Err(QuicpulseError::Config(format!(
+            "Undefined environment variables: {}",
+            missing.join(", ")
+        )));
+    }
+
+    Ok(result)
+}
+
+/// Replace known variables, keeping unknown ones; returns the names left unexpanded
+fn substitute_variables(input: &str, vars: &HashMap<String, String>) -> (String, Vec<String>) {
     let mut result = input.to_string();
     let mut missing: Vec<String> = Vec::new();
 
@@ -219,15 +239,7 @@ pub fn expand_variables(input: &str, vars: &HashMap<String, String>) -> Result<S
         result = result.replacen(&pattern, &value, 1);
     }
 
-    // If there are missing variables without defaults, return error
-    if !missing.is_empty() {
-        return Err(QuicpulseError::Config(format!(
-            "Undefined environment variables: {}",
-            missing.join(", ")
-        )));
-    }
-
-    Ok(result)
+    (result, missing)
 }
 
 /// Check if a string contains {{variable}} syntax
@@ -329,6 +341,13 @@ OTHER=test
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_expand_known_keeps_missing() {
+        let mut env = EnvVars::new();
+        env.set("HOST".to_string(), "api.example.com".to_string());
+        assert_eq!(env.expand_known("{{HOST}}/users/{{QP_TEST_UNSET_ID}}"), "api.example.com/users/{{QP_TEST_UNSET_ID}}");
+    }
+
     #[test]
     fn test_has_variables() {
         assert!(has_variables("{{VAR}}"));
@@ -37,6 +37,11 @@ pub fn apply_filter(json: &JsonValue, filter_expr: &str) -> Result<Vec<JsonValue
     Ok(output)
 }
 
+/// Check that a JQ filter expression parses, without running it
+pub fn check_filter(filter_expr: &str) -> Result<(), QuicpulseError> {
+    parse_filter(filter_expr).map(|_| ())
+}
+
 /// Parse a JQ filter expression
 fn parse_filter(expr: &str) -> Result<jaq_core::Filter<jaq_core::Native<Val>>, QuicpulseError> {
     use jaq_core::load::{Arena, File, Loader};
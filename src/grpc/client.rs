@@ -156,20 +156,7 @@ impl GrpcClient {
         request_json: &JsonValue,
     ) -> Result<GrpcResponse, QuicpulseError> {
         // Bug #6 fix: Try to load schema via reflection if not available
-        if self.schema.is_none() || self.schema.as_ref().and_then(|s| s.grpc_schema()).is_none() {
-            // Try to fetch schema from server reflection
-            match super::reflection::fetch_schema_for_service(self.channel.clone(), service).await {
-                Ok(grpc_schema) => {
-                    // Create a ProtoSchema wrapper with the GrpcSchema
-                    let mut proto_schema = ProtoSchema::default();
-                    proto_schema.set_grpc_schema(grpc_schema);
-                    self.schema = Some(proto_schema);
-                }
-                Err(_) => {
-                    // Reflection failed - will error below if no schema
-                }
-            }
-        }
+        self.ensure_schema(service).await;
 
         // Try to encode using GrpcSchema if available
         let request_bytes = if let Some(ref schema) = self.schema {
@@ -264,6 +251,41 @@ impl GrpcClient {
         }
     }
 
+    /// Fetch the schema by server reflection when none is loaded; on failure
+    /// the schema stays missing and encoding reports it
+    async fn ensure_schema(&mut self, service: &str) {
+        if self.schema.as_ref().and_then(|s| s.grpc_schema()).is_some() {
+            return;
+        }
+        if let Ok(grpc_schema) = super::reflection::fetch_schema_for_service(self.channel.clone(), service).await {
+            // Create a ProtoSchema wrapper with the GrpcSchema
+            let mut proto_schema = ProtoSchema::default();
+            proto_schema.set_grpc_schema(grpc_schema);
+            self.schema = Some(proto_schema);
+        }
+    }
+
+    /// Encode a unary call once so it can be sent many times, as benchmarks do
+    pub async fn prepare_unary(
+        &mut self,
+        service: &str,
+        method: &str,
+        request_json: &JsonValue,
+    ) -> Result<UnaryCall, QuicpulseError> {
+        self.ensure_schema(service).await;
+        let body = self.encode_request(service, method, request_json)?;
+        let path = format!("/{}/{}", service, method).parse()
+            .map_err(|e| QuicpulseError::Argument(format!("Invalid gRPC path: {}", e)))?;
+
+        Ok(UnaryCall {
+            channel: self.channel.clone(),
+            path,
+            body,
+            metadata: self.metadata.clone(),
+            timeout: self.timeout,
+        })
+    }
+
     /// Get the GrpcSchema if available
     pub fn grpc_schema(&self) -> Option<&GrpcSchema> {
         self.schema.as_ref().and_then(|s| s.grpc_schema())
@@ -638,6 +660,34 @@ impl GrpcRequest {
     }
 }
 
+/// A unary call encoded up front; cheap to send repeatedly and concurrently
+#[derive(Debug, Clone)]
+pub struct UnaryCall {
+    channel: Channel,
+    path: http::uri::PathAndQuery,
+    body: Bytes,
+    metadata: MetadataMap,
+    timeout: Option<Duration>,
+}
+
+impl UnaryCall {
+    /// Send the call, returning the size of the response message
+    pub async fn send(&self) -> Result<usize, Status> {
+        let mut client = tonic::client::Grpc::new(self.channel.clone());
+        client.ready().await
+            .map_err(|e| Status::unavailable(e.to_string()))?;
+
+        let mut request = tonic::Request::new(RawMessage(self.body.clone()));
+        *request.metadata_mut() = self.metadata.clone();
+        if let Some(t) = self.timeout {
+            request.set_timeout(t);
+        }
+
+        let response = client.unary(request, self.path.clone(), RawCodec).await?;
+        Ok(response.into_inner().0.len())
+    }
+}
+
 /// A gRPC response
 #[derive(Debug, Clone)]
 pub struct GrpcResponse {
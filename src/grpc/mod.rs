@@ -13,8 +13,10 @@ pub use interactive::run_interactive;
 
 use crate::cli::Args;
 use crate::cli::parser::ProcessedArgs;
+use crate::client::ssl::SslConfig;
 use crate::context::Environment;
 use crate::errors::QuicpulseError;
+use crate::input::InputItem;
 use crate::output::terminal::{self, colors, RESET};
 use crate::output::formatters::{ColorFormatter, ColorStyle};
 use crate::status::ExitStatus;
@@ -153,16 +155,9 @@ mod tests {
     }
 }
 
-pub async fn run_grpc(
-    args: &Args,
-    processed: &ProcessedArgs,
-    _env: &Environment,
-) -> Result<ExitStatus, QuicpulseError> {
-    use crate::input::InputItem;
-
-    let endpoint = parse_grpc_endpoint(&processed.url)?;
-
-    let headers: Vec<(String, String)> = processed.items.iter()
+/// Metadata from the header items
+pub fn request_metadata(items: &[InputItem]) -> Vec<(String, String)> {
+    items.iter()
         .filter_map(|item| {
             match item {
                 InputItem::Header { name, value } => Some((name.clone(), value.clone())),
@@ -173,19 +168,62 @@ pub async fn run_grpc(
                 _ => None,
             }
         })
-        .collect();
-
-    let timeout = args.timeout.map(|t| std::time::Duration::from_secs_f64(t));
+        .collect()
+}
 
-    use crate::client::ssl::SslConfig;
-    let ssl_config = SslConfig::from_args(
+/// TLS settings from the SSL arguments
+pub fn ssl_config(args: &Args) -> SslConfig {
+    SslConfig::from_args(
         &args.verify,
         args.ssl.as_deref(),
         args.ciphers.as_deref(),
         args.cert.as_ref().and_then(|p| p.to_str()),
         args.cert_key.as_ref().and_then(|p| p.to_str()),
         args.cert_key_pass.as_deref(),
-    );
+    )
+}
+
+/// Request message from `--raw`, or else from the data and JSON items
+pub fn request_json(args: &Args, items: &[InputItem]) -> Result<serde_json::Value, QuicpulseError> {
+    if let Some(ref raw_body) = args.raw {
+        return serde_json::from_str(raw_body)
+            .map_err(|e| QuicpulseError::Argument(format!("Invalid JSON body: {}", e)));
+    }
+
+    let mut obj = serde_json::Map::new();
+    for item in items {
+        let (key, value) = match item {
+            InputItem::DataField { key, value } => {
+                (key.clone(), serde_json::json!(value))
+            }
+            InputItem::DataFieldFile { key, path } => {
+                let content = std::fs::read_to_string(path).unwrap_or_default();
+                (key.clone(), serde_json::json!(content.trim()))
+            }
+            InputItem::JsonField { key, value } => {
+                (key.clone(), value.clone())
+            }
+            InputItem::JsonFieldFile { key, path } => {
+                let content = std::fs::read_to_string(path).unwrap_or_default();
+                let json_val = serde_json::from_str(&content).unwrap_or(serde_json::json!(content));
+                (key.clone(), json_val)
+            }
+            _ => continue,
+        };
+        obj.insert(key, value);
+    }
+    Ok(serde_json::Value::Object(obj))
+}
+
+pub async fn run_grpc(
+    args: &Args,
+    processed: &ProcessedArgs,
+    _env: &Environment,
+) -> Result<ExitStatus, QuicpulseError> {
+    let endpoint = parse_grpc_endpoint(&processed.url)?;
+    let headers = request_metadata(&processed.items);
+    let timeout = args.timeout.map(|t| std::time::Duration::from_secs_f64(t));
+    let ssl_config = ssl_config(args);
 
     let mut client = client::GrpcClient::connect_with_options(endpoint.clone(), timeout, Some(headers), Some(&ssl_config)).await?;
 
@@ -286,34 +324,7 @@ pub async fn run_grpc(
             "gRPC call requires service and method. Use format: grpc://host:port/package.Service/Method".to_string()
         ))?;
 
-    let request_json = if let Some(ref raw_body) = args.raw {
-        serde_json::from_str(raw_body)
-            .map_err(|e| QuicpulseError::Argument(format!("Invalid JSON body: {}", e)))?
-    } else {
-        let mut obj = serde_json::Map::new();
-        for item in &processed.items {
-            let (key, value) = match item {
-                InputItem::DataField { key, value } => {
-                    (key.clone(), serde_json::json!(value))
-                }
-                InputItem::DataFieldFile { key, path } => {
-                    let content = std::fs::read_to_string(path).unwrap_or_default();
-                    (key.clone(), serde_json::json!(content.trim()))
-                }
-                InputItem::JsonField { key, value } => {
-                    (key.clone(), value.clone())
-                }
-                InputItem::JsonFieldFile { key, path } => {
-                    let content = std::fs::read_to_string(path).unwrap_or_default();
-                    let json_val = serde_json::from_str(&content).unwrap_or(serde_json::json!(content));
-                    (key.clone(), json_val)
-                }
-                _ => continue,
-            };
-            obj.insert(key, value);
-        }
-        serde_json::Value::Object(obj)
-    };
+    let request_json = request_json(args, &processed.items)?;
 
     if args.verbose > 0 {
         eprintln!("{}: {}/{}", terminal::info("gRPC Call"), terminal::label(&service), terminal::value(&method));
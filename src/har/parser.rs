@@ -8,7 +8,7 @@ use std::sync::RwLock;
 use std::collections::HashMap;
 use once_cell::sync::Lazy;
 use crate::errors::QuicpulseError;
-use super::types::Har;
+use super::types::{Har, HarEntry, HarLog};
 
 /// Bug #50 fix: Cache for compiled regex patterns to avoid recompilation
 /// Uses RwLock to allow concurrent reads with occasional writes
@@ -51,9 +51,39 @@ pub fn load_har(path: &Path) -> Result<Har, QuicpulseError> {
 }
 
 /// Parse HAR from JSON string
+///
+/// Also accepts newline-delimited JSON with one HAR entry per line, as
+/// written by the mock server's NDJSON recorder.
 pub fn parse_har(json: &str) -> Result<Har, QuicpulseError> {
-    serde_json::from_str(json).map_err(|e| {
-        QuicpulseError::Parse(format!("Invalid HAR format: {}", e))
+    match serde_json::from_str(json) {
+        Ok(har) => Ok(har),
+        Err(e) => parse_ndjson_entries(json).ok_or_else(|| {
+            QuicpulseError::Parse(format!("Invalid HAR format: {}", e))
+        }),
+    }
+}
+
+/// Parse newline-delimited HAR entries into a HAR document
+fn parse_ndjson_entries(content: &str) -> Option<Har> {
+    let entries = content.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| serde_json::from_str::<HarEntry>(line).ok())
+        .collect::<Option<Vec<_>>>()?;
+
+    if entries.is_empty() {
+        return None;
+    }
+
+    Some(Har {
+        log: HarLog {
+            version: "1.2".to_string(),
+            creator: None,
+            browser: None,
+            entries,
+            pages: None,
+            comment: None,
+        },
     })
 }
 
@@ -233,6 +263,22 @@ mod tests {
         assert_eq!(har.log.entries.len(), 2);
     }
 
+    #[test]
+    fn test_parse_ndjson_entries() {
+        let har = parse_har(&create_test_har()).unwrap();
+        let ndjson = har.log.entries.iter()
+            .map(|e| serde_json::to_string(e).unwrap())
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let parsed = parse_har(&ndjson).unwrap();
+        assert_eq!(parsed.log.entries.len(), 2);
+        assert_eq!(parsed.log.entries[1].request.method, "POST");
+
+        assert!(parse_har("not json").is_err());
+        assert!(parse_har("").is_err());
+    }
+
     #[test]
     fn test_filter_entries() {
         let json = create_test_har();
@@ -0,0 +1,235 @@
+//! Admin API for the mock server
+//!
+//! Off unless the config sets `admin` (`--mock-admin`). Requests under
+//! [`ADMIN_PREFIX`] are then answered by the server itself and never reach the
+//! configured routes, the request journal or the recorder. With `admin_token`
+//! set, they need an `Authorization: Bearer <token>` header.
+//!
+//! | Endpoint | Description |
+//! |----------|-------------|
+//! | `GET /__admin/routes` | List routes with their ids |
+//! | `POST /__admin/routes` | Add a route (JSON `RouteConfig`) |
+//! | `DELETE /__admin/routes/{id}` | Remove a route |
+//! | `GET /__admin/requests` | Request journal |
+//! | `DELETE /__admin/requests` | Clear the journal |
+//! | `POST /__admin/requests/verify` | Count journal entries matching a pattern |
+//! | `GET /__admin/scenarios` | Current scenario states |
+//! | `POST /__admin/scenarios/reset` | Reset scenarios and response sequences |
+//! | `POST /__admin/reset` | Reset scenarios and clear the journal |
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use super::config::MockServerConfig;
+use super::routes::{HttpMethod, RequestMatcher, Route, RouteConfig};
+use super::server::ConnectionContext;
+
+/// Path prefix reserved for admin endpoints
+pub const ADMIN_PREFIX: &str = "/__admin";
+
+/// Status and JSON body of an admin response
+pub(super) struct AdminResponse {
+    pub status: u16,
+    pub body: Value,
+}
+
+impl AdminResponse {
+    fn ok(body: Value) -> Self {
+        Self { status: 200, body }
+    }
+
+    fn error(status: u16, message: impl Into<String>) -> Self {
+        Self { status, body: json!({ "error": message.into() }) }
+    }
+}
+
+/// Pattern for counting journal entries
+#[derive(Debug, Deserialize)]
+pub struct VerifyRequest {
+    /// Method to match (any method when omitted)
+    #[serde(default)]
+    pub method: HttpMethod,
+
+    /// Path pattern, with the same syntax as route paths
+    pub path: String,
+
+    /// Header, query and body matchers, with the same syntax as route `match` blocks
+    #[serde(flatten)]
+    pub matcher: RequestMatcher,
+
+    /// Expected number of matching requests
+    #[serde(default)]
+    pub count: Option<usize>,
+}
+
+/// Whether a path belongs to the admin API
+pub fn is_admin_path(path: &str) -> bool {
+    path == ADMIN_PREFIX || path.starts_with(&format!("{}/", ADMIN_PREFIX))
+}
+
+/// Whether an `Authorization` header satisfies the configured admin token
+pub fn authorized(config: &MockServerConfig, authorization: Option<&str>) -> bool {
+    let Some(ref expected) = config.admin_token else {
+        return true;
+    };
+    let given = authorization.and_then(|value| value.strip_prefix("Bearer ")).unwrap_or("");
+    // Compare in constant time so the token cannot be guessed byte by byte
+    expected.len() == given.len()
+        && expected.bytes().zip(given.bytes()).fold(0, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+/// Handle an admin request
+pub(super) async fn handle(
+    ctx: &ConnectionContext,
+    method: &str,
+    path: &str,
+    authorization: Option<&str>,
+    body: &str,
+) -> AdminResponse {
+    if !authorized(&ctx.config, authorization) {
+        return AdminResponse::error(401, "Missing or wrong admin token");
+    }
+    let endpoint = path[ADMIN_PREFIX.len()..].trim_end_matches('/');
+
+    match (method, endpoint) {
+        ("GET", "/routes") => {
+            let routes: Vec<Value> = ctx.routes.read().await.iter().map(route_json).collect();
+            AdminResponse::ok(json!({ "routes": routes }))
+        }
+        ("POST", "/routes") => add_route(ctx, body).await,
+        ("DELETE", id) if id.starts_with("/routes/") => remove_route(ctx, &id["/routes/".len()..]).await,
+
+        ("GET", "/requests") => {
+            let requests = ctx.request_log.all();
+            AdminResponse::ok(json!({ "total": requests.len(), "limit": ctx.request_log.limit(), "requests": requests }))
+        }
+        ("DELETE", "/requests") => {
+            ctx.request_log.clear();
+            AdminResponse::ok(json!({ "cleared": true }))
+        }
+        ("POST", "/requests/verify") => verify(ctx, body).await,
+
+        ("GET", "/scenarios") => AdminResponse::ok(json!({ "scenarios": ctx.scenarios.lock().all() })),
+        ("POST", "/scenarios/reset") => {
+            ctx.reset_scenarios().await;
+            AdminResponse::ok(json!({ "reset": true }))
+        }
+        ("POST", "/reset") => {
+            ctx.reset_scenarios().await;
+            ctx.request_log.clear();
+            AdminResponse::ok(json!({ "reset": true }))
+        }
+
+        _ => AdminResponse::error(404, format!("Unknown admin endpoint: {} {}", method, path)),
+    }
+}
+
+/// Route config plus its id
+fn route_json(route: &Route) -> Value {
+    let mut value = serde_json::to_value(&route.config).unwrap_or_else(|_| json!({}));
+    if let Value::Object(ref mut map) = value {
+        map.insert("id".to_string(), Value::String(route.id.clone()));
+    }
+    value
+}
+
+async fn add_route(ctx: &ConnectionContext, body: &str) -> AdminResponse {
+    let config: RouteConfig = match serde_json::from_str(body) {
+        Ok(config) => config,
+        Err(e) => return AdminResponse::error(400, format!("Invalid route: {}", e)),
+    };
+    // Serving files would let any admin client read the server's filesystem
+    if std::iter::once(&config.response).chain(&config.responses).any(|response| response.body_file.is_some()) {
+        return AdminResponse::error(400, "Invalid route: body_file is not allowed in routes added at runtime");
+    }
+    let route = match Route::new(config) {
+        Ok(route) => route,
+        Err(e) => return AdminResponse::error(400, format!("Invalid route: {}", e)),
+    };
+
+    let response = AdminResponse { status: 201, body: route_json(&route) };
+    if ctx.config.log_requests {
+        eprintln!("Admin: added route {} {:?} {}", route.id, route.config.method, route.config.path);
+    }
+    ctx.routes.write().await.push(route);
+    response
+}
+
+async fn remove_route(ctx: &ConnectionContext, id: &str) -> AdminResponse {
+    let mut routes = ctx.routes.write().await;
+    match routes.iter().position(|route| route.id == id) {
+        Some(index) => {
+            let route = routes.remove(index);
+            if ctx.config.log_requests {
+                eprintln!("Admin: removed route {} {:?} {}", route.id, route.config.method, route.config.path);
+            }
+            AdminResponse::ok(route_json(&route))
+        }
+        None => AdminResponse::error(404, format!("No route with id {}", id)),
+    }
+}
+
+async fn verify(ctx: &ConnectionContext, body: &str) -> AdminResponse {
+    let request: VerifyRequest = match serde_json::from_str(body) {
+        Ok(request) => request,
+        Err(e) => return AdminResponse::error(400, format!("Invalid verification: {}", e)),
+    };
+
+    let pattern = match Route::new(RouteConfig {
+        method: request.method,
+        path: request.path,
+        matcher: request.matcher,
+        ..Default::default()
+    }) {
+        Ok(pattern) => pattern,
+        Err(e) => return AdminResponse::error(400, format!("Invalid verification: {}", e)),
+    };
+
+    let count = ctx.request_log.count(|info| pattern.matches_request(info).is_some());
+    let verified = request.count.is_none_or(|expected| expected == count);
+
+    AdminResponse::ok(json!({
+        "count": count,
+        "expected": request.count,
+        "verified": verified,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_admin_path() {
+        assert!(is_admin_path("/__admin"));
+        assert!(is_admin_path("/__admin/scenarios"));
+        assert!(!is_admin_path("/__administrator"));
+        assert!(!is_admin_path("/api/__admin"));
+    }
+
+    #[test]
+    fn test_authorized() {
+        let mut config = MockServerConfig::new();
+        assert!(authorized(&config, None));
+
+        config.admin_token = Some("secret".to_string());
+        assert!(authorized(&config, Some("Bearer secret")));
+        assert!(!authorized(&config, Some("Bearer secreT")));
+        assert!(!authorized(&config, Some("secret")));
+        assert!(!authorized(&config, None));
+    }
+
+    #[test]
+    fn test_verify_request_flattened_matchers() {
+        let request: VerifyRequest = serde_json::from_str(
+            r#"{"method": "POST", "path": "/orders", "body_regex": "sku", "count": 2}"#
+        ).unwrap();
+        assert_eq!(request.method, HttpMethod::Post);
+        assert_eq!(request.matcher.body_regex.as_deref(), Some("sku"));
+        assert_eq!(request.count, Some(2));
+
+        let request: VerifyRequest = serde_json::from_str(r#"{"path": "/health"}"#).unwrap();
+        assert_eq!(request.method, HttpMethod::Any);
+        assert!(request.matcher.is_empty());
+    }
+}
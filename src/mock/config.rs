@@ -1,7 +1,7 @@
 //! Mock server configuration
 
 use serde::{Deserialize, Serialize};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use crate::errors::QuicpulseError;
 use super::routes::RouteConfig;
 
@@ -28,6 +28,22 @@ pub struct MockServerConfig {
     #[serde(default)]
     pub record_to: Option<String>,
 
+    /// Most recent requests kept in the journal for the admin API; 0 disables it
+    #[serde(default = "default_journal_limit")]
+    pub journal_limit: usize,
+
+    /// Largest request body accepted, in bytes; larger ones get a 413
+    #[serde(default = "default_max_body_bytes")]
+    pub max_body_bytes: usize,
+
+    /// Serve the admin API under `/__admin`
+    #[serde(default)]
+    pub admin: bool,
+
+    /// Bearer token the admin API requires
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub admin_token: Option<String>,
+
     /// Default response for unmatched routes
     #[serde(default)]
     pub default_response: Option<super::routes::ResponseConfig>,
@@ -47,6 +63,14 @@ pub struct MockServerConfig {
     /// Latency simulation (min, max) in milliseconds
     #[serde(default)]
     pub latency: Option<(u64, u64)>,
+
+    /// File the config was loaded from; watched for changes while the server runs
+    #[serde(skip)]
+    pub source: Option<PathBuf>,
+
+    /// Number of leading `routes` that came from `source` and are replaced on reload
+    #[serde(skip)]
+    pub source_routes: usize,
 }
 
 fn default_host() -> String {
@@ -61,13 +85,29 @@ fn default_true() -> bool {
     true
 }
 
+fn default_journal_limit() -> usize {
+    super::journal::DEFAULT_JOURNAL_LIMIT
+}
+
+fn default_max_body_bytes() -> usize {
+    super::server::DEFAULT_MAX_BODY_BYTES
+}
+
 /// TLS configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct TlsConfig {
     /// Path to certificate file
+    #[serde(default)]
     pub cert: String,
     /// Path to private key file
+    #[serde(default)]
     pub key: String,
+    /// Generate a throwaway self-signed certificate instead of loading cert/key
+    #[serde(default)]
+    pub self_signed: bool,
+    /// Advertise HTTP/2 via ALPN
+    #[serde(default)]
+    pub http2: bool,
 }
 
 impl Default for MockServerConfig {
@@ -78,11 +118,17 @@ impl Default for MockServerConfig {
             log_requests: true,
             cors: false,
             record_to: None,
+            journal_limit: default_journal_limit(),
+            max_body_bytes: default_max_body_bytes(),
+            admin: false,
+            admin_token: None,
             default_response: None,
             routes: Vec::new(),
             tls: None,
             proxy_to: None,
             latency: None,
+            source: None,
+            source_routes: 0,
         }
     }
 }
@@ -107,10 +153,18 @@ impl MockServerConfig {
 
     /// Load config from a file
     pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, QuicpulseError> {
-        let content = std::fs::read_to_string(path.as_ref())
+        let mut config = Self::parse_file(path.as_ref())?;
+        config.source = Some(path.as_ref().to_path_buf());
+        config.source_routes = config.routes.len();
+        Ok(config)
+    }
+
+    /// Parse a config file by extension
+    fn parse_file(path: &Path) -> Result<Self, QuicpulseError> {
+        let content = std::fs::read_to_string(path)
             .map_err(QuicpulseError::Io)?;
 
-        let ext = path.as_ref()
+        let ext = path
             .extension()
             .and_then(|e| e.to_str())
             .unwrap_or("json");
@@ -137,6 +191,18 @@ impl MockServerConfig {
             .map_err(|e| QuicpulseError::Config(format!("Failed to parse YAML: {}", e)))
     }
 
+    /// Every `body_file` referenced by the routes and the default response
+    pub fn body_files(&self) -> Vec<PathBuf> {
+        let mut files: Vec<PathBuf> = self.routes.iter()
+            .flat_map(|route| std::iter::once(&route.response).chain(&route.responses))
+            .chain(&self.default_response)
+            .filter_map(|response| response.body_file.as_ref().map(PathBuf::from))
+            .collect();
+        files.sort();
+        files.dedup();
+        files
+    }
+
     /// Get address string
     pub fn address(&self) -> String {
         format!("{}:{}", self.host, self.port)
@@ -150,8 +216,24 @@ impl MockServerConfig {
                 .map_err(|e| QuicpulseError::Config(format!("Invalid route '{}': {}", route.path, e)))?;
         }
 
+        // Check proxy upstream is an absolute http(s) URL
+        if let Some(ref upstream) = self.proxy_to {
+            let url = url::Url::parse(upstream)
+                .map_err(|e| QuicpulseError::Config(format!("Invalid proxy URL '{}': {}", upstream, e)))?;
+            if url.scheme() != "http" && url.scheme() != "https" {
+                return Err(QuicpulseError::Config(format!(
+                    "Proxy URL must use http or https: {}", upstream
+                )));
+            }
+        }
+
         // Check TLS files exist
-        if let Some(ref tls) = self.tls {
+        if let Some(tls) = self.tls.as_ref().filter(|tls| !tls.self_signed) {
+            if tls.cert.is_empty() || tls.key.is_empty() {
+                return Err(QuicpulseError::Config(
+                    "TLS requires both cert and key, or self_signed: true".to_string()
+                ));
+            }
             if !Path::new(&tls.cert).exists() {
                 return Err(QuicpulseError::Config(format!("TLS cert file not found: {}", tls.cert)));
             }
@@ -201,4 +283,70 @@ routes:
         assert!(config.cors);
         assert_eq!(config.routes.len(), 2);
     }
+
+    #[test]
+    fn test_validate_proxy_url() {
+        let mut config = MockServerConfig::default();
+        config.proxy_to = Some("https://api.example.com".to_string());
+        assert!(config.validate().is_ok());
+
+        config.proxy_to = Some("ftp://api.example.com".to_string());
+        assert!(config.validate().is_err());
+
+        config.proxy_to = Some("not a url".to_string());
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_validate_tls() {
+        let mut config = MockServerConfig::default();
+        config.tls = Some(TlsConfig { self_signed: true, ..Default::default() });
+        assert!(config.validate().is_ok());
+
+        config.tls = Some(TlsConfig { cert: "cert.pem".to_string(), ..Default::default() });
+        assert!(config.validate().is_err());
+
+        config.tls = Some(TlsConfig {
+            cert: "/nonexistent/cert.pem".to_string(),
+            key: "/nonexistent/key.pem".to_string(),
+            ..Default::default()
+        });
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_yaml_tls_config() {
+        let config = MockServerConfig::from_yaml("tls:\n  self_signed: true\n  http2: true\n").unwrap();
+        let tls = config.tls.unwrap();
+        assert!(tls.self_signed);
+        assert!(tls.http2);
+        assert!(tls.cert.is_empty());
+    }
+
+    #[test]
+    fn test_load_tracks_source_and_body_files() {
+        use std::io::Write;
+
+        let mut file = tempfile::Builder::new().suffix(".yaml").tempfile().unwrap();
+        file.write_all(br#"
+routes:
+  - path: /a
+    response:
+      body_file: a.json
+  - path: /b
+    responses:
+      - body_file: b.json
+      - body_file: a.json
+default_response:
+  body_file: missing.json
+"#).unwrap();
+
+        let config = MockServerConfig::load(file.path()).unwrap();
+        assert_eq!(config.source.as_deref(), Some(file.path()));
+        assert_eq!(config.source_routes, 2);
+        assert_eq!(
+            config.body_files(),
+            vec![PathBuf::from("a.json"), PathBuf::from("b.json"), PathBuf::from("missing.json")]
+        );
+    }
 }
@@ -0,0 +1,100 @@
+//! Bounded request journal
+//!
+//! The journal backs `get_requests` and the admin API's verification. It keeps
+//! the most recent `journal_limit` requests so a mock used as a benchmark
+//! target does not grow without bound; a limit of 0 turns it off.
+
+use std::collections::VecDeque;
+use std::sync::{Mutex, MutexGuard};
+
+use super::routes::RequestInfo;
+
+/// Requests kept when the config does not set `journal_limit`
+pub const DEFAULT_JOURNAL_LIMIT: usize = 1000;
+
+/// Ring buffer of the most recent requests
+pub struct RequestJournal {
+    limit: usize,
+    entries: Mutex<VecDeque<RequestInfo>>,
+}
+
+impl RequestJournal {
+    pub fn new(limit: usize) -> Self {
+        Self { limit, entries: Mutex::new(VecDeque::new()) }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, VecDeque<RequestInfo>> {
+        self.entries.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Maximum number of requests kept
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// Add a request, dropping the oldest one when full
+    pub fn push(&self, info: &RequestInfo) {
+        if self.limit == 0 {
+            return;
+        }
+        let info = info.clone();
+        let mut entries = self.lock();
+        if entries.len() >= self.limit {
+            entries.pop_front();
+        }
+        entries.push_back(info);
+    }
+
+    /// Journaled requests, oldest first
+    pub fn all(&self) -> Vec<RequestInfo> {
+        self.lock().iter().cloned().collect()
+    }
+
+    /// Number of journaled requests for which `filter` holds
+    pub fn count(&self, filter: impl Fn(&RequestInfo) -> bool) -> usize {
+        self.lock().iter().filter(|info| filter(info)).count()
+    }
+
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn request(path: &str) -> RequestInfo {
+        RequestInfo::new(
+            "GET".to_string(),
+            path.to_string(),
+            HashMap::new(),
+            HashMap::new(),
+            String::new(),
+            HashMap::new(),
+            "127.0.0.1".to_string(),
+        )
+    }
+
+    #[test]
+    fn test_drops_oldest_when_full() {
+        let journal = RequestJournal::new(2);
+        for path in ["/a", "/b", "/c"] {
+            journal.push(&request(path));
+        }
+        let paths: Vec<String> = journal.all().into_iter().map(|info| info.path).collect();
+        assert_eq!(paths, vec!["/b", "/c"]);
+        assert_eq!(journal.count(|info| info.path == "/c"), 1);
+
+        journal.clear();
+        assert!(journal.all().is_empty());
+    }
+
+    #[test]
+    fn test_zero_limit_keeps_nothing() {
+        let journal = RequestJournal::new(0);
+        journal.push(&request("/a"));
+        assert!(journal.all().is_empty());
+    }
+}
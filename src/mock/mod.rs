@@ -6,7 +6,15 @@
 pub mod server;
 pub mod routes;
 pub mod config;
+pub mod recorder;
+pub mod tls;
+pub mod scenarios;
+pub mod admin;
+pub mod openapi;
+pub mod reload;
+pub mod journal;
 
 pub use server::MockServer;
 pub use routes::{Route, RouteConfig, ResponseConfig};
 pub use config::MockServerConfig;
+pub use recorder::{Recorder, RecordFormat};
@@ -0,0 +1,291 @@
+//! Mock routes generated from an OpenAPI specification
+//!
+//! Every operation becomes a route that serves the documented success
+//! response: the spec's `example`/`examples` when present, otherwise data
+//! synthesized from the response schema.
+
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+use super::routes::{HttpMethod, ResponseConfig, RouteConfig};
+use crate::openapi::{Endpoint, MediaType, OpenApiSpec, Schema, SchemaMapper};
+
+/// Build one route per operation in the spec.
+///
+/// With `validate_requests`, operations with a JSON request body get a
+/// `body_schema` so invalid bodies are rejected with a 400.
+pub fn routes_from_spec(spec: &OpenApiSpec, validate_requests: bool) -> Vec<RouteConfig> {
+    let base_path = base_path(spec);
+
+    spec.endpoints.iter()
+        .map(|endpoint| RouteConfig {
+            method: http_method(&endpoint.method),
+            path: format!("{}{}", base_path, route_path(&endpoint.path)),
+            response: success_response(endpoint, &spec.schemas),
+            name: endpoint.operation_id.clone(),
+            body_schema: if validate_requests { request_schema(endpoint, &spec.schemas) } else { None },
+            ..Default::default()
+        })
+        .collect()
+}
+
+fn http_method(method: &str) -> HttpMethod {
+    match method.to_uppercase().as_str() {
+        "GET" => HttpMethod::Get,
+        "POST" => HttpMethod::Post,
+        "PUT" => HttpMethod::Put,
+        "DELETE" => HttpMethod::Delete,
+        "PATCH" => HttpMethod::Patch,
+        "HEAD" => HttpMethod::Head,
+        "OPTIONS" => HttpMethod::Options,
+        _ => HttpMethod::Any,
+    }
+}
+
+/// Path prefix of the first server URL, e.g. `/v1` for `https://api.example.com/v1`
+fn base_path(spec: &OpenApiSpec) -> String {
+    let Some(server) = spec.servers.first() else {
+        return String::new();
+    };
+
+    let mut url = server.url.clone();
+    for (name, variable) in &server.variables {
+        url = url.replace(&format!("{{{}}}", name), &variable.default);
+    }
+
+    let path = if url.starts_with('/') {
+        url
+    } else {
+        url::Url::parse(&url).map(|u| u.path().to_string()).unwrap_or_default()
+    };
+    path.trim_end_matches('/').to_string()
+}
+
+/// Convert an OpenAPI path template (`/users/{user-id}`) to route syntax (`/users/:user_id`)
+fn route_path(path: &str) -> String {
+    let mut result = String::with_capacity(path.len());
+    let mut chars = path.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '{' {
+            result.push(c);
+            continue;
+        }
+        result.push(':');
+        for c in chars.by_ref() {
+            if c == '}' {
+                break;
+            }
+            result.push(if c.is_alphanumeric() || c == '_' { c } else { '_' });
+        }
+    }
+
+    result
+}
+
+/// The documented success response: the lowest 2xx status, else `default`
+fn success_response(endpoint: &Endpoint, schemas: &HashMap<String, Schema>) -> ResponseConfig {
+    let mut candidates: Vec<(u16, &str)> = endpoint.responses.keys()
+        .filter_map(|code| {
+            let status = match code.to_ascii_uppercase().as_str() {
+                "2XX" => 200,
+                other => other.parse().ok().filter(|s: &u16| (200..300).contains(s))?,
+            };
+            Some((status, code.as_str()))
+        })
+        .collect();
+    candidates.sort();
+
+    let (status, response) = match candidates.first() {
+        Some(&(status, code)) => (status, &endpoint.responses[code]),
+        None => match endpoint.responses.get("default") {
+            Some(response) => (200, response),
+            None => return ResponseConfig::default(),
+        },
+    };
+
+    let Some((content_type, media)) = pick_media_type(&response.content) else {
+        return ResponseConfig {
+            status,
+            ..Default::default()
+        };
+    };
+
+    let mut config = ResponseConfig {
+        status,
+        headers: [("Content-Type".to_string(), content_type.to_string())].into_iter().collect(),
+        ..Default::default()
+    };
+    match media_example(media, schemas) {
+        Some(Value::String(text)) if !is_json(content_type) => config.body = Some(text),
+        Some(value) => config.json = Some(value),
+        None => {}
+    }
+    config
+}
+
+/// Prefer a JSON media type, then whatever is listed first alphabetically
+fn pick_media_type(content: &HashMap<String, MediaType>) -> Option<(&str, &MediaType)> {
+    let mut types: Vec<(&String, &MediaType)> = content.iter().collect();
+    types.sort_by_key(|(name, _)| (!is_json(name), name.as_str()));
+    types.first().map(|(name, media)| (name.as_str(), *media))
+}
+
+fn is_json(content_type: &str) -> bool {
+    let essence = content_type.split(';').next().unwrap_or("").trim();
+    essence == "application/json" || essence.ends_with("+json")
+}
+
+/// Example from the spec, else data synthesized from the schema
+fn media_example(media: &MediaType, schemas: &HashMap<String, Schema>) -> Option<Value> {
+    if let Some(ref example) = media.example {
+        return Some(example.clone());
+    }
+
+    // Named examples are Example Objects holding the payload under `value`
+    let mut names: Vec<&String> = media.examples.keys().collect();
+    names.sort();
+    if let Some(example) = names.first().map(|name| &media.examples[*name]) {
+        return Some(example.get("value").cloned().unwrap_or_else(|| example.clone()));
+    }
+
+    media.schema.as_ref().map(|schema| SchemaMapper::generate_example(schema, schemas))
+}
+
+/// JSON Schema for the operation's JSON request body
+fn request_schema(endpoint: &Endpoint, schemas: &HashMap<String, Schema>) -> Option<Value> {
+    let body = endpoint.request_body.as_ref()?;
+    let (content_type, media) = pick_media_type(&body.content)?;
+    if !is_json(content_type) {
+        return None;
+    }
+
+    let schema = SchemaMapper::to_json_schema(media.schema.as_ref()?, schemas);
+    if body.required {
+        Some(schema)
+    } else {
+        // An optional body may be left out entirely
+        Some(serde_json::json!({ "anyOf": [{ "type": "null" }, schema] }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    const SPEC: &str = r##"
+openapi: 3.0.0
+info:
+  title: Pets
+  version: "1.0"
+servers:
+  - url: https://api.example.com/v1
+paths:
+  /pets:
+    get:
+      operationId: listPets
+      responses:
+        "200":
+          description: ok
+          content:
+            application/json:
+              example: [{"id": 1, "name": "Rex"}]
+    post:
+      operationId: createPet
+      requestBody:
+        required: true
+        content:
+          application/json:
+            schema:
+              $ref: "#/components/schemas/Pet"
+      responses:
+        "201":
+          description: created
+          content:
+            application/json:
+              schema:
+                $ref: "#/components/schemas/Pet"
+  /pets/{pet-id}:
+    get:
+      operationId: getPet
+      responses:
+        "404":
+          description: missing
+        "200":
+          description: ok
+          content:
+            application/json:
+              examples:
+                rex:
+                  value: {"id": 1, "name": "Rex"}
+    delete:
+      responses:
+        "204":
+          description: deleted
+components:
+  schemas:
+    Pet:
+      type: object
+      required: [name]
+      properties:
+        id:
+          type: integer
+        name:
+          type: string
+"##;
+
+    fn load_spec() -> OpenApiSpec {
+        let mut file = tempfile::Builder::new().suffix(".yaml").tempfile().unwrap();
+        file.write_all(SPEC.as_bytes()).unwrap();
+        crate::openapi::parse_spec(file.path()).unwrap()
+    }
+
+    fn route<'a>(routes: &'a [RouteConfig], method: HttpMethod, path: &str) -> &'a RouteConfig {
+        routes.iter().find(|r| r.method == method && r.path == path).unwrap()
+    }
+
+    #[test]
+    fn test_route_path() {
+        assert_eq!(route_path("/pets/{pet-id}"), "/pets/:pet_id");
+        assert_eq!(route_path("/files/{name}.json"), "/files/:name.json");
+        assert_eq!(route_path("/pets"), "/pets");
+    }
+
+    #[test]
+    fn test_routes_from_spec() {
+        let routes = routes_from_spec(&load_spec(), false);
+        assert_eq!(routes.len(), 4);
+
+        let list = route(&routes, HttpMethod::Get, "/v1/pets");
+        assert_eq!(list.name.as_deref(), Some("listPets"));
+        assert_eq!(list.response.json, Some(serde_json::json!([{"id": 1, "name": "Rex"}])));
+
+        let get = route(&routes, HttpMethod::Get, "/v1/pets/:pet_id");
+        assert_eq!(get.response.status, 200);
+        assert_eq!(get.response.json.as_ref().unwrap()["name"], "Rex");
+
+        let create = route(&routes, HttpMethod::Post, "/v1/pets");
+        assert_eq!(create.response.status, 201);
+        let body = create.response.json.as_ref().unwrap();
+        assert!(body["id"].is_i64());
+        assert!(body["name"].is_string());
+        assert!(create.body_schema.is_none());
+
+        let delete = route(&routes, HttpMethod::Delete, "/v1/pets/:pet_id");
+        assert_eq!(delete.response.status, 204);
+        assert!(delete.response.get_body().is_empty());
+    }
+
+    #[test]
+    fn test_request_validation_schema() {
+        let routes = routes_from_spec(&load_spec(), true);
+        let create = route(&routes, HttpMethod::Post, "/v1/pets");
+        let schema = create.body_schema.as_ref().unwrap();
+        assert_eq!(schema["required"], serde_json::json!(["name"]));
+        assert_eq!(schema["properties"]["id"]["type"], "integer");
+
+        assert!(route(&routes, HttpMethod::Get, "/v1/pets").body_schema.is_none());
+    }
+}
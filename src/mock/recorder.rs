@@ -0,0 +1,619 @@
+//! Request recording for the mock server
+//!
+//! Every exchange handled by the mock server can be captured as a HAR entry.
+//! HAR output stays open and each entry is written over the document's closing
+//! brackets, which are written again after it, so the file always loads with
+//! `har::parser::load_har` and recording costs the same for every request;
+//! NDJSON output appends one entry per line, which `load_har` also understands. Routes output turns
+//! proxied exchanges into `RouteConfig` entries that `MockServerConfig::load`
+//! can serve later ("record once, mock forever").
+
+use std::collections::HashMap;
+use std::io::SeekFrom;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use base64::Engine;
+use tokio::io::{AsyncSeekExt, AsyncWriteExt};
+use tokio::sync::Mutex;
+
+use super::routes::{HttpMethod, RequestInfo, RequestMatcher, ResponseConfig, RouteConfig};
+use crate::errors::QuicpulseError;
+use crate::har::types::{
+    HarContent, HarCreator, HarEntry, HarHeader, HarPostData, HarQueryParam, HarRequest,
+    HarResponse,
+};
+
+/// Output format for recorded requests
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordFormat {
+    /// A single HAR 1.2 document
+    Har,
+    /// One HAR entry per line
+    Ndjson,
+    /// Mock server config (YAML) with one route per proxied request
+    Routes,
+}
+
+impl RecordFormat {
+    /// Pick the format from the file extension
+    /// (`.ndjson`/`.jsonl` → NDJSON, `.yaml`/`.yml` → routes, otherwise HAR)
+    pub fn from_path(path: &Path) -> Self {
+        let ext = path.extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_ascii_lowercase())
+            .unwrap_or_default();
+
+        match ext.as_str() {
+            "ndjson" | "jsonl" => RecordFormat::Ndjson,
+            "yaml" | "yml" => RecordFormat::Routes,
+            _ => RecordFormat::Har,
+        }
+    }
+}
+
+/// Where the response of a recorded exchange came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExchangeSource {
+    /// Served by a mock route or the default response
+    Mock,
+    /// Forwarded to the `proxy_to` upstream
+    Proxy,
+}
+
+/// Response side of a recorded exchange
+pub struct RecordedResponse<'a> {
+    pub status: u16,
+    pub headers: &'a HashMap<String, String>,
+    pub body: &'a [u8],
+}
+
+/// Appends mock server exchanges to a HAR, NDJSON or routes file
+pub struct Recorder {
+    path: PathBuf,
+    format: RecordFormat,
+    state: Mutex<RecordState>,
+}
+
+/// What recording needs to keep between requests
+#[derive(Default)]
+struct RecordState {
+    /// The open HAR document
+    har: Option<HarFile>,
+    /// Everything written so far, as the routes document is rewritten whole
+    routes: Vec<RouteConfig>,
+}
+
+/// Closes the `entries` array and the document after the last entry
+const HAR_TRAILER: &str = "\n]}}\n";
+
+/// A HAR document whose entries are appended in place
+struct HarFile {
+    file: tokio::fs::File,
+    entries: usize,
+}
+
+impl HarFile {
+    /// Start a document holding `entries`, replacing whatever `path` held
+    fn create(path: &Path, entries: &[HarEntry]) -> Result<Self, QuicpulseError> {
+        let creator = HarCreator {
+            name: "quicpulse".to_string(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            comment: Some("mock server recording".to_string()),
+        };
+        let mut document = format!(
+            "{{\"log\":{{\"version\":\"1.2\",\"creator\":{},\"entries\":[",
+            serde_json::to_string(&creator)?
+        );
+        for (index, entry) in entries.iter().enumerate() {
+            document.push_str(if index == 0 { "\n" } else { ",\n" });
+            document.push_str(&serde_json::to_string(entry)?);
+        }
+        document.push_str(HAR_TRAILER);
+        std::fs::write(path, document)?;
+
+        let file = std::fs::OpenOptions::new().write(true).open(path)?;
+        Ok(Self { file: tokio::fs::File::from_std(file), entries: entries.len() })
+    }
+
+    /// Write `entry` in place of the trailer, then the trailer again
+    async fn append(&mut self, entry: &HarEntry) -> Result<(), QuicpulseError> {
+        let mut chunk = String::from(if self.entries == 0 { "\n" } else { ",\n" });
+        chunk.push_str(&serde_json::to_string(entry)
+            .map_err(|e| QuicpulseError::Parse(format!("Failed to serialize HAR entry: {}", e)))?);
+        chunk.push_str(HAR_TRAILER);
+
+        self.file.seek(SeekFrom::End(-(HAR_TRAILER.len() as i64))).await?;
+        self.file.write_all(chunk.as_bytes()).await?;
+        self.file.flush().await?;
+        self.entries += 1;
+        Ok(())
+    }
+}
+
+/// Serialized shape of a routes recording
+#[derive(serde::Serialize)]
+struct RoutesDocument<'a> {
+    routes: &'a [RouteConfig],
+}
+
+impl Recorder {
+    /// Create a recorder for the given file.
+    ///
+    /// An existing HAR or routes file is loaded so new entries are appended to
+    /// it rather than replacing it. A HAR file is opened right away.
+    pub fn new<P: AsRef<Path>>(path: P) -> Result<Self, QuicpulseError> {
+        let path = path.as_ref().to_path_buf();
+        let format = RecordFormat::from_path(&path);
+        let existing = std::fs::metadata(&path).map(|m| m.len() > 0).unwrap_or(false);
+
+        let mut state = RecordState::default();
+        let append_error = |e: QuicpulseError| QuicpulseError::Config(format!(
+            "Cannot append to record file {}: {}", path.display(), e
+        ));
+        match format {
+            RecordFormat::Har => {
+                let entries = if existing {
+                    crate::har::parser::load_har(&path).map_err(append_error)?.log.entries
+                } else {
+                    Vec::new()
+                };
+                state.har = Some(HarFile::create(&path, &entries)?);
+            }
+            RecordFormat::Routes if existing => {
+                state.routes = super::config::MockServerConfig::load(&path).map_err(append_error)?.routes;
+            }
+            _ => {}
+        }
+
+        Ok(Self {
+            path,
+            format,
+            state: Mutex::new(state),
+        })
+    }
+
+    /// Path of the record file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Output format
+    pub fn format(&self) -> RecordFormat {
+        self.format
+    }
+
+    /// Append an entry to the record file
+    ///
+    /// In routes mode only proxied exchanges are kept, one route per method,
+    /// path, query and body; the first response seen for a route wins.
+    pub async fn record(&self, entry: HarEntry, source: ExchangeSource) -> Result<(), QuicpulseError> {
+        // The lock also serializes writers so lines and documents never interleave
+        let mut state = self.state.lock().await;
+
+        match self.format {
+            RecordFormat::Ndjson => {
+                let mut line = serde_json::to_string(&entry)
+                    .map_err(|e| QuicpulseError::Parse(format!("Failed to serialize HAR entry: {}", e)))?;
+                line.push('\n');
+
+                let mut file = tokio::fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(&self.path)
+                    .await
+                    .map_err(QuicpulseError::Io)?;
+                file.write_all(line.as_bytes()).await.map_err(QuicpulseError::Io)?;
+                file.flush().await.map_err(QuicpulseError::Io)?;
+            }
+            RecordFormat::Har => {
+                if let Some(ref mut har) = state.har {
+                    har.append(&entry).await?;
+                }
+            }
+            RecordFormat::Routes => {
+                if source != ExchangeSource::Proxy {
+                    return Ok(());
+                }
+                let Some(mut route) = route_from_entry(&entry) else {
+                    return Ok(());
+                };
+                let duplicate = state.routes.iter()
+                    .any(|r| r.method == route.method && r.path == route.path && r.matcher == route.matcher);
+                if duplicate {
+                    return Ok(());
+                }
+
+                // Binary bodies cannot live in YAML; store them next to the routes file
+                if entry.response.content.encoding.as_deref() == Some("base64") {
+                    let bytes = entry.response.content.text.as_deref()
+                        .and_then(|t| base64::engine::general_purpose::STANDARD.decode(t).ok())
+                        .unwrap_or_default();
+                    let dir = self.bodies_dir();
+                    tokio::fs::create_dir_all(&dir).await.map_err(QuicpulseError::Io)?;
+                    let file = dir.join(format!("{:04}.bin", state.routes.len() + 1));
+                    tokio::fs::write(&file, bytes).await.map_err(QuicpulseError::Io)?;
+                    route.response.body = None;
+                    route.response.body_file = Some(file.to_string_lossy().to_string());
+                }
+
+                state.routes.push(route);
+                let yaml = serde_yaml::to_string(&RoutesDocument { routes: &state.routes })
+                    .map_err(|e| QuicpulseError::Parse(format!("Failed to serialize routes: {}", e)))?;
+                self.replace_file(yaml).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Directory holding binary response bodies for routes recordings
+    fn bodies_dir(&self) -> PathBuf {
+        let stem = self.path.file_stem().and_then(|s| s.to_str()).unwrap_or("recorded");
+        self.path.with_file_name(format!("{}_bodies", stem))
+    }
+
+    /// Write to a sibling temp file and rename so readers never see a partial document
+    async fn replace_file(&self, content: String) -> Result<(), QuicpulseError> {
+        let mut tmp = self.path.clone().into_os_string();
+        tmp.push(".tmp");
+        tokio::fs::write(&tmp, content).await.map_err(QuicpulseError::Io)?;
+        tokio::fs::rename(&tmp, &self.path).await.map_err(QuicpulseError::Io)?;
+        Ok(())
+    }
+}
+
+/// Headers that describe the original transfer rather than the response itself
+const TRANSFER_HEADERS: &[&str] = &[
+    "connection", "content-length", "date", "keep-alive", "transfer-encoding",
+];
+
+/// Turn a recorded exchange into a mock route serving the same response to
+/// requests with the same query parameters and body
+pub fn route_from_entry(entry: &HarEntry) -> Option<RouteConfig> {
+    let url = url::Url::parse(&entry.request.url).ok()?;
+    let method: HttpMethod = serde_json::from_value(
+        serde_json::Value::String(entry.request.method.to_uppercase())
+    ).ok()?;
+
+    let headers: HashMap<String, String> = entry.response.headers.iter()
+        .filter(|h| !TRANSFER_HEADERS.iter().any(|t| h.name.eq_ignore_ascii_case(t)))
+        .map(|h| (h.name.clone(), h.value.clone()))
+        .collect();
+
+    let content = &entry.response.content;
+    let text = content.text.clone().unwrap_or_default();
+    let json = if content.encoding.is_none() && content.mime_type.contains("json") {
+        serde_json::from_str::<serde_json::Value>(&text).ok()
+    } else {
+        None
+    };
+
+    let response = ResponseConfig {
+        status: entry.response.status as u16,
+        headers,
+        body: if json.is_some() || text.is_empty() { None } else { Some(text) },
+        json,
+        ..Default::default()
+    };
+
+    Some(RouteConfig {
+        method,
+        path: url.path().to_string(),
+        response,
+        matcher: request_matcher(entry),
+        priority: 0,
+        enabled: true,
+        name: Some(format!("recorded {} {}", entry.request.method, url.path())),
+        ..Default::default()
+    })
+}
+
+/// Match the recorded request's query parameters and its body, as JSON when it is JSON
+fn request_matcher(entry: &HarEntry) -> RequestMatcher {
+    let mut matcher = RequestMatcher {
+        query: entry.request.query_string.iter()
+            .map(|param| (param.name.clone(), param.value.clone()))
+            .collect(),
+        ..Default::default()
+    };
+
+    let body = entry.request.post_data.as_ref()
+        .and_then(|post| post.text.as_deref().map(|text| (post.mime_type.as_str(), text)))
+        .filter(|(_, text)| !text.is_empty());
+    if let Some((mime_type, text)) = body {
+        let json = mime_type.contains("json")
+            .then(|| serde_json::from_str::<serde_json::Value>(text).ok())
+            .flatten();
+        match json {
+            Some(json) => matcher.body_json = Some(json),
+            None => matcher.body_regex = Some(format!(r"\A{}\z", regex::escape(text))),
+        }
+    }
+    matcher
+}
+
+/// Build a HAR entry from a handled request and the response sent back
+/// over `http_version` (e.g. `HTTP/1.1` or `HTTP/2`)
+pub fn build_entry(
+    request: &RequestInfo,
+    base_url: &str,
+    http_version: &str,
+    response: RecordedResponse<'_>,
+    elapsed: Duration,
+) -> HarEntry {
+    let mut query: Vec<(&String, &String)> = request.query.iter().collect();
+    query.sort();
+
+    let mut url = format!("{}{}", base_url.trim_end_matches('/'), request.path);
+    if !query.is_empty() {
+        let qs = query.iter()
+            .map(|(k, v)| format!("{}={}", urlencoding::encode(k), urlencoding::encode(v)))
+            .collect::<Vec<_>>()
+            .join("&");
+        url.push('?');
+        url.push_str(&qs);
+    }
+
+    let request_content_type = request.headers.get("content-type").cloned();
+    let post_data = if request.body.is_empty() {
+        None
+    } else {
+        Some(HarPostData {
+            mime_type: request_content_type.unwrap_or_default(),
+            text: Some(request.body.clone()),
+            params: None,
+            comment: None,
+        })
+    };
+
+    let response_content_type = response.headers.iter()
+        .find(|(k, _)| k.eq_ignore_ascii_case("content-type"))
+        .map(|(_, v)| v.clone())
+        .unwrap_or_default();
+
+    let (text, encoding) = match std::str::from_utf8(response.body) {
+        Ok(s) => (s.to_string(), None),
+        Err(_) => (
+            base64::engine::general_purpose::STANDARD.encode(response.body),
+            Some("base64".to_string()),
+        ),
+    };
+
+    HarEntry {
+        pageref: None,
+        started_date_time: request.timestamp.clone(),
+        time: elapsed.as_secs_f64() * 1000.0,
+        request: HarRequest {
+            method: request.method.clone(),
+            url,
+            http_version: http_version.to_string(),
+            cookies: vec![],
+            headers: to_har_headers(&request.headers),
+            query_string: query.iter()
+                .map(|(k, v)| HarQueryParam {
+                    name: (*k).clone(),
+                    value: (*v).clone(),
+                    comment: None,
+                })
+                .collect(),
+            post_data,
+            headers_size: -1,
+            body_size: request.body.len() as i64,
+            comment: None,
+        },
+        response: HarResponse {
+            status: response.status as i32,
+            status_text: super::server::http_status_text(response.status).to_string(),
+            http_version: http_version.to_string(),
+            cookies: vec![],
+            headers: to_har_headers(response.headers),
+            content: HarContent {
+                size: response.body.len() as i64,
+                compression: None,
+                mime_type: response_content_type,
+                text: Some(text),
+                encoding,
+                comment: None,
+            },
+            redirect_url: response.headers.iter()
+                .find(|(k, _)| k.eq_ignore_ascii_case("location"))
+                .map(|(_, v)| v.clone())
+                .unwrap_or_default(),
+            headers_size: -1,
+            body_size: response.body.len() as i64,
+            comment: None,
+        },
+        cache: None,
+        timings: None,
+        server_ip_address: None,
+        connection: None,
+        comment: None,
+    }
+}
+
+/// Convert a header map to HAR headers, sorted by name for stable output
+fn to_har_headers(headers: &HashMap<String, String>) -> Vec<HarHeader> {
+    let mut out: Vec<HarHeader> = headers.iter()
+        .map(|(name, value)| HarHeader {
+            name: name.clone(),
+            value: value.clone(),
+            comment: None,
+        })
+        .collect();
+    out.sort_by(|a, b| a.name.cmp(&b.name));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::har::parser::load_har;
+
+    fn sample_request(path: &str, body: &str) -> RequestInfo {
+        RequestInfo::new(
+            "POST".to_string(),
+            path.to_string(),
+            HashMap::from([("page".to_string(), "2".to_string())]),
+            HashMap::from([("content-type".to_string(), "application/json".to_string())]),
+            body.to_string(),
+            HashMap::new(),
+            "127.0.0.1".to_string(),
+        )
+    }
+
+    fn sample_entry(path: &str) -> HarEntry {
+        let headers = HashMap::from([("Content-Type".to_string(), "application/json".to_string())]);
+        build_entry(
+            &sample_request(path, r#"{"name":"a"}"#),
+            "http://localhost:8080",
+            "HTTP/1.1",
+            RecordedResponse { status: 201, headers: &headers, body: br#"{"id":1}"# },
+            Duration::from_millis(5),
+        )
+    }
+
+    #[test]
+    fn test_format_from_path() {
+        assert_eq!(RecordFormat::from_path(Path::new("out.har")), RecordFormat::Har);
+        assert_eq!(RecordFormat::from_path(Path::new("out.json")), RecordFormat::Har);
+        assert_eq!(RecordFormat::from_path(Path::new("out.ndjson")), RecordFormat::Ndjson);
+        assert_eq!(RecordFormat::from_path(Path::new("out.JSONL")), RecordFormat::Ndjson);
+        assert_eq!(RecordFormat::from_path(Path::new("routes.yaml")), RecordFormat::Routes);
+    }
+
+    #[test]
+    fn test_build_entry() {
+        let entry = sample_entry("/api/users");
+
+        assert_eq!(entry.request.method, "POST");
+        assert_eq!(entry.request.url, "http://localhost:8080/api/users?page=2");
+        assert_eq!(entry.request.query_string.len(), 1);
+        let post = entry.request.post_data.as_ref().unwrap();
+        assert_eq!(post.mime_type, "application/json");
+        assert_eq!(post.text.as_deref(), Some(r#"{"name":"a"}"#));
+        assert_eq!(entry.response.status, 201);
+        assert_eq!(entry.response.status_text, "Created");
+        assert_eq!(entry.response.content.mime_type, "application/json");
+        assert_eq!(entry.response.content.text.as_deref(), Some(r#"{"id":1}"#));
+        assert!(entry.response.content.encoding.is_none());
+        assert!((entry.time - 5.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_build_entry_binary_body() {
+        let headers = HashMap::new();
+        let entry = build_entry(
+            &sample_request("/bin", ""),
+            "http://localhost:8080",
+            "HTTP/1.1",
+            RecordedResponse { status: 200, headers: &headers, body: &[0xff, 0xfe, 0x00] },
+            Duration::ZERO,
+        );
+
+        assert!(entry.request.post_data.is_none());
+        assert_eq!(entry.response.content.encoding.as_deref(), Some("base64"));
+        assert_eq!(entry.response.content.text.as_deref(), Some("//4A"));
+    }
+
+    #[tokio::test]
+    async fn test_record_har_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.har");
+
+        let recorder = Recorder::new(&path).unwrap();
+        recorder.record(sample_entry("/a"), ExchangeSource::Mock).await.unwrap();
+        recorder.record(sample_entry("/b"), ExchangeSource::Mock).await.unwrap();
+
+        let har = load_har(&path).unwrap();
+        assert_eq!(har.log.version, "1.2");
+        assert_eq!(har.log.entries.len(), 2);
+        assert!(har.log.entries[1].request.url.contains("/b"));
+
+        // A new recorder appends to the existing document
+        let recorder = Recorder::new(&path).unwrap();
+        recorder.record(sample_entry("/c"), ExchangeSource::Mock).await.unwrap();
+        assert_eq!(load_har(&path).unwrap().log.entries.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_record_ndjson_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.ndjson");
+
+        let recorder = Recorder::new(&path).unwrap();
+        assert_eq!(recorder.format(), RecordFormat::Ndjson);
+        recorder.record(sample_entry("/a"), ExchangeSource::Mock).await.unwrap();
+        recorder.record(sample_entry("/b"), ExchangeSource::Mock).await.unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(content.lines().count(), 2);
+
+        let har = load_har(&path).unwrap();
+        assert_eq!(har.log.entries.len(), 2);
+        assert_eq!(har.log.entries[0].response.status, 201);
+    }
+
+    #[test]
+    fn test_route_from_entry() {
+        let route = route_from_entry(&sample_entry("/api/users")).unwrap();
+
+        assert_eq!(route.method, HttpMethod::Post);
+        assert_eq!(route.path, "/api/users");
+        assert_eq!(route.response.status, 201);
+        assert_eq!(route.response.json, Some(serde_json::json!({"id": 1})));
+        assert!(route.response.body.is_none());
+        assert_eq!(route.response.headers.get("Content-Type").map(String::as_str), Some("application/json"));
+        assert_eq!(route.matcher.query.get("page").map(String::as_str), Some("2"));
+        assert_eq!(route.matcher.body_json, Some(serde_json::json!({"name": "a"})));
+
+        let mut entry = sample_entry("/upload");
+        entry.request.post_data.as_mut().unwrap().mime_type = "text/plain".to_string();
+        let route = route_from_entry(&entry).unwrap();
+        assert!(route.matcher.body_json.is_none());
+        assert_eq!(route.matcher.body_regex.as_deref(), Some(r#"\A\{"name":"a"\}\z"#));
+    }
+
+    #[tokio::test]
+    async fn test_record_routes_only_proxied() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("recorded.yaml");
+
+        let recorder = Recorder::new(&path).unwrap();
+        assert_eq!(recorder.format(), RecordFormat::Routes);
+        recorder.record(sample_entry("/mocked"), ExchangeSource::Mock).await.unwrap();
+        recorder.record(sample_entry("/a"), ExchangeSource::Proxy).await.unwrap();
+        recorder.record(sample_entry("/a"), ExchangeSource::Proxy).await.unwrap();
+        recorder.record(sample_entry("/b"), ExchangeSource::Proxy).await.unwrap();
+        // Another query on the same path is another route
+        let mut other_page = sample_entry("/a");
+        other_page.request.query_string[0].value = "3".to_string();
+        recorder.record(other_page, ExchangeSource::Proxy).await.unwrap();
+
+        let config = crate::mock::MockServerConfig::load(&path).unwrap();
+        let paths: Vec<&str> = config.routes.iter().map(|r| r.path.as_str()).collect();
+        assert_eq!(paths, vec!["/a", "/b", "/a"]);
+        assert_eq!(config.routes[2].matcher.query["page"], "3");
+        config.validate().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_record_routes_binary_body() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("recorded.yaml");
+        let headers = HashMap::new();
+        let entry = build_entry(
+            &sample_request("/image", ""),
+            "http://localhost:8080",
+            "HTTP/1.1",
+            RecordedResponse { status: 200, headers: &headers, body: &[0xff, 0xd8, 0xff] },
+            Duration::ZERO,
+        );
+
+        let recorder = Recorder::new(&path).unwrap();
+        recorder.record(entry, ExchangeSource::Proxy).await.unwrap();
+
+        let config = crate::mock::MockServerConfig::load(&path).unwrap();
+        assert_eq!(config.routes[0].response.get_body(), vec![0xff, 0xd8, 0xff]);
+    }
+}
@@ -0,0 +1,227 @@
+//! Hot reload of the mock config file
+//!
+//! The config file and every `body_file` it references are polled for
+//! changes. On a change the file's routes are recompiled and swapped in under
+//! one write lock; routes from the command line or the admin API are kept.
+//! If the new config fails to load or validate, the current routes stay.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use tokio::sync::RwLock;
+
+use super::config::MockServerConfig;
+use super::routes::{Route, RouteConfig};
+use crate::errors::QuicpulseError;
+
+/// How often watched files are checked
+pub const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Modification time and size of a file; `None` when it does not exist
+type FileStamp = Option<(Option<SystemTime>, u64)>;
+
+/// Watches the config file and reloads its routes
+pub struct ConfigWatcher {
+    path: PathBuf,
+    stamps: HashMap<PathBuf, FileStamp>,
+    /// Ids of the routes that came from the config file
+    file_routes: Vec<String>,
+}
+
+impl ConfigWatcher {
+    /// Start watching `path`, whose routes are currently `file_routes`
+    pub fn new(path: PathBuf, body_files: Vec<PathBuf>, file_routes: Vec<String>) -> Self {
+        let stamps = watched(&path, body_files).into_iter()
+            .map(|file| {
+                let stamp = stamp(&file);
+                (file, stamp)
+            })
+            .collect();
+
+        Self { path, stamps, file_routes }
+    }
+
+    /// Poll forever, reloading whenever a watched file changes
+    pub async fn run(mut self, routes: Arc<RwLock<Vec<Route>>>) {
+        let mut interval = tokio::time::interval(POLL_INTERVAL);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+            let changed = self.changed_files();
+            if changed.is_empty() {
+                continue;
+            }
+
+            for file in &changed {
+                eprintln!("Changed: {}", file.display());
+            }
+            match self.reload(&routes).await {
+                Ok(summary) => {
+                    eprintln!("Reloaded {}", self.path.display());
+                    if summary.is_empty() {
+                        eprintln!("  no route changes");
+                    }
+                    for line in summary {
+                        eprintln!("  {}", line);
+                    }
+                }
+                Err(e) => eprintln!("Failed to reload {}: {}; keeping current routes", self.path.display(), e),
+            }
+        }
+    }
+
+    /// Files whose stamp changed since the last check
+    fn changed_files(&mut self) -> Vec<PathBuf> {
+        let mut changed = Vec::new();
+        for (file, previous) in self.stamps.iter_mut() {
+            let current = stamp(file);
+            if current != *previous {
+                *previous = current;
+                changed.push(file.clone());
+            }
+        }
+        changed.sort();
+        changed
+    }
+
+    /// Load, validate and swap in the config file's routes.
+    ///
+    /// Returns one line per added, removed or changed route.
+    pub async fn reload(&mut self, routes: &RwLock<Vec<Route>>) -> Result<Vec<String>, QuicpulseError> {
+        let config = MockServerConfig::load(&self.path)?;
+        config.validate()?;
+
+        let body_files = config.body_files();
+        if let Some(missing) = body_files.iter().find(|file| !file.exists()) {
+            return Err(QuicpulseError::Config(format!("body_file not found: {}", missing.display())));
+        }
+
+        let mut compiled = config.routes.iter()
+            .map(|rc| Route::new(rc.clone()))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| QuicpulseError::Config(format!("Invalid route: {}", e)))?;
+        let new_ids: Vec<String> = compiled.iter().map(|route| route.id.clone()).collect();
+
+        let summary = {
+            let mut current = routes.write().await;
+            let (old, kept): (Vec<Route>, Vec<Route>) = current.drain(..)
+                .partition(|route| self.file_routes.contains(&route.id));
+
+            let summary = diff_routes(
+                &old.iter().map(|route| &route.config).collect::<Vec<_>>(),
+                &config.routes.iter().collect::<Vec<_>>(),
+            );
+
+            // File routes come first so they keep winning ties, as at startup
+            compiled.extend(kept);
+            compiled.sort_by_key(|route| std::cmp::Reverse(route.config.priority));
+            *current = compiled;
+            summary
+        };
+
+        self.file_routes = new_ids;
+        for file in watched(&self.path, body_files) {
+            self.stamps.entry(file.clone()).or_insert_with(|| stamp(&file));
+        }
+
+        Ok(summary)
+    }
+}
+
+/// The config file plus its body files
+fn watched(path: &Path, body_files: Vec<PathBuf>) -> Vec<PathBuf> {
+    std::iter::once(path.to_path_buf()).chain(body_files).collect()
+}
+
+fn stamp(path: &Path) -> FileStamp {
+    std::fs::metadata(path).ok().map(|meta| (meta.modified().ok(), meta.len()))
+}
+
+/// Describe how the route list changed, keyed by method, path and name
+fn diff_routes(old: &[&RouteConfig], new: &[&RouteConfig]) -> Vec<String> {
+    fn key(route: &RouteConfig) -> String {
+        match route.name {
+            Some(ref name) => format!("{:?} {} ({})", route.method, route.path, name),
+            None => format!("{:?} {}", route.method, route.path),
+        }
+    }
+    fn serialized(route: &RouteConfig) -> serde_json::Value {
+        serde_json::to_value(route).unwrap_or_default()
+    }
+
+    let old_by_key: HashMap<String, &RouteConfig> = old.iter().map(|route| (key(route), *route)).collect();
+    let new_by_key: HashMap<String, &RouteConfig> = new.iter().map(|route| (key(route), *route)).collect();
+
+    let mut lines = Vec::new();
+    for route in new {
+        let k = key(route);
+        match old_by_key.get(&k) {
+            None => lines.push(format!("+ {}", k)),
+            Some(previous) if serialized(previous) != serialized(route) => lines.push(format!("~ {}", k)),
+            Some(_) => {}
+        }
+    }
+    for route in old {
+        let k = key(route);
+        if !new_by_key.contains_key(&k) {
+            lines.push(format!("- {}", k));
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::routes::{HttpMethod, ResponseConfig};
+
+    #[test]
+    fn test_diff_routes() {
+        let a = RouteConfig::get("/a", "one");
+        let b = RouteConfig::get("/b", "two");
+        let a2 = RouteConfig::get("/a", "changed");
+        let c = RouteConfig {
+            method: HttpMethod::Post,
+            path: "/c".to_string(),
+            response: ResponseConfig::default(),
+            ..Default::default()
+        };
+
+        let lines = diff_routes(&[&a, &b], &[&a2, &c]);
+        assert_eq!(lines, vec!["~ Get /a", "+ Post /c", "- Get /b"]);
+        assert!(diff_routes(&[&a], &[&a]).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_reload_swaps_file_routes_only() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("mock.yaml");
+        std::fs::write(&path, "routes:\n  - path: /a\n    response:\n      body: one\n").unwrap();
+
+        let config = MockServerConfig::load(&path).unwrap();
+        let file_route = Route::new(config.routes[0].clone()).unwrap();
+        let cli_route = Route::new(RouteConfig::get("/cli", "cli")).unwrap();
+        let mut watcher = ConfigWatcher::new(path.clone(), config.body_files(), vec![file_route.id.clone()]);
+        let routes = RwLock::new(vec![file_route, cli_route]);
+
+        std::fs::write(&path, "routes:\n  - path: /b\n    response:\n      body: two\n").unwrap();
+        let summary = watcher.reload(&routes).await.unwrap();
+        assert_eq!(summary, vec!["+ Any /b", "- Any /a"]);
+
+        let paths: Vec<String> = routes.read().await.iter().map(|r| r.config.path.clone()).collect();
+        assert_eq!(paths, vec!["/b", "/cli"]);
+
+        // Invalid config keeps the current routes
+        std::fs::write(&path, "routes:\n  - path: /bad/:\n    response: {}\n").unwrap();
+        assert!(watcher.reload(&routes).await.is_err());
+        assert_eq!(routes.read().await.len(), 2);
+
+        // So does a missing body file
+        std::fs::write(&path, "routes:\n  - path: /c\n    response:\n      body_file: /nonexistent/body.json\n").unwrap();
+        assert!(watcher.reload(&routes).await.is_err());
+        assert_eq!(routes.read().await[0].config.path, "/b");
+    }
+}
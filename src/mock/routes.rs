@@ -2,8 +2,12 @@
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
 use regex::Regex;
 
+use super::scenarios::ScenarioStates;
+
 /// HTTP method for route matching
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "UPPERCASE")]
@@ -145,8 +149,29 @@ pub struct RouteConfig {
     pub path: String,
 
     /// Response configuration
+    #[serde(default)]
     pub response: ResponseConfig,
 
+    /// Responses returned in turn on successive matches, instead of `response`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub responses: Vec<ResponseConfig>,
+
+    /// What to do once every response in `responses` has been returned
+    #[serde(default)]
+    pub sequence: SequenceMode,
+
+    /// Scenario this route belongs to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scenario: Option<String>,
+
+    /// Scenario state required for this route to match
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub required_state: Option<String>,
+
+    /// Scenario state to move to after this route matches
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub new_state: Option<String>,
+
     /// Route priority (higher = matched first)
     #[serde(default)]
     pub priority: i32,
@@ -158,12 +183,101 @@ pub struct RouteConfig {
     /// Optional name for logging
     #[serde(default)]
     pub name: Option<String>,
+
+    /// Additional request matchers on headers, query and body
+    #[serde(default, rename = "match", skip_serializing_if = "RequestMatcher::is_empty")]
+    pub matcher: RequestMatcher,
+
+    /// JSON Schema the request body must satisfy; violations get a 400 response
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body_schema: Option<serde_json::Value>,
 }
 
 fn default_true() -> bool {
     true
 }
 
+/// Behaviour of a response sequence after its last response
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SequenceMode {
+    /// Keep returning the last response
+    #[default]
+    Stick,
+    /// Start again from the first response
+    Cycle,
+}
+
+impl Default for RouteConfig {
+    fn default() -> Self {
+        Self {
+            method: HttpMethod::Any,
+            path: "/".to_string(),
+            response: ResponseConfig::default(),
+            responses: Vec::new(),
+            sequence: SequenceMode::default(),
+            scenario: None,
+            required_state: None,
+            new_state: None,
+            priority: 0,
+            enabled: true,
+            name: None,
+            matcher: RequestMatcher::default(),
+            body_schema: None,
+        }
+    }
+}
+
+/// Request matchers beyond method and path; every configured matcher must pass
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RequestMatcher {
+    /// Headers that must be present with exactly these values (names are case-insensitive)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub headers: HashMap<String, String>,
+
+    /// Query parameters that must be present with exactly these values
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub query: HashMap<String, String>,
+
+    /// Body must be JSON equal to this value (object key order is ignored)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body_json: Option<serde_json::Value>,
+
+    /// Body must be JSON containing this value; extra object fields are ignored
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body_json_partial: Option<serde_json::Value>,
+
+    /// JSONPath expression that must select at least one value from the JSON body
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body_jsonpath: Option<String>,
+
+    /// Regular expression the raw body must match
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body_regex: Option<String>,
+}
+
+impl RequestMatcher {
+    /// Whether no matchers are configured
+    pub fn is_empty(&self) -> bool {
+        self.conditions() == 0
+    }
+
+    /// Number of individual conditions, used to rank overlapping routes
+    pub fn conditions(&self) -> usize {
+        self.headers.len()
+            + self.query.len()
+            + usize::from(self.body_json.is_some())
+            + usize::from(self.body_json_partial.is_some())
+            + usize::from(self.body_jsonpath.is_some())
+            + usize::from(self.body_regex.is_some())
+    }
+
+    /// Whether any matcher needs the body parsed as JSON
+    fn needs_json(&self) -> bool {
+        self.body_json.is_some() || self.body_json_partial.is_some() || self.body_jsonpath.is_some()
+    }
+}
+
 impl RouteConfig {
     /// Create a simple GET route
     pub fn get(path: &str, body: &str) -> Self {
@@ -171,9 +285,7 @@ impl RouteConfig {
             method: HttpMethod::Get,
             path: path.to_string(),
             response: ResponseConfig::text(body),
-            priority: 0,
-            enabled: true,
-            name: None,
+            ..Default::default()
         }
     }
 
@@ -183,9 +295,7 @@ impl RouteConfig {
             method: HttpMethod::Post,
             path: path.to_string(),
             response: ResponseConfig::json_body(json),
-            priority: 0,
-            enabled: true,
-            name: None,
+            ..Default::default()
         }
     }
 }
@@ -193,22 +303,182 @@ impl RouteConfig {
 /// A compiled route for efficient matching
 #[derive(Debug, Clone)]
 pub struct Route {
+    /// Unique id, used to remove the route through the admin API
+    pub id: String,
     pub config: RouteConfig,
     path_regex: Regex,
     param_names: Vec<String>,
+    body_regex: Option<Regex>,
+    body_validator: Option<BodyValidator>,
+    /// Matches served so far, shared by clones so sequences advance globally
+    hits: Arc<AtomicUsize>,
 }
 
 impl Route {
     /// Create a new route from config
     pub fn new(config: RouteConfig) -> Result<Self, String> {
         let (regex, params) = compile_path_pattern(&config.path)?;
+
+        let body_regex = config.matcher.body_regex.as_deref()
+            .map(Regex::new)
+            .transpose()
+            .map_err(|e| format!("Invalid body_regex: {}", e))?;
+
+        if let Some(ref path) = config.matcher.body_jsonpath {
+            use jsonpath_rust::JsonPath;
+            serde_json::Value::Null.query(path)
+                .map_err(|e| format!("Invalid body_jsonpath '{}': {}", path, e))?;
+        }
+
+        let body_validator = config.body_schema.as_ref()
+            .map(|schema| jsonschema::validator_for(schema).map(|v| BodyValidator(Arc::new(v))))
+            .transpose()
+            .map_err(|e| format!("Invalid body_schema: {}", e))?;
+
+        if config.scenario.is_none() && (config.required_state.is_some() || config.new_state.is_some()) {
+            return Err("required_state and new_state need a scenario".to_string());
+        }
+
         Ok(Self {
+            id: uuid::Uuid::new_v4().to_string(),
             config,
             path_regex: regex,
             param_names: params,
+            body_regex,
+            body_validator,
+            hits: Arc::new(AtomicUsize::new(0)),
         })
     }
 
+    /// Whether the route's scenario is in the required state
+    pub fn scenario_matches(&self, states: &ScenarioStates) -> bool {
+        match (&self.config.scenario, &self.config.required_state) {
+            (Some(scenario), Some(required)) => states.get(scenario) == required,
+            _ => true,
+        }
+    }
+
+    /// Apply the route's scenario transition, if any
+    pub fn transition(&self, states: &mut ScenarioStates) {
+        if let (Some(scenario), Some(new_state)) = (&self.config.scenario, &self.config.new_state) {
+            states.set(scenario, new_state);
+        }
+    }
+
+    /// Check the request body against `body_schema`, describing every violation.
+    ///
+    /// An empty body is validated as JSON `null`.
+    pub fn validate_body(&self, body: &str) -> Result<(), Vec<String>> {
+        let Some(ref validator) = self.body_validator else {
+            return Ok(());
+        };
+
+        let instance = if body.trim().is_empty() {
+            serde_json::Value::Null
+        } else {
+            serde_json::from_str(body)
+                .map_err(|e| vec![format!("Request body is not valid JSON: {}", e)])?
+        };
+
+        let violations: Vec<String> = validator.0.iter_errors(&instance)
+            .map(|e| {
+                let path = e.instance_path().to_string();
+                if path.is_empty() {
+                    e.to_string()
+                } else {
+                    format!("{}: {}", path, e)
+                }
+            })
+            .collect();
+
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(violations)
+        }
+    }
+
+    /// Response for the next match, advancing the sequence if there is one
+    pub fn next_response(&self) -> ResponseConfig {
+        let responses = &self.config.responses;
+        if responses.is_empty() {
+            return self.config.response.clone();
+        }
+
+        let hit = self.hits.fetch_add(1, Ordering::Relaxed);
+        let index = match self.config.sequence {
+            SequenceMode::Stick => hit.min(responses.len() - 1),
+            SequenceMode::Cycle => hit % responses.len(),
+        };
+        responses[index].clone()
+    }
+
+    /// Restart the response sequence from the first response
+    pub fn reset_sequence(&self) {
+        self.hits.store(0, Ordering::Relaxed);
+    }
+
+    /// Check if this route matches the full request, including header, query
+    /// and body matchers
+    pub fn matches_request(&self, request: &RequestInfo) -> Option<HashMap<String, String>> {
+        let params = self.matches(&request.method, &request.path)?;
+        let matcher = &self.config.matcher;
+
+        for (name, expected) in &matcher.headers {
+            let actual = request.headers.iter()
+                .find(|(k, _)| k.eq_ignore_ascii_case(name))
+                .map(|(_, v)| v);
+            if actual != Some(expected) {
+                return None;
+            }
+        }
+
+        for (name, expected) in &matcher.query {
+            if request.query.get(name) != Some(expected) {
+                return None;
+            }
+        }
+
+        if let Some(ref regex) = self.body_regex {
+            if !regex.is_match(&request.body) {
+                return None;
+            }
+        }
+
+        if matcher.needs_json() {
+            let body: serde_json::Value = serde_json::from_str(&request.body).ok()?;
+
+            if let Some(ref expected) = matcher.body_json {
+                if &body != expected {
+                    return None;
+                }
+            }
+
+            if let Some(ref expected) = matcher.body_json_partial {
+                if !json_contains(&body, expected) {
+                    return None;
+                }
+            }
+
+            if let Some(ref path) = matcher.body_jsonpath {
+                use jsonpath_rust::JsonPath;
+                match body.query(path) {
+                    Ok(found) if !found.is_empty() => {}
+                    _ => return None,
+                }
+            }
+        }
+
+        Some(params)
+    }
+
+    /// How specific this route is: matcher conditions first, then literal path
+    /// characters. When several routes match, the most specific one wins.
+    pub fn specificity(&self) -> (usize, usize) {
+        let conditions = self.config.matcher.conditions() + usize::from(self.config.required_state.is_some());
+        (conditions, literal_path_len(&self.config.path))
+    }
+
     /// Check if this route matches the request
     pub fn matches(&self, method: &str, path: &str) -> Option<HashMap<String, String>> {
         if !self.config.enabled {
@@ -231,6 +501,50 @@ impl Route {
     }
 }
 
+/// Compiled `body_schema`
+#[derive(Clone)]
+struct BodyValidator(Arc<jsonschema::Validator>);
+
+impl std::fmt::Debug for BodyValidator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("BodyValidator")
+    }
+}
+
+/// Whether `actual` contains everything in `expected`.
+///
+/// Objects may have extra fields; arrays must have the same length and match
+/// element by element; everything else must be equal.
+fn json_contains(actual: &serde_json::Value, expected: &serde_json::Value) -> bool {
+    use serde_json::Value;
+
+    match (actual, expected) {
+        (Value::Object(actual), Value::Object(expected)) => expected.iter()
+            .all(|(key, value)| actual.get(key).is_some_and(|a| json_contains(a, value))),
+        (Value::Array(actual), Value::Array(expected)) => actual.len() == expected.len()
+            && actual.iter().zip(expected).all(|(a, e)| json_contains(a, e)),
+        _ => actual == expected,
+    }
+}
+
+/// Number of path characters outside `:param` placeholders and wildcards
+fn literal_path_len(pattern: &str) -> usize {
+    let mut len = 0;
+    let mut in_param = false;
+    for c in pattern.chars() {
+        match c {
+            ':' => in_param = true,
+            '*' => in_param = false,
+            c if in_param && (c.is_alphanumeric() || c == '_') => {}
+            _ => {
+                in_param = false;
+                len += 1;
+            }
+        }
+    }
+    len
+}
+
 /// Compile a path pattern into a regex
 fn compile_path_pattern(pattern: &str) -> Result<(Regex, Vec<String>), String> {
     let mut regex_str = String::from("^");
@@ -370,4 +684,174 @@ mod tests {
         assert!(!HttpMethod::Get.matches("POST"));
         assert!(HttpMethod::Any.matches("DELETE"));
     }
+
+    fn request(method: &str, path: &str, body: &str) -> RequestInfo {
+        RequestInfo::new(
+            method.to_string(),
+            path.to_string(),
+            HashMap::new(),
+            HashMap::new(),
+            body.to_string(),
+            HashMap::new(),
+            "127.0.0.1".to_string(),
+        )
+    }
+
+    #[test]
+    fn test_match_headers_and_query() {
+        let mut config = RouteConfig::get("/items", "v2");
+        config.matcher.headers.insert("X-Api-Version".to_string(), "2".to_string());
+        config.matcher.query.insert("page".to_string(), "1".to_string());
+        let route = Route::new(config).unwrap();
+
+        let mut req = request("GET", "/items", "");
+        assert!(route.matches_request(&req).is_none());
+
+        req.headers.insert("x-api-version".to_string(), "2".to_string());
+        assert!(route.matches_request(&req).is_none());
+
+        req.query.insert("page".to_string(), "1".to_string());
+        assert!(route.matches_request(&req).is_some());
+
+        req.headers.insert("x-api-version".to_string(), "3".to_string());
+        assert!(route.matches_request(&req).is_none());
+    }
+
+    #[test]
+    fn test_match_json_body() {
+        let mut config = RouteConfig::post_json("/graphql", serde_json::json!({}));
+        config.matcher.body_json_partial = Some(serde_json::json!({"operationName": "GetUser"}));
+        let partial = Route::new(config).unwrap();
+
+        let body = r#"{"operationName":"GetUser","variables":{"id":1}}"#;
+        assert!(partial.matches_request(&request("POST", "/graphql", body)).is_some());
+        assert!(partial.matches_request(&request("POST", "/graphql", r#"{"operationName":"ListUsers"}"#)).is_none());
+        assert!(partial.matches_request(&request("POST", "/graphql", "not json")).is_none());
+
+        let mut config = RouteConfig::post_json("/graphql", serde_json::json!({}));
+        config.matcher.body_json = Some(serde_json::json!({"variables": {"id": 1}, "operationName": "GetUser"}));
+        let exact = Route::new(config).unwrap();
+        assert!(exact.matches_request(&request("POST", "/graphql", body)).is_some());
+        assert!(exact.matches_request(&request("POST", "/graphql", r#"{"operationName":"GetUser"}"#)).is_none());
+    }
+
+    #[test]
+    fn test_match_jsonpath_and_regex() {
+        let mut config = RouteConfig::post_json("/orders", serde_json::json!({}));
+        config.matcher.body_jsonpath = Some("$.items[?(@.sku == 'A1')]".to_string());
+        let route = Route::new(config).unwrap();
+        assert!(route.matches_request(&request("POST", "/orders", r#"{"items":[{"sku":"A1"}]}"#)).is_some());
+        assert!(route.matches_request(&request("POST", "/orders", r#"{"items":[{"sku":"B2"}]}"#)).is_none());
+
+        let mut config = RouteConfig::post_json("/orders", serde_json::json!({}));
+        config.matcher.body_regex = Some(r"^<order id=\d+".to_string());
+        let route = Route::new(config).unwrap();
+        assert!(route.matches_request(&request("POST", "/orders", "<order id=7/>")).is_some());
+        assert!(route.matches_request(&request("POST", "/orders", "<order/>")).is_none());
+
+        let mut config = RouteConfig::get("/", "");
+        config.matcher.body_regex = Some("(".to_string());
+        assert!(Route::new(config).is_err());
+    }
+
+    #[test]
+    fn test_json_contains() {
+        use serde_json::json;
+        assert!(json_contains(&json!({"a": 1, "b": {"c": 2, "d": 3}}), &json!({"b": {"c": 2}})));
+        assert!(!json_contains(&json!({"a": 1}), &json!({"a": 2})));
+        assert!(json_contains(&json!([{"a": 1, "b": 2}]), &json!([{"a": 1}])));
+        assert!(!json_contains(&json!([1, 2]), &json!([1])));
+    }
+
+    #[test]
+    fn test_specificity() {
+        let plain = Route::new(RouteConfig::get("/users/:id", "")).unwrap();
+        let literal = Route::new(RouteConfig::get("/users/me", "")).unwrap();
+        assert!(literal.specificity() > plain.specificity());
+
+        let mut config = RouteConfig::get("/users/:id", "");
+        config.matcher.headers.insert("authorization".to_string(), "Bearer x".to_string());
+        let with_header = Route::new(config).unwrap();
+        assert!(with_header.specificity() > literal.specificity());
+    }
+
+    #[test]
+    fn test_response_sequence() {
+        let mut config = RouteConfig::get("/jobs/1", "");
+        config.responses = vec![ResponseConfig::text("pending"), ResponseConfig::text("done")];
+        let route = Route::new(config.clone()).unwrap();
+        let bodies: Vec<_> = (0..3).map(|_| route.next_response().body.unwrap()).collect();
+        assert_eq!(bodies, vec!["pending", "done", "done"]);
+
+        route.reset_sequence();
+        assert_eq!(route.next_response().body.as_deref(), Some("pending"));
+
+        config.sequence = SequenceMode::Cycle;
+        let route = Route::new(config).unwrap();
+        let bodies: Vec<_> = (0..3).map(|_| route.next_response().body.unwrap()).collect();
+        assert_eq!(bodies, vec!["pending", "done", "pending"]);
+    }
+
+    #[test]
+    fn test_scenario_matching() {
+        let mut config = RouteConfig::get("/orders/1", "pending");
+        config.scenario = Some("order".to_string());
+        config.required_state = Some("Created".to_string());
+        config.new_state = Some("Shipped".to_string());
+        let route = Route::new(config).unwrap();
+
+        let mut states = ScenarioStates::default();
+        assert!(!route.scenario_matches(&states));
+
+        states.set("order", "Created");
+        assert!(route.scenario_matches(&states));
+        route.transition(&mut states);
+        assert_eq!(states.get("order"), "Shipped");
+        assert!(!route.scenario_matches(&states));
+
+        let mut config = RouteConfig::get("/", "");
+        config.new_state = Some("Next".to_string());
+        assert!(Route::new(config).is_err());
+    }
+
+    #[test]
+    fn test_validate_body() {
+        let mut config = RouteConfig::post_json("/users", serde_json::json!({}));
+        config.body_schema = Some(serde_json::json!({
+            "type": "object",
+            "required": ["name"],
+            "properties": {"age": {"type": "integer"}}
+        }));
+        let route = Route::new(config).unwrap();
+
+        assert!(route.validate_body(r#"{"name": "a", "age": 3}"#).is_ok());
+
+        let violations = route.validate_body(r#"{"age": "old"}"#).unwrap_err();
+        assert_eq!(violations.len(), 2);
+        assert!(violations.iter().any(|v| v.starts_with("/age: ")));
+
+        assert!(route.validate_body("{").unwrap_err()[0].contains("not valid JSON"));
+        assert!(route.validate_body("").is_err());
+
+        let plain = Route::new(RouteConfig::get("/", "")).unwrap();
+        assert!(plain.validate_body("anything").is_ok());
+    }
+
+    #[test]
+    fn test_yaml_match_block() {
+        let yaml = r#"
+method: POST
+path: /graphql
+match:
+  headers:
+    Content-Type: application/json
+  body_json_partial:
+    operationName: GetUser
+response:
+  status: 200
+"#;
+        let config: RouteConfig = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(config.matcher.conditions(), 2);
+        assert!(config.enabled);
+    }
 }
@@ -0,0 +1,81 @@
+//! Scenario state for stateful mock routes
+//!
+//! A scenario is a named state machine. Routes can require the scenario to be
+//! in a given state and move it to a new state when they match, which models
+//! flows like "create → poll pending → done". Every scenario starts in
+//! [`STARTED`].
+
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+
+/// State every scenario is in before any transition
+pub const STARTED: &str = "Started";
+
+/// Current state of every scenario that has left [`STARTED`]
+#[derive(Debug, Default)]
+pub struct ScenarioStates {
+    states: HashMap<String, String>,
+}
+
+impl ScenarioStates {
+    /// Current state of a scenario
+    pub fn get(&self, scenario: &str) -> &str {
+        self.states.get(scenario).map(String::as_str).unwrap_or(STARTED)
+    }
+
+    /// Move a scenario to a new state
+    pub fn set(&mut self, scenario: &str, state: &str) {
+        self.states.insert(scenario.to_string(), state.to_string());
+    }
+
+    /// Snapshot of all scenarios that have left the initial state
+    pub fn all(&self) -> HashMap<String, String> {
+        self.states.clone()
+    }
+
+    /// Put every scenario back in [`STARTED`]
+    pub fn reset(&mut self) {
+        self.states.clear();
+    }
+}
+
+/// Scenario states shared by all connections
+#[derive(Debug, Default)]
+pub struct ScenarioStore {
+    states: Mutex<ScenarioStates>,
+}
+
+impl ScenarioStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Lock the states; route selection and the transition happen under one lock
+    pub fn lock(&self) -> MutexGuard<'_, ScenarioStates> {
+        self.states.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Put every scenario back in [`STARTED`]
+    pub fn reset(&self) {
+        self.lock().reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scenario_states() {
+        let store = ScenarioStore::new();
+        assert_eq!(store.lock().get("order"), STARTED);
+
+        store.lock().set("order", "Pending");
+        assert_eq!(store.lock().get("order"), "Pending");
+        assert_eq!(store.lock().get("other"), STARTED);
+        assert_eq!(store.lock().all().len(), 1);
+
+        store.reset();
+        assert_eq!(store.lock().get("order"), STARTED);
+    }
+}
@@ -1,20 +1,60 @@
 //! Mock HTTP server implementation
 
 use std::collections::HashMap;
+use std::convert::Infallible;
 use std::net::SocketAddr;
+use std::path::Path;
 use std::sync::Arc;
+use std::time::Instant;
+
+use bytes::Bytes;
+use http_body_util::combinators::UnsyncBoxBody;
+use http_body_util::{BodyExt, Full, LengthLimitError, Limited, StreamBody};
+use hyper::body::Frame;
+use tokio::io::{AsyncRead, AsyncWrite};
 use tokio::sync::RwLock;
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
 
+use super::admin;
 use super::config::MockServerConfig;
-use super::routes::{Route, ResponseConfig, RequestInfo};
+use super::journal::RequestJournal;
+use super::recorder::{self, ExchangeSource, RecordedResponse, Recorder};
+use super::reload::ConfigWatcher;
+use super::routes::{Route, RouteConfig, ResponseConfig, RequestInfo};
+use super::scenarios::{ScenarioStates, ScenarioStore};
+use super::tls::MockTls;
 use crate::errors::QuicpulseError;
 
+/// Error type for streamed response bodies
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Response body type
+type HyperBody = UnsyncBoxBody<Bytes, BoxError>;
+
+/// Largest request body read unless configured otherwise (10 MiB)
+pub const DEFAULT_MAX_BODY_BYTES: usize = 10 * 1024 * 1024;
+
 /// Mock HTTP server
 pub struct MockServer {
     config: MockServerConfig,
-    routes: Vec<Route>,
-    request_log: Arc<RwLock<Vec<RequestInfo>>>,
+    routes: Arc<RwLock<Vec<Route>>>,
+    request_log: Arc<RequestJournal>,
+    recorder: Option<Arc<Recorder>>,
+    proxy_client: Option<reqwest::Client>,
+    tls: Option<MockTls>,
+    scenarios: Arc<ScenarioStore>,
+    /// Ids of the routes loaded from `config.source`
+    file_routes: Vec<String>,
+}
+
+/// State shared by every connection handler
+pub(super) struct ConnectionContext {
+    pub(super) routes: Arc<RwLock<Vec<Route>>>,
+    pub(super) config: MockServerConfig,
+    pub(super) request_log: Arc<RequestJournal>,
+    pub(super) recorder: Option<Arc<Recorder>>,
+    pub(super) proxy_client: Option<reqwest::Client>,
+    pub(super) scheme: &'static str,
+    pub(super) scenarios: Arc<ScenarioStore>,
 }
 
 impl MockServer {
@@ -26,17 +66,64 @@ impl MockServer {
             .map(|rc| Route::new(rc.clone()))
             .collect::<Result<Vec<_>, _>>()
             .map_err(|e| QuicpulseError::Config(format!("Invalid route: {}", e)))?;
+        let file_routes = routes.iter()
+            .take(config.source_routes)
+            .map(|route| route.id.clone())
+            .collect();
 
         // Sort by priority (higher first)
         routes.sort_by(|a, b| b.config.priority.cmp(&a.config.priority));
 
+        let recorder = config.record_to.as_ref()
+            .map(Recorder::new)
+            .transpose()?
+            .map(Arc::new);
+
+        // The proxy passes bodies through untouched, so no decompression or redirects
+        let proxy_client = match config.proxy_to {
+            Some(_) => Some(
+                reqwest::Client::builder()
+                    .redirect(reqwest::redirect::Policy::none())
+                    .no_gzip()
+                    .no_brotli()
+                    .no_deflate()
+                    .no_zstd()
+                    .build()
+                    .map_err(|e| QuicpulseError::Config(format!("Failed to create proxy client: {}", e)))?,
+            ),
+            None => None,
+        };
+
+        let tls = config.tls.as_ref()
+            .map(|tls| MockTls::from_config(tls, &config.host))
+            .transpose()?;
+
+        let request_log = Arc::new(RequestJournal::new(config.journal_limit));
+
         Ok(Self {
             config,
-            routes,
-            request_log: Arc::new(RwLock::new(Vec::new())),
+            routes: Arc::new(RwLock::new(routes)),
+            request_log,
+            recorder,
+            proxy_client,
+            tls,
+            scenarios: Arc::new(ScenarioStore::new()),
+            file_routes,
         })
     }
 
+    /// URL scheme the server is reachable on
+    pub fn scheme(&self) -> &'static str {
+        if self.tls.is_some() { "https" } else { "http" }
+    }
+
+    /// Path of the generated CA certificate when running with a self-signed certificate
+    pub fn ca_cert_path(&self) -> Option<&Path> {
+        self.tls.as_ref()?
+            .self_signed.as_ref()
+            .map(|cert| cert.cert_path.as_path())
+    }
+
     /// Start the server
     pub async fn run(&self) -> Result<(), QuicpulseError> {
         let addr: SocketAddr = self.config.address().parse()
@@ -45,13 +132,34 @@ impl MockServer {
         let listener = tokio::net::TcpListener::bind(&addr).await
             .map_err(|e| QuicpulseError::Io(e))?;
 
-        eprintln!("Mock server listening on http://{}", addr);
+        eprintln!("Mock server listening on {}://{}", self.scheme(), addr);
 
-        if self.routes.is_empty() {
+        if let Some(ref tls) = self.tls {
+            if let Some(ref cert) = tls.self_signed {
+                eprintln!("Using self-signed TLS certificate");
+                eprintln!("  SHA-256 fingerprint: {}", cert.fingerprint);
+                eprintln!("  CA certificate: {}", cert.cert_path.display());
+                eprintln!("  Trust it with: --verify={}", cert.cert_path.display());
+            }
+            if self.config.tls.as_ref().is_some_and(|tls| tls.http2) {
+                eprintln!("HTTP/2 enabled via ALPN");
+            }
+        }
+
+        if let Some(ref recorder) = self.recorder {
+            eprintln!("Recording requests to {}", recorder.path().display());
+        }
+
+        if let Some(ref upstream) = self.config.proxy_to {
+            eprintln!("Proxying unmatched requests to {}", upstream);
+        }
+
+        let routes = self.routes.read().await;
+        if routes.is_empty() {
             eprintln!("Warning: No routes configured, all requests will return 404");
         } else {
             eprintln!("Configured routes:");
-            for route in &self.routes {
+            for route in routes.iter() {
                 let name = route.config.name.as_deref().unwrap_or("");
                 eprintln!("  {:?} {} -> {} {}",
                     route.config.method,
@@ -62,34 +170,89 @@ impl MockServer {
             }
         }
 
-        loop {
-            match listener.accept().await {
-                Ok((stream, peer_addr)) => {
-                    let routes = self.routes.clone();
-                    let config = self.config.clone();
-                    let log = Arc::clone(&self.request_log);
+        drop(routes);
+        if self.config.admin {
+            eprintln!("Admin API available at {}", admin::ADMIN_PREFIX);
+        }
 
-                    tokio::spawn(async move {
-                        if let Err(e) = handle_connection(stream, peer_addr, &routes, &config, log).await {
-                            eprintln!("Connection error from {}: {}", peer_addr, e);
-                        }
-                    });
-                }
-                Err(e) => {
-                    eprintln!("Accept error: {}", e);
+        let ctx = Arc::new(ConnectionContext {
+            routes: Arc::clone(&self.routes),
+            config: self.config.clone(),
+            request_log: Arc::clone(&self.request_log),
+            recorder: self.recorder.clone(),
+            proxy_client: self.proxy_client.clone(),
+            scheme: self.scheme(),
+            scenarios: Arc::clone(&self.scenarios),
+        });
+        let acceptor = self.tls.as_ref().map(|tls| tls.acceptor.clone());
+
+        let watcher = self.config.source.clone().map(|path| {
+            eprintln!("Watching {} for changes", path.display());
+            ConfigWatcher::new(path, self.config.body_files(), self.file_routes.clone())
+        });
+
+        let accept_loop = async {
+            loop {
+                match listener.accept().await {
+                    Ok((stream, peer_addr)) => {
+                        let ctx = Arc::clone(&ctx);
+                        let acceptor = acceptor.clone();
+
+                        tokio::spawn(async move {
+                            let result = match acceptor {
+                                Some(acceptor) => match acceptor.accept(stream).await {
+                                    Ok(tls_stream) => serve_connection(tls_stream, peer_addr, ctx).await,
+                                    Err(e) => Err(QuicpulseError::Ssl(format!("TLS handshake failed: {}", e))),
+                                },
+                                None => serve_connection(stream, peer_addr, ctx).await,
+                            };
+
+                            if let Err(e) = result {
+                                eprintln!("Connection error from {}: {}", peer_addr, e);
+                            }
+                        });
+                    }
+                    Err(e) => {
+                        eprintln!("Accept error: {}", e);
+                    }
                 }
             }
+        };
+
+        match watcher {
+            Some(watcher) => tokio::select! {
+                _ = accept_loop => {}
+                _ = watcher.run(Arc::clone(&self.routes)) => {}
+            },
+            None => accept_loop.await,
         }
+
+        Ok(())
     }
 
-    /// Get the request log
+    /// Get the request log; only the most recent `journal_limit` requests are kept
     pub async fn get_requests(&self) -> Vec<RequestInfo> {
-        self.request_log.read().await.clone()
+        self.request_log.all()
     }
 
     /// Clear the request log
     pub async fn clear_requests(&self) {
-        self.request_log.write().await.clear();
+        self.request_log.clear();
+    }
+
+    /// Put every scenario back in its initial state and restart response sequences
+    pub async fn reset_scenarios(&self) {
+        self.scenarios.reset();
+        for route in self.routes.read().await.iter() {
+            route.reset_sequence();
+        }
+    }
+
+    /// Get the currently active routes
+    pub async fn get_routes(&self) -> Vec<RouteConfig> {
+        self.routes.read().await.iter()
+            .map(|route| route.config.clone())
+            .collect()
     }
 
     /// Generate a simple config for the given routes
@@ -110,32 +273,47 @@ impl MockServer {
                 priority: 0,
                 enabled: true,
                 name: None,
+                ..Default::default()
             });
         }
         config
     }
 }
 
-/// Handle a single connection
-async fn handle_connection(
-    mut stream: tokio::net::TcpStream,
-    peer_addr: SocketAddr,
-    routes: &[Route],
-    config: &MockServerConfig,
-    log: Arc<RwLock<Vec<RequestInfo>>>,
-) -> Result<(), QuicpulseError> {
-    let mut buf = vec![0u8; 8192];
-    let n = stream.read(&mut buf).await.map_err(QuicpulseError::Io)?;
+/// Status line and headers of a mock response
+#[derive(Debug, Clone)]
+struct ResponseHead {
+    status: u16,
+    /// Headers in send order; repeated names (Set-Cookie) are kept
+    headers: Vec<(String, String)>,
+    source: ExchangeSource,
+}
 
-    if n == 0 {
-        return Ok(());
-    }
+/// Body of a mock response
+enum MockBody {
+    /// Fully built body (routes, default and error responses)
+    Full(Vec<u8>),
+    /// Upstream body, relayed as it arrives
+    Stream(reqwest::Response),
+}
 
-    let request_str = String::from_utf8_lossy(&buf[..n]);
-    let request = parse_request(&request_str)?;
+/// A response produced for a request, independent of the wire protocol
+struct MockResponse {
+    head: ResponseHead,
+    body: MockBody,
+}
 
-    // Log request if enabled
-    if config.log_requests {
+impl ConnectionContext {
+    /// Put every scenario back in its initial state and restart response sequences
+    pub(super) async fn reset_scenarios(&self) {
+        self.scenarios.reset();
+        for route in self.routes.read().await.iter() {
+            route.reset_sequence();
+        }
+    }
+
+    /// Build the request info and add it to the journal, printing it if enabled
+    async fn log_request(&self, request: &ParsedRequest, peer_addr: SocketAddr) -> RequestInfo {
         let info = RequestInfo::new(
             request.method.clone(),
             request.path.clone(),
@@ -146,30 +324,247 @@ async fn handle_connection(
             peer_addr.ip().to_string(),
         );
 
-        eprintln!("[{}] {} {} {} from {}",
-            info.timestamp,
-            request.method,
-            request.path,
-            if request.query.is_empty() { String::new() } else { format!("?{}", query_string(&request.query)) },
-            peer_addr
-        );
+        if self.config.log_requests {
+            eprintln!("[{}] {} {} {} from {}",
+                info.timestamp,
+                request.method,
+                request.path,
+                if request.query.is_empty() { String::new() } else { format!("?{}", query_string(&request.query)) },
+                peer_addr
+            );
+        }
+
+        // The journal backs the admin API's verification, so it is kept even without logging
+        self.request_log.push(&info);
 
-        log.write().await.push(info);
+        info
     }
 
-    // Find matching route
-    let (response, params) = find_route(routes, &request.method, &request.path, config);
+    /// Serve a matching route, forward to the upstream, or fall back to the default response
+    async fn respond(&self, request: &ParsedRequest, info: &RequestInfo) -> MockResponse {
+        let config = &self.config;
 
-    // Apply latency if configured
-    if let Some((min, max)) = config.latency {
-        let delay = if min == max {
-            min
-        } else {
-            min + rand::random::<u64>() % (max - min)
+        // Apply latency if configured
+        if let Some((min, max)) = config.latency {
+            let delay = if min == max {
+                min
+            } else {
+                min + rand::random::<u64>() % (max - min)
+            };
+            tokio::time::sleep(tokio::time::Duration::from_millis(delay)).await;
+        }
+
+        // Select the route and apply its scenario transition atomically
+        let matched = {
+            let routes = self.routes.read().await;
+            let mut states = self.scenarios.lock();
+            find_route(&routes, info, &states).map(|(route, params)| match route.validate_body(&info.body) {
+                Ok(()) => {
+                    route.transition(&mut states);
+                    (route.next_response(), params)
+                }
+                Err(violations) => (invalid_body_response(violations), params),
+            })
         };
-        tokio::time::sleep(tokio::time::Duration::from_millis(delay)).await;
+
+        match matched {
+            Some((response, params)) => route_response(&response, request, &params, config).await,
+            None => match (config.proxy_to.as_deref(), self.proxy_client.as_ref()) {
+                (Some(upstream), Some(client)) => proxy_request(client, upstream, request, config).await,
+                _ => {
+                    let response = config.default_response.clone().unwrap_or_else(|| {
+                        ResponseConfig::error(404, "Not Found")
+                    });
+                    route_response(&response, request, &HashMap::new(), config).await
+                }
+            },
+        }
+    }
+
+    /// Record a completed exchange; a failing record file must not break the response
+    async fn record(&self, info: &RequestInfo, version: &str, head: &ResponseHead, body: &[u8], started: Instant) {
+        let Some(ref recorder) = self.recorder else {
+            return;
+        };
+
+        let host = info.headers.get("host").cloned().unwrap_or_else(|| self.config.address());
+        let headers: HashMap<String, String> = head.headers.iter().cloned().collect();
+        let mut entry = recorder::build_entry(
+            info,
+            &format!("{}://{}", self.scheme, host),
+            version,
+            RecordedResponse {
+                status: head.status,
+                headers: &headers,
+                body,
+            },
+            started.elapsed(),
+        );
+        if head.source == ExchangeSource::Proxy {
+            entry.comment = self.config.proxy_to.as_ref().map(|upstream| format!("proxied to {}", upstream));
+        }
+        if let Err(e) = recorder.record(entry, head.source).await {
+            eprintln!("Failed to record request to {}: {}", recorder.path().display(), e);
+        }
+    }
+}
+
+/// Serve an HTTP/1.1 or HTTP/2 connection.
+///
+/// HTTP/2 is detected from the connection preface, so it is served both after
+/// ALPN negotiation over TLS and with prior knowledge over plain TCP (h2c).
+/// HTTP/1.1 connections are kept alive and pipelined requests are answered in
+/// order; chunked request bodies and `Expect: 100-continue` are handled by
+/// the protocol layer.
+async fn serve_connection<S>(
+    io: S,
+    peer_addr: SocketAddr,
+    ctx: Arc<ConnectionContext>,
+) -> Result<(), QuicpulseError>
+where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    let service = hyper::service::service_fn(move |req| {
+        let ctx = Arc::clone(&ctx);
+        async move { Ok::<_, Infallible>(handle_request(req, peer_addr, ctx).await) }
+    });
+
+    let mut builder = hyper_util::server::conn::auto::Builder::new(hyper_util::rt::TokioExecutor::new());
+    builder.http1()
+        .keep_alive(true)
+        .pipeline_flush(true);
+
+    builder.serve_connection(hyper_util::rt::TokioIo::new(io), service)
+        .await
+        .map_err(|e| QuicpulseError::Connection(format!("HTTP connection error: {}", e)))
+}
+
+/// Handle one request
+async fn handle_request(
+    req: hyper::Request<hyper::body::Incoming>,
+    peer_addr: SocketAddr,
+    ctx: Arc<ConnectionContext>,
+) -> hyper::Response<HyperBody> {
+    let started = Instant::now();
+    let (parts, body) = req.into_parts();
+    let version = http_version_name(parts.version);
+    let limit = ctx.config.max_body_bytes;
+    let body = match Limited::new(body, limit).collect().await {
+        Ok(collected) => collected.to_bytes(),
+        Err(e) if e.is::<LengthLimitError>() => {
+            return plain_response(413, format!("Request body larger than {} bytes", limit));
+        }
+        Err(e) => return plain_response(400, format!("Failed to read request body: {}", e)),
+    };
+
+    let request = ParsedRequest::from_http(&parts, body);
+    if ctx.config.admin && admin::is_admin_path(&request.path) {
+        let authorization = request.headers.get("authorization").map(String::as_str);
+        let response = admin::handle(&ctx, &request.method, &request.path, authorization, &request.body).await;
+        return admin_response(response, &ctx.config);
     }
 
+    let info = ctx.log_request(&request, peer_addr).await;
+    let MockResponse { head, body } = ctx.respond(&request, &info).await;
+
+    let mut builder = hyper::Response::builder().status(head.status);
+    for (name, value) in &head.headers {
+        if !is_hop_by_hop(name) {
+            builder = builder.header(name.as_str(), value.as_str());
+        }
+    }
+
+    let body = match body {
+        MockBody::Full(bytes) => {
+            ctx.record(&info, version, &head, &bytes, started).await;
+            Full::new(Bytes::from(bytes))
+                .map_err(|never| match never {})
+                .boxed_unsync()
+        }
+        MockBody::Stream(mut upstream) => {
+            let capture = ctx.recorder.is_some();
+            // The last chunk is held back until the exchange is recorded: with a
+            // Content-Length, hyper stops polling once it has written that many bytes
+            let stream = async_stream::stream! {
+                let mut captured = Vec::new();
+                let mut pending = None;
+                loop {
+                    match upstream.chunk().await {
+                        Ok(Some(chunk)) => {
+                            if capture {
+                                captured.extend_from_slice(&chunk);
+                            }
+                            if let Some(previous) = pending.replace(chunk) {
+                                yield Ok::<_, BoxError>(Frame::data(previous));
+                            }
+                        }
+                        Ok(None) => break,
+                        Err(e) => {
+                            yield Err(Box::new(e) as BoxError);
+                            return;
+                        }
+                    }
+                }
+                ctx.record(&info, version, &head, &captured, started).await;
+                if let Some(last) = pending {
+                    yield Ok(Frame::data(last));
+                }
+            };
+            StreamBody::new(stream).boxed_unsync()
+        }
+    };
+
+    builder.body(body).unwrap_or_else(|e| plain_response(500, format!("Invalid response: {}", e)))
+}
+
+/// 400 response listing the ways a request body violates its route's schema
+fn invalid_body_response(violations: Vec<String>) -> ResponseConfig {
+    let mut response = ResponseConfig::json_body(serde_json::json!({
+        "error": "Request body does not match the schema",
+        "violations": violations,
+    }));
+    response.status = 400;
+    response
+}
+
+/// Build the JSON response for an admin request
+fn admin_response(response: admin::AdminResponse, config: &MockServerConfig) -> hyper::Response<HyperBody> {
+    let body = serde_json::to_vec_pretty(&response.body).unwrap_or_default();
+    let mut headers = HashMap::new();
+    if config.cors {
+        add_cors_headers(&mut headers);
+    }
+
+    let mut builder = hyper::Response::builder()
+        .status(response.status)
+        .header("Content-Type", "application/json");
+    for (name, value) in &headers {
+        builder = builder.header(name.as_str(), value.as_str());
+    }
+    builder
+        .body(Full::new(Bytes::from(body)).map_err(|never| match never {}).boxed_unsync())
+        .unwrap_or_else(|e| plain_response(500, format!("Invalid response: {}", e)))
+}
+
+/// Build a plain-text hyper response
+fn plain_response(status: u16, message: String) -> hyper::Response<HyperBody> {
+    let mut response = hyper::Response::new(
+        Full::new(Bytes::from(message))
+            .map_err(|never| match never {})
+            .boxed_unsync(),
+    );
+    *response.status_mut() = hyper::StatusCode::from_u16(status)
+        .unwrap_or(hyper::StatusCode::INTERNAL_SERVER_ERROR);
+    response
+}
+
+/// Build the response for a configured route
+async fn route_response(
+    response: &ResponseConfig,
+    request: &ParsedRequest,
+    params: &HashMap<String, String>,
+    config: &MockServerConfig,
+) -> MockResponse {
     // Apply route-specific delay
     if response.delay_ms > 0 {
         tokio::time::sleep(tokio::time::Duration::from_millis(response.delay_ms)).await;
@@ -177,17 +572,16 @@ async fn handle_connection(
 
     // Build response
     let mut response_headers = response.headers.clone();
+    response_headers.retain(|name, _| !name.eq_ignore_ascii_case("content-length"));
 
     // Add CORS headers if enabled
     if config.cors {
-        response_headers.insert("Access-Control-Allow-Origin".to_string(), "*".to_string());
-        response_headers.insert("Access-Control-Allow-Methods".to_string(), "GET, POST, PUT, DELETE, PATCH, OPTIONS".to_string());
-        response_headers.insert("Access-Control-Allow-Headers".to_string(), "*".to_string());
+        add_cors_headers(&mut response_headers);
     }
 
     // Get body, applying template if needed
     let body = if response.template {
-        apply_template(&response.get_body(), &request, &params)
+        apply_template(&response.get_body(), request, params)
     } else {
         response.get_body()
     };
@@ -195,77 +589,138 @@ async fn handle_connection(
     // Set content-length
     response_headers.insert("Content-Length".to_string(), body.len().to_string());
 
-    // Build HTTP response
-    let status_text = http_status_text(response.status);
-    let mut response_str = format!("HTTP/1.1 {} {}\r\n", response.status, status_text);
+    MockResponse {
+        head: ResponseHead {
+            status: response.status,
+            headers: response_headers.into_iter().collect(),
+            source: ExchangeSource::Mock,
+        },
+        body: MockBody::Full(body),
+    }
+}
+
+/// Add permissive CORS headers
+fn add_cors_headers(headers: &mut HashMap<String, String>) {
+    headers.insert("Access-Control-Allow-Origin".to_string(), "*".to_string());
+    headers.insert("Access-Control-Allow-Methods".to_string(), "GET, POST, PUT, DELETE, PATCH, OPTIONS".to_string());
+    headers.insert("Access-Control-Allow-Headers".to_string(), "*".to_string());
+}
+
+/// Headers that apply to a single connection and must not be forwarded
+fn is_hop_by_hop(name: &str) -> bool {
+    matches!(
+        name.to_ascii_lowercase().as_str(),
+        "connection" | "keep-alive" | "proxy-authenticate" | "proxy-authorization"
+            | "te" | "trailer" | "transfer-encoding" | "upgrade"
+    )
+}
 
-    for (name, value) in &response_headers {
-        response_str.push_str(&format!("{}: {}\r\n", name, value));
+/// Forward a request to the upstream server; the body is streamed back by the caller
+async fn proxy_request(
+    client: &reqwest::Client,
+    upstream: &str,
+    request: &ParsedRequest,
+    config: &MockServerConfig,
+) -> MockResponse {
+    let mut url = format!("{}{}", upstream.trim_end_matches('/'), request.path);
+    if !request.raw_query.is_empty() {
+        url.push('?');
+        url.push_str(&request.raw_query);
     }
-    response_str.push_str("\r\n");
 
-    // Send response
-    stream.write_all(response_str.as_bytes()).await.map_err(QuicpulseError::Io)?;
-    stream.write_all(&body).await.map_err(QuicpulseError::Io)?;
-    stream.flush().await.map_err(QuicpulseError::Io)?;
+    let Ok(method) = reqwest::Method::from_bytes(request.method.as_bytes()) else {
+        let response = ResponseConfig::error(400, &format!("Invalid HTTP method: {}", request.method));
+        return route_response(&response, request, &HashMap::new(), config).await;
+    };
+
+    let mut builder = client.request(method, &url);
+    for (name, value) in &request.headers {
+        if is_hop_by_hop(name) || name == "host" || name == "content-length" {
+            continue;
+        }
+        builder = builder.header(name.as_str(), value.as_str());
+    }
+    if !request.raw_body.is_empty() {
+        builder = builder.body(request.raw_body.clone());
+    }
 
-    Ok(())
+    let upstream_response = match builder.send().await {
+        Ok(resp) => resp,
+        Err(e) => {
+            eprintln!("Proxy error for {}: {}", url, e);
+            let response = ResponseConfig::error(502, &format!("Bad Gateway: {}", e));
+            return route_response(&response, request, &HashMap::new(), config).await;
+        }
+    };
+
+    let mut headers: Vec<(String, String)> = upstream_response.headers().iter()
+        .filter(|(name, _)| !is_hop_by_hop(name.as_str()))
+        .map(|(name, value)| (name.to_string(), String::from_utf8_lossy(value.as_bytes()).to_string()))
+        .collect();
+    if config.cors {
+        let mut cors = HashMap::new();
+        add_cors_headers(&mut cors);
+        headers.extend(cors);
+    }
+
+    MockResponse {
+        head: ResponseHead {
+            status: upstream_response.status().as_u16(),
+            headers,
+            source: ExchangeSource::Proxy,
+        },
+        body: MockBody::Stream(upstream_response),
+    }
+}
+
+/// HAR name of a protocol version
+fn http_version_name(version: hyper::Version) -> &'static str {
+    match version {
+        hyper::Version::HTTP_09 => "HTTP/0.9",
+        hyper::Version::HTTP_10 => "HTTP/1.0",
+        hyper::Version::HTTP_2 => "HTTP/2",
+        hyper::Version::HTTP_3 => "HTTP/3",
+        _ => "HTTP/1.1",
+    }
 }
 
 /// Parsed HTTP request
 struct ParsedRequest {
     method: String,
     path: String,
+    /// Query string as received, forwarded verbatim when proxying
+    raw_query: String,
     query: HashMap<String, String>,
     headers: HashMap<String, String>,
+    /// Body as text, for templates and logging
     body: String,
+    /// Body exactly as received, forwarded when proxying
+    raw_body: Bytes,
 }
 
-/// Parse an HTTP request
-fn parse_request(data: &str) -> Result<ParsedRequest, QuicpulseError> {
-    let mut lines = data.lines();
-
-    // Parse request line
-    let request_line = lines.next()
-        .ok_or_else(|| QuicpulseError::Argument("Empty request".to_string()))?;
-
-    let parts: Vec<&str> = request_line.split_whitespace().collect();
-    if parts.len() < 2 {
-        return Err(QuicpulseError::Argument("Invalid request line".to_string()));
-    }
-
-    let method = parts[0].to_string();
-    let full_path = parts[1];
+impl ParsedRequest {
+    /// Build from the head and collected body of a request
+    fn from_http(parts: &hyper::http::request::Parts, body: Bytes) -> Self {
+        let mut headers: HashMap<String, String> = parts.headers.iter()
+            .map(|(name, value)| (name.as_str().to_string(), String::from_utf8_lossy(value.as_bytes()).to_string()))
+            .collect();
+        // HTTP/2 carries the host in the :authority pseudo-header
+        if let Some(authority) = parts.uri.authority() {
+            headers.entry("host".to_string()).or_insert_with(|| authority.to_string());
+        }
 
-    // Parse path and query
-    let (path, query_str) = full_path.split_once('?').unwrap_or((full_path, ""));
-    let query = parse_query_string(query_str);
+        let raw_query = parts.uri.query().unwrap_or("").to_string();
 
-    // Parse headers
-    let mut headers = HashMap::new();
-    let mut body_start = false;
-    let mut body_lines = Vec::new();
-
-    for line in lines {
-        if body_start {
-            body_lines.push(line);
-        } else if line.is_empty() {
-            body_start = true;
-        } else if let Some((name, value)) = line.split_once(':') {
-            headers.insert(
-                name.trim().to_lowercase(),
-                value.trim().to_string()
-            );
+        Self {
+            method: parts.method.to_string(),
+            path: parts.uri.path().to_string(),
+            query: parse_query_string(&raw_query),
+            raw_query,
+            headers,
+            body: String::from_utf8_lossy(&body).to_string(),
+            raw_body: body,
         }
     }
-
-    Ok(ParsedRequest {
-        method,
-        path: path.to_string(),
-        query,
-        headers,
-        body: body_lines.join("\n"),
-    })
 }
 
 /// Parse query string into map
@@ -294,25 +749,37 @@ fn query_string(query: &HashMap<String, String>) -> String {
         .join("&")
 }
 
-/// Find matching route or return default response
-fn find_route(
-    routes: &[Route],
-    method: &str,
-    path: &str,
-    config: &MockServerConfig,
-) -> (ResponseConfig, HashMap<String, String>) {
+/// Find the best matching route.
+///
+/// Routes whose scenario is in another state are skipped. Higher priority
+/// wins; among equal priorities the most specific route wins, and remaining
+/// ties go to the route defined first.
+fn find_route<'a>(
+    routes: &'a [Route],
+    request: &RequestInfo,
+    states: &ScenarioStates,
+) -> Option<(&'a Route, HashMap<String, String>)> {
+    let mut best: Option<(&Route, HashMap<String, String>)> = None;
+
     for route in routes {
-        if let Some(params) = route.matches(method, path) {
-            return (route.config.response.clone(), params);
+        if !route.scenario_matches(states) {
+            continue;
+        }
+        let Some(params) = route.matches_request(request) else {
+            continue;
+        };
+        let better = match best {
+            Some((current, _)) => {
+                (route.config.priority, route.specificity()) > (current.config.priority, current.specificity())
+            }
+            None => true,
+        };
+        if better {
+            best = Some((route, params));
         }
     }
 
-    // Return default or 404
-    let default_response = config.default_response.clone().unwrap_or_else(|| {
-        ResponseConfig::error(404, "Not Found")
-    });
-
-    (default_response, HashMap::new())
+    best
 }
 
 /// Apply template substitution to response body
@@ -344,7 +811,7 @@ fn apply_template(body: &[u8], request: &ParsedRequest, params: &HashMap<String,
 }
 
 /// Get HTTP status text
-fn http_status_text(status: u16) -> &'static str {
+pub(crate) fn http_status_text(status: u16) -> &'static str {
     match status {
         100 => "Continue",
         101 => "Switching Protocols",
@@ -442,6 +909,7 @@ pub async fn run_mock_server(
             priority: 0,
             enabled: true,
             name: None,
+            ..Default::default()
         });
     }
 
@@ -455,13 +923,32 @@ mod tests {
 
     #[test]
     fn test_parse_request() {
-        let request = "GET /api/users?page=1 HTTP/1.1\r\nHost: localhost\r\nContent-Type: application/json\r\n\r\n{\"test\": true}";
-        let parsed = parse_request(request).unwrap();
+        let (parts, ()) = hyper::Request::get("/api/users?page=1")
+            .header("Host", "localhost")
+            .header("Content-Type", "application/json")
+            .body(())
+            .unwrap()
+            .into_parts();
+        let parsed = ParsedRequest::from_http(&parts, Bytes::from_static(b"{\"test\": true}"));
 
         assert_eq!(parsed.method, "GET");
         assert_eq!(parsed.path, "/api/users");
+        assert_eq!(parsed.raw_query, "page=1");
         assert_eq!(parsed.query.get("page"), Some(&"1".to_string()));
         assert_eq!(parsed.headers.get("host"), Some(&"localhost".to_string()));
+        assert_eq!(parsed.body, "{\"test\": true}");
+    }
+
+    #[test]
+    fn test_parse_request_authority_as_host() {
+        let (parts, ()) = hyper::Request::get("https://example.com:8443/items")
+            .body(())
+            .unwrap()
+            .into_parts();
+        let parsed = ParsedRequest::from_http(&parts, Bytes::new());
+
+        assert_eq!(parsed.path, "/items");
+        assert_eq!(parsed.headers.get("host"), Some(&"example.com:8443".to_string()));
     }
 
     #[test]
@@ -470,9 +957,11 @@ mod tests {
         let request = ParsedRequest {
             method: "GET".to_string(),
             path: "/test".to_string(),
+            raw_query: String::new(),
             query: HashMap::new(),
             headers: HashMap::new(),
             body: String::new(),
+            raw_body: Bytes::new(),
         };
         let params: HashMap<String, String> = [("name".to_string(), "World".to_string())].into_iter().collect();
 
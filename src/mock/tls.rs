@@ -0,0 +1,202 @@
+//! TLS support for the mock server
+//!
+//! Builds a rustls acceptor from PEM files, or from a throwaway self-signed
+//! certificate generated at startup.
+
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use sha2::{Digest, Sha256};
+use tokio_rustls::TlsAcceptor;
+
+use super::config::TlsConfig;
+use crate::errors::QuicpulseError;
+
+/// ALPN protocol id for HTTP/2
+pub const ALPN_H2: &[u8] = b"h2";
+/// ALPN protocol id for HTTP/1.1
+pub const ALPN_HTTP11: &[u8] = b"http/1.1";
+
+/// A certificate generated at startup, written to a temporary directory
+pub struct SelfSignedCert {
+    /// PEM certificate; clients can trust it as a CA (`--verify=<path>`)
+    pub cert_path: PathBuf,
+    /// PEM private key
+    pub key_path: PathBuf,
+    /// SHA-256 fingerprint of the DER certificate
+    pub fingerprint: String,
+    /// Keeps the files alive for as long as the server runs
+    _dir: tempfile::TempDir,
+}
+
+/// TLS acceptor plus the generated certificate, if any
+pub struct MockTls {
+    pub acceptor: TlsAcceptor,
+    pub self_signed: Option<SelfSignedCert>,
+}
+
+impl MockTls {
+    /// Build the acceptor described by the config
+    pub fn from_config(tls: &TlsConfig, host: &str) -> Result<Self, QuicpulseError> {
+        let self_signed = if tls.self_signed {
+            Some(generate_self_signed(host)?)
+        } else {
+            None
+        };
+
+        let (cert_path, key_path) = match self_signed {
+            Some(ref generated) => (generated.cert_path.as_path(), generated.key_path.as_path()),
+            None => (Path::new(&tls.cert), Path::new(&tls.key)),
+        };
+
+        let certs = load_certs(cert_path)?;
+        let key = load_key(key_path)?;
+        let server_config = server_config(certs, key, tls.http2)?;
+
+        Ok(Self {
+            acceptor: TlsAcceptor::from(Arc::new(server_config)),
+            self_signed,
+        })
+    }
+}
+
+/// Build a rustls server config, advertising h2 via ALPN when requested
+pub fn server_config(
+    certs: Vec<CertificateDer<'static>>,
+    key: PrivateKeyDer<'static>,
+    http2: bool,
+) -> Result<rustls::ServerConfig, QuicpulseError> {
+    // Install default crypto provider if not already installed
+    let _ = rustls::crypto::aws_lc_rs::default_provider().install_default();
+
+    let mut config = rustls::ServerConfig::builder()
+        .with_no_client_auth()
+        .with_single_cert(certs, key)
+        .map_err(|e| QuicpulseError::Ssl(format!("Invalid TLS certificate or key: {}", e)))?;
+
+    config.alpn_protocols = if http2 {
+        vec![ALPN_H2.to_vec(), ALPN_HTTP11.to_vec()]
+    } else {
+        vec![ALPN_HTTP11.to_vec()]
+    };
+
+    Ok(config)
+}
+
+/// Generate a self-signed certificate valid for localhost and the bind host
+pub fn generate_self_signed(host: &str) -> Result<SelfSignedCert, QuicpulseError> {
+    let mut names = vec!["localhost".to_string(), "127.0.0.1".to_string(), "::1".to_string()];
+    if !host.is_empty() && host != "0.0.0.0" && host != "::" && !names.iter().any(|n| n == host) {
+        names.push(host.to_string());
+    }
+
+    let generated = rcgen::generate_simple_self_signed(names)
+        .map_err(|e| QuicpulseError::Ssl(format!("Failed to generate self-signed certificate: {}", e)))?;
+
+    let dir = tempfile::Builder::new()
+        .prefix("quicpulse-mock-tls-")
+        .tempdir()
+        .map_err(QuicpulseError::Io)?;
+    let cert_path = dir.path().join("ca.pem");
+    let key_path = dir.path().join("key.pem");
+
+    std::fs::write(&cert_path, generated.cert.pem()).map_err(QuicpulseError::Io)?;
+    std::fs::write(&key_path, generated.key_pair.serialize_pem()).map_err(QuicpulseError::Io)?;
+
+    Ok(SelfSignedCert {
+        cert_path,
+        key_path,
+        fingerprint: fingerprint(generated.cert.der()),
+        _dir: dir,
+    })
+}
+
+/// SHA-256 fingerprint as colon-separated uppercase hex
+pub fn fingerprint(der: &[u8]) -> String {
+    Sha256::digest(der)
+        .iter()
+        .map(|b| format!("{:02X}", b))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+/// Load a PEM certificate chain
+fn load_certs(path: &Path) -> Result<Vec<CertificateDer<'static>>, QuicpulseError> {
+    let data = std::fs::read(path)
+        .map_err(|e| QuicpulseError::Ssl(format!("Failed to read certificate '{}': {}", path.display(), e)))?;
+
+    let certs = rustls_pemfile::certs(&mut BufReader::new(&data[..]))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| QuicpulseError::Ssl(format!("Failed to parse certificate: {}", e)))?;
+
+    if certs.is_empty() {
+        return Err(QuicpulseError::Ssl(format!("No certificates found in {}", path.display())));
+    }
+
+    Ok(certs)
+}
+
+/// Load a PEM private key (PKCS#8, PKCS#1 or SEC1)
+fn load_key(path: &Path) -> Result<PrivateKeyDer<'static>, QuicpulseError> {
+    let data = std::fs::read(path)
+        .map_err(|e| QuicpulseError::Ssl(format!("Failed to read key file '{}': {}", path.display(), e)))?;
+
+    rustls_pemfile::private_key(&mut BufReader::new(&data[..]))
+        .map_err(|e| QuicpulseError::Ssl(format!("Failed to parse private key: {}", e)))?
+        .ok_or_else(|| QuicpulseError::Ssl(format!("No private key found in {}", path.display())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fingerprint_format() {
+        let fp = fingerprint(b"hello");
+        assert_eq!(fp.split(':').count(), 32);
+        assert!(fp.starts_with("2C:F2:4D:BA"));
+    }
+
+    #[test]
+    fn test_generate_self_signed() {
+        let cert = generate_self_signed("127.0.0.1").unwrap();
+        assert!(cert.cert_path.exists());
+        assert!(cert.key_path.exists());
+        assert_eq!(cert.fingerprint.len(), 32 * 3 - 1);
+
+        let certs = load_certs(&cert.cert_path).unwrap();
+        assert_eq!(fingerprint(&certs[0]), cert.fingerprint);
+        assert!(load_key(&cert.key_path).is_ok());
+    }
+
+    #[test]
+    fn test_from_config_self_signed_alpn() {
+        let tls = TlsConfig {
+            self_signed: true,
+            http2: true,
+            ..Default::default()
+        };
+        let mock_tls = MockTls::from_config(&tls, "localhost").unwrap();
+        assert!(mock_tls.self_signed.is_some());
+
+        let generated = mock_tls.self_signed.as_ref().unwrap();
+        let config = server_config(
+            load_certs(&generated.cert_path).unwrap(),
+            load_key(&generated.key_path).unwrap(),
+            false,
+        ).unwrap();
+        assert_eq!(config.alpn_protocols, vec![ALPN_HTTP11.to_vec()]);
+    }
+
+    #[test]
+    fn test_from_config_missing_files() {
+        let tls = TlsConfig {
+            cert: "/nonexistent/cert.pem".to_string(),
+            key: "/nonexistent/key.pem".to_string(),
+            ..Default::default()
+        };
+        assert!(MockTls::from_config(&tls, "localhost").is_err());
+    }
+}
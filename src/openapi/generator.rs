@@ -9,7 +9,7 @@
 
 use super::parser::{OpenApiSpec, Endpoint, Schema, RequestBody, Parameter};
 use super::schema_mapper::SchemaMapper;
-use crate::pipeline::workflow::{Workflow, WorkflowStep, StepAssertions, StatusAssertion};
+use crate::pipeline::workflow::{Workflow, WorkflowStep, StepAssertions, StatusAssertion, BodyAssertion, AssertOp};
 use std::collections::HashMap;
 use once_cell::sync::Lazy;
 use serde_json::Value;
@@ -145,6 +145,8 @@ pub fn generate_workflow(spec: &OpenApiSpec, options: &GeneratorOptions) -> Work
         dotenv: None,
         plugins: None,
         output: None,
+        max_parallel: None,
+        load: None,
         steps,
     }
 }
@@ -168,6 +170,9 @@ fn generate_step(
     // Generate extractions for chaining
     let extract = if options.enable_chaining {
         generate_extractions(endpoint, schemas, extracted_vars)
+            .into_iter()
+            .map(|(name, path)| (name, path.into()))
+            .collect()
     } else {
         HashMap::new()
     };
@@ -194,6 +199,7 @@ fn generate_step(
         extract,
         assert,
         skip_if: None,
+        run_if: None,
         delay: None,
         timeout: None,
         retries: None,
@@ -203,6 +209,10 @@ fn generate_step(
         foreach: None,
         foreach_var: None,
         while_condition: None,
+        until: None,
+        if_condition: None,
+        then_steps: Vec::new(),
+        else_steps: Vec::new(),
         max_iterations: None,
         parallel: false,
         fail_fast: None,
@@ -504,10 +514,11 @@ fn generate_assertions(endpoint: &Endpoint, options: &GeneratorOptions) -> StepA
                 if schema.schema_type.as_deref() == Some("object") {
                     for required_field in &schema.required {
                         let escaped = escape_jq_field(required_field);
-                        assertions.body.insert(
-                            format!(".[\"{}\"] | type", escaped),
-                            Value::String("!= \"null\"".to_string()),
-                        );
+                        assertions.body.push(BodyAssertion::new(
+                            &format!(".[\"{}\"]", escaped),
+                            AssertOp::Exists,
+                            None,
+                        ));
                     }
                 }
             }
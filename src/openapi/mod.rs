@@ -4,7 +4,7 @@ mod parser;
 pub mod generator;
 mod schema_mapper;
 
-pub use parser::{OpenApiSpec, parse_spec};
+pub use parser::{OpenApiSpec, Endpoint, MediaType, Schema, parse_spec, read_spec_value, response_schema};
 pub use generator::{generate_workflow, GeneratorOptions, workflow_to_yaml};
 pub use schema_mapper::SchemaMapper;
 
@@ -190,6 +190,22 @@ pub struct OAuthFlow {
 
 /// Parse an OpenAPI specification from a file
 pub fn parse_spec(path: &Path) -> Result<OpenApiSpec, QuicpulseError> {
+    let value = read_spec_value(path)?;
+
+    // Detect OpenAPI version
+    if value.get("openapi").is_some() {
+        parse_openapi_3(&value)
+    } else if value.get("swagger").is_some() {
+        parse_swagger_2(&value)
+    } else {
+        Err(QuicpulseError::Argument(
+            "Unknown spec format: missing 'openapi' or 'swagger' field".to_string()
+        ))
+    }
+}
+
+/// Read a spec file (JSON or YAML) without interpreting it
+pub fn read_spec_value(path: &Path) -> Result<Value, QuicpulseError> {
     // Check file size
     let metadata = fs::metadata(path)
         .map_err(|e| QuicpulseError::Io(e))?;
@@ -219,15 +235,95 @@ pub fn parse_spec(path: &Path) -> Result<OpenApiSpec, QuicpulseError> {
             .map_err(|e| QuicpulseError::Argument(format!("Failed to parse spec: {}", e)))?
     };
 
-    // Detect OpenAPI version
-    if value.get("openapi").is_some() {
-        parse_openapi_3(&value)
-    } else if value.get("swagger").is_some() {
-        parse_swagger_2(&value)
+    Ok(value)
+}
+
+/// JSON Schema of the `status` response of `operation_id`.
+///
+/// The schema is the whole spec with a root `$ref` to the response schema, so
+/// `#/components/...` and `#/definitions/...` references keep resolving.
+/// `status` falls back to its `NXX` class and then to `default`.
+pub fn response_schema(spec: &Value, operation_id: &str, status: &str) -> Result<Value, QuicpulseError> {
+    let (path, method, operation) = spec.get("paths")
+        .and_then(|p| p.as_object())
+        .into_iter()
+        .flatten()
+        .filter_map(|(path, item)| Some((path, item.as_object()?)))
+        .flat_map(|(path, item)| item.iter().map(move |(method, op)| (path, method, op)))
+        .find(|(_, _, op)| op.get("operationId").and_then(|id| id.as_str()) == Some(operation_id))
+        .ok_or_else(|| QuicpulseError::Argument(format!("OpenAPI operation '{}' not found", operation_id)))?;
+
+    let responses = operation.get("responses").and_then(|r| r.as_object())
+        .ok_or_else(|| QuicpulseError::Argument(format!("OpenAPI operation '{}' has no responses", operation_id)))?;
+    let class = status.get(..1).map(|c| format!("{}XX", c)).unwrap_or_default();
+    let lower_class = class.to_lowercase();
+    let code = [status.to_string(), class, lower_class, "default".to_string()]
+        .into_iter()
+        .find(|code| responses.contains_key(code))
+        .ok_or_else(|| QuicpulseError::Argument(format!(
+            "OpenAPI operation '{}' has no response for status {}", operation_id, status
+        )))?;
+
+    let mut pointer = format!("/paths/{}/{}/responses/{}", escape_pointer(path), escape_pointer(method), escape_pointer(&code));
+    // Responses may themselves be references to #/components/responses
+    if let Some(target) = spec.pointer(&pointer).and_then(|r| r.get("$ref")).and_then(|r| r.as_str()) {
+        pointer = target.trim_start_matches('#').to_string();
+    }
+    let response = spec.pointer(&pointer)
+        .ok_or_else(|| QuicpulseError::Argument(format!("Unresolvable response reference '{}'", pointer)))?;
+
+    let schema_pointer = if let Some(content) = response.get("content").and_then(|c| c.as_object()) {
+        // OpenAPI 3: prefer a JSON media type
+        content.keys()
+            .find(|m| m.as_str() == "application/json")
+            .or_else(|| content.keys().find(|m| m.ends_with("json")))
+            .or_else(|| content.keys().next())
+            .filter(|m| content[m.as_str()].get("schema").is_some())
+            .map(|media| format!("{}/content/{}/schema", pointer, escape_pointer(media)))
     } else {
-        Err(QuicpulseError::Argument(
-            "Unknown spec format: missing 'openapi' or 'swagger' field".to_string()
-        ))
+        // Swagger 2
+        response.get("schema").map(|_| format!("{}/schema", pointer))
+    };
+    let schema_pointer = schema_pointer.ok_or_else(|| QuicpulseError::Argument(format!(
+        "OpenAPI response {} of '{}' has no schema", code, operation_id
+    )))?;
+
+    let mut schema = spec.clone();
+    nullable_to_type(&mut schema);
+    if let Some(root) = schema.as_object_mut() {
+        root.insert("$ref".to_string(), Value::String(format!("#{}", uri_fragment(&schema_pointer))));
+    }
+    Ok(schema)
+}
+
+/// Escape a JSON pointer segment
+fn escape_pointer(segment: &str) -> String {
+    segment.replace('~', "~0").replace('/', "~1")
+}
+
+/// Percent-encode a JSON pointer for use as a URI fragment (`{id}` -> `%7Bid%7D`)
+fn uri_fragment(pointer: &str) -> String {
+    pointer.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' | b':' | b'@' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+/// Rewrite OpenAPI 3.0 `nullable: true` as a JSON Schema type union
+fn nullable_to_type(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            if map.get("nullable") == Some(&Value::Bool(true)) {
+                if let Some(Value::String(ty)) = map.get("type").cloned() {
+                    map.insert("type".to_string(), serde_json::json!([ty, "null"]));
+                }
+            }
+            map.values_mut().for_each(nullable_to_type);
+        }
+        Value::Array(items) => items.iter_mut().for_each(nullable_to_type),
+        _ => {}
     }
 }
 
@@ -900,4 +996,46 @@ mod tests {
 
         assert_eq!(schema.ref_path, Some("#/components/schemas/User".to_string()));
     }
+
+    #[test]
+    fn test_response_schema() {
+        let spec = serde_json::json!({
+            "openapi": "3.0.0",
+            "paths": {
+                "/users/{id}": {
+                    "get": {
+                        "operationId": "getUser",
+                        "responses": {
+                            "200": {"content": {"application/json": {"schema": {"$ref": "#/components/schemas/User"}}}},
+                            "4XX": {"$ref": "#/components/responses/Error"}
+                        }
+                    }
+                }
+            },
+            "components": {
+                "schemas": {
+                    "User": {
+                        "type": "object",
+                        "required": ["id"],
+                        "properties": {"id": {"type": "integer"}, "nickname": {"type": "string", "nullable": true}}
+                    }
+                },
+                "responses": {
+                    "Error": {"content": {"application/json": {"schema": {"type": "object", "required": ["message"]}}}}
+                }
+            }
+        });
+
+        let schema = response_schema(&spec, "getUser", "200").unwrap();
+        assert_eq!(schema["$ref"], "#/paths/~1users~1%7Bid%7D/get/responses/200/content/application~1json/schema");
+        let validator = jsonschema::validator_for(&schema).unwrap();
+        assert!(validator.is_valid(&serde_json::json!({"id": 1, "nickname": null})));
+        assert!(!validator.is_valid(&serde_json::json!({"id": "1"})));
+
+        let schema = response_schema(&spec, "getUser", "404").unwrap();
+        assert_eq!(schema["$ref"], "#/components/responses/Error/content/application~1json/schema");
+
+        assert!(response_schema(&spec, "getUser", "500").is_err());
+        assert!(response_schema(&spec, "deleteUser", "200").is_err());
+    }
 }
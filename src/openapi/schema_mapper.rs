@@ -228,6 +228,138 @@ impl SchemaMapper {
         }
     }
 
+    /// Generate concrete example data from a schema.
+    ///
+    /// Like [`generate_request_body`](Self::generate_request_body), but magic
+    /// values are expanded and numbers and booleans get their JSON types, so
+    /// the result can be served as-is.
+    pub fn generate_example(schema: &Schema, schemas: &HashMap<String, Schema>) -> Value {
+        let generated = Self::generate_request_body(schema, schemas);
+        Self::concretize(generated, schema, schemas, 0)
+    }
+
+    /// Expand magic values in generated data and coerce them to the schema's types
+    fn concretize(value: Value, schema: &Schema, schemas: &HashMap<String, Schema>, depth: usize) -> Value {
+        if depth > 10 {
+            return value;
+        }
+
+        let schema = Self::resolve(schema, schemas);
+
+        match (schema.schema_type.as_deref(), value) {
+            (Some("object"), Value::Object(mut obj)) => {
+                for (name, prop_schema) in &schema.properties {
+                    let prop = obj.remove(name)
+                        .unwrap_or_else(|| Self::generate_request_body(prop_schema, schemas));
+                    obj.insert(name.clone(), Self::concretize(prop, prop_schema, schemas, depth + 1));
+                }
+                Value::Object(obj)
+            }
+            (Some("array"), Value::Array(items)) => match &schema.items {
+                Some(item_schema) => Value::Array(
+                    items.into_iter()
+                        .map(|item| Self::concretize(item, item_schema, schemas, depth + 1))
+                        .collect(),
+                ),
+                None => Value::Array(items),
+            },
+            // Nested objects and arrays come back from the generator as templates
+            (Some("object" | "array"), _) => {
+                let regenerated = Self::generate_value(schema, schemas, depth + 1);
+                match regenerated {
+                    Value::Object(_) | Value::Array(_) => Self::concretize(regenerated, schema, schemas, depth + 1),
+                    _ => regenerated,
+                }
+            }
+            (schema_type, Value::String(template)) => {
+                let expanded = crate::magic::expand_magic_values(&template).value;
+                match schema_type {
+                    Some("integer") => expanded.parse::<i64>().map(Value::from).unwrap_or(Value::String(expanded)),
+                    Some("number") => expanded.parse::<f64>().ok()
+                        .and_then(serde_json::Number::from_f64)
+                        .map(Value::Number)
+                        .unwrap_or(Value::String(expanded)),
+                    Some("boolean") => expanded.parse::<bool>().map(Value::Bool).unwrap_or(Value::String(expanded)),
+                    _ => Value::String(expanded),
+                }
+            }
+            (_, value) => value,
+        }
+    }
+
+    /// Follow a `$ref` to its component schema
+    fn resolve<'a>(schema: &'a Schema, schemas: &'a HashMap<String, Schema>) -> &'a Schema {
+        let mut current = schema;
+        for _ in 0..10 {
+            let Some(ref_path) = &current.ref_path else {
+                break;
+            };
+            match schemas.get(ref_path.rsplit('/').next().unwrap_or("")) {
+                Some(target) => current = target,
+                None => break,
+            }
+        }
+        current
+    }
+
+    /// Convert a schema to a standalone JSON Schema document, inlining `$ref`s
+    pub fn to_json_schema(schema: &Schema, schemas: &HashMap<String, Schema>) -> Value {
+        Self::json_schema_value(schema, schemas, 0)
+    }
+
+    fn json_schema_value(schema: &Schema, schemas: &HashMap<String, Schema>, depth: usize) -> Value {
+        // Recursive schemas are cut off by accepting anything below this depth
+        if depth > 10 {
+            return Value::Object(serde_json::Map::new());
+        }
+
+        let schema = Self::resolve(schema, schemas);
+        let mut out = serde_json::Map::new();
+
+        if let Some(ref schema_type) = schema.schema_type {
+            out.insert(
+                "type".to_string(),
+                if schema.nullable {
+                    serde_json::json!([schema_type, "null"])
+                } else {
+                    Value::String(schema_type.clone())
+                },
+            );
+        }
+        if !schema.properties.is_empty() {
+            let properties = schema.properties.iter()
+                .map(|(name, prop)| (name.clone(), Self::json_schema_value(prop, schemas, depth + 1)))
+                .collect();
+            out.insert("properties".to_string(), Value::Object(properties));
+        }
+        if !schema.required.is_empty() {
+            out.insert("required".to_string(), serde_json::json!(schema.required));
+        }
+        if let Some(ref items) = schema.items {
+            out.insert("items".to_string(), Self::json_schema_value(items, schemas, depth + 1));
+        }
+        if !schema.enum_values.is_empty() {
+            out.insert("enum".to_string(), Value::Array(schema.enum_values.clone()));
+        }
+        if let Some(minimum) = schema.minimum {
+            out.insert("minimum".to_string(), serde_json::json!(minimum));
+        }
+        if let Some(maximum) = schema.maximum {
+            out.insert("maximum".to_string(), serde_json::json!(maximum));
+        }
+        if let Some(min_length) = schema.min_length {
+            out.insert("minLength".to_string(), serde_json::json!(min_length));
+        }
+        if let Some(max_length) = schema.max_length {
+            out.insert("maxLength".to_string(), serde_json::json!(max_length));
+        }
+        if let Some(ref pattern) = schema.pattern {
+            out.insert("pattern".to_string(), Value::String(pattern.clone()));
+        }
+
+        Value::Object(out)
+    }
+
     /// Map OpenAPI type to fuzz category for automated security testing
     pub fn type_to_fuzz_category(schema: &Schema) -> Vec<String> {
         let mut categories = Vec::new();
@@ -311,6 +443,71 @@ mod tests {
         assert_eq!(SchemaMapper::schema_to_magic(&schema), "{pick:active,inactive}");
     }
 
+    #[test]
+    fn test_generate_example_types() {
+        let mut schemas = HashMap::new();
+        schemas.insert("Tag".to_string(), Schema {
+            schema_type: Some("object".to_string()),
+            properties: [("label".to_string(), Schema {
+                schema_type: Some("string".to_string()),
+                ..Default::default()
+            })].into_iter().collect(),
+            ..Default::default()
+        });
+
+        let schema = Schema {
+            schema_type: Some("object".to_string()),
+            properties: [
+                ("id".to_string(), Schema {
+                    schema_type: Some("integer".to_string()),
+                    minimum: Some(1.0),
+                    maximum: Some(9.0),
+                    ..Default::default()
+                }),
+                ("active".to_string(), Schema {
+                    schema_type: Some("boolean".to_string()),
+                    ..Default::default()
+                }),
+                ("tags".to_string(), Schema {
+                    schema_type: Some("array".to_string()),
+                    items: Some(Box::new(Schema {
+                        ref_path: Some("#/components/schemas/Tag".to_string()),
+                        ..Default::default()
+                    })),
+                    ..Default::default()
+                }),
+            ].into_iter().collect(),
+            ..Default::default()
+        };
+
+        let example = SchemaMapper::generate_example(&schema, &schemas);
+        let id = example["id"].as_i64().unwrap();
+        assert!((1..=9).contains(&id));
+        assert!(example["active"].is_boolean());
+        assert!(example["tags"][0]["label"].as_str().is_some_and(|s| !s.contains('{')));
+    }
+
+    #[test]
+    fn test_to_json_schema() {
+        let schema = Schema {
+            schema_type: Some("object".to_string()),
+            required: vec!["name".to_string()],
+            properties: [("name".to_string(), Schema {
+                schema_type: Some("string".to_string()),
+                min_length: Some(1),
+                nullable: true,
+                ..Default::default()
+            })].into_iter().collect(),
+            ..Default::default()
+        };
+
+        let json_schema = SchemaMapper::to_json_schema(&schema, &HashMap::new());
+        assert_eq!(json_schema["type"], "object");
+        assert_eq!(json_schema["required"], serde_json::json!(["name"]));
+        assert_eq!(json_schema["properties"]["name"]["type"], serde_json::json!(["string", "null"]));
+        assert_eq!(json_schema["properties"]["name"]["minLength"], 1);
+    }
+
     #[test]
     fn test_example_takes_precedence() {
         let schema = Schema {
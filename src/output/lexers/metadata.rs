@@ -1 +1,73 @@
-//! Metadata lexer for timing info
+//! Metadata output (`--meta`, `--print=m`): elapsed time, phase timings and connection details
+
+use std::time::Duration;
+
+use crate::client::timing::RequestTiming;
+
+/// Render the metadata block printed after a response
+pub fn format_metadata(timing: &RequestTiming) -> String {
+    let mut lines = vec![format!("Elapsed time: {:.6}s", timing.total().as_secs_f64())];
+
+    if let Some(opened) = timing.opened {
+        if let Some(dns) = opened.dns {
+            lines.push(phase("DNS lookup", dns));
+        }
+        lines.push(phase("TCP connect", opened.connect));
+        if let Some(tls) = opened.tls {
+            lines.push(phase("TLS handshake", tls));
+        }
+    }
+    lines.push(phase("Waiting (TTFB)", timing.wait()));
+    lines.push(phase("Content transfer", timing.transfer));
+
+    if let Some(addr) = timing.remote_addr {
+        lines.push(format!("Remote address: {}", addr));
+    }
+    lines.push(format!("Connection: {}", if timing.reused() { "reused" } else { "new" }));
+    if let Some(session) = timing.tls_session() {
+        lines.push(format!("TLS: {} {}", session.version, session.cipher));
+        if let Some(alpn) = session.alpn {
+            lines.push(format!("ALPN: {}", alpn));
+        }
+    }
+
+    lines.join("\n")
+}
+
+fn phase(name: &str, duration: Duration) -> String {
+    format!("  {:<20}{:>10.3} ms", format!("{}:", name), duration.as_secs_f64() * 1000.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::timing::{ConnectTiming, TlsSession};
+
+    #[test]
+    fn test_format_metadata() {
+        let timing = RequestTiming {
+            opened: Some(ConnectTiming {
+                dns: Some(Duration::from_millis(2)),
+                connect: Duration::from_millis(3),
+                tls: Some(Duration::from_millis(5)),
+                session: Some(TlsSession { version: "TLSv1.3", cipher: "TLS13_AES_256_GCM_SHA384", alpn: Some("h2") }),
+            }),
+            ttfb: Duration::from_millis(30),
+            transfer: Duration::from_millis(4),
+            remote_addr: Some("10.0.0.1:443".parse().unwrap()),
+        };
+        let output = format_metadata(&timing);
+        assert!(output.starts_with("Elapsed time: 0.034000s"), "{}", output);
+        assert!(output.contains("  DNS lookup:              2.000 ms"), "{}", output);
+        assert!(output.contains("  Waiting (TTFB):         20.000 ms"), "{}", output);
+        assert!(output.contains("Remote address: 10.0.0.1:443"));
+        assert!(output.contains("Connection: new"));
+        assert!(output.contains("TLS: TLSv1.3 TLS13_AES_256_GCM_SHA384"));
+        assert!(output.contains("ALPN: h2"));
+
+        let reused = format_metadata(&RequestTiming { ttfb: Duration::from_millis(7), ..Default::default() });
+        assert!(reused.contains("Connection: reused"));
+        assert!(!reused.contains("TCP connect"));
+        assert!(!reused.contains("TLS:"));
+    }
+}
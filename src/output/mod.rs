@@ -10,6 +10,7 @@ pub mod pager;
 pub mod streams;
 pub mod terminal;
 pub mod writer;
+pub mod write_out;
 
 pub use codec::{EncodedCodec, PrettyCodec, RawCodec};
 pub use error::{StreamError, StreamResult};
@@ -0,0 +1,297 @@
+//! `--write-out` templates
+//!
+//! A template mixes literal text with `{name}` placeholders that are filled in
+//! once the response has been received, e.g.
+//! `{status} {time_total} {header.content-type} {json:.id}\n`.
+//! `{{` and `}}` produce literal braces; `\n`, `\t`, `\r` and `\\` are unescaped.
+
+use std::io::Write;
+use std::path::Path;
+
+use reqwest::header::HeaderMap;
+use reqwest::{StatusCode, Version};
+use serde_json::Value as JsonValue;
+
+use crate::client::timing::{self, RequestTiming};
+use crate::errors::QuicpulseError;
+use crate::filter;
+
+/// Response variables, in addition to [`timing::VARIABLES`]
+pub const VARIABLES: &[&str] = &[
+    "status",
+    "http_code",
+    "reason",
+    "http_version",
+    "method",
+    "url_effective",
+    "content_type",
+    "size_download",
+    "num_headers",
+    "num_redirects",
+];
+
+/// What a template is rendered against
+pub struct WriteOutContext<'a> {
+    pub status: StatusCode,
+    pub version: Version,
+    pub method: &'a str,
+    pub url: &'a str,
+    pub headers: &'a HeaderMap,
+    pub body: &'a str,
+    pub num_redirects: usize,
+    pub timing: &'a RequestTiming,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Text(String),
+    Variable(String),
+    Header(String),
+    Json(String),
+}
+
+/// A parsed `--write-out` template
+#[derive(Debug, Clone)]
+pub struct WriteOut {
+    segments: Vec<Segment>,
+}
+
+impl WriteOut {
+    /// Parse a template; `@path` reads it from a file
+    pub fn parse(template: &str) -> Result<Self, QuicpulseError> {
+        if let Some(path) = template.strip_prefix('@') {
+            let contents = std::fs::read_to_string(path)
+                .map_err(|e| QuicpulseError::Argument(format!("Cannot read --write-out file '{}': {}", path, e)))?;
+            return Self::parse_template(&contents);
+        }
+        Self::parse_template(template)
+    }
+
+    fn parse_template(template: &str) -> Result<Self, QuicpulseError> {
+        let mut segments = Vec::new();
+        let mut text = String::new();
+        let mut chars = template.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => match chars.next() {
+                    Some('n') => text.push('\n'),
+                    Some('t') => text.push('\t'),
+                    Some('r') => text.push('\r'),
+                    Some('\\') => text.push('\\'),
+                    Some(other) => {
+                        text.push('\\');
+                        text.push(other);
+                    }
+                    None => text.push('\\'),
+                },
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    text.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    text.push('}');
+                }
+                '{' => {
+                    // Nested braces are allowed so `{json:{id: .id}}` works
+                    let mut depth = 1;
+                    let mut name = String::new();
+                    for c in chars.by_ref() {
+                        match c {
+                            '{' => depth += 1,
+                            '}' => {
+                                depth -= 1;
+                                if depth == 0 {
+                                    break;
+                                }
+                            }
+                            _ => {}
+                        }
+                        name.push(c);
+                    }
+                    if depth != 0 {
+                        return Err(QuicpulseError::Argument(format!(
+                            "Unterminated placeholder in --write-out: {{{}", name
+                        )));
+                    }
+                    if !text.is_empty() {
+                        segments.push(Segment::Text(std::mem::take(&mut text)));
+                    }
+                    segments.push(placeholder(name.trim())?);
+                }
+                _ => text.push(c),
+            }
+        }
+        if !text.is_empty() {
+            segments.push(Segment::Text(text));
+        }
+
+        Ok(Self { segments })
+    }
+
+    /// Fill in the template for a response
+    pub fn render(&self, ctx: &WriteOutContext) -> Result<String, QuicpulseError> {
+        let mut body_json: Option<Option<JsonValue>> = None;
+        let mut output = String::new();
+
+        for segment in &self.segments {
+            match segment {
+                Segment::Text(text) => output.push_str(text),
+                Segment::Variable(name) => {
+                    let value = response_variable(name, ctx)
+                        .or_else(|| ctx.timing.variable(name))
+                        .unwrap_or(JsonValue::Null);
+                    output.push_str(&display(&value));
+                }
+                Segment::Header(name) => {
+                    let values: Vec<_> = ctx.headers.get_all(name.as_str())
+                        .iter()
+                        .filter_map(|v| v.to_str().ok())
+                        .collect();
+                    output.push_str(&values.join(", "));
+                }
+                Segment::Json(expr) => {
+                    let json = body_json.get_or_insert_with(|| serde_json::from_str(ctx.body).ok());
+                    if let Some(json) = json {
+                        let results = filter::apply_filter(json, expr)?;
+                        let rendered: Vec<_> = results.iter().map(display).collect();
+                        output.push_str(&rendered.join("\n"));
+                    }
+                }
+            }
+        }
+
+        Ok(output)
+    }
+
+    /// Render and write to `path` (appending) or stdout
+    pub fn write(&self, ctx: &WriteOutContext, path: Option<&Path>) -> Result<(), QuicpulseError> {
+        let output = self.render(ctx)?;
+        match path {
+            Some(path) => {
+                let mut file = std::fs::OpenOptions::new().create(true).append(true).open(path)?;
+                file.write_all(output.as_bytes())?;
+            }
+            None => {
+                let mut stdout = std::io::stdout();
+                stdout.write_all(output.as_bytes())?;
+                stdout.flush()?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn placeholder(name: &str) -> Result<Segment, QuicpulseError> {
+    if let Some(header) = name.strip_prefix("header.") {
+        if header.is_empty() || reqwest::header::HeaderName::from_bytes(header.as_bytes()).is_err() {
+            return Err(QuicpulseError::Argument(format!("Invalid header name in --write-out: {{{}}}", name)));
+        }
+        return Ok(Segment::Header(header.to_ascii_lowercase()));
+    }
+    if let Some(expr) = name.strip_prefix("json:") {
+        return Ok(Segment::Json(expr.trim().to_string()));
+    }
+    if VARIABLES.contains(&name) || timing::VARIABLES.contains(&name) {
+        return Ok(Segment::Variable(name.to_string()));
+    }
+    Err(QuicpulseError::Argument(format!(
+        "Unknown --write-out variable: {{{}}} (expected header.NAME, json:EXPR or one of: {}, {})",
+        name,
+        VARIABLES.join(", "),
+        timing::VARIABLES.join(", ")
+    )))
+}
+
+fn response_variable(name: &str, ctx: &WriteOutContext) -> Option<JsonValue> {
+    let value: JsonValue = match name {
+        "status" | "http_code" => ctx.status.as_u16().into(),
+        "reason" => ctx.status.canonical_reason().unwrap_or("").into(),
+        "http_version" => match ctx.version {
+            Version::HTTP_09 => "0.9",
+            Version::HTTP_10 => "1.0",
+            Version::HTTP_11 => "1.1",
+            Version::HTTP_2 => "2",
+            Version::HTTP_3 => "3",
+            _ => "",
+        }.into(),
+        "method" => ctx.method.into(),
+        "url_effective" => ctx.url.into(),
+        "content_type" => ctx.headers.get("content-type")
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("")
+            .into(),
+        "size_download" => ctx.body.len().into(),
+        "num_headers" => ctx.headers.len().into(),
+        "num_redirects" => ctx.num_redirects.into(),
+        _ => return None,
+    };
+    Some(value)
+}
+
+/// Strings unquoted, null as nothing, everything else as compact JSON
+fn display(value: &JsonValue) -> String {
+    match value {
+        JsonValue::Null => String::new(),
+        JsonValue::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn render(template: &str, body: &str) -> Result<String, QuicpulseError> {
+        let mut headers = HeaderMap::new();
+        headers.insert("content-type", "application/json".parse().unwrap());
+        headers.append("set-cookie", "a=1".parse().unwrap());
+        headers.append("set-cookie", "b=2".parse().unwrap());
+        let timing = RequestTiming {
+            ttfb: Duration::from_millis(120),
+            transfer: Duration::from_millis(5),
+            ..Default::default()
+        };
+        let ctx = WriteOutContext {
+            status: StatusCode::CREATED,
+            version: Version::HTTP_11,
+            method: "POST",
+            url: "http://localhost/items",
+            headers: &headers,
+            body,
+            num_redirects: 0,
+            timing: &timing,
+        };
+        WriteOut::parse(template)?.render(&ctx)
+    }
+
+    #[test]
+    fn test_variables_and_headers() {
+        let output = render("{status} {reason} {method} {time_total} {size_download}\\n", "{}").unwrap();
+        assert_eq!(output, "201 Created POST 0.125 2\n");
+
+        assert_eq!(render("{header.Content-Type}", "").unwrap(), "application/json");
+        assert_eq!(render("{header.set-cookie}", "").unwrap(), "a=1, b=2");
+        assert_eq!(render("[{header.x-missing}] [{remote_ip}]", "").unwrap(), "[] []");
+    }
+
+    #[test]
+    fn test_json_filters() {
+        let body = r#"{"id": 7, "name": "widget", "tags": ["a", "b"], "meta": {"ok": true}}"#;
+        assert_eq!(render("{json:.id} {json:.name}", body).unwrap(), "7 widget");
+        assert_eq!(render("{json:.meta}", body).unwrap(), r#"{"ok":true}"#);
+        assert_eq!(render("{json:.tags[]}", body).unwrap(), "a\nb");
+        assert_eq!(render("{json:{n: .name}}", body).unwrap(), r#"{"n":"widget"}"#);
+        assert_eq!(render("[{json:.id}]", "not json").unwrap(), "[]");
+    }
+
+    #[test]
+    fn test_literal_braces_and_errors() {
+        assert_eq!(render("{{status}} {status}", "").unwrap(), "{status} 201");
+        assert!(WriteOut::parse("{nope}").is_err());
+        assert!(WriteOut::parse("{status").is_err());
+        assert!(WriteOut::parse("{header.}").is_err());
+    }
+}
@@ -7,6 +7,7 @@ use reqwest::header::HeaderMap;
 use serde_json::Value as JsonValue;
 use crate::cli::Args;
 use crate::filter;
+use super::workflow::{AssertOp, BodyAssertion};
 
 /// Represents an assertion to check against a response
 #[derive(Debug, Clone)]
@@ -231,6 +232,199 @@ fn check_body(body: &str, pattern: &str) -> AssertionResult {
     }
 }
 
+/// Validate the body against a JSON Schema.
+///
+/// Passes with a single result; otherwise each violation becomes a failed
+/// result labelled with its instance path.
+pub fn check_schema(schema: &JsonValue, source: &str, body: &str) -> Vec<AssertionResult> {
+    let assertion = format!("schema {}", source);
+
+    let validator = match jsonschema::validator_for(schema) {
+        Ok(validator) => validator,
+        Err(e) => return vec![AssertionResult::fail(&assertion, &format!("Invalid schema: {}", e))],
+    };
+    let instance = match serde_json::from_str::<JsonValue>(body) {
+        Ok(instance) => instance,
+        Err(e) => return vec![AssertionResult::fail(&assertion, &format!("Response is not JSON: {}", e))],
+    };
+
+    let violations: Vec<AssertionResult> = validator.iter_errors(&instance)
+        .map(|e| {
+            let path = e.instance_path().to_string();
+            let path = if path.is_empty() { "/".to_string() } else { path };
+            AssertionResult::fail(&format!("{} at {}", assertion, path), &e.to_string())
+        })
+        .collect();
+
+    if violations.is_empty() {
+        vec![AssertionResult::pass(&assertion, "Body matches schema")]
+    } else {
+        violations
+    }
+}
+
+/// Check typed body assertions, parsing the body once
+pub fn check_body_assertions(assertions: &[BodyAssertion], body: &str) -> Vec<AssertionResult> {
+    let json = serde_json::from_str::<JsonValue>(body).ok();
+    assertions.iter()
+        .map(|assertion| check_body_assertion(json.as_ref(), assertion))
+        .collect()
+}
+
+/// Check one typed body assertion against the parsed body
+pub fn check_body_assertion(json: Option<&JsonValue>, assertion: &BodyAssertion) -> AssertionResult {
+    let path = if assertion.path.starts_with('.') {
+        assertion.path.clone()
+    } else {
+        format!(".{}", assertion.path)
+    };
+    let expected = assertion.value.clone().unwrap_or(JsonValue::Null);
+    let label = if assertion.op.takes_value() {
+        format!("body {} {} {}", path, assertion.op, expected)
+    } else {
+        format!("body {} {}", path, assertion.op)
+    };
+
+    let Some(json) = json else {
+        return AssertionResult::fail(&label, "Response is not JSON, cannot evaluate JQ path");
+    };
+    let actual = match filter::apply_filter(json, &path) {
+        Ok(results) => results.into_iter().next().unwrap_or(JsonValue::Null),
+        Err(e) => return AssertionResult::fail(&label, &format!("JQ filter error: {}", e)),
+    };
+
+    match evaluate_op(assertion.op, &actual, &expected, assertion.tolerance) {
+        Ok(true) => AssertionResult::pass(&label, &format!("{} = {}", path, actual)),
+        Ok(false) => AssertionResult::fail(&label, &format!(
+            "{}: expected {} {}, got {}", path, assertion.op, expected, actual
+        )),
+        Err(reason) => AssertionResult::fail(&label, &format!(
+            "{}: {} (expected {} {}, got {})", path, reason, assertion.op, expected, actual
+        )),
+    }
+}
+
+fn evaluate_op(op: AssertOp, actual: &JsonValue, expected: &JsonValue, tolerance: Option<f64>) -> Result<bool, String> {
+    Ok(match op {
+        AssertOp::Eq => values_equal(actual, expected),
+        AssertOp::Ne => !values_equal(actual, expected),
+        AssertOp::Gt | AssertOp::Gte | AssertOp::Lt | AssertOp::Lte => {
+            let ordering = compare(actual, expected)
+                .ok_or_else(|| "values are not comparable".to_string())?;
+            match op {
+                AssertOp::Gt => ordering.is_gt(),
+                AssertOp::Gte => ordering.is_ge(),
+                AssertOp::Lt => ordering.is_lt(),
+                _ => ordering.is_le(),
+            }
+        }
+        AssertOp::Contains => contains(actual, expected)?,
+        AssertOp::NotContains => !contains(actual, expected)?,
+        AssertOp::Matches => {
+            let pattern = expected.as_str().ok_or_else(|| "pattern must be a string".to_string())?;
+            let regex = regex::Regex::new(pattern).map_err(|e| format!("invalid regex: {}", e))?;
+            regex.is_match(&scalar_string(actual).ok_or_else(|| "value is not a scalar".to_string())?)
+        }
+        AssertOp::In => members(expected)?.iter().any(|v| values_equal(actual, v)),
+        AssertOp::NotIn => !members(expected)?.iter().any(|v| values_equal(actual, v)),
+        AssertOp::Length | AssertOp::LengthGt | AssertOp::LengthGte | AssertOp::LengthLt | AssertOp::LengthLte => {
+            let length = match actual {
+                JsonValue::Array(a) => a.len(),
+                JsonValue::Object(o) => o.len(),
+                JsonValue::String(s) => s.chars().count(),
+                _ => return Err("value has no length".to_string()),
+            } as f64;
+            let wanted = number(expected).ok_or_else(|| "length must be a number".to_string())?;
+            match op {
+                AssertOp::Length => length == wanted,
+                AssertOp::LengthGt => length > wanted,
+                AssertOp::LengthGte => length >= wanted,
+                AssertOp::LengthLt => length < wanted,
+                _ => length <= wanted,
+            }
+        }
+        AssertOp::Type => {
+            let wanted = expected.as_str().ok_or_else(|| "type must be a string".to_string())?;
+            match wanted {
+                "integer" => actual.is_i64() || actual.is_u64(),
+                "bool" => actual.is_boolean(),
+                _ => type_name(actual) == wanted,
+            }
+        }
+        AssertOp::Exists => !actual.is_null(),
+        AssertOp::NotExists => actual.is_null(),
+        AssertOp::Approx => {
+            let (a, e) = number(actual).zip(number(expected))
+                .ok_or_else(|| "approx needs numbers".to_string())?;
+            (a - e).abs() <= tolerance.unwrap_or(1e-6)
+        }
+    })
+}
+
+/// JSON equality, with numbers compared by value and scalars matching their string form
+fn values_equal(actual: &JsonValue, expected: &JsonValue) -> bool {
+    if actual == expected {
+        return true;
+    }
+    if let (Some(a), Some(e)) = (actual.as_f64(), expected.as_f64()) {
+        return a == e;
+    }
+    match (expected, scalar_string(actual)) {
+        (JsonValue::String(e), Some(a)) if !actual.is_string() => a == *e,
+        _ => false,
+    }
+}
+
+fn compare(actual: &JsonValue, expected: &JsonValue) -> Option<std::cmp::Ordering> {
+    match (actual, expected) {
+        (JsonValue::String(a), JsonValue::String(e)) => Some(a.cmp(e)),
+        _ => number(actual)?.partial_cmp(&number(expected)?),
+    }
+}
+
+fn contains(actual: &JsonValue, expected: &JsonValue) -> Result<bool, String> {
+    match actual {
+        JsonValue::Array(items) => Ok(items.iter().any(|v| values_equal(v, expected))),
+        JsonValue::Object(map) => Ok(expected.as_str().is_some_and(|key| map.contains_key(key))),
+        JsonValue::String(s) => Ok(s.contains(&scalar_string(expected).unwrap_or_else(|| expected.to_string()))),
+        _ => Err("value is not a string, array or object".to_string()),
+    }
+}
+
+fn members(expected: &JsonValue) -> Result<&Vec<JsonValue>, String> {
+    expected.as_array().ok_or_else(|| "expected value must be a list".to_string())
+}
+
+/// Numbers, or strings holding a number (values rendered from templates)
+fn number(value: &JsonValue) -> Option<f64> {
+    match value {
+        JsonValue::Number(n) => n.as_f64(),
+        JsonValue::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+fn scalar_string(value: &JsonValue) -> Option<String> {
+    match value {
+        JsonValue::String(s) => Some(s.clone()),
+        JsonValue::Number(n) => Some(n.to_string()),
+        JsonValue::Bool(b) => Some(b.to_string()),
+        JsonValue::Null => Some("null".to_string()),
+        _ => None,
+    }
+}
+
+fn type_name(value: &JsonValue) -> &'static str {
+    match value {
+        JsonValue::Null => "null",
+        JsonValue::Bool(_) => "boolean",
+        JsonValue::Number(_) => "number",
+        JsonValue::String(_) => "string",
+        JsonValue::Array(_) => "array",
+        JsonValue::Object(_) => "object",
+    }
+}
+
 /// Check header assertion
 fn check_header(headers: &HeaderMap, name: &str, expected_value: Option<&str>) -> AssertionResult {
     let assertion = if let Some(val) = expected_value {
@@ -328,4 +522,74 @@ mod tests {
         let result = check_body(r#"{"success": false}"#, "success:true");
         assert!(!result.passed);
     }
+
+    fn check(body: &str, path: &str, op: AssertOp, value: JsonValue) -> AssertionResult {
+        let json = serde_json::from_str::<JsonValue>(body).ok();
+        check_body_assertion(json.as_ref(), &BodyAssertion::new(path, op, Some(value)))
+    }
+
+    #[test]
+    fn test_typed_body_operators() {
+        use serde_json::json;
+        let body = r#"{"count": 3, "price": 9.99, "name": "widget", "items": [1, 2, 3], "ok": true, "tags": {"a": 1}}"#;
+
+        assert!(check(body, ".count", AssertOp::Gt, json!(0)).passed);
+        assert!(!check(body, "count", AssertOp::Lt, json!(3)).passed);
+        assert!(check(body, ".count", AssertOp::Gte, json!("3")).passed);
+        assert!(check(body, ".items", AssertOp::Length, json!(3)).passed);
+        assert!(check(body, ".items", AssertOp::LengthGte, json!(1)).passed);
+        assert!(!check(body, ".name", AssertOp::LengthLt, json!(6)).passed);
+        assert!(check(body, ".name", AssertOp::Matches, json!("^wid")).passed);
+        assert!(check(body, ".count", AssertOp::In, json!([1, 3])).passed);
+        assert!(check(body, ".name", AssertOp::NotIn, json!(["gadget"])).passed);
+        assert!(check(body, ".items", AssertOp::Type, json!("array")).passed);
+        assert!(check(body, ".count", AssertOp::Type, json!("integer")).passed);
+        assert!(check(body, ".items", AssertOp::Contains, json!(2)).passed);
+        assert!(check(body, ".tags", AssertOp::Contains, json!("a")).passed);
+        assert!(check(body, ".price", AssertOp::Approx, json!(9.99000001)).passed);
+        assert!(check(body, ".ok", AssertOp::Eq, json!("true")).passed);
+        assert!(check(body, ".missing", AssertOp::NotExists, JsonValue::Null).passed);
+        assert!(check(body, ".name", AssertOp::Exists, JsonValue::Null).passed);
+    }
+
+    #[test]
+    fn test_typed_body_failure_message() {
+        let result = check(r#"{"items": []}"#, ".items", AssertOp::LengthGte, serde_json::json!(1));
+        assert!(!result.passed);
+        assert_eq!(result.message, ".items: expected length_gte 1, got []");
+
+        let result = check(r#"{"name": "x"}"#, ".name", AssertOp::Gt, serde_json::json!(1));
+        assert_eq!(result.message, r#".name: values are not comparable (expected gt 1, got "x")"#);
+
+        let result = check("not json", ".name", AssertOp::Exists, JsonValue::Null);
+        assert!(!result.passed);
+    }
+
+    #[test]
+    fn test_schema_violations() {
+        let schema = serde_json::json!({
+            "type": "object",
+            "required": ["id", "items"],
+            "properties": {
+                "id": {"type": "integer"},
+                "items": {"type": "array", "items": {"type": "string"}}
+            }
+        });
+
+        let results = check_schema(&schema, "user.json", r#"{"id": 1, "items": ["a"]}"#);
+        assert_eq!(results.len(), 1);
+        assert!(results[0].passed);
+
+        let results = check_schema(&schema, "user.json", r#"{"id": "1", "items": ["a", 2]}"#);
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().all(|r| !r.passed));
+        assert!(results.iter().any(|r| r.assertion == "schema user.json at /id"));
+        assert!(results.iter().any(|r| r.assertion == "schema user.json at /items/1" && r.message.contains("string")));
+
+        let results = check_schema(&schema, "user.json", r#"{}"#);
+        assert_eq!(results[0].assertion, "schema user.json at /");
+
+        assert!(!check_schema(&schema, "user.json", "not json")[0].passed);
+        assert!(!check_schema(&serde_json::json!({"type": 12}), "inline", "{}")[0].passed);
+    }
 }
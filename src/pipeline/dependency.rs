@@ -95,8 +95,10 @@ pub fn resolve_dependencies(steps: &[&WorkflowStep]) -> Result<DependencyOrder,
 
     // Process level by level for parallel execution grouping
     while !queue.is_empty() {
-        // All steps in the current queue can run in parallel
-        let level: Vec<usize> = queue.drain(..).collect();
+        // All steps in the current queue can run in parallel. Keep them in
+        // declaration order so execution and reports are deterministic.
+        let mut level: Vec<usize> = queue.drain(..).collect();
+        level.sort_unstable();
 
         for &idx in &level {
             order.push(idx);
@@ -210,6 +212,22 @@ mod tests {
         assert_eq!(result.levels[2], vec![3]); // d
     }
 
+    #[test]
+    fn test_levels_keep_declaration_order() {
+        // d becomes ready before b (a is processed before c), but levels stay sorted
+        let steps = vec![
+            make_step("a", vec![]),
+            make_step("b", vec!["c"]),
+            make_step("c", vec![]),
+            make_step("d", vec!["a"]),
+        ];
+        let refs: Vec<&WorkflowStep> = steps.iter().collect();
+
+        let result = resolve_dependencies(&refs).unwrap();
+        assert_eq!(result.levels, vec![vec![0, 2], vec![1, 3]]);
+        assert_eq!(result.order, vec![0, 2, 1, 3]);
+    }
+
     #[test]
     fn test_cycle_detection() {
         let steps = vec![
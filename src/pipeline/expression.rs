@@ -0,0 +1,485 @@
+//! Condition expressions for `skip_if`, `run_if`, `while`, `until` and `if`
+//!
+//! ```text
+//! {{status}} >= 500 || {{items | length}} == 0
+//! job.state == "done" && !contains(lower(job.message), "error")
+//! ```
+//!
+//! `{{ ... }}` is a JQ filter over the variables (`{{items | length}}` runs
+//! `.items | length`), and bare names like `job.state` look variables up
+//! directly. Operators, loosest first: `||`, `&&`, `==` `!=`, `<` `<=` `>`
+//! `>=`, `+` `-`, `*` `/` `%`, then unary `!` and `-`. Numeric strings compare
+//! and calculate as numbers; `+` on strings concatenates.
+
+use std::collections::HashMap;
+
+use regex::Regex;
+use serde_json::{Map, Value as JsonValue};
+
+use crate::errors::QuicpulseError;
+use crate::filter;
+
+/// Functions callable from expressions
+pub const FUNCTIONS: &[&str] = &[
+    "length", "lower", "upper", "trim", "contains", "starts_with", "ends_with", "matches", "number", "string",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Str(String),
+    Ident(String),
+    Jq(String),
+    Op(&'static str),
+}
+
+/// Parsed expression
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Literal(JsonValue),
+    /// JQ filter over the variables object
+    Jq(String),
+    Not(Box<Expr>),
+    Neg(Box<Expr>),
+    Binary(&'static str, Box<Expr>, Box<Expr>),
+    Call(String, Vec<Expr>),
+}
+
+/// Operators grouped by precedence, loosest first
+const PRECEDENCE: &[&[&str]] = &[
+    &["||"],
+    &["&&"],
+    &["==", "!="],
+    &["<", "<=", ">", ">="],
+    &["+", "-"],
+    &["*", "/", "%"],
+];
+
+/// Parse and evaluate `expr`, returning whether the result is truthy
+pub fn evaluate_condition(expr: &str, variables: &HashMap<String, JsonValue>) -> Result<bool, QuicpulseError> {
+    Ok(is_truthy(&evaluate(expr, variables)?))
+}
+
+/// Parse and evaluate `expr` against the workflow variables
+pub fn evaluate(expr: &str, variables: &HashMap<String, JsonValue>) -> Result<JsonValue, QuicpulseError> {
+    let parsed = parse(expr)?;
+    let scope = JsonValue::Object(variables.iter().map(|(k, v)| (k.clone(), v.clone())).collect::<Map<_, _>>());
+    parsed.eval(&scope)
+        .map_err(|e| QuicpulseError::Argument(format!("Cannot evaluate condition '{}': {}", expr, e)))
+}
+
+/// Parse an expression without evaluating it
+pub fn parse(expr: &str) -> Result<Expr, QuicpulseError> {
+    let invalid = |e: String| QuicpulseError::Argument(format!("Invalid condition '{}': {}", expr, e));
+    let tokens = tokenize(expr).map_err(invalid)?;
+    let mut parser = Parser { tokens, pos: 0 };
+    let parsed = parser.binary(0).map_err(invalid)?;
+    if let Some(token) = parser.tokens.get(parser.pos) {
+        return Err(invalid(format!("unexpected {}", describe(token))));
+    }
+    Ok(parsed)
+}
+
+/// Truthiness used by conditions: false, null, 0, "" and empty collections are false
+pub fn is_truthy(value: &JsonValue) -> bool {
+    match value {
+        JsonValue::Bool(b) => *b,
+        JsonValue::Null => false,
+        JsonValue::String(s) => !s.is_empty(),
+        JsonValue::Number(n) => n.as_f64().map(|f| f != 0.0).unwrap_or(false),
+        JsonValue::Array(a) => !a.is_empty(),
+        JsonValue::Object(o) => !o.is_empty(),
+    }
+}
+
+fn tokenize(expr: &str) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = expr.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c == '{' && chars.get(i + 1) == Some(&'{') {
+            let rest: String = chars[i + 2..].iter().collect();
+            let end = rest.find("}}").ok_or("unterminated {{")?;
+            let inner = rest[..end].trim();
+            if inner.is_empty() {
+                return Err("empty {{ }}".to_string());
+            }
+            tokens.push(Token::Jq(variable_filter(inner)));
+            i += 2 + rest[..end].chars().count() + 2;
+        } else if c.is_ascii_digit() {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                i += 1;
+            }
+            let text: String = chars[start..i].iter().collect();
+            tokens.push(Token::Number(text.parse().map_err(|_| format!("invalid number {}", text))?));
+        } else if c == '"' || c == '\'' {
+            let mut text = String::new();
+            i += 1;
+            loop {
+                match chars.get(i) {
+                    None => return Err("unterminated string".to_string()),
+                    Some(&q) if q == c => break,
+                    Some('\\') => {
+                        i += 1;
+                        match chars.get(i) {
+                            Some('n') => text.push('\n'),
+                            Some('t') => text.push('\t'),
+                            Some(&other) => text.push(other),
+                            None => return Err("unterminated string".to_string()),
+                        }
+                    }
+                    Some(&other) => text.push(other),
+                }
+                i += 1;
+            }
+            i += 1;
+            tokens.push(Token::Str(text));
+        } else if c.is_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_' || chars[i] == '.') {
+                i += 1;
+            }
+            tokens.push(Token::Ident(chars[start..i].iter().collect()));
+        } else {
+            let two: String = chars[i..(i + 2).min(chars.len())].iter().collect();
+            let op = ["||", "&&", "==", "!=", "<=", ">="].into_iter().find(|op| *op == two)
+                .or_else(|| ["<", ">", "!", "+", "-", "*", "/", "%", "(", ")", ","].into_iter()
+                    .find(|op| op.starts_with(c)))
+                .ok_or_else(|| format!("unexpected '{}'", c))?;
+            i += op.len();
+            tokens.push(Token::Op(op));
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// JQ filter for the inside of `{{ }}`: `{{name}}` and `{{name.path | f}}` refer
+/// to variables, and names that are not identifiers, like `user-id`, are quoted
+/// (`.["user-id"]`) so JQ does not read them as arithmetic
+fn variable_filter(inner: &str) -> String {
+    if !inner.starts_with(|c: char| c.is_alphabetic() || c == '_') {
+        return inner.to_string();
+    }
+    let end = inner.find(|c: char| c.is_whitespace() || matches!(c, '.' | '[' | '|' | '?')).unwrap_or(inner.len());
+    let (name, rest) = inner.split_at(end);
+    if name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        format!(".{}", inner)
+    } else {
+        format!(".[{}]{}", JsonValue::String(name.to_string()), rest)
+    }
+}
+
+fn describe(token: &Token) -> String {
+    match token {
+        Token::Number(n) => format!("number {}", n),
+        Token::Str(s) => format!("string '{}'", s),
+        Token::Ident(name) => format!("'{}'", name),
+        Token::Jq(filter) => format!("{{{{{}}}}}", filter),
+        Token::Op(op) => format!("'{}'", op),
+    }
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek_op(&self) -> Option<&'static str> {
+        match self.tokens.get(self.pos) {
+            Some(Token::Op(op)) => Some(*op),
+            _ => None,
+        }
+    }
+
+    fn expect(&mut self, op: &str) -> Result<(), String> {
+        match self.tokens.get(self.pos) {
+            Some(Token::Op(found)) if *found == op => {
+                self.pos += 1;
+                Ok(())
+            }
+            Some(token) => Err(format!("expected '{}', found {}", op, describe(token))),
+            None => Err(format!("expected '{}'", op)),
+        }
+    }
+
+    fn binary(&mut self, level: usize) -> Result<Expr, String> {
+        let Some(ops) = PRECEDENCE.get(level) else {
+            return self.unary();
+        };
+        let mut left = self.binary(level + 1)?;
+        while let Some(op) = self.peek_op().filter(|op| ops.contains(op)) {
+            self.pos += 1;
+            let right = self.binary(level + 1)?;
+            left = Expr::Binary(op, Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> Result<Expr, String> {
+        match self.peek_op() {
+            Some("!") => {
+                self.pos += 1;
+                Ok(Expr::Not(Box::new(self.unary()?)))
+            }
+            Some("-") => {
+                self.pos += 1;
+                Ok(Expr::Neg(Box::new(self.unary()?)))
+            }
+            _ => self.primary(),
+        }
+    }
+
+    fn primary(&mut self) -> Result<Expr, String> {
+        let token = self.tokens.get(self.pos).cloned().ok_or("unexpected end of expression")?;
+        self.pos += 1;
+        match token {
+            Token::Number(n) => Ok(Expr::Literal(number(n))),
+            Token::Str(s) => Ok(Expr::Literal(JsonValue::String(s))),
+            Token::Jq(filter) => Ok(Expr::Jq(filter)),
+            Token::Op("(") => {
+                let inner = self.binary(0)?;
+                self.expect(")")?;
+                Ok(inner)
+            }
+            Token::Ident(name) if self.peek_op() == Some("(") => {
+                if !FUNCTIONS.contains(&name.as_str()) {
+                    return Err(format!("unknown function '{}' (expected one of: {})", name, FUNCTIONS.join(", ")));
+                }
+                self.pos += 1;
+                let mut args = Vec::new();
+                if self.peek_op() != Some(")") {
+                    loop {
+                        args.push(self.binary(0)?);
+                        if self.peek_op() != Some(",") {
+                            break;
+                        }
+                        self.pos += 1;
+                    }
+                }
+                self.expect(")")?;
+                Ok(Expr::Call(name, args))
+            }
+            Token::Ident(name) => Ok(match name.as_str() {
+                "true" => Expr::Literal(JsonValue::Bool(true)),
+                "false" => Expr::Literal(JsonValue::Bool(false)),
+                "null" => Expr::Literal(JsonValue::Null),
+                _ => Expr::Jq(format!(".{}", name)),
+            }),
+            Token::Op(op) => Err(format!("unexpected '{}'", op)),
+        }
+    }
+}
+
+impl Expr {
+    fn eval(&self, scope: &JsonValue) -> Result<JsonValue, String> {
+        match self {
+            Expr::Literal(value) => Ok(value.clone()),
+            Expr::Jq(filter) => filter::apply_filter(scope, filter)
+                .map(|results| results.into_iter().next().unwrap_or(JsonValue::Null))
+                .map_err(|e| e.to_string()),
+            Expr::Not(inner) => Ok(JsonValue::Bool(!is_truthy(&inner.eval(scope)?))),
+            Expr::Neg(inner) => {
+                let value = inner.eval(scope)?;
+                let n = as_number(&value).ok_or_else(|| format!("cannot negate {}", value))?;
+                Ok(number(-n))
+            }
+            Expr::Binary("||", left, right) => {
+                let left = left.eval(scope)?;
+                if is_truthy(&left) { Ok(JsonValue::Bool(true)) } else { Ok(JsonValue::Bool(is_truthy(&right.eval(scope)?))) }
+            }
+            Expr::Binary("&&", left, right) => {
+                let left = left.eval(scope)?;
+                if !is_truthy(&left) { Ok(JsonValue::Bool(false)) } else { Ok(JsonValue::Bool(is_truthy(&right.eval(scope)?))) }
+            }
+            Expr::Binary(op, left, right) => binary(op, &left.eval(scope)?, &right.eval(scope)?),
+            Expr::Call(name, args) => {
+                let args = args.iter().map(|arg| arg.eval(scope)).collect::<Result<Vec<_>, _>>()?;
+                call(name, &args)
+            }
+        }
+    }
+}
+
+fn binary(op: &str, left: &JsonValue, right: &JsonValue) -> Result<JsonValue, String> {
+    let numbers = as_number(left).zip(as_number(right));
+    let result = match op {
+        "==" => JsonValue::Bool(loose_eq(left, right)),
+        "!=" => JsonValue::Bool(!loose_eq(left, right)),
+        "<" | "<=" | ">" | ">=" => {
+            let ordering = match (numbers, left, right) {
+                (Some((a, b)), _, _) => a.partial_cmp(&b),
+                (None, JsonValue::String(a), JsonValue::String(b)) => Some(a.cmp(b)),
+                _ => None,
+            }
+            .ok_or_else(|| format!("cannot compare {} and {}", left, right))?;
+            JsonValue::Bool(match op {
+                "<" => ordering.is_lt(),
+                "<=" => ordering.is_le(),
+                ">" => ordering.is_gt(),
+                _ => ordering.is_ge(),
+            })
+        }
+        "+" if (left.is_string() && right.is_string()) || (numbers.is_none() && (left.is_string() || right.is_string())) => {
+            JsonValue::String(format!("{}{}", display(left), display(right)))
+        }
+        _ => {
+            let (a, b) = numbers.ok_or_else(|| format!("cannot apply '{}' to {} and {}", op, left, right))?;
+            if (op == "/" || op == "%") && b == 0.0 {
+                return Err("division by zero".to_string());
+            }
+            number(match op {
+                "+" => a + b,
+                "-" => a - b,
+                "*" => a * b,
+                "/" => a / b,
+                _ => a % b,
+            })
+        }
+    };
+    Ok(result)
+}
+
+fn call(name: &str, args: &[JsonValue]) -> Result<JsonValue, String> {
+    let arity = match name {
+        "contains" | "starts_with" | "ends_with" | "matches" => 2,
+        _ => 1,
+    };
+    if args.len() != arity {
+        return Err(format!("{}() takes {} argument(s), got {}", name, arity, args.len()));
+    }
+    let result: JsonValue = match name {
+        "length" => match &args[0] {
+            JsonValue::String(s) => s.chars().count().into(),
+            JsonValue::Array(a) => a.len().into(),
+            JsonValue::Object(o) => o.len().into(),
+            JsonValue::Null => 0.into(),
+            other => return Err(format!("length() of {}", other)),
+        },
+        "lower" => display(&args[0]).to_lowercase().into(),
+        "upper" => display(&args[0]).to_uppercase().into(),
+        "trim" => display(&args[0]).trim().into(),
+        "contains" => match &args[0] {
+            JsonValue::Array(items) => items.iter().any(|item| loose_eq(item, &args[1])).into(),
+            JsonValue::Object(map) => map.contains_key(&display(&args[1])).into(),
+            other => display(other).contains(&display(&args[1])).into(),
+        },
+        "starts_with" => display(&args[0]).starts_with(&display(&args[1])).into(),
+        "ends_with" => display(&args[0]).ends_with(&display(&args[1])).into(),
+        "matches" => {
+            let re = Regex::new(&display(&args[1])).map_err(|e| format!("invalid regex: {}", e))?;
+            re.is_match(&display(&args[0])).into()
+        }
+        "number" => as_number(&args[0]).map(number).unwrap_or(JsonValue::Null),
+        _ => display(&args[0]).into(),
+    };
+    Ok(result)
+}
+
+/// Equality that treats numeric strings as numbers, so `"200" == 200`
+fn loose_eq(left: &JsonValue, right: &JsonValue) -> bool {
+    match (left, right) {
+        (JsonValue::Number(_), JsonValue::String(_)) | (JsonValue::String(_), JsonValue::Number(_))
+        | (JsonValue::Number(_), JsonValue::Number(_)) => {
+            matches!(as_number(left).zip(as_number(right)), Some((a, b)) if a == b)
+        }
+        (JsonValue::Bool(b), JsonValue::String(s)) | (JsonValue::String(s), JsonValue::Bool(b)) => s == &b.to_string(),
+        _ => left == right,
+    }
+}
+
+fn as_number(value: &JsonValue) -> Option<f64> {
+    match value {
+        JsonValue::Number(n) => n.as_f64(),
+        JsonValue::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+/// Whole numbers as integers, everything else as floats
+fn number(n: f64) -> JsonValue {
+    if n.fract() == 0.0 && n.abs() < i64::MAX as f64 {
+        JsonValue::from(n as i64)
+    } else {
+        serde_json::Number::from_f64(n).map(JsonValue::Number).unwrap_or(JsonValue::Null)
+    }
+}
+
+fn display(value: &JsonValue) -> String {
+    match value {
+        JsonValue::String(s) => s.clone(),
+        JsonValue::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn vars() -> HashMap<String, JsonValue> {
+        HashMap::from([
+            ("status".to_string(), json!(503)),
+            ("code".to_string(), json!("200")),
+            ("items".to_string(), json!([])),
+            ("job".to_string(), json!({"state": "done", "message": "All OK", "progress": 0.5})),
+            ("name".to_string(), json!("quic")),
+            ("enabled".to_string(), json!(true)),
+            ("user-id".to_string(), json!({"name": "ada"})),
+        ])
+    }
+
+    fn eval(expr: &str) -> JsonValue {
+        evaluate(expr, &vars()).unwrap()
+    }
+
+    #[test]
+    fn test_comparisons_and_logic() {
+        assert_eq!(eval("{{status}} >= 500 || {{items | length}} == 0"), json!(true));
+        assert_eq!(eval("{{status}} < 500 && {{enabled}}"), json!(false));
+        assert_eq!(eval("job.state == \"done\""), json!(true));
+        assert_eq!(eval("job.state != 'done'"), json!(false));
+        assert_eq!(eval("{{ code }} == 200"), json!(true));
+        assert_eq!(eval("!({{status}} == 503)"), json!(false));
+        assert_eq!(eval("'abc' < 'abd'"), json!(true));
+        assert_eq!(eval("missing == null"), json!(true));
+        assert!(evaluate("{{name}} > 1", &vars()).is_err());
+    }
+
+    #[test]
+    fn test_arithmetic_and_functions() {
+        assert_eq!(eval("1 + 2 * 3"), json!(7));
+        assert_eq!(eval("(1 + 2) * 3 - -1"), json!(10));
+        assert_eq!(eval("job.progress * 100"), json!(50));
+        assert_eq!(eval("7 / 2"), json!(3.5));
+        assert_eq!(eval("{{code}} + 1"), json!(201));
+        assert_eq!(eval("name + '-' + 1"), json!("quic-1"));
+        assert_eq!(eval("length(name) == 4 && upper(name) == 'QUIC'"), json!(true));
+        assert_eq!(eval("contains(lower(job.message), 'ok') && starts_with(name, 'q')"), json!(true));
+        assert_eq!(eval("matches(job.message, '^All\\\\s')"), json!(true));
+        assert_eq!(eval("{{job | keys | length}}"), json!(3));
+        assert!(evaluate("1 / 0", &vars()).is_err());
+    }
+
+    #[test]
+    fn test_legacy_conditions_and_errors() {
+        let variables = vars();
+        assert!(evaluate_condition("{{enabled}}", &variables).unwrap());
+        assert!(!evaluate_condition("!{{enabled}}", &variables).unwrap());
+        assert!(evaluate_condition("!{{missing}}", &variables).unwrap());
+        assert!(evaluate_condition("{{user-id}}", &variables).unwrap());
+        assert!(evaluate_condition("{{ user-id.name | length }} == 3", &variables).unwrap());
+        assert!(!evaluate_condition("{{other-id}}", &variables).unwrap());
+
+        for invalid in ["{{status", "1 +", "(1", "'open", "nope(1)", "1 2", "a & b"] {
+            assert!(parse(invalid).is_err(), "{}", invalid);
+        }
+    }
+}
@@ -0,0 +1,460 @@
+//! Variable extraction from responses
+//!
+//! An extraction source is one of:
+//! - `status`, `duration_ms`: response status code and time
+//! - `body` / `response.body`: the whole body (parsed as JSON when possible)
+//! - `header:NAME`: a response header (repeated headers joined with `, `)
+//! - `cookie:NAME`: a cookie set by the response
+//! - `regex:PATTERN`: the first match; its capture group when there is one,
+//!   an array of groups when there are several, an object for named groups
+//! - `xpath:EXPR`: text of the first matching XML node or attribute
+//! - anything else: a JQ path on the JSON body (`.user.id`, `body.user.id`)
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use regex::Regex;
+use reqwest::header::{HeaderMap, SET_COOKIE};
+use serde_json::{Map, Value as JsonValue};
+
+use super::assertions::AssertionResult;
+use super::workflow::Extraction;
+use crate::filter;
+
+/// The response values are extracted from
+pub struct ExtractContext<'a> {
+    pub status: u16,
+    pub duration: Duration,
+    pub headers: &'a HeaderMap,
+    pub body: &'a str,
+}
+
+/// Run all extractions of a step
+///
+/// Returns the extracted variables and a failed assertion for every required
+/// extraction that matched nothing or whose source is invalid.
+pub fn extract_all(
+    extract: &HashMap<String, Extraction>,
+    ctx: &ExtractContext,
+) -> (HashMap<String, JsonValue>, Vec<AssertionResult>) {
+    let mut extracted = HashMap::new();
+    let mut failures = Vec::new();
+    let json = serde_json::from_str::<JsonValue>(ctx.body).ok();
+
+    let mut names: Vec<&String> = extract.keys().collect();
+    names.sort();
+    for name in names {
+        let extraction = &extract[name];
+        match extract_value(extraction.source(), ctx, json.as_ref()) {
+            Ok(Some(value)) => {
+                extracted.insert(name.clone(), value);
+            }
+            Ok(None) if extraction.required() => failures.push(AssertionResult::fail(
+                &format!("extract {}", name),
+                &format!("{} matched nothing", extraction.source()),
+            )),
+            Ok(None) => {}
+            Err(message) => failures.push(AssertionResult::fail(&format!("extract {}", name), &message)),
+        }
+    }
+
+    (extracted, failures)
+}
+
+/// Extract a single value; `Ok(None)` when the source matched nothing
+pub fn extract_value(source: &str, ctx: &ExtractContext, json: Option<&JsonValue>) -> Result<Option<JsonValue>, String> {
+    let source = source.trim();
+    match source {
+        "status" => return Ok(Some(ctx.status.into())),
+        "duration_ms" => return Ok(Some((ctx.duration.as_millis() as u64).into())),
+        "body" | "response.body" => {
+            return Ok(Some(json.cloned().unwrap_or_else(|| JsonValue::String(ctx.body.to_string()))));
+        }
+        _ => {}
+    }
+
+    if let Some(name) = source.strip_prefix("header:") {
+        let values: Vec<_> = ctx.headers.get_all(name.trim())
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .collect();
+        return Ok((!values.is_empty()).then(|| JsonValue::String(values.join(", "))));
+    }
+    if let Some(name) = source.strip_prefix("cookie:") {
+        return Ok(cookie(ctx.headers, name.trim()).map(JsonValue::String));
+    }
+    if let Some(pattern) = source.strip_prefix("regex:") {
+        let re = Regex::new(pattern).map_err(|e| format!("Invalid regex '{}': {}", pattern, e))?;
+        return Ok(regex_match(&re, ctx.body));
+    }
+    if let Some(expr) = source.strip_prefix("xpath:") {
+        let document = parse_xml(ctx.body).map_err(|e| format!("Cannot parse XML body: {}", e))?;
+        let results = xpath(&document, expr.trim())?;
+        return Ok(results.into_iter().next().map(JsonValue::String));
+    }
+
+    // JQ path on the JSON body
+    let expr = if let Some(path) = source.strip_prefix("response.body.").or_else(|| source.strip_prefix("body.")) {
+        format!(".{}", path)
+    } else if source.starts_with('.') {
+        source.to_string()
+    } else {
+        format!(".{}", source)
+    };
+    let Some(json) = json else {
+        return Ok(None);
+    };
+    Ok(filter::apply_filter(json, &expr)
+        .ok()
+        .and_then(|results| results.into_iter().find(|v| !v.is_null())))
+}
+
+/// Value of a cookie from the response's `Set-Cookie` headers
+fn cookie(headers: &HeaderMap, name: &str) -> Option<String> {
+    headers.get_all(SET_COOKIE)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .filter_map(|v| v.split(';').next()?.split_once('='))
+        .find(|(key, _)| key.trim() == name)
+        .map(|(_, value)| value.trim().trim_matches('"').to_string())
+}
+
+fn regex_match(re: &Regex, body: &str) -> Option<JsonValue> {
+    let captures = re.captures(body)?;
+    let group = |i: usize| captures.get(i).map(|m| JsonValue::String(m.as_str().to_string())).unwrap_or(JsonValue::Null);
+
+    let named: Map<String, JsonValue> = re.capture_names()
+        .enumerate()
+        .filter_map(|(i, name)| Some((name?.to_string(), group(i))))
+        .collect();
+    let value = match re.captures_len() {
+        1 => group(0),
+        2 => group(1),
+        _ if !named.is_empty() => JsonValue::Object(named),
+        n => JsonValue::Array((1..n).map(group).collect()),
+    };
+    Some(value)
+}
+
+/// Minimal XML tree for XPath lookups
+#[derive(Debug, Default)]
+struct Element {
+    name: String,
+    attributes: Vec<(String, String)>,
+    children: Vec<Node>,
+}
+
+#[derive(Debug)]
+enum Node {
+    Element(Element),
+    Text(String),
+}
+
+impl Element {
+    fn elements(&self) -> impl Iterator<Item = &Element> {
+        self.children.iter().filter_map(|node| match node {
+            Node::Element(e) => Some(e),
+            Node::Text(_) => None,
+        })
+    }
+
+    /// This element followed by all its descendants, in document order
+    fn descendants_or_self<'a>(&'a self, out: &mut Vec<&'a Element>) {
+        out.push(self);
+        for child in self.elements() {
+            child.descendants_or_self(out);
+        }
+    }
+
+    fn text(&self) -> String {
+        self.children.iter()
+            .map(|node| match node {
+                Node::Text(text) => text.clone(),
+                Node::Element(e) => e.text(),
+            })
+            .collect()
+    }
+
+    fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes.iter().find(|(key, _)| key == name).map(|(_, value)| value.as_str())
+    }
+}
+
+/// Parse XML into a document node whose children are the top-level elements
+fn parse_xml(xml: &str) -> Result<Element, String> {
+    let mut reader = Reader::from_str(xml);
+    let mut stack = vec![Element::default()];
+    loop {
+        match reader.read_event().map_err(|e| e.to_string())? {
+            Event::Start(e) => {
+                stack.push(Element {
+                    name: String::from_utf8_lossy(e.local_name().as_ref()).into_owned(),
+                    attributes: attributes(&e),
+                    children: Vec::new(),
+                });
+            }
+            Event::Empty(e) => {
+                let element = Element {
+                    name: String::from_utf8_lossy(e.local_name().as_ref()).into_owned(),
+                    attributes: attributes(&e),
+                    children: Vec::new(),
+                };
+                if let Some(parent) = stack.last_mut() {
+                    parent.children.push(Node::Element(element));
+                }
+            }
+            Event::End(_) => {
+                if stack.len() < 2 {
+                    return Err("unexpected closing tag".to_string());
+                }
+                let element = stack.pop().unwrap_or_default();
+                if let Some(parent) = stack.last_mut() {
+                    parent.children.push(Node::Element(element));
+                }
+            }
+            Event::Text(e) => push_text(&mut stack, &unescape(&String::from_utf8_lossy(e.as_ref()))),
+            Event::CData(e) => push_text(&mut stack, &String::from_utf8_lossy(e.as_ref())),
+            Event::GeneralRef(e) => {
+                push_text(&mut stack, &unescape(&format!("&{};", String::from_utf8_lossy(e.as_ref()))));
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    if stack.len() != 1 {
+        return Err("unclosed element".to_string());
+    }
+    let document = stack.pop().unwrap_or_default();
+    if document.elements().next().is_none() {
+        return Err("no root element".to_string());
+    }
+    Ok(document)
+}
+
+fn attributes(e: &quick_xml::events::BytesStart) -> Vec<(String, String)> {
+    e.attributes()
+        .flatten()
+        .map(|attr| {
+            let key = String::from_utf8_lossy(attr.key.local_name().as_ref()).into_owned();
+            (key, unescape(&String::from_utf8_lossy(&attr.value)))
+        })
+        .collect()
+}
+
+fn unescape(text: &str) -> String {
+    quick_xml::escape::unescape(text).map(|s| s.into_owned()).unwrap_or_else(|_| text.to_string())
+}
+
+fn push_text(stack: &mut [Element], text: &str) {
+    let Some(parent) = stack.last_mut() else { return };
+    // Entity references arrive as separate events, so merge adjacent text
+    if let Some(Node::Text(last)) = parent.children.last_mut() {
+        last.push_str(text);
+    } else if !text.trim().is_empty() {
+        parent.children.push(Node::Text(text.to_string()));
+    }
+}
+
+/// Evaluate an XPath subset: `/a/b`, `//b`, `*`, `b[2]`, `b[@id='x']`, `@attr` and `text()`
+fn xpath(document: &Element, expr: &str) -> Result<Vec<String>, String> {
+    let invalid = || format!("Invalid xpath '{}'", expr);
+
+    // Paths are evaluated from the document; an empty step after the first marks `//`
+    let mut steps = Vec::new();
+    let mut descendant = false;
+    for (i, token) in split_steps(expr).ok_or_else(invalid)?.into_iter().enumerate() {
+        if token.is_empty() {
+            if descendant {
+                return Err(invalid());
+            }
+            descendant = i > 0;
+            continue;
+        }
+        steps.push((descendant, token.trim()));
+        descendant = false;
+    }
+    if descendant || steps.is_empty() {
+        return Err(invalid());
+    }
+
+    let mut context = vec![document];
+    for (i, (descendant, step)) in steps.iter().enumerate() {
+        let last = i == steps.len() - 1;
+        let candidates: Vec<&Element> = if *descendant {
+            let mut all: Vec<&Element> = Vec::new();
+            for node in &context {
+                let mut nodes = Vec::new();
+                node.descendants_or_self(&mut nodes);
+                for node in nodes {
+                    if !all.iter().any(|e| std::ptr::eq(*e, node)) {
+                        all.push(node);
+                    }
+                }
+            }
+            all
+        } else {
+            context
+        };
+
+        if let Some(name) = step.strip_prefix('@') {
+            if !last {
+                return Err(invalid());
+            }
+            return Ok(candidates.iter().filter_map(|e| e.attribute(name)).map(String::from).collect());
+        }
+        if *step == "text()" {
+            if !last {
+                return Err(invalid());
+            }
+            return Ok(candidates.iter()
+                .flat_map(|e| e.children.iter())
+                .filter_map(|node| match node {
+                    Node::Text(text) => Some(text.trim().to_string()),
+                    Node::Element(_) => None,
+                })
+                .collect());
+        }
+
+        let (name, predicate) = match step.find('[') {
+            Some(start) if step.ends_with(']') => (&step[..start], Some(step[start + 1..step.len() - 1].trim())),
+            Some(_) => return Err(invalid()),
+            None => (*step, None),
+        };
+        if name.is_empty() {
+            return Err(invalid());
+        }
+
+        let mut next = Vec::new();
+        for parent in candidates {
+            let matching: Vec<&Element> = parent.elements().filter(|e| name == "*" || e.name == name).collect();
+            match predicate {
+                None => next.extend(matching),
+                Some(p) if p.starts_with('@') => {
+                    let (attr, value) = match p[1..].split_once('=') {
+                        Some((attr, value)) => (attr.trim(), Some(value.trim().trim_matches(|c: char| c == '\'' || c == '"'))),
+                        None => (p[1..].trim(), None),
+                    };
+                    next.extend(matching.into_iter().filter(|e| match (e.attribute(attr), value) {
+                        (Some(actual), Some(expected)) => actual == expected,
+                        (Some(_), None) => true,
+                        (None, _) => false,
+                    }));
+                }
+                Some(p) => {
+                    let index: usize = p.parse().map_err(|_| invalid())?;
+                    next.extend(index.checked_sub(1).and_then(|i| matching.get(i).copied()));
+                }
+            }
+        }
+        context = next;
+    }
+
+    Ok(context.iter().map(|e| e.text().trim().to_string()).collect())
+}
+
+/// Split a path on `/` outside predicates; `//` becomes an empty step
+fn split_steps(path: &str) -> Option<Vec<&str>> {
+    let mut steps = Vec::new();
+    let (mut start, mut depth) = (0, 0);
+    for (i, c) in path.char_indices() {
+        match c {
+            '[' => depth += 1,
+            ']' => depth -= 1,
+            '/' if depth == 0 => {
+                steps.push(&path[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    steps.push(&path[start..]);
+    (depth == 0).then_some(steps)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn extract(source: &str, body: &str) -> Result<Option<JsonValue>, String> {
+        let mut headers = HeaderMap::new();
+        headers.insert("location", "/users/42".parse().unwrap());
+        headers.append(SET_COOKIE, "theme=dark; Path=/".parse().unwrap());
+        headers.append(SET_COOKIE, "session_id=abc123; HttpOnly".parse().unwrap());
+        let ctx = ExtractContext { status: 201, duration: Duration::from_millis(37), headers: &headers, body };
+        let json = serde_json::from_str::<JsonValue>(body).ok();
+        extract_value(source, &ctx, json.as_ref())
+    }
+
+    #[test]
+    fn test_response_sources() {
+        assert_eq!(extract("status", "").unwrap(), Some(json!(201)));
+        assert_eq!(extract("duration_ms", "").unwrap(), Some(json!(37)));
+        assert_eq!(extract("header:Location", "").unwrap(), Some(json!("/users/42")));
+        assert_eq!(extract("header:X-Missing", "").unwrap(), None);
+        assert_eq!(extract("cookie:session_id", "").unwrap(), Some(json!("abc123")));
+        assert_eq!(extract("cookie:missing", "").unwrap(), None);
+        assert_eq!(extract("body", "plain").unwrap(), Some(json!("plain")));
+        assert_eq!(extract("body", r#"{"a": 1}"#).unwrap(), Some(json!({"a": 1})));
+    }
+
+    #[test]
+    fn test_json_and_regex_sources() {
+        let body = r#"{"user": {"id": 7}}"#;
+        assert_eq!(extract(".user.id", body).unwrap(), Some(json!(7)));
+        assert_eq!(extract("response.body.user.id", body).unwrap(), Some(json!(7)));
+        assert_eq!(extract("body.user.id", body).unwrap(), Some(json!(7)));
+        assert_eq!(extract("user.id", body).unwrap(), Some(json!(7)));
+        assert_eq!(extract(".user.missing", body).unwrap(), None);
+        assert_eq!(extract(".user.id", "not json").unwrap(), None);
+
+        let text = "token=abc-123 expires=3600";
+        assert_eq!(extract(r"regex:expires=\d+", text).unwrap(), Some(json!("expires=3600")));
+        assert_eq!(extract(r"regex:token=([\w-]+)", text).unwrap(), Some(json!("abc-123")));
+        assert_eq!(extract(r"regex:token=(\w+)-(\d+)", text).unwrap(), Some(json!(["abc", "123"])));
+        assert_eq!(
+            extract(r"regex:token=(?P<name>\w+)-(?P<num>\d+)", text).unwrap(),
+            Some(json!({"name": "abc", "num": "123"}))
+        );
+        assert_eq!(extract("regex:nomatch", text).unwrap(), None);
+        assert!(extract("regex:(", text).is_err());
+    }
+
+    #[test]
+    fn test_xpath_source() {
+        let xml = r#"<?xml version="1.0"?>
+            <soap:Envelope xmlns:soap="http://schemas.xmlsoap.org/soap/envelope/">
+              <soap:Body>
+                <order id="17" status="open"><item sku="a">Tea &amp; cake</item><item sku="b">Milk</item></order>
+              </soap:Body>
+            </soap:Envelope>"#;
+        assert_eq!(extract("xpath:/Envelope/Body/order/@id", xml).unwrap(), Some(json!("17")));
+        assert_eq!(extract("xpath://item", xml).unwrap(), Some(json!("Tea & cake")));
+        assert_eq!(extract("xpath://item[2]", xml).unwrap(), Some(json!("Milk")));
+        assert_eq!(extract("xpath://item[@sku='b']/text()", xml).unwrap(), Some(json!("Milk")));
+        assert_eq!(extract("xpath://order/*[1]/@sku", xml).unwrap(), Some(json!("a")));
+        assert_eq!(extract("xpath://missing", xml).unwrap(), None);
+        assert!(extract("xpath://item", "not xml").is_err());
+        assert!(extract("xpath://item[x]", xml).is_err());
+    }
+
+    #[test]
+    fn test_required_extractions() {
+        let headers = HeaderMap::new();
+        let ctx = ExtractContext { status: 200, duration: Duration::ZERO, headers: &headers, body: r#"{"id": 1}"# };
+        let extract = HashMap::from([
+            ("id".to_string(), Extraction::from(".id".to_string())),
+            ("optional".to_string(), Extraction::from("header:Location".to_string())),
+            ("location".to_string(), Extraction::Config { from: "header:Location".to_string(), required: true }),
+        ]);
+
+        let (extracted, failures) = extract_all(&extract, &ctx);
+        assert_eq!(extracted, HashMap::from([("id".to_string(), json!(1))]));
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].assertion, "extract location");
+        assert_eq!(failures[0].message, "header:Location matched nothing");
+    }
+}
@@ -0,0 +1,381 @@
+//! Workflow load mode
+//!
+//! A workflow with a `load` section is replayed by virtual users instead of
+//! run once. Each user loops over the steps with its own variables, so values
+//! extracted by one step feed the next, and every response is recorded in a
+//! per-step [`StatsCollector`]. The users' collectors are merged at the end.
+
+use std::time::Duration;
+
+use hdrhistogram::Histogram;
+use serde::Serialize;
+
+use super::runner::StepResult;
+use super::workflow::LoadConfig;
+use crate::bench::stats::{new_histogram, BenchmarkStats, LatencyStats, StatsCollector};
+use crate::errors::QuicpulseError;
+use crate::output::terminal::{self, colors, RESET};
+
+/// Parsed `load` section of a workflow
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoadPlan {
+    pub users: u32,
+    /// Iterations per user; `None` keeps going until `duration` is over
+    pub iterations: Option<u64>,
+    pub duration: Option<Duration>,
+    pub ramp_up: Duration,
+    pub think_time: Duration,
+}
+
+impl LoadPlan {
+    pub fn from_config(config: &LoadConfig) -> Result<Self, QuicpulseError> {
+        let duration = parse_duration("duration", &config.duration)?;
+        let iterations = match (config.iterations, duration) {
+            (Some(n), _) => Some(n as u64),
+            (None, Some(_)) => None,
+            (None, None) => Some(1),
+        };
+
+        Ok(Self {
+            users: config.users.max(1),
+            iterations,
+            duration,
+            ramp_up: parse_duration("ramp_up", &config.ramp_up)?.unwrap_or_default(),
+            think_time: parse_duration("think_time", &config.think_time)?.unwrap_or_default(),
+        })
+    }
+
+    /// How long user `user` (counting from 0) waits before starting
+    pub fn start_delay(&self, user: u32) -> Duration {
+        self.ramp_up.mul_f64(user as f64 / self.users as f64)
+    }
+
+    /// Short description such as `10 users, 5 iterations each` or `10 users for 1m`
+    pub fn describe(&self) -> String {
+        let users = plural(self.users as u64, "user");
+        let mut text = match (self.iterations, self.duration) {
+            (Some(n), Some(d)) => format!("{}, {} each, at most {}", users, plural(n, "iteration"), humantime::format_duration(d)),
+            (Some(n), None) => format!("{}, {} each", users, plural(n, "iteration")),
+            (None, Some(d)) => format!("{} for {}", users, humantime::format_duration(d)),
+            (None, None) => users,
+        };
+        if !self.ramp_up.is_zero() {
+            text.push_str(&format!(", ramp-up {}", humantime::format_duration(self.ramp_up)));
+        }
+        text
+    }
+}
+
+fn plural(count: u64, noun: &str) -> String {
+    if count == 1 { format!("1 {}", noun) } else { format!("{} {}s", count, noun) }
+}
+
+fn parse_duration(field: &str, value: &Option<String>) -> Result<Option<Duration>, QuicpulseError> {
+    value.as_deref()
+        .map(|text| humantime::parse_duration(text).map_err(|e| QuicpulseError::Argument(format!(
+            "Workflow load.{} '{}' is not a duration: {}", field, text, e
+        ))))
+        .transpose()
+}
+
+struct StepStats {
+    name: String,
+    collector: StatsCollector,
+    assertion_failures: u64,
+}
+
+/// Statistics of one virtual user, or of several after merging
+pub struct LoadStats {
+    iterations: u64,
+    failed_iterations: u64,
+    /// Time for a full pass over the steps, in microseconds
+    iteration_times: Histogram<u64>,
+    /// One entry per step, in step order
+    steps: Vec<StepStats>,
+}
+
+impl LoadStats {
+    /// Empty statistics with an entry for each step, so results follow step order
+    pub fn new<'a>(step_names: impl IntoIterator<Item = &'a str>) -> Self {
+        let mut stats = Self {
+            iterations: 0,
+            failed_iterations: 0,
+            iteration_times: new_histogram(),
+            steps: Vec::new(),
+        };
+        for name in step_names {
+            stats.step(name);
+        }
+        stats
+    }
+
+    fn step(&mut self, name: &str) -> &mut StepStats {
+        let index = match self.steps.iter().position(|step| step.name == name) {
+            Some(index) => index,
+            None => {
+                self.steps.push(StepStats {
+                    name: name.to_string(),
+                    collector: StatsCollector::new(),
+                    assertion_failures: 0,
+                });
+                self.steps.len() - 1
+            }
+        };
+        &mut self.steps[index]
+    }
+
+    /// Record one execution of a step; skipped steps are not counted
+    pub fn record_step(&mut self, result: &StepResult) {
+        if result.skipped {
+            return;
+        }
+        let passed = result.passed();
+        let step = self.step(&result.name);
+        step.collector.record_outcome(result.status_code, result.response_time, result.bytes, passed, result.error.clone());
+        if result.error.is_none() && !passed {
+            step.assertion_failures += 1;
+        }
+    }
+
+    /// Record a completed pass over the steps
+    pub fn record_iteration(&mut self, elapsed: Duration, passed: bool) {
+        self.iterations += 1;
+        if !passed {
+            self.failed_iterations += 1;
+        }
+        let _ = self.iteration_times.record((elapsed.as_micros() as u64).max(1));
+    }
+
+    pub fn merge(&mut self, other: LoadStats) {
+        self.iterations += other.iterations;
+        self.failed_iterations += other.failed_iterations;
+        let _ = self.iteration_times.add(&other.iteration_times);
+        for step in other.steps {
+            let mine = self.step(&step.name);
+            mine.collector.merge(step.collector);
+            mine.assertion_failures += step.assertion_failures;
+        }
+    }
+
+    pub fn finish(self, workflow: &str, users: u32, duration: Duration) -> LoadResult {
+        LoadResult {
+            workflow: workflow.to_string(),
+            users,
+            duration_s: duration.as_secs_f64(),
+            iterations: self.iterations,
+            failed_iterations: self.failed_iterations,
+            iteration_latency: LatencyStats::from_histogram(&self.iteration_times),
+            steps: self.steps.into_iter()
+                .map(|step| StepLoadStats {
+                    name: step.name,
+                    assertion_failures: step.assertion_failures,
+                    stats: step.collector.finalize(duration),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Statistics of one step across all virtual users
+#[derive(Debug, Clone, Serialize)]
+pub struct StepLoadStats {
+    pub name: String,
+    /// Failed executions that got a response but failed the step's
+    /// assertions; counted among the failed requests too
+    pub assertion_failures: u64,
+    pub stats: BenchmarkStats,
+}
+
+/// Outcome of a workflow load run
+#[derive(Debug, Clone, Serialize)]
+pub struct LoadResult {
+    pub workflow: String,
+    pub users: u32,
+    pub duration_s: f64,
+    pub iterations: u64,
+    /// Iterations in which a step failed
+    pub failed_iterations: u64,
+    /// Time for a full pass over the steps
+    pub iteration_latency: LatencyStats,
+    pub steps: Vec<StepLoadStats>,
+}
+
+impl LoadResult {
+    pub fn passed(&self) -> bool {
+        self.failed_iterations == 0
+    }
+}
+
+/// Format load results for display
+pub fn format_load_results(result: &LoadResult) -> String {
+    let mut output = String::new();
+
+    let header_line = terminal::colorize("═══════════════════════════════════════════════════════════════════", colors::GREY);
+    let section_line = terminal::colorize("───────────────────────────────────────────────────────────────────", colors::GREY);
+
+    output.push('\n');
+    output.push_str(&header_line);
+    output.push('\n');
+    output.push_str(&format!("{}                        LOAD TEST RESULTS{}\n", terminal::bold_fg(colors::WHITE), RESET));
+    output.push_str(&header_line);
+    output.push_str("\n\n");
+
+    let failed_color = if result.failed_iterations == 0 { colors::GREEN } else { colors::RED };
+    output.push_str(&format!("  {}         {}\n", terminal::label("Workflow:"), terminal::colorize(&result.workflow, colors::AQUA)));
+    output.push_str(&format!("  {}            {}\n", terminal::label("Users:"), terminal::number(&result.users.to_string())));
+    output.push_str(&format!("  {}         {}\n", terminal::label("Duration:"), terminal::number(&format!("{:.2}s", result.duration_s))));
+    output.push_str(&format!("  {}       {}\n", terminal::label("Iterations:"), terminal::number(&result.iterations.to_string())));
+    output.push_str(&format!("  {}           {}\n", terminal::label("Failed:"), terminal::colorize(&result.failed_iterations.to_string(), failed_color)));
+    output.push_str(&format!("  {}   {} {} {} {}\n",
+        terminal::label("Iteration time:"),
+        terminal::muted("p50"),
+        terminal::number(&format!("{:.2}ms", result.iteration_latency.p50_ms)),
+        terminal::muted("p99"),
+        terminal::number(&format!("{:.2}ms", result.iteration_latency.p99_ms))));
+    output.push('\n');
+
+    output.push_str(&section_line);
+    output.push('\n');
+    output.push_str(&format!("  {}\n", terminal::bold("STEPS", colors::WHITE)));
+    output.push_str(&section_line);
+    output.push('\n');
+    output.push_str(&format!("  {}\n", terminal::muted(&format!(
+        "{:<20}  {:>8}  {:>8}  {:>7}  {:>9}  {:>9}  {:>9}",
+        "Step", "Requests", "rps", "Errors", "p50", "p95", "p99"
+    ))));
+
+    for step in &result.steps {
+        let stats = &step.stats;
+        let errors = stats.failed_requests;
+        let error_color = if errors == 0 { colors::GREEN } else { colors::RED };
+        output.push_str(&format!(
+            "  {}  {}  {}  {}  {}  {}  {}\n",
+            terminal::label(&format!("{:<20}", truncate(&step.name, 20))),
+            terminal::number(&format!("{:>8}", stats.successful_requests + stats.failed_requests)),
+            terminal::number(&format!("{:>8.1}", stats.requests_per_second)),
+            terminal::colorize(&format!("{:>7}", errors), error_color),
+            terminal::number(&format!("{:>7.2}ms", stats.latency.p50_ms)),
+            terminal::number(&format!("{:>7.2}ms", stats.latency.p95_ms)),
+            terminal::colorize(&format!("{:>7.2}ms", stats.latency.p99_ms), colors::ORANGE),
+        ));
+    }
+
+    output.push('\n');
+    output.push_str(&header_line);
+    output.push('\n');
+
+    output
+}
+
+fn truncate(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        text.to_string()
+    } else {
+        let mut short: String = text.chars().take(width - 1).collect();
+        short.push('…');
+        short
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn step_result(name: &str, status: u16, millis: u64) -> StepResult {
+        StepResult {
+            name: name.to_string(),
+            method: "GET".to_string(),
+            url: "http://localhost/".to_string(),
+            status_code: Some(status),
+            response_time: Duration::from_millis(millis),
+            timing: None,
+            bytes: 2,
+            assertions: Vec::new(),
+            extracted: HashMap::new(),
+            error: None,
+            skipped: false,
+        }
+    }
+
+    #[test]
+    fn test_load_plan() {
+        let config = LoadConfig {
+            users: 4,
+            iterations: None,
+            duration: None,
+            ramp_up: Some("2s".to_string()),
+            think_time: None,
+        };
+        let plan = LoadPlan::from_config(&config).unwrap();
+        assert_eq!(plan.iterations, Some(1));
+        assert_eq!(plan.start_delay(0), Duration::ZERO);
+        assert_eq!(plan.start_delay(2), Duration::from_secs(1));
+        assert_eq!(plan.describe(), "4 users, 1 iteration each, ramp-up 2s");
+
+        let timed = LoadPlan::from_config(&LoadConfig { duration: Some("1m".to_string()), ..config.clone() }).unwrap();
+        assert_eq!(timed.iterations, None);
+        assert_eq!(timed.duration, Some(Duration::from_secs(60)));
+
+        assert!(LoadPlan::from_config(&LoadConfig { think_time: Some("later".to_string()), ..config }).is_err());
+    }
+
+    #[test]
+    fn test_merge_keeps_step_order() {
+        let mut first = LoadStats::new(["login", "list"]);
+        first.record_step(&step_result("login", 200, 10));
+        first.record_step(&step_result("list", 200, 20));
+        first.record_iteration(Duration::from_millis(30), true);
+
+        // A 401 failing its status assertion is one failed request
+        let mut second = LoadStats::new(["login", "list"]);
+        let mut unauthorized = step_result("login", 401, 5);
+        unauthorized.assertions.push(crate::pipeline::assertions::AssertionResult::fail("status", "expected 200"));
+        second.record_step(&unauthorized);
+        second.record_iteration(Duration::from_millis(5), false);
+
+        first.merge(second);
+        let result = first.finish("journey", 2, Duration::from_secs(1));
+
+        assert_eq!(result.iterations, 2);
+        assert_eq!(result.failed_iterations, 1);
+        assert!(!result.passed());
+        let names: Vec<&str> = result.steps.iter().map(|step| step.name.as_str()).collect();
+        assert_eq!(names, vec!["login", "list"]);
+        assert_eq!(result.steps[0].stats.histogram.len(), 2);
+        assert_eq!(result.steps[0].stats.failed_requests, 1);
+        assert_eq!(result.steps[0].assertion_failures, 1);
+        assert_eq!(result.steps[0].stats.total_bytes, 4);
+        assert_eq!(result.steps[1].stats.successful_requests, 1);
+    }
+
+    #[test]
+    fn test_skipped_and_assertion_failures() {
+        let mut stats = LoadStats::new(["get"]);
+        let mut skipped = step_result("get", 200, 1);
+        skipped.skipped = true;
+        stats.record_step(&skipped);
+
+        let mut failed = step_result("get", 200, 1);
+        failed.assertions.push(crate::pipeline::assertions::AssertionResult {
+            assertion: "status".to_string(),
+            passed: false,
+            message: "expected 201".to_string(),
+        });
+        stats.record_step(&failed);
+
+        // Success comes from the assertions, not the status code
+        let mut accepted = step_result("get", 404, 1);
+        accepted.assertions.push(crate::pipeline::assertions::AssertionResult {
+            assertion: "status".to_string(),
+            passed: true,
+            message: "404".to_string(),
+        });
+        stats.record_step(&accepted);
+
+        let result = stats.finish("journey", 1, Duration::from_secs(1));
+        assert_eq!(result.steps[0].stats.successful_requests, 1);
+        assert_eq!(result.steps[0].stats.failed_requests, 1);
+        assert_eq!(result.steps[0].assertion_failures, 1);
+    }
+}
@@ -2,12 +2,17 @@
 
 pub mod assertions;
 pub mod dependency;
+pub mod expression;
+pub mod extract;
+pub mod load;
 pub mod runner;
 pub mod sharing;
+pub mod snapshot;
 pub mod workflow;
 pub mod report;
 
 pub use assertions::Assertion;
+pub use load::{LoadPlan, LoadResult, format_load_results};
 pub use runner::{PipelineRunner, WorkflowOptions, StepResult, format_workflow_results, format_workflow_results_json};
 pub use sharing::handle_workflow_commands;
 pub use workflow::{load_workflow, apply_environment, apply_cli_variables};
@@ -28,6 +33,12 @@ pub fn has_assertions(args: &crate::cli::Args) -> bool {
         || !args.assert_header.is_empty()
 }
 
+/// `__snapshots__/<workflow name>` next to the workflow file
+fn snapshot_dir(workflow_path: &std::path::Path) -> std::path::PathBuf {
+    let stem = workflow_path.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+    workflow_path.parent().unwrap_or_else(|| std::path::Path::new("")).join("__snapshots__").join(stem)
+}
+
 pub async fn run_workflow(
     args: &Args,
     workflow_path: &std::path::Path,
@@ -50,6 +61,10 @@ pub async fn run_workflow(
         include: args.workflow_include.clone(),
         exclude: args.workflow_exclude.clone(),
         save_responses: args.save_responses.clone(),
+        snapshot_dir: Some(snapshot_dir(workflow_path)),
+        workflow_dir: workflow_path.parent().map(std::path::Path::to_path_buf),
+        update_snapshots: args.update_snapshots,
+        timing: args.meta,
     };
 
     let mut runner = PipelineRunner::with_options(args.dry_run, options)?;
@@ -87,6 +102,13 @@ pub async fn run_workflow(
         eprintln!("  {}", workflow.description);
     }
     eprintln!("  Steps: {}", workflow.steps.len());
+    let load_plan = match workflow.load {
+        Some(ref load) if !args.dry_run => Some(LoadPlan::from_config(load)?),
+        _ => None,
+    };
+    if let Some(ref plan) = load_plan {
+        eprintln!("  Load: {}", plan.describe());
+    }
     if args.continue_on_failure {
         eprintln!("  Continue on failure: enabled");
     }
@@ -95,6 +117,10 @@ pub async fn run_workflow(
     }
     eprintln!();
 
+    if let Some(plan) = load_plan {
+        return run_load(args, &mut runner, &workflow, &plan).await;
+    }
+
     let results = runner.run(&workflow).await?;
 
     // Use JSON format if specified, otherwise use pretty format
@@ -115,6 +141,28 @@ pub async fn run_workflow(
     }
 }
 
+/// Replay the workflow with virtual users and print per-step statistics
+async fn run_load(
+    args: &Args,
+    runner: &mut PipelineRunner,
+    workflow: &workflow::Workflow,
+    plan: &LoadPlan,
+) -> Result<ExitStatus, QuicpulseError> {
+    let result = runner.run_load(workflow, plan).await?;
+
+    if matches!(args.log_format, Some(crate::cli::LogFormat::Json)) {
+        println!("{}", serde_json::to_string_pretty(&result)?);
+    } else {
+        print!("{}", format_load_results(&result));
+    }
+
+    if result.passed() {
+        Ok(ExitStatus::Success)
+    } else {
+        Ok(ExitStatus::from_code(EXIT_ASSERTION_FAILED))
+    }
+}
+
 fn generate_workflow_reports(
     args: &Args,
     workflow_name: &str,
@@ -3,6 +3,7 @@
 //! Supports multiple output formats including JUnit XML for CI/CD integration.
 
 use super::runner::StepResult;
+use crate::client::timing::RequestTiming;
 use crate::errors::QuicpulseError;
 use junit_report::{Duration, Report, TestCase, TestSuite};
 use std::fs::File;
@@ -189,6 +190,7 @@ pub fn generate_json_report(
                 "url": r.url,
                 "status_code": r.status_code,
                 "response_time_ms": r.response_time.as_millis(),
+                "timing": r.timing.as_ref().map(RequestTiming::to_json),
                 "passed": r.passed(),
                 "skipped": r.skipped,
                 "error": r.error,
@@ -317,6 +319,8 @@ mod tests {
             url: "https://api.example.com/test".to_string(),
             status_code: Some(200),
             response_time: StdDuration::from_millis(150),
+            timing: None,
+            bytes: 0,
             assertions: vec![
                 AssertionResult::pass("status", "Status is 200"),
             ],
@@ -333,6 +337,8 @@ mod tests {
             url: "https://api.example.com/users".to_string(),
             status_code: Some(404),
             response_time: StdDuration::from_millis(250),
+            timing: None,
+            bytes: 0,
             assertions: vec![
                 AssertionResult::fail("status", "Expected 200, got 404"),
                 AssertionResult::fail("body.id", "Field 'id' is missing"),
@@ -350,6 +356,8 @@ mod tests {
             url: "https://api.example.com/users/1".to_string(),
             status_code: None,
             response_time: StdDuration::ZERO,
+            timing: None,
+            bytes: 0,
             assertions: vec![],
             extracted: HashMap::new(),
             error: None,
@@ -409,8 +417,11 @@ mod tests {
 
     #[test]
     fn test_json_report_generation() {
+        let mut timed = make_passing_result("Timed Step");
+        timed.timing = Some(RequestTiming { ttfb: StdDuration::from_millis(150), ..Default::default() });
         let results = vec![
             make_passing_result("Test Step"),
+            timed,
         ];
 
         let config = ReportConfig {
@@ -426,7 +437,9 @@ mod tests {
 
         let content = std::fs::read_to_string(&config.output_path).unwrap();
         assert!(content.contains("\"name\": \"Test Workflow\""));
-        assert!(content.contains("\"passed\": 1"));
+        assert!(content.contains("\"passed\": 2"));
+        assert!(content.contains("\"time_starttransfer\": 0.15"));
+        assert!(content.contains("\"timing\": null"));
 
         std::fs::remove_file(&config.output_path).ok();
     }
@@ -3,7 +3,9 @@
 //! Executes workflow steps with variable substitution, extraction, and assertions.
 
 use std::collections::HashMap;
-use std::path::Path;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 use reqwest::{Client, Method, header::HeaderMap, redirect::Policy};
 use serde_json::Value as JsonValue;
@@ -11,9 +13,11 @@ use tera::{Context, Tera};
 use regex::Regex;
 use once_cell::sync::Lazy;
 
+use crate::client::ssl::SslConfig;
+use crate::client::timing::{self, RequestTiming};
 use crate::errors::QuicpulseError;
 use crate::fuzz::{FuzzRunner, FuzzOptions, FuzzBodyFormat, format_fuzz_results, PayloadCategory};
-use crate::bench::{BenchmarkRunner, BenchmarkConfig, format_results as format_bench_results};
+use crate::bench::{BenchmarkRunner, BenchmarkConfig, RateProfile, format_results as format_bench_results};
 use crate::output::terminal::{self, colors, RESET};
 use crate::sessions::Session;
 use crate::config::Config;
@@ -28,7 +32,7 @@ static TEMPLATE_VAR_RE: Lazy<Regex> = Lazy::new(|| {
 static VAR_NOT_FOUND_RE: Lazy<Regex> = Lazy::new(|| {
     Regex::new(r"Variable `([^`]+)` not found").unwrap()
 });
-use crate::filter;
+use crate::openapi;
 use crate::magic::expand_magic_values;
 use crate::grpc::{GrpcEndpoint, client::GrpcClient};
 use crate::websocket::{self, types::{WsEndpoint, WsOptions, BinaryMode}};
@@ -36,10 +40,14 @@ use crate::scripting::{ScriptEngine, ScriptContext, ScriptResult, RequestData, R
 use super::workflow::{
     Workflow, WorkflowStep, StatusAssertion, GraphQLConfig, GrpcConfig, WebSocketConfig,
     ScriptConfig, FuzzConfig, BenchConfig, DownloadConfig, HarConfig, OpenApiConfig,
-    PluginConfig, UploadConfig, OutputConfig, FilterConfig, SaveConfig
+    PluginConfig, UploadConfig, OutputConfig, FilterConfig, SaveConfig, AssertOp, SchemaAssertion
 };
-use super::assertions::{AssertionResult, Assertion, check_assertions};
+use super::assertions::{AssertionResult, Assertion, check_assertions, check_body_assertion, check_body_assertions, check_schema};
 use super::dependency::{resolve_dependencies, has_dependencies};
+use super::load::{LoadPlan, LoadResult, LoadStats};
+use super::expression;
+use super::extract::{ExtractContext, extract_all};
+use super::snapshot;
 
 /// Maximum number of steps in a workflow (prevents resource exhaustion)
 const MAX_WORKFLOW_STEPS: usize = 100_000;
@@ -47,6 +55,9 @@ const MAX_WORKFLOW_STEPS: usize = 100_000;
 /// Maximum number of retries per step
 const MAX_RETRIES_PER_STEP: u32 = 10;
 
+/// Default cap on steps executing concurrently within a parallel batch
+const DEFAULT_MAX_PARALLEL: usize = 10;
+
 /// Workflow configuration options
 #[derive(Debug, Clone, Default)]
 pub struct WorkflowOptions {
@@ -66,6 +77,14 @@ pub struct WorkflowOptions {
     pub exclude: Vec<String>,
     /// Directory to save response data
     pub save_responses: Option<std::path::PathBuf>,
+    /// Directory holding snapshot files (default: `__snapshots__`)
+    pub snapshot_dir: Option<std::path::PathBuf>,
+    /// Directory of the workflow file, which relative schema paths start from
+    pub workflow_dir: Option<std::path::PathBuf>,
+    /// Overwrite snapshots that no longer match
+    pub update_snapshots: bool,
+    /// Time connection phases of HTTP steps into `StepResult::timing`
+    pub timing: bool,
 }
 
 /// Result of executing a single step
@@ -76,6 +95,10 @@ pub struct StepResult {
     pub url: String,
     pub status_code: Option<u16>,
     pub response_time: Duration,
+    /// Phases and connection details of the HTTP request, when one was timed
+    pub timing: Option<RequestTiming>,
+    /// Size of the response body; 0 when nothing was received
+    pub bytes: usize,
     pub assertions: Vec<AssertionResult>,
     pub extracted: HashMap<String, JsonValue>,
     pub error: Option<String>,
@@ -86,6 +109,28 @@ impl StepResult {
     pub fn passed(&self) -> bool {
         !self.skipped && self.error.is_none() && self.assertions.iter().all(|a| a.passed)
     }
+
+    /// A step that did not run
+    fn skipped(step: &WorkflowStep) -> Self {
+        StepResult {
+            name: step.name.clone(),
+            method: step.method.clone(),
+            url: String::new(),
+            status_code: None,
+            response_time: Duration::ZERO,
+            timing: None,
+            bytes: 0,
+            assertions: Vec::new(),
+            extracted: HashMap::new(),
+            error: None,
+            skipped: true,
+        }
+    }
+
+    /// A step that failed before sending a request
+    fn failed(step: &WorkflowStep, error: String) -> Self {
+        StepResult { error: Some(error), skipped: false, ..Self::skipped(step) }
+    }
 }
 
 /// Workflow execution engine
@@ -97,8 +142,10 @@ pub struct PipelineRunner {
     dry_run: bool,
     options: WorkflowOptions,
     default_timeout: Duration,
-    /// Multi-language script engine (Rune + JavaScript)
-    script_engine: MultiScriptEngine,
+    /// Multi-language script engine (Rune + JavaScript), shared with forked runners
+    script_engine: Arc<MultiScriptEngine>,
+    /// Schema files and OpenAPI specs by resolved path, read once and shared with forked runners
+    schemas: Arc<Mutex<HashMap<PathBuf, Arc<JsonValue>>>>,
     /// Session for persistent cookies/headers (optional)
     session: Option<Session>,
     /// Session name for saving
@@ -117,8 +164,12 @@ impl PipelineRunner {
 
     /// Create a new pipeline runner with options
     pub fn with_options(dry_run: bool, options: WorkflowOptions) -> Result<Self, QuicpulseError> {
-        let client = Client::builder()
-            .build()
+        let builder = if options.timing {
+            timing::instrument(Client::builder(), &SslConfig::new(), timing::ALPN_DEFAULT)?
+        } else {
+            Client::builder()
+        };
+        let client = builder.build()
             .map_err(|e| QuicpulseError::Request(e))?;
 
         // Bug #4 fix: Create script engine once and reuse for all scripts
@@ -128,7 +179,7 @@ impl PipelineRunner {
         let js_enabled = true; // TODO: Check bundled plugin config
         #[cfg(not(feature = "javascript"))]
         let js_enabled = false;
-        let script_engine = MultiScriptEngine::new(js_enabled)?;
+        let script_engine = Arc::new(MultiScriptEngine::new(js_enabled)?);
 
         Ok(Self {
             client,
@@ -137,6 +188,7 @@ impl PipelineRunner {
             options,
             default_timeout: Duration::from_secs(30),
             script_engine,
+            schemas: Arc::default(),
             session: None,
             session_name: None,
             session_host: None,
@@ -144,6 +196,36 @@ impl PipelineRunner {
         })
     }
 
+    /// Create a child runner for executing one step of a parallel batch
+    ///
+    /// The fork starts from a snapshot of the current variables and session
+    /// and shares the HTTP client and script engine. It never saves the session;
+    /// cookies it collects are folded back with `merge_fork`.
+    fn fork(&self) -> Self {
+        Self {
+            client: self.client.clone(),
+            variables: self.variables.clone(),
+            dry_run: self.dry_run,
+            options: self.options.clone(),
+            default_timeout: self.default_timeout,
+            script_engine: Arc::clone(&self.script_engine),
+            schemas: Arc::clone(&self.schemas),
+            session: self.session.clone(),
+            session_name: None,
+            session_host: None,
+            session_read_only: true,
+        }
+    }
+
+    /// Fold cookies collected by a forked runner back into this runner's session
+    fn merge_fork(&mut self, fork: PipelineRunner) {
+        if let (Some(session), Some(forked)) = (self.session.as_mut(), fork.session) {
+            for cookie in forked.cookies {
+                session.update_cookie(cookie);
+            }
+        }
+    }
+
     /// Load session for the workflow
     pub fn load_session(&mut self, workflow: &Workflow) -> Result<(), QuicpulseError> {
         if let Some(ref session_name) = workflow.session {
@@ -261,6 +343,29 @@ impl PipelineRunner {
             builder = builder.proxy(proxy);
         }
 
+        // Force HTTP/2 if requested
+        let alpn = if step.http2 == Some(true) {
+            builder = builder.http2_prior_knowledge();
+            &["h2"][..]
+        } else {
+            timing::ALPN_DEFAULT
+        };
+
+        if self.options.timing {
+            // TLS options, set up so the step's requests stay timed
+            let mut ssl = SslConfig::new();
+            if step.insecure == Some(true) {
+                ssl.verify = false;
+            }
+            ssl.ca_bundle = step.ca_cert.as_ref().map(std::path::PathBuf::from);
+            ssl.client_cert.cert_file = step.client_cert.as_ref().map(std::path::PathBuf::from);
+            ssl.client_cert.key_file = step.client_key.as_ref().map(std::path::PathBuf::from);
+
+            return timing::instrument(builder, &ssl, alpn)?
+                .build()
+                .map_err(|e| QuicpulseError::Request(e));
+        }
+
         // Configure TLS options
         if step.insecure == Some(true) {
             builder = builder.danger_accept_invalid_certs(true);
@@ -298,11 +403,6 @@ impl PipelineRunner {
             }
         }
 
-        // Force HTTP/2 if requested
-        if step.http2 == Some(true) {
-            builder = builder.http2_prior_knowledge();
-        }
-
         builder.build()
             .map_err(|e| QuicpulseError::Request(e))
     }
@@ -432,6 +532,15 @@ impl PipelineRunner {
                     errors.push(format!("{}: Too many retries {} (max {})", step_prefix, retries, MAX_RETRIES_PER_STEP));
                 }
             }
+
+            // Validate condition expressions, including those of `if` branches
+            let mut conditions = Vec::new();
+            collect_conditions(step, &mut conditions);
+            for condition in conditions {
+                if let Err(e) = expression::parse(condition) {
+                    errors.push(format!("{}: {}", step_prefix, e));
+                }
+            }
         }
 
         if errors.is_empty() {
@@ -454,8 +563,10 @@ impl PipelineRunner {
         undefined
     }
 
-    /// Run a complete workflow
-    pub async fn run(&mut self, workflow: &Workflow) -> Result<Vec<StepResult>, QuicpulseError> {
+    /// Load the session, dotenv file and variables, then select and order the
+    /// steps to run. Returns the steps and, with `depends_on`, the size of
+    /// each dependency level.
+    fn prepare<'w>(&mut self, workflow: &'w Workflow) -> Result<(Vec<&'w WorkflowStep>, Option<Vec<usize>>), QuicpulseError> {
         // Safety check: prevent resource exhaustion from too many steps
         if workflow.steps.len() > MAX_WORKFLOW_STEPS {
             return Err(QuicpulseError::Argument(format!(
@@ -491,18 +602,26 @@ impl PipelineRunner {
             .collect();
 
         // Apply dependency ordering if any step has depends_on
-        let ordered_steps: Vec<&WorkflowStep> = if has_dependencies(&filtered_steps) {
+        let (ordered_steps, level_sizes): (Vec<&'w WorkflowStep>, Option<Vec<usize>>) = if has_dependencies(&filtered_steps) {
             let dep_order = resolve_dependencies(&filtered_steps)?;
             if self.options.verbose && !self.dry_run {
                 eprintln!("{} ({} execution levels)",
                     terminal::info("Resolved step dependencies"),
                     terminal::number(&dep_order.levels.len().to_string()));
             }
-            dep_order.order.iter().map(|&i| filtered_steps[i]).collect()
+            let sizes = dep_order.levels.iter().map(|level| level.len()).collect();
+            (dep_order.order.iter().map(|&i| filtered_steps[i]).collect(), Some(sizes))
         } else {
-            filtered_steps
+            (filtered_steps, None)
         };
 
+        Ok((ordered_steps, level_sizes))
+    }
+
+    /// Run a complete workflow
+    pub async fn run(&mut self, workflow: &Workflow) -> Result<Vec<StepResult>, QuicpulseError> {
+        let (ordered_steps, level_sizes) = self.prepare(workflow)?;
+
         let total_steps = ordered_steps.len();
         let mut results = Vec::with_capacity(total_steps);
 
@@ -519,71 +638,248 @@ impl PipelineRunner {
                 terminal::muted("(filtered)"));
         }
 
-        for (i, step) in ordered_steps.iter().enumerate() {
+        // Dry runs only print, so keep them strictly sequential
+        let batches = if self.dry_run {
+            (0..total_steps).map(|i| i..i + 1).collect()
+        } else {
+            plan_batches(&ordered_steps, level_sizes.as_deref())
+        };
+        let max_parallel = workflow.max_parallel.unwrap_or(DEFAULT_MAX_PARALLEL).max(1);
+
+        for batch in batches {
+            let batch_steps = &ordered_steps[batch.clone()];
+            let concurrent = batch_steps.len() > 1;
+
             // Progress output
             if self.options.verbose && !self.dry_run {
-                eprintln!("\n{}{}/{}{} {} {}",
-                    terminal::muted("["),
-                    terminal::number(&(i + 1).to_string()),
-                    terminal::number(&total_steps.to_string()),
-                    terminal::muted("]"),
-                    terminal::info("Running:"),
-                    terminal::label(&step.name));
-            }
-
-            let step_results = self.run_step_with_control_flow(step, workflow).await?;
-
-            // Handle multiple results from loops
-            for result in step_results {
-                // Extract variables from successful steps
-                if result.error.is_none() && !result.skipped {
-                    for (key, value) in &result.extracted {
-                        self.variables.insert(key.clone(), value.clone());
+                if concurrent {
+                    eprintln!("\n{} {} {}",
+                        terminal::info("Running"),
+                        terminal::number(&batch_steps.len().to_string()),
+                        terminal::info(&format!("steps in parallel (max {}):", max_parallel)));
+                }
+                for (offset, step) in batch_steps.iter().enumerate() {
+                    eprintln!("{}{}{}/{}{} {} {}",
+                        if concurrent { "" } else { "\n" },
+                        terminal::muted("["),
+                        terminal::number(&(batch.start + offset + 1).to_string()),
+                        terminal::number(&total_steps.to_string()),
+                        terminal::muted("]"),
+                        terminal::info("Running:"),
+                        terminal::label(&step.name));
+                }
+            }
+
+            let batch_results = if concurrent {
+                self.run_parallel_batch(batch_steps, workflow, max_parallel).await?
+            } else {
+                vec![self.run_step_with_control_flow(batch_steps[0], workflow).await?]
+            };
+
+            // Results are processed in step order, so later steps in a batch win
+            // when several of them extract the same variable
+            let mut failed = false;
+            'batch: for step_results in batch_results {
+                // Handle multiple results from loops
+                for result in step_results {
+                    let passed = self.record_step_result(result, concurrent, &mut results)?;
+
+                    // Stop on failure unless continue_on_failure is set. A parallel
+                    // batch has already run to completion, so report all of it.
+                    if !passed && !self.options.continue_on_failure {
+                        failed = true;
+                        if !concurrent {
+                            break 'batch;
+                        }
                     }
                 }
+            }
 
-                let passed = result.passed();
+            if failed {
+                // Save session before returning
+                self.save_session()?;
+                return Ok(results);
+            }
+        }
 
-                // Progress feedback
-                if self.options.verbose && !self.dry_run {
-                    if result.skipped {
-                        eprintln!("  {} {}", terminal::muted("->"), terminal::muted("Skipped"));
-                    } else if passed {
-                        eprintln!("  {} {} {}",
-                            terminal::muted("->"),
-                            terminal::success("Passed"),
-                            terminal::muted(&format!("({:?})", result.response_time)));
-                    } else {
-                        eprintln!("  {} {} {}",
-                            terminal::muted("->"),
-                            terminal::error("Failed:"),
-                            terminal::colorize(
-                                result.error.as_ref().map(|e| e.as_str())
-                                    .unwrap_or("assertion failed"),
-                                colors::RED));
+        // Save session if configured
+        self.save_session()?;
+
+        Ok(results)
+    }
+
+    /// Run the workflow as a load test
+    ///
+    /// Each virtual user runs on a fork of this runner and loops over the steps
+    /// in order, `parallel` or not, carrying extracted variables from step to
+    /// step. Every iteration starts again from the workflow's variables, with
+    /// `_vu` and `_vu_iteration` set. Users start `plan.ramp_up` apart and run
+    /// concurrently in this task.
+    pub async fn run_load(&mut self, workflow: &Workflow, plan: &LoadPlan) -> Result<LoadResult, QuicpulseError> {
+        let (steps, _) = self.prepare(workflow)?;
+        let step_names: Vec<&str> = steps.iter().map(|step| step.name.as_str()).collect();
+
+        let start = Instant::now();
+        let deadline = plan.duration.map(|duration| start + duration);
+
+        let mut users: Vec<PipelineRunner> = (0..plan.users)
+            .map(|_| {
+                let mut user = self.fork();
+                user.options.verbose = false;
+                user
+            })
+            .collect();
+
+        let user_stats = futures::future::try_join_all(users.iter_mut().zip(0u32..).map(|(user, index)| {
+            let mut stats = LoadStats::new(step_names.iter().copied());
+            let steps = &steps;
+            async move {
+                tokio::time::sleep(plan.start_delay(index)).await;
+                user.run_virtual_user(index, steps, workflow, plan, deadline, &mut stats).await?;
+                Ok::<_, QuicpulseError>(stats)
+            }
+        }))
+        .await?;
+
+        let mut total = LoadStats::new(step_names.iter().copied());
+        for stats in user_stats {
+            total.merge(stats);
+        }
+        Ok(total.finish(&workflow.name, plan.users, start.elapsed()))
+    }
+
+    /// Loop over the steps as virtual user `index` until the plan is done
+    async fn run_virtual_user(
+        &mut self,
+        index: u32,
+        steps: &[&WorkflowStep],
+        workflow: &Workflow,
+        plan: &LoadPlan,
+        deadline: Option<Instant>,
+        stats: &mut LoadStats,
+    ) -> Result<(), QuicpulseError> {
+        let initial_variables = self.variables.clone();
+        let mut iteration: u64 = 0;
+
+        while plan.iterations.is_none_or(|n| iteration < n)
+            && deadline.is_none_or(|deadline| Instant::now() < deadline)
+        {
+            self.variables = initial_variables.clone();
+            self.variables.insert("_vu".to_string(), serde_json::json!(index));
+            self.variables.insert("_vu_iteration".to_string(), serde_json::json!(iteration));
+
+            let started = Instant::now();
+            let mut passed = true;
+            for step in steps {
+                let mut step_passed = true;
+                for result in self.run_step_with_control_flow(step, workflow).await? {
+                    stats.record_step(&result);
+                    step_passed &= result.passed() || result.skipped;
+                    if result.error.is_none() && !result.skipped {
+                        self.variables.extend(result.extracted);
                     }
                 }
 
-                // Save response data if configured
-                if !self.dry_run {
-                    self.save_response_data(&result)?;
+                if !step_passed {
+                    passed = false;
+                    if !self.options.continue_on_failure {
+                        break;
+                    }
+                }
+                if !plan.think_time.is_zero() {
+                    tokio::time::sleep(plan.think_time).await;
                 }
+            }
 
-                results.push(result);
+            stats.record_iteration(started.elapsed(), passed);
+            iteration += 1;
+        }
 
-                // Stop on failure unless continue_on_failure is set
-                if !passed && !self.options.continue_on_failure {
-                    // Save session before returning
-                    self.save_session()?;
-                    return Ok(results);
-                }
+        Ok(())
+    }
+
+    /// Run a batch of independent steps concurrently
+    ///
+    /// Each step executes on a forked runner, so steps in the same batch see the
+    /// variables as they were when the batch started. At most `max_parallel`
+    /// steps are in flight at once, and results are returned in step order
+    /// regardless of completion order.
+    async fn run_parallel_batch(
+        &mut self,
+        steps: &[&WorkflowStep],
+        workflow: &Workflow,
+        max_parallel: usize,
+    ) -> Result<Vec<Vec<StepResult>>, QuicpulseError> {
+        use futures::stream::{self, StreamExt, TryStreamExt};
+
+        let mut forks: Vec<PipelineRunner> = steps.iter().map(|_| self.fork()).collect();
+
+        let batch_results: Vec<Vec<StepResult>> = stream::iter(forks.iter_mut().zip(steps.iter()))
+            .map(|(fork, step)| fork.run_step_with_control_flow(step, workflow))
+            .buffered(max_parallel)
+            .try_collect()
+            .await?;
+
+        for fork in forks {
+            self.merge_fork(fork);
+        }
+
+        Ok(batch_results)
+    }
+
+    /// Apply a finished step result: merge extracted variables, report progress,
+    /// persist response data and append it to `results`. Returns whether it
+    /// passed or was skipped, i.e. whether the workflow may go on.
+    fn record_step_result(
+        &mut self,
+        result: StepResult,
+        show_name: bool,
+        results: &mut Vec<StepResult>,
+    ) -> Result<bool, QuicpulseError> {
+        // Extract variables from successful steps
+        if result.error.is_none() && !result.skipped {
+            for (key, value) in &result.extracted {
+                self.variables.insert(key.clone(), value.clone());
             }
         }
 
-        // Save session if configured
-        self.save_session()?;
+        let passed = result.passed();
 
-        Ok(results)
+        // Progress feedback
+        if self.options.verbose && !self.dry_run {
+            let name = if show_name {
+                format!(" {}", terminal::label(&result.name))
+            } else {
+                String::new()
+            };
+            if result.skipped {
+                eprintln!("  {}{} {}", terminal::muted("->"), name, terminal::muted("Skipped"));
+            } else if passed {
+                eprintln!("  {}{} {} {}",
+                    terminal::muted("->"),
+                    name,
+                    terminal::success("Passed"),
+                    terminal::muted(&format!("({:?})", result.response_time)));
+            } else {
+                eprintln!("  {}{} {} {}",
+                    terminal::muted("->"),
+                    name,
+                    terminal::error("Failed:"),
+                    terminal::colorize(
+                        result.error.as_ref().map(|e| e.as_str())
+                            .unwrap_or("assertion failed"),
+                        colors::RED));
+            }
+        }
+
+        // Save response data if configured
+        if !self.dry_run {
+            self.save_response_data(&result)?;
+        }
+
+        let skipped = result.skipped;
+        results.push(result);
+        Ok(passed || skipped)
     }
 
     /// Check if a step should be run based on filtering options
@@ -621,10 +917,15 @@ impl PipelineRunner {
         true
     }
 
-    /// Run a step with control flow (repeat, foreach, while)
+    /// Run a step with control flow (if/else, repeat, foreach, while, until)
     async fn run_step_with_control_flow(&mut self, step: &WorkflowStep, workflow: &Workflow) -> Result<Vec<StepResult>, QuicpulseError> {
         let mut results = Vec::new();
 
+        // Handle if/else branches
+        if let Some(ref condition) = step.if_condition {
+            return self.run_branch(step, condition, workflow).await;
+        }
+
         // Handle repeat
         if let Some(count) = step.repeat {
             let count = count.min(1000); // Safety limit
@@ -677,11 +978,18 @@ impl PipelineRunner {
         if let Some(ref condition) = step.while_condition {
             let max_iters = step.max_iterations.unwrap_or(100).min(1000);
             for i in 0..max_iters {
-                if !self.evaluate_condition(condition) {
-                    break;
+                match self.evaluate_condition(condition) {
+                    Ok(true) => {}
+                    Ok(false) => break,
+                    Err(e) => {
+                        results.push(StepResult::failed(step, e.to_string()));
+                        break;
+                    }
                 }
                 self.variables.insert("_iteration".to_string(), serde_json::json!(i));
                 let result = self.run_step_with_retry(step, workflow).await?;
+                // Later iterations see what this one extracted
+                self.merge_extracted(&result);
                 let passed = result.passed();
                 results.push(result);
                 if !passed && step.fail_fast.unwrap_or(true) {
@@ -691,12 +999,84 @@ impl PipelineRunner {
             return Ok(results);
         }
 
+        // Handle until loop: the condition is checked after each run
+        if let Some(ref condition) = step.until {
+            // Dry runs extract nothing, so show the step once
+            let max_iters = if self.dry_run { 1 } else { step.max_iterations.unwrap_or(100).min(1000) };
+            for i in 0..max_iters {
+                self.variables.insert("_iteration".to_string(), serde_json::json!(i));
+                let mut result = self.run_step_with_retry(step, workflow).await?;
+                self.merge_extracted(&result);
+                let done = match self.evaluate_condition(condition) {
+                    Ok(done) => done,
+                    Err(e) => {
+                        result.error.get_or_insert(e.to_string());
+                        true
+                    }
+                };
+                if !done && i + 1 == max_iters && !self.dry_run {
+                    result.assertions.push(AssertionResult::fail(
+                        "until",
+                        &format!("'{}' still false after {} iterations", condition, max_iters),
+                    ));
+                }
+                let passed = result.passed();
+                results.push(result);
+                if done || (!passed && step.fail_fast.unwrap_or(true)) {
+                    break;
+                }
+            }
+            return Ok(results);
+        }
+
         // No control flow - run once
         let result = self.run_step_with_retry(step, workflow).await?;
         results.push(result);
         Ok(results)
     }
 
+    /// Run the `then` or `else` steps of an `if` step in order
+    ///
+    /// Each sub-step sees the variables extracted by the ones before it. A
+    /// failing sub-step stops the branch unless `continue_on_failure` is set.
+    async fn run_branch(&mut self, step: &WorkflowStep, condition: &str, workflow: &Workflow) -> Result<Vec<StepResult>, QuicpulseError> {
+        match self.should_skip(step) {
+            Ok(true) => return Ok(vec![StepResult::skipped(step)]),
+            Ok(false) => {}
+            Err(e) => return Ok(vec![StepResult::failed(step, e.to_string())]),
+        }
+        let (branch, steps) = match self.evaluate_condition(condition) {
+            Ok(true) => ("then", &step.then_steps),
+            Ok(false) => ("else", &step.else_steps),
+            Err(e) => return Ok(vec![StepResult::failed(step, e.to_string())]),
+        };
+        if self.options.verbose && !self.dry_run {
+            eprintln!("  {} {} {}", terminal::muted("->"), terminal::info("Branch:"), terminal::label(branch));
+        }
+
+        let mut results = Vec::new();
+        for sub_step in steps {
+            let sub_results = Box::pin(self.run_step_with_control_flow(sub_step, workflow)).await?;
+            let mut failed = false;
+            for result in sub_results {
+                self.merge_extracted(&result);
+                failed |= !result.passed() && !result.skipped;
+                results.push(result);
+            }
+            if failed && !self.options.continue_on_failure {
+                break;
+            }
+        }
+        Ok(results)
+    }
+
+    /// Make the variables extracted by a successful step available right away
+    fn merge_extracted(&mut self, result: &StepResult) {
+        if result.error.is_none() && !result.skipped {
+            self.variables.extend(result.extracted.iter().map(|(k, v)| (k.clone(), v.clone())));
+        }
+    }
+
     /// Run a step with retry logic
     async fn run_step_with_retry(&mut self, step: &WorkflowStep, workflow: &Workflow) -> Result<StepResult, QuicpulseError> {
         // Cap retries to prevent runaway loops
@@ -734,21 +1114,11 @@ impl PipelineRunner {
 
     /// Run a single workflow step
     async fn run_step(&mut self, step: &WorkflowStep, workflow: &Workflow) -> Result<StepResult, QuicpulseError> {
-        // Check skip condition
-        if let Some(ref condition) = step.skip_if {
-            if self.evaluate_condition(condition) {
-                return Ok(StepResult {
-                    name: step.name.clone(),
-                    method: step.method.clone(),
-                    url: String::new(),
-                    status_code: None,
-                    response_time: Duration::ZERO,
-                    assertions: Vec::new(),
-                    extracted: HashMap::new(),
-                    error: None,
-                    skipped: true,
-                });
-            }
+        // Check skip_if / run_if conditions
+        match self.should_skip(step) {
+            Ok(true) => return Ok(StepResult::skipped(step)),
+            Ok(false) => {}
+            Err(e) => return Ok(StepResult::failed(step, e.to_string())),
         }
 
         // Apply delay if specified
@@ -816,6 +1186,8 @@ impl PipelineRunner {
                 url: full_url,
                 status_code: None,
                 response_time: Duration::ZERO,
+                timing: None,
+                bytes: 0,
                 assertions: Vec::new(),
                 extracted: HashMap::new(),
                 error: None,
@@ -852,6 +1224,8 @@ impl PipelineRunner {
                         url: full_url,
                         status_code: None,
                         response_time: Duration::ZERO,
+                        timing: None,
+                        bytes: 0,
                         assertions: Vec::new(),
                         extracted: HashMap::new(),
                         error: Some(format!("Pre-script error: {}", e)),
@@ -1179,23 +1553,26 @@ impl PipelineRunner {
 
         // Execute request
         let start = Instant::now();
-        let response = request.send().await;
+        let (response, opened) = timing::track(request.send()).await;
         let response_time = start.elapsed();
 
         match response {
             Ok(resp) => {
                 let status_code = resp.status().as_u16();
                 let response_headers = resp.headers().clone();
+                let mut timing = RequestTiming::new(opened, response_time, &resp);
 
                 // Update session with response cookies
                 self.update_session_from_response(&response_headers, &full_url);
 
                 // Handle download if configured
+                let transfer_start = Instant::now();
                 let body = if let Some(ref download_config) = step.download {
                     self.handle_download_response(resp, download_config, &step.name).await?
                 } else {
                     resp.text().await.unwrap_or_default()
                 };
+                timing.transfer = transfer_start.elapsed();
 
                 // Execute post-script if configured
                 if let Some(ref post_script) = step.post_script {
@@ -1224,6 +1601,8 @@ impl PipelineRunner {
                             url: full_url,
                             status_code: Some(status_code),
                             response_time,
+                            timing: self.options.timing.then_some(timing),
+                            bytes: body.len(),
                             assertions: Vec::new(),
                             extracted: HashMap::new(),
                             error: Some(format!("Post-script error: {}", e)),
@@ -1270,7 +1649,8 @@ impl PipelineRunner {
                 }
 
                 // Extract variables
-                let extracted = self.extract_variables(step, &body)?;
+                let (extracted, extract_failures) = self.extract_variables(step, status_code, response_time, &response_headers, &body);
+                assertions.extend(extract_failures);
 
                 // Save response if configured
                 if let Some(ref save_config) = step.save {
@@ -1298,6 +1678,8 @@ impl PipelineRunner {
                     url: full_url,
                     status_code: Some(status_code),
                     response_time,
+                    timing: self.options.timing.then_some(timing),
+                    bytes: body.len(),
                     assertions,
                     extracted,
                     error: None,
@@ -1322,6 +1704,8 @@ impl PipelineRunner {
                     url: full_url,
                     status_code: None,
                     response_time,
+                    timing: None,
+                    bytes: 0,
                     assertions: Vec::new(),
                     extracted: HashMap::new(),
                     error: Some(error_msg),
@@ -1418,26 +1802,22 @@ impl PipelineRunner {
         self.render_template_for_step(&json_str, step_name, "body")
     }
 
-    /// Evaluate a skip condition
-    fn evaluate_condition(&self, condition: &str) -> bool {
-        // Simple variable check: {{var}} or !{{var}}
-        let negated = condition.starts_with('!');
-        let var_name = condition.trim_start_matches('!')
-            .trim_matches(|c| c == '{' || c == '}')
-            .trim();
-
-        let value = self.variables.get(var_name);
-        let is_truthy = match value {
-            Some(JsonValue::Bool(b)) => *b,
-            Some(JsonValue::Null) => false,
-            Some(JsonValue::String(s)) => !s.is_empty(),
-            Some(JsonValue::Number(n)) => n.as_f64().map(|f| f != 0.0).unwrap_or(false),
-            Some(JsonValue::Array(a)) => !a.is_empty(),
-            Some(JsonValue::Object(o)) => !o.is_empty(),
-            None => false,
-        };
+    /// Evaluate a condition expression against the current variables
+    fn evaluate_condition(&self, condition: &str) -> Result<bool, QuicpulseError> {
+        expression::evaluate_condition(condition, &self.variables)
+    }
 
-        if negated { !is_truthy } else { is_truthy }
+    /// Whether `skip_if` is true or `run_if` is false
+    fn should_skip(&self, step: &WorkflowStep) -> Result<bool, QuicpulseError> {
+        if let Some(ref condition) = step.skip_if {
+            if self.evaluate_condition(condition)? {
+                return Ok(true);
+            }
+        }
+        if let Some(ref condition) = step.run_if {
+            return Ok(!self.evaluate_condition(condition)?);
+        }
+        Ok(false)
     }
 
     /// Build assertions from step configuration
@@ -1475,47 +1855,106 @@ impl PipelineRunner {
             assertions.extend(check_assertions(&assertion_list, status_code, response_time, headers, body));
         }
 
-        // Body assertions (key:value pairs)
-        for (key, expected) in &step.assert.body {
-            let pattern = format!("{}:{}", key, expected);
-            let assertion_list = vec![Assertion::Body(pattern)];
-            assertions.extend(check_assertions(&assertion_list, status_code, response_time, headers, body));
+        // Typed body assertions on JQ paths
+        assertions.extend(check_body_assertions(&step.assert.body, body));
+
+        // JSON Schema assertion
+        if let Some(ref schema) = step.assert.schema {
+            match self.resolve_schema(schema, &step.name, status_code) {
+                Ok((schema, source)) => assertions.extend(check_schema(&schema, &source, body)),
+                Err(e) => assertions.push(AssertionResult::fail("schema", &e.to_string())),
+            }
+        }
+
+        // Snapshot assertion
+        if let Some(config) = step.assert.snapshot() {
+            let dir = self.options.snapshot_dir.clone().unwrap_or_else(|| std::path::PathBuf::from("__snapshots__"));
+            let path = snapshot::snapshot_path(&dir, &step.name, &config);
+            let checked = snapshot::normalize(status_code, headers, body, &config)
+                .and_then(|actual| snapshot::check_snapshot(&path, &actual, self.options.update_snapshots));
+            match checked {
+                Ok((result, changes)) => {
+                    if !changes.is_empty() {
+                        eprintln!("  Snapshot mismatch in {}:\n{}", path.display(), snapshot::format_diff(&changes, true));
+                    }
+                    assertions.push(result);
+                }
+                Err(e) => assertions.push(AssertionResult::fail("snapshot", &e.to_string())),
+            }
         }
 
         assertions
     }
 
-    /// Extract variables from response
-    fn extract_variables(&self, step: &WorkflowStep, body: &str) -> Result<HashMap<String, JsonValue>, QuicpulseError> {
-        let mut extracted = HashMap::new();
-
-        if step.extract.is_empty() {
-            return Ok(extracted);
+    /// Load the schema of a schema assertion, with a label for reports
+    fn resolve_schema(
+        &self,
+        schema: &SchemaAssertion,
+        step_name: &str,
+        status_code: u16,
+    ) -> Result<(Arc<JsonValue>, String), QuicpulseError> {
+        match schema {
+            SchemaAssertion::File(path) => {
+                let path = self.render_template_for_step(path, step_name, "schema file")?;
+                let schema = self.load_schema_document(&path, |resolved| {
+                    let content = std::fs::read_to_string(resolved)
+                        .map_err(|e| QuicpulseError::Argument(format!("Cannot read schema '{}': {}", resolved.display(), e)))?;
+                    if path.ends_with(".yaml") || path.ends_with(".yml") {
+                        serde_yaml::from_str(&content)
+                            .map_err(|e| QuicpulseError::Parse(format!("Invalid schema '{}': {}", path, e)))
+                    } else {
+                        serde_json::from_str(&content)
+                            .map_err(|e| QuicpulseError::Parse(format!("Invalid schema '{}': {}", path, e)))
+                    }
+                })?;
+                Ok((schema, path))
+            }
+            SchemaAssertion::OpenApi { openapi: spec_path, operation_id, status } => {
+                let path = self.render_template_for_step(spec_path, step_name, "OpenAPI spec")?;
+                let spec = self.load_schema_document(&path, openapi::read_spec_value)?;
+                let status = match status {
+                    Some(StatusAssertion::Exact(code)) => code.to_string(),
+                    Some(StatusAssertion::Range(range)) => range.clone(),
+                    None => status_code.to_string(),
+                };
+                let schema = openapi::response_schema(&spec, operation_id, &status)?;
+                Ok((Arc::new(schema), format!("{}#{} {}", path, operation_id, status)))
+            }
+            SchemaAssertion::Inline(schema) => Ok((Arc::new(schema.clone()), "inline".to_string())),
         }
+    }
 
-        // Parse body as JSON
-        let json: JsonValue = serde_json::from_str(body)
-            .unwrap_or(JsonValue::Null);
-
-        for (var_name, jq_expr) in &step.extract {
-            // Convert extraction path to JQ expression
-            let expr = if jq_expr.starts_with("response.body.") {
-                format!(".{}", jq_expr.strip_prefix("response.body.").unwrap())
-            } else if jq_expr.starts_with('.') {
-                jq_expr.clone()
-            } else {
-                format!(".{}", jq_expr)
-            };
-
-            // Apply JQ filter
-            if let Ok(results) = filter::apply_filter(&json, &expr) {
-                if !results.is_empty() {
-                    extracted.insert(var_name.clone(), results[0].clone());
-                }
-            }
+    /// Schema file or OpenAPI spec at `path`, relative to the workflow file,
+    /// parsed by `parse` the first time it is used
+    fn load_schema_document(
+        &self,
+        path: &str,
+        parse: impl FnOnce(&Path) -> Result<JsonValue, QuicpulseError>,
+    ) -> Result<Arc<JsonValue>, QuicpulseError> {
+        let resolved = match &self.options.workflow_dir {
+            Some(dir) => dir.join(path),
+            None => PathBuf::from(path),
+        };
+        let mut schemas = self.schemas.lock().unwrap_or_else(|p| p.into_inner());
+        if let Some(document) = schemas.get(&resolved) {
+            return Ok(Arc::clone(document));
         }
+        let document = Arc::new(parse(&resolved)?);
+        schemas.insert(resolved, Arc::clone(&document));
+        Ok(document)
+    }
 
-        Ok(extracted)
+    /// Extract variables from response; required extractions that match nothing fail the step
+    fn extract_variables(
+        &self,
+        step: &WorkflowStep,
+        status_code: u16,
+        response_time: Duration,
+        headers: &HeaderMap,
+        body: &str,
+    ) -> (HashMap<String, JsonValue>, Vec<AssertionResult>) {
+        let ctx = ExtractContext { status: status_code, duration: response_time, headers, body };
+        extract_all(&step.extract, &ctx)
     }
 
     /// Run a gRPC step
@@ -1615,6 +2054,8 @@ impl PipelineRunner {
                         url: endpoint.uri(),
                         status_code: Some(500),
                         response_time,
+                        timing: None,
+                        bytes: 0,
                         assertions: Vec::new(),
                         extracted: HashMap::new(),
                         error: Some(format!("gRPC error: {}", response.message())),
@@ -1636,6 +2077,8 @@ impl PipelineRunner {
                                 url: endpoint.uri(),
                                 status_code: None,
                                 response_time,
+                                timing: None,
+                                bytes: 0,
                                 assertions: Vec::new(),
                                 extracted: HashMap::new(),
                                 error: Some(format!("Stream error: {}", e)),
@@ -1647,8 +2090,9 @@ impl PipelineRunner {
 
                 let response_time = start.elapsed();
                 let body = serde_json::to_string_pretty(&responses).unwrap_or_default();
-                let assertions = self.build_step_assertions(step, 200, response_time, &HeaderMap::new(), &body);
-                let extracted = self.extract_variables(step, &body)?;
+                let mut assertions = self.build_step_assertions(step, 200, response_time, &HeaderMap::new(), &body);
+                let (extracted, extract_failures) = self.extract_variables(step, 200, response_time, &HeaderMap::new(), &body);
+                assertions.extend(extract_failures);
 
                 Ok(StepResult {
                     name: step.name.clone(),
@@ -1656,6 +2100,8 @@ impl PipelineRunner {
                     url: endpoint.uri(),
                     status_code: Some(200),
                     response_time,
+                    timing: None,
+                    bytes: body.len(),
                     assertions,
                     extracted,
                     error: None,
@@ -1687,8 +2133,9 @@ impl PipelineRunner {
                     .unwrap_or_default();
 
                 let status_code = if response.is_ok() { 200 } else { 500 };
-                let assertions = self.build_step_assertions(step, status_code, response_time, &HeaderMap::new(), &body);
-                let extracted = self.extract_variables(step, &body)?;
+                let mut assertions = self.build_step_assertions(step, status_code, response_time, &HeaderMap::new(), &body);
+                let (extracted, extract_failures) = self.extract_variables(step, status_code, response_time, &HeaderMap::new(), &body);
+                assertions.extend(extract_failures);
 
                 Ok(StepResult {
                     name: step.name.clone(),
@@ -1696,6 +2143,8 @@ impl PipelineRunner {
                     url: endpoint.uri(),
                     status_code: Some(status_code),
                     response_time,
+                    timing: None,
+                    bytes: body.len(),
                     assertions,
                     extracted,
                     error: if response.is_ok() { None } else { Some(response.message().to_string()) },
@@ -1729,6 +2178,8 @@ impl PipelineRunner {
                         url: endpoint.uri(),
                         status_code: Some(500),
                         response_time,
+                        timing: None,
+                        bytes: 0,
                         assertions: Vec::new(),
                         extracted: HashMap::new(),
                         error: Some(format!("gRPC error: {}", response.message())),
@@ -1750,6 +2201,8 @@ impl PipelineRunner {
                                 url: endpoint.uri(),
                                 status_code: None,
                                 response_time,
+                                timing: None,
+                                bytes: 0,
                                 assertions: Vec::new(),
                                 extracted: HashMap::new(),
                                 error: Some(format!("Stream error: {}", e)),
@@ -1761,8 +2214,9 @@ impl PipelineRunner {
 
                 let response_time = start.elapsed();
                 let body = serde_json::to_string_pretty(&responses).unwrap_or_default();
-                let assertions = self.build_step_assertions(step, 200, response_time, &HeaderMap::new(), &body);
-                let extracted = self.extract_variables(step, &body)?;
+                let mut assertions = self.build_step_assertions(step, 200, response_time, &HeaderMap::new(), &body);
+                let (extracted, extract_failures) = self.extract_variables(step, 200, response_time, &HeaderMap::new(), &body);
+                assertions.extend(extract_failures);
 
                 Ok(StepResult {
                     name: step.name.clone(),
@@ -1770,6 +2224,8 @@ impl PipelineRunner {
                     url: endpoint.uri(),
                     status_code: Some(200),
                     response_time,
+                    timing: None,
+                    bytes: body.len(),
                     assertions,
                     extracted,
                     error: None,
@@ -1792,8 +2248,9 @@ impl PipelineRunner {
                             .unwrap_or_default();
 
                         let status_code = if response.is_ok() { 200 } else { 500 };
-                        let assertions = self.build_step_assertions(step, status_code, response_time, &HeaderMap::new(), &body);
-                        let extracted = self.extract_variables(step, &body)?;
+                        let mut assertions = self.build_step_assertions(step, status_code, response_time, &HeaderMap::new(), &body);
+                        let (extracted, extract_failures) = self.extract_variables(step, status_code, response_time, &HeaderMap::new(), &body);
+                        assertions.extend(extract_failures);
 
                         Ok(StepResult {
                             name: step.name.clone(),
@@ -1801,6 +2258,8 @@ impl PipelineRunner {
                             url: endpoint.uri(),
                             status_code: Some(status_code),
                             response_time,
+                            timing: None,
+                            bytes: body.len(),
                             assertions,
                             extracted,
                             error: None,
@@ -1815,6 +2274,8 @@ impl PipelineRunner {
                             url: endpoint.uri(),
                             status_code: None,
                             response_time,
+                            timing: None,
+                            bytes: 0,
                             assertions: Vec::new(),
                             extracted: HashMap::new(),
                             error: Some(format!("gRPC call failed: {}", e)),
@@ -2001,17 +2462,23 @@ impl PipelineRunner {
         };
 
         // Build assertions
-        let assertions = if !step.assert.body.is_empty() {
+        let mut assertions = if !step.assert.body.is_empty() {
+            let json = serde_json::from_str::<JsonValue>(&body).ok();
             let mut results = Vec::new();
-            for (expr, expected) in &step.assert.body {
+            for check in &step.assert.body {
+                if check.op != AssertOp::Eq {
+                    results.push(check_body_assertion(json.as_ref(), check));
+                    continue;
+                }
                 // Simple contains check for WebSocket responses
-                let expected_str = match expected {
-                    JsonValue::String(s) => s.clone(),
-                    _ => expected.to_string(),
+                let expected_str = match &check.value {
+                    Some(JsonValue::String(s)) => s.clone(),
+                    Some(expected) => expected.to_string(),
+                    None => String::new(),
                 };
                 let passed = body.contains(&expected_str);
                 results.push(AssertionResult {
-                    assertion: format!("body.{} contains", expr),
+                    assertion: format!("body.{} contains", check.path),
                     passed,
                     message: if passed {
                         format!("Body contains expected value")
@@ -2027,36 +2494,8 @@ impl PipelineRunner {
         };
 
         // Extract variables from response
-        let mut extracted = HashMap::new();
-        if !step.extract.is_empty() {
-            // Try to parse response as JSON for extraction
-            if let Ok(json) = serde_json::from_str::<JsonValue>(&body) {
-                for (var_name, jq_expr) in &step.extract {
-                    // Convert extraction path to JQ expression (same as extract_variables)
-                    let expr = if jq_expr.starts_with("response.body.") {
-                        format!(".{}", jq_expr.strip_prefix("response.body.").unwrap())
-                    } else if jq_expr.starts_with('.') {
-                        jq_expr.clone()
-                    } else {
-                        format!(".{}", jq_expr)
-                    };
-
-                    // Apply JQ filter
-                    if let Ok(results) = filter::apply_filter(&json, &expr) {
-                        if !results.is_empty() {
-                            extracted.insert(var_name.clone(), results[0].clone());
-                        }
-                    }
-                }
-            } else {
-                // For non-JSON responses, allow extracting the raw body
-                for (var_name, json_path) in &step.extract {
-                    if json_path == "response.body" || json_path == "body" {
-                        extracted.insert(var_name.clone(), JsonValue::String(body.clone()));
-                    }
-                }
-            }
-        }
+        let (extracted, extract_failures) = self.extract_variables(step, 101, response_time, &HeaderMap::new(), &body);
+        assertions.extend(extract_failures);
 
         Ok(StepResult {
             name: step.name.clone(),
@@ -2064,6 +2503,8 @@ impl PipelineRunner {
             url: endpoint.url(),
             status_code: Some(101), // WebSocket upgrade status
             response_time,
+            timing: None,
+            bytes: body.len(),
             assertions,
             extracted,
             error: None,
@@ -2131,6 +2572,8 @@ impl PipelineRunner {
                 url: url.to_string(),
                 status_code: None,
                 response_time: start.elapsed(),
+                timing: None,
+                bytes: 0,
                 assertions: Vec::new(),
                 extracted: HashMap::new(),
                 error: Some("No fields to fuzz. Provide fields in fuzz config or body.".to_string()),
@@ -2185,6 +2628,8 @@ impl PipelineRunner {
             url: url.to_string(),
             status_code: if summary.server_errors > 0 { Some(500) } else { Some(200) },
             response_time,
+            timing: None,
+            bytes: 0,
             assertions,
             extracted: HashMap::new(),
             error: None,
@@ -2221,6 +2666,11 @@ impl PipelineRunner {
             concurrency: bench_config.concurrency.unwrap_or(10),
             url: url.to_string(),
             method: step.method.clone(),
+            rate: bench_config.rate_limit
+                .filter(|rate| *rate > 0)
+                .map(|rate| RateProfile::constant(rate as f64)),
+            duration: None,
+            protocol: crate::bench::protocol::Protocol::Http,
         };
 
         // Create benchmark runner with our custom client
@@ -2229,6 +2679,10 @@ impl PipelineRunner {
             client,
             body: step.body.as_ref().map(|b| serde_json::to_vec(b).unwrap_or_default()),
             headers: headers.clone(),
+            reporter: None,
+            variation: None,
+            target: None,
+            track_connections: false,
         };
 
         // Run warmup if configured
@@ -2271,10 +2725,15 @@ impl PipelineRunner {
 
         Ok(StepResult {
             name: step.name.clone(),
-            method: format!("BENCH ({} req @ {} conc)", config.total_requests, config.concurrency),
+            method: match config.rate {
+                Some(ref rate) => format!("BENCH ({} req @ {})", config.total_requests, rate.describe()),
+                None => format!("BENCH ({} req @ {} conc)", config.total_requests, config.concurrency),
+            },
             url: url.to_string(),
             status_code: Some(200),
             response_time,
+            timing: None,
+            bytes: 0,
             assertions,
             extracted: HashMap::from([
                 ("bench_rps".to_string(), JsonValue::Number(serde_json::Number::from_f64(result.stats.requests_per_second).unwrap_or(0.into()))),
@@ -2490,6 +2949,8 @@ impl PipelineRunner {
                 url: har_path,
                 status_code: None,
                 response_time: start.elapsed(),
+                timing: None,
+                bytes: 0,
                 assertions: Vec::new(),
                 extracted: HashMap::new(),
                 error: Some(format!("HAR entry index {} out of bounds (max {})", entry_index, har.log.entries.len() - 1)),
@@ -2531,7 +2992,7 @@ impl PipelineRunner {
         match response {
             Ok(resp) => {
                 let status_code = resp.status().as_u16();
-                let _resp_body = resp.text().await.unwrap_or_default();
+                let body = resp.text().await.unwrap_or_default();
 
                 Ok(StepResult {
                     name: step.name.clone(),
@@ -2539,6 +3000,8 @@ impl PipelineRunner {
                     url,
                     status_code: Some(status_code),
                     response_time,
+                    timing: None,
+                    bytes: body.len(),
                     assertions: Vec::new(),
                     extracted: HashMap::new(),
                     error: None,
@@ -2551,6 +3014,8 @@ impl PipelineRunner {
                 url,
                 status_code: None,
                 response_time,
+                timing: None,
+                bytes: 0,
                 assertions: Vec::new(),
                 extracted: HashMap::new(),
                 error: Some(format!("HAR request failed: {}", e)),
@@ -2578,6 +3043,8 @@ impl PipelineRunner {
             url: openapi_config.spec.clone(),
             status_code: None,
             response_time: start.elapsed(),
+            timing: None,
+            bytes: 0,
             assertions: Vec::new(),
             extracted: HashMap::new(),
             error: Some("OpenAPI step execution requires running the openapi import command first".to_string()),
@@ -2717,6 +3184,7 @@ impl PipelineRunner {
                 "url": result.url,
                 "status_code": result.status_code,
                 "duration_ms": result.response_time.as_millis(),
+                "timing": result.timing.as_ref().map(RequestTiming::to_json),
                 "passed": result.passed(),
                 "skipped": result.skipped,
                 "error": result.error,
@@ -2870,8 +3338,7 @@ impl PipelineRunner {
             }
 
             // Show assertions
-            if step.assert.status.is_some() || step.assert.latency.is_some() ||
-               !step.assert.headers.is_empty() || !step.assert.body.is_empty() {
+            if !step.assert.is_empty() {
                 let mut assertion_parts = Vec::new();
                 if let Some(ref status) = step.assert.status {
                     assertion_parts.push(format!("status={:?}", status));
@@ -2885,17 +3352,31 @@ impl PipelineRunner {
                 if !step.assert.body.is_empty() {
                     assertion_parts.push(format!("{} body checks", step.assert.body.len()));
                 }
+                if step.assert.schema.is_some() {
+                    assertion_parts.push("schema".to_string());
+                }
+                if step.assert.snapshot().is_some() {
+                    assertion_parts.push("snapshot".to_string());
+                }
                 eprintln!("    {} {} {}",
                     terminal::colorize("✓", colors::GREEN),
                     terminal::muted("Asserts:"),
                     terminal::info(&assertion_parts.join(", ")));
             }
 
-            // Show skip condition
-            if let Some(ref skip_if) = step.skip_if {
-                eprintln!("    {} {}",
-                    terminal::muted("Skip if:"),
-                    terminal::colorize(skip_if, colors::YELLOW));
+            // Show conditions
+            for (label, condition) in [
+                ("Skip if:", &step.skip_if),
+                ("Run if:", &step.run_if),
+                ("While:", &step.while_condition),
+                ("Until:", &step.until),
+                ("If:", &step.if_condition),
+            ] {
+                if let Some(condition) = condition {
+                    eprintln!("    {} {}",
+                        terminal::muted(label),
+                        terminal::colorize(condition, colors::YELLOW));
+                }
             }
         }
 
@@ -2904,6 +3385,46 @@ impl PipelineRunner {
     }
 }
 
+/// Condition expressions of a step and of the steps in its branches
+fn collect_conditions<'a>(step: &'a WorkflowStep, out: &mut Vec<&'a str>) {
+    let own = [&step.skip_if, &step.run_if, &step.while_condition, &step.until, &step.if_condition];
+    out.extend(own.into_iter().flatten().map(String::as_str));
+    for sub_step in step.then_steps.iter().chain(&step.else_steps) {
+        collect_conditions(sub_step, out);
+    }
+}
+
+/// Group ordered steps into batches that may execute concurrently
+///
+/// With dependency levels, each level forms one batch (`level_sizes` gives the
+/// number of consecutive steps per level). Otherwise runs of adjacent steps
+/// marked `parallel: true` are grouped and every other step runs on its own.
+fn plan_batches(steps: &[&WorkflowStep], level_sizes: Option<&[usize]>) -> Vec<Range<usize>> {
+    let mut batches = Vec::new();
+    let mut start = 0;
+
+    if let Some(sizes) = level_sizes {
+        for &size in sizes {
+            batches.push(start..start + size);
+            start += size;
+        }
+        return batches;
+    }
+
+    while start < steps.len() {
+        let mut end = start + 1;
+        if steps[start].parallel {
+            while end < steps.len() && steps[end].parallel {
+                end += 1;
+            }
+        }
+        batches.push(start..end);
+        start = end;
+    }
+
+    batches
+}
+
 /// Compress data using deflate algorithm
 fn compress_deflate(data: &[u8]) -> Vec<u8> {
     use std::io::Write;
@@ -3047,10 +3568,10 @@ mod tests {
         runner.variables.insert("enabled".to_string(), JsonValue::Bool(true));
         runner.variables.insert("disabled".to_string(), JsonValue::Bool(false));
 
-        assert!(runner.evaluate_condition("{{enabled}}"));
-        assert!(!runner.evaluate_condition("{{disabled}}"));
-        assert!(!runner.evaluate_condition("!{{enabled}}"));
-        assert!(runner.evaluate_condition("!{{disabled}}"));
+        assert!(runner.evaluate_condition("{{enabled}}").unwrap());
+        assert!(!runner.evaluate_condition("{{disabled}}").unwrap());
+        assert!(!runner.evaluate_condition("!{{enabled}}").unwrap());
+        assert!(runner.evaluate_condition("!{{disabled}}").unwrap());
     }
 
     fn make_step(name: &str) -> WorkflowStep {
@@ -3157,6 +3678,28 @@ mod tests {
         assert!(!runner.should_run_step(&step_include_only));
     }
 
+    #[test]
+    fn test_plan_batches_groups_adjacent_parallel_steps() {
+        let steps = vec![
+            make_step("login"),
+            WorkflowStep { name: "users".to_string(), parallel: true, ..Default::default() },
+            WorkflowStep { name: "orders".to_string(), parallel: true, ..Default::default() },
+            make_step("checkout"),
+            WorkflowStep { name: "cleanup".to_string(), parallel: true, ..Default::default() },
+        ];
+        let refs: Vec<&WorkflowStep> = steps.iter().collect();
+
+        assert_eq!(plan_batches(&refs, None), vec![0..1, 1..3, 3..4, 4..5]);
+    }
+
+    #[test]
+    fn test_plan_batches_uses_dependency_levels() {
+        let steps = vec![make_step("a"), make_step("b"), make_step("c"), make_step("d")];
+        let refs: Vec<&WorkflowStep> = steps.iter().collect();
+
+        assert_eq!(plan_batches(&refs, Some(&[1, 2, 1])), vec![0..1, 1..3, 3..4]);
+    }
+
     #[test]
     fn test_no_filters_runs_all() {
         let runner = PipelineRunner::new(true).unwrap();
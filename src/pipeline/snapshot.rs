@@ -0,0 +1,247 @@
+//! Snapshot (golden-file) assertions
+//!
+//! A step with `assert: { snapshot: true }` stores its normalized response
+//! (status, selected headers, body) under `__snapshots__/` on the first run and
+//! compares against it afterwards. Volatile body fields are dropped by JQ path.
+//! Files are named after a slug of the step name, so a workflow must not have
+//! two snapshotted steps whose names give the same slug.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use reqwest::header::HeaderMap;
+use serde_json::{json, Map, Value as JsonValue};
+
+use super::assertions::AssertionResult;
+use super::workflow::SnapshotConfig;
+use crate::errors::QuicpulseError;
+use crate::filter;
+use crate::output::terminal::{self, colors};
+
+/// Headers kept when a snapshot doesn't list any
+const DEFAULT_HEADERS: &[&str] = &["content-type"];
+
+/// One difference between the stored and the actual snapshot
+#[derive(Debug, Clone, PartialEq)]
+pub struct Change {
+    /// JSON pointer, e.g. `/body/items/0/name`
+    pub path: String,
+    pub expected: Option<JsonValue>,
+    pub actual: Option<JsonValue>,
+}
+
+/// File holding the snapshot of a step
+pub fn snapshot_path(dir: &Path, step_name: &str, config: &SnapshotConfig) -> PathBuf {
+    dir.join(snapshot_file_name(step_name, config))
+}
+
+/// Name of the snapshot file of a step: its snapshot name or step name, slugged
+pub fn snapshot_file_name(step_name: &str, config: &SnapshotConfig) -> String {
+    let name = config.name.as_deref().unwrap_or(step_name);
+    let slug: String = name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c.to_ascii_lowercase() } else { '_' })
+        .collect();
+    format!("{}.json", slug)
+}
+
+/// The part of a response that is snapshotted; fails on an ignore path that is not valid JQ
+pub fn normalize(status: u16, headers: &HeaderMap, body: &str, config: &SnapshotConfig) -> Result<JsonValue, QuicpulseError> {
+    let names: Vec<String> = if config.headers.is_empty() {
+        DEFAULT_HEADERS.iter().map(|h| h.to_string()).collect()
+    } else {
+        config.headers.iter().map(|h| h.to_ascii_lowercase()).collect()
+    };
+    let headers: Map<String, JsonValue> = names.into_iter()
+        .filter_map(|name| {
+            let value = headers.get(name.as_str())?.to_str().ok()?.to_string();
+            Some((name, JsonValue::String(value)))
+        })
+        .collect();
+
+    let mut body = serde_json::from_str(body).unwrap_or_else(|_| JsonValue::String(body.to_string()));
+    for path in &config.ignore {
+        let expr = format!("del({})", path);
+        match filter::apply_filter(&body, &expr) {
+            Ok(mut results) => {
+                if let Some(pruned) = results.pop() {
+                    body = pruned;
+                }
+            }
+            // Paths that don't apply to this body are left alone
+            Err(_) => filter::check_filter(&expr).map_err(|e| QuicpulseError::Argument(format!(
+                "Invalid snapshot ignore path '{}': {}", path, e
+            )))?,
+        }
+    }
+
+    Ok(json!({ "status": status, "headers": headers, "body": body }))
+}
+
+/// Compare `actual` with the snapshot at `path`, writing it when missing or when `update` is set
+pub fn check_snapshot(path: &Path, actual: &JsonValue, update: bool) -> Result<(AssertionResult, Vec<Change>), QuicpulseError> {
+    let assertion = format!("snapshot {}", path.display());
+
+    let stored = match fs::read_to_string(path) {
+        Ok(content) => Some(serde_json::from_str::<JsonValue>(&content)
+            .map_err(|e| QuicpulseError::Parse(format!("Invalid snapshot '{}': {}", path.display(), e)))?),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+        Err(e) => return Err(QuicpulseError::Io(e)),
+    };
+
+    let changes = stored.as_ref().map(|stored| diff(stored, actual)).unwrap_or_default();
+    if stored.is_none() || (update && !changes.is_empty()) {
+        write_snapshot(path, actual)?;
+        let message = if stored.is_none() { "Snapshot written" } else { "Snapshot updated" };
+        return Ok((AssertionResult::pass(&assertion, message), Vec::new()));
+    }
+
+    if changes.is_empty() {
+        return Ok((AssertionResult::pass(&assertion, "Matches snapshot"), changes));
+    }
+    let message = format!(
+        "{} difference(s), run with --update-snapshots to accept\n{}",
+        changes.len(),
+        format_diff(&changes, false)
+    );
+    Ok((AssertionResult::fail(&assertion, &message), changes))
+}
+
+fn write_snapshot(path: &Path, value: &JsonValue) -> Result<(), QuicpulseError> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let content = serde_json::to_string_pretty(value)
+        .map_err(|e| QuicpulseError::Parse(format!("Cannot serialize snapshot: {}", e)))?;
+    fs::write(path, content + "\n")?;
+    Ok(())
+}
+
+/// Structural diff: objects by key, arrays by index, everything else by value
+pub fn diff(expected: &JsonValue, actual: &JsonValue) -> Vec<Change> {
+    let mut changes = Vec::new();
+    diff_at("", expected, actual, &mut changes);
+    changes
+}
+
+fn diff_at(path: &str, expected: &JsonValue, actual: &JsonValue, changes: &mut Vec<Change>) {
+    match (expected, actual) {
+        (JsonValue::Object(e), JsonValue::Object(a)) => {
+            for (key, value) in e {
+                let child = format!("{}/{}", path, key.replace('~', "~0").replace('/', "~1"));
+                match a.get(key) {
+                    Some(other) => diff_at(&child, value, other, changes),
+                    None => changes.push(Change { path: child, expected: Some(value.clone()), actual: None }),
+                }
+            }
+            for (key, value) in a.iter().filter(|(key, _)| !e.contains_key(*key)) {
+                let child = format!("{}/{}", path, key.replace('~', "~0").replace('/', "~1"));
+                changes.push(Change { path: child, expected: None, actual: Some(value.clone()) });
+            }
+        }
+        (JsonValue::Array(e), JsonValue::Array(a)) => {
+            for i in 0..e.len().max(a.len()) {
+                let child = format!("{}/{}", path, i);
+                match (e.get(i), a.get(i)) {
+                    (Some(x), Some(y)) => diff_at(&child, x, y, changes),
+                    (x, y) => changes.push(Change { path: child, expected: x.cloned(), actual: y.cloned() }),
+                }
+            }
+        }
+        _ if expected != actual => changes.push(Change {
+            path: if path.is_empty() { "/".to_string() } else { path.to_string() },
+            expected: Some(expected.clone()),
+            actual: Some(actual.clone()),
+        }),
+        _ => {}
+    }
+}
+
+/// One line per change: `- removed`, `+ added`, `~ changed`
+pub fn format_diff(changes: &[Change], colored: bool) -> String {
+    let paint = |color: u8, text: String| if colored { terminal::colorize(&text, color) } else { text };
+    changes.iter()
+        .map(|change| match (&change.expected, &change.actual) {
+            (Some(e), None) => paint(colors::RED, format!("  - {}: {}", change.path, e)),
+            (None, Some(a)) => paint(colors::GREEN, format!("  + {}: {}", change.path, a)),
+            (Some(e), Some(a)) => format!(
+                "  ~ {}: {} → {}",
+                change.path,
+                paint(colors::RED, e.to_string()),
+                paint(colors::GREEN, a.to_string())
+            ),
+            (None, None) => String::new(),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_ignores_paths() {
+        let mut headers = HeaderMap::new();
+        headers.insert("content-type", "application/json".parse().unwrap());
+        headers.insert("date", "Mon, 01 Jan 2024 00:00:00 GMT".parse().unwrap());
+        let config = SnapshotConfig {
+            ignore: vec![".created_at".to_string(), ".items[].id".to_string(), ".missing[]".to_string()],
+            ..Default::default()
+        };
+        let body = r#"{"name": "x", "created_at": "now", "items": [{"id": 1, "v": 2}]}"#;
+
+        let snapshot = normalize(200, &headers, body, &config).unwrap();
+        assert_eq!(snapshot, json!({
+            "status": 200,
+            "headers": {"content-type": "application/json"},
+            "body": {"name": "x", "items": [{"v": 2}]}
+        }));
+
+        let broken = SnapshotConfig { ignore: vec![".items[".to_string()], ..Default::default() };
+        let err = normalize(200, &headers, body, &broken).unwrap_err();
+        assert!(err.to_string().contains("'.items['"), "{}", err);
+    }
+
+    #[test]
+    fn test_diff() {
+        let expected = json!({"status": 200, "body": {"name": "a", "tags": [1, 2], "old": true}});
+        let actual = json!({"status": 201, "body": {"name": "b", "tags": [1], "new": null}});
+        let changes = diff(&expected, &actual);
+        assert_eq!(changes, vec![
+            Change { path: "/status".into(), expected: Some(json!(200)), actual: Some(json!(201)) },
+            Change { path: "/body/name".into(), expected: Some(json!("a")), actual: Some(json!("b")) },
+            Change { path: "/body/tags/1".into(), expected: Some(json!(2)), actual: None },
+            Change { path: "/body/old".into(), expected: Some(json!(true)), actual: None },
+            Change { path: "/body/new".into(), expected: None, actual: Some(json!(null)) },
+        ]);
+        assert_eq!(
+            format_diff(&changes[..3], false),
+            "  ~ /status: 200 → 201\n  ~ /body/name: \"a\" → \"b\"\n  - /body/tags/1: 2"
+        );
+        assert!(diff(&expected, &expected).is_empty());
+    }
+
+    #[test]
+    fn test_check_snapshot_lifecycle() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("__snapshots__").join("step.json");
+        let first = json!({"status": 200, "body": {"n": 1}});
+        let second = json!({"status": 200, "body": {"n": 2}});
+
+        let (result, _) = check_snapshot(&path, &first, false).unwrap();
+        assert!(result.passed);
+        assert!(path.exists());
+
+        let (result, _) = check_snapshot(&path, &first, false).unwrap();
+        assert!(result.passed);
+
+        let (result, changes) = check_snapshot(&path, &second, false).unwrap();
+        assert!(!result.passed);
+        assert_eq!(changes.len(), 1);
+        assert!(result.message.contains("~ /body/n: 1 → 2"));
+
+        let (result, _) = check_snapshot(&path, &second, true).unwrap();
+        assert!(result.passed);
+        assert!(check_snapshot(&path, &second, false).unwrap().0.passed);
+    }
+}
@@ -58,6 +58,15 @@ pub struct Workflow {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub output: Option<OutputConfig>,
 
+    /// Maximum number of steps executed concurrently within a parallel batch
+    /// (dependency level or run of adjacent `parallel` steps, default: 10)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_parallel: Option<usize>,
+
+    /// Run the steps as a load test with virtual users instead of once
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub load: Option<LoadConfig>,
+
     /// Workflow steps
     pub steps: Vec<WorkflowStep>,
 }
@@ -82,6 +91,7 @@ pub struct WorkflowStep {
     pub method: String,
 
     /// URL or path (combined with base_url if relative)
+    #[serde(default)]
     pub url: String,
 
     /// Query parameters (appended to URL)
@@ -114,16 +124,20 @@ pub struct WorkflowStep {
 
     /// Extract values from response into variables
     #[serde(default, skip_serializing_if = "HashMap::is_empty")]
-    pub extract: HashMap<String, String>,
+    pub extract: HashMap<String, Extraction>,
 
     /// Assertions to check
     #[serde(default, skip_serializing_if = "StepAssertions::is_empty")]
     pub assert: StepAssertions,
 
-    /// Skip this step if condition is false
+    /// Skip this step if the condition expression is true
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub skip_if: Option<String>,
 
+    /// Run this step only if the condition expression is true
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_if: Option<String>,
+
     /// Delay before executing this step
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub delay: Option<String>,
@@ -161,14 +175,30 @@ pub struct WorkflowStep {
     pub foreach_var: Option<String>,
 
     /// While condition - repeat while expression is true
-    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(default, alias = "while", skip_serializing_if = "Option::is_none")]
     pub while_condition: Option<String>,
 
+    /// Until condition - repeat until expression is true, checked after each run
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub until: Option<String>,
+
+    /// Branch condition: run `then` steps if true, `else` steps otherwise
+    #[serde(default, rename = "if", skip_serializing_if = "Option::is_none")]
+    pub if_condition: Option<String>,
+
+    /// Steps run when `if` is true
+    #[serde(default, rename = "then", skip_serializing_if = "Vec::is_empty")]
+    pub then_steps: Vec<WorkflowStep>,
+
+    /// Steps run when `if` is false
+    #[serde(default, rename = "else", skip_serializing_if = "Vec::is_empty")]
+    pub else_steps: Vec<WorkflowStep>,
+
     /// Maximum iterations for while loop
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub max_iterations: Option<u32>,
 
-    /// Run this step in parallel with other steps marked parallel
+    /// Run this step concurrently with adjacent steps also marked parallel
     #[serde(default)]
     pub parallel: bool,
 
@@ -480,6 +510,29 @@ pub struct BenchConfig {
     pub warmup: Option<u32>,
 }
 
+/// Workflow load mode: virtual users replaying the steps in a loop
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoadConfig {
+    /// Number of virtual users
+    pub users: u32,
+
+    /// Iterations of the steps per user (default: 1 unless `duration` is set)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub iterations: Option<u32>,
+
+    /// Stop starting new iterations after this long (e.g. "1m")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration: Option<String>,
+
+    /// Spread user start times evenly over this period (e.g. "10s")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ramp_up: Option<String>,
+
+    /// Pause after each step (e.g. "500ms")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub think_time: Option<String>,
+}
+
 /// Download configuration for saving responses to file
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DownloadConfig {
@@ -729,13 +782,21 @@ pub struct StepAssertions {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub latency: Option<String>,
 
-    /// Body assertions
-    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
-    pub body: HashMap<String, serde_json::Value>,
+    /// Body assertions: a map of JQ path to expected value or operator, or a list of checks
+    #[serde(default, skip_serializing_if = "Vec::is_empty", deserialize_with = "deserialize_body_assertions")]
+    pub body: Vec<BodyAssertion>,
 
     /// Header assertions
     #[serde(default, skip_serializing_if = "HashMap::is_empty")]
     pub headers: HashMap<String, String>,
+
+    /// JSON Schema the body must satisfy
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schema: Option<SchemaAssertion>,
+
+    /// Compare the response with a stored snapshot
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snapshot: Option<SnapshotAssertion>,
 }
 
 impl StepAssertions {
@@ -745,6 +806,17 @@ impl StepAssertions {
             && self.latency.is_none()
             && self.body.is_empty()
             && self.headers.is_empty()
+            && self.schema.is_none()
+            && self.snapshot().is_none()
+    }
+
+    /// Snapshot settings, if snapshot testing is enabled
+    pub fn snapshot(&self) -> Option<SnapshotConfig> {
+        match self.snapshot {
+            Some(SnapshotAssertion::Enabled(true)) => Some(SnapshotConfig::default()),
+            Some(SnapshotAssertion::Config(ref config)) => Some(config.clone()),
+            _ => None,
+        }
     }
 }
 
@@ -756,6 +828,339 @@ pub enum StatusAssertion {
     Range(String),
 }
 
+/// `name: source` or `name: { from: source, required: true }`
+///
+/// Sources are `status`, `duration_ms`, `body`, `header:NAME`, `cookie:NAME`,
+/// `regex:PATTERN`, `xpath:EXPR` or a JQ path on the JSON body.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Extraction {
+    Source(String),
+    Config {
+        from: String,
+        /// Fail the step when the source matches nothing
+        #[serde(default)]
+        required: bool,
+    },
+}
+
+impl Extraction {
+    pub fn source(&self) -> &str {
+        match self {
+            Extraction::Source(source) | Extraction::Config { from: source, .. } => source,
+        }
+    }
+
+    pub fn required(&self) -> bool {
+        matches!(self, Extraction::Config { required: true, .. })
+    }
+}
+
+impl From<String> for Extraction {
+    fn from(source: String) -> Self {
+        Extraction::Source(source)
+    }
+}
+
+/// `snapshot: true` or snapshot settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum SnapshotAssertion {
+    Enabled(bool),
+    Config(SnapshotConfig),
+}
+
+/// What a snapshot stores and ignores
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SnapshotConfig {
+    /// JQ paths of volatile body fields to leave out (e.g. `.id`, `.items[].created_at`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ignore: Vec<String>,
+
+    /// Response headers to include (default: content-type)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub headers: Vec<String>,
+
+    /// Snapshot file name, defaults to the step name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+/// Where a schema assertion gets its schema from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum SchemaAssertion {
+    /// Path to a JSON or YAML schema file (can use variables)
+    File(String),
+    /// Response schema of an OpenAPI operation
+    OpenApi {
+        /// Path to the OpenAPI spec
+        openapi: String,
+        #[serde(alias = "operationId")]
+        operation_id: String,
+        /// Response code, defaults to the actual status
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        status: Option<StatusAssertion>,
+    },
+    /// Inline schema
+    Inline(serde_json::Value),
+}
+
+/// Operator of a typed body assertion
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AssertOp {
+    #[serde(alias = "==", alias = "equals")]
+    Eq,
+    #[serde(alias = "!=")]
+    Ne,
+    #[serde(alias = ">")]
+    Gt,
+    #[serde(alias = ">=")]
+    Gte,
+    #[serde(alias = "<")]
+    Lt,
+    #[serde(alias = "<=")]
+    Lte,
+    Contains,
+    NotContains,
+    #[serde(alias = "=~")]
+    Matches,
+    In,
+    NotIn,
+    #[serde(alias = "length_eq")]
+    Length,
+    LengthGt,
+    LengthGte,
+    LengthLt,
+    LengthLte,
+    Type,
+    Exists,
+    NotExists,
+    #[serde(alias = "~=")]
+    Approx,
+}
+
+impl AssertOp {
+    const ALL: &'static [AssertOp] = &[
+        AssertOp::Eq, AssertOp::Ne, AssertOp::Gt, AssertOp::Gte, AssertOp::Lt, AssertOp::Lte,
+        AssertOp::Contains, AssertOp::NotContains, AssertOp::Matches, AssertOp::In, AssertOp::NotIn,
+        AssertOp::Length, AssertOp::LengthGt, AssertOp::LengthGte, AssertOp::LengthLt, AssertOp::LengthLte,
+        AssertOp::Type, AssertOp::Exists, AssertOp::NotExists, AssertOp::Approx,
+    ];
+
+    /// Name used in YAML and in failure messages
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AssertOp::Eq => "eq",
+            AssertOp::Ne => "ne",
+            AssertOp::Gt => "gt",
+            AssertOp::Gte => "gte",
+            AssertOp::Lt => "lt",
+            AssertOp::Lte => "lte",
+            AssertOp::Contains => "contains",
+            AssertOp::NotContains => "not_contains",
+            AssertOp::Matches => "matches",
+            AssertOp::In => "in",
+            AssertOp::NotIn => "not_in",
+            AssertOp::Length => "length",
+            AssertOp::LengthGt => "length_gt",
+            AssertOp::LengthGte => "length_gte",
+            AssertOp::LengthLt => "length_lt",
+            AssertOp::LengthLte => "length_lte",
+            AssertOp::Type => "type",
+            AssertOp::Exists => "exists",
+            AssertOp::NotExists => "not_exists",
+            AssertOp::Approx => "approx",
+        }
+    }
+
+    /// Whether the operator compares against a `value`
+    pub fn takes_value(&self) -> bool {
+        !matches!(self, AssertOp::Exists | AssertOp::NotExists)
+    }
+
+    /// Look up an operator by name or symbol (`>=`, `==`, `length_gte`, ...)
+    pub fn parse(token: &str) -> Option<Self> {
+        let symbol = match token {
+            "==" => Some(AssertOp::Eq),
+            "!=" => Some(AssertOp::Ne),
+            ">" => Some(AssertOp::Gt),
+            ">=" => Some(AssertOp::Gte),
+            "<" => Some(AssertOp::Lt),
+            "<=" => Some(AssertOp::Lte),
+            "=~" => Some(AssertOp::Matches),
+            "~=" => Some(AssertOp::Approx),
+            _ => None,
+        };
+        symbol.or_else(|| Self::ALL.iter().copied().find(|op| op.as_str() == token))
+    }
+}
+
+impl std::fmt::Display for AssertOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A typed check on a JQ path of the response body
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BodyAssertion {
+    /// JQ path (a leading `.` is added when missing)
+    pub path: String,
+
+    /// Operator, `eq` by default
+    #[serde(default = "default_assert_op")]
+    pub op: AssertOp,
+
+    /// Expected value
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<serde_json::Value>,
+
+    /// Allowed difference for `approx` (default 1e-6)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tolerance: Option<f64>,
+}
+
+impl BodyAssertion {
+    pub fn new(path: &str, op: AssertOp, value: Option<serde_json::Value>) -> Self {
+        Self { path: path.to_string(), op, value, tolerance: None }
+    }
+
+    /// Map form: `path: expected`, `path: "length >= 1"` or `path: {op: gte, value: 1}`
+    ///
+    /// A string is an operator only when [`parse_value_shorthand`] accepts it
+    /// whole; anything else, like `"in progress"`, is an expected value.
+    fn from_entry(path: &str, expected: serde_json::Value) -> Result<Self, String> {
+        match expected {
+            serde_json::Value::Object(ref map) if map.contains_key("op") => {
+                let mut map = map.clone();
+                map.insert("path".to_string(), path.into());
+                serde_json::from_value(map.into()).map_err(|e| format!("body assertion '{}': {}", path, e))
+            }
+            serde_json::Value::String(ref s) => {
+                Ok(parse_value_shorthand(path, s).unwrap_or_else(|| Self::new(path, AssertOp::Eq, Some(expected.clone()))))
+            }
+            other => Ok(Self::new(path, AssertOp::Eq, Some(other))),
+        }
+    }
+
+    /// List form string: `PATH`, `PATH exists` or `PATH OP VALUE`
+    fn parse(s: &str) -> Result<Self, String> {
+        let s = s.trim();
+        match s.split_once(char::is_whitespace) {
+            None => Ok(Self::new(s, AssertOp::Exists, None)),
+            Some((path, rest)) => parse_shorthand(path, rest)
+                .ok_or_else(|| format!("body assertion '{}': unknown operator in '{}'", path, rest.trim())),
+        }
+    }
+}
+
+fn default_assert_op() -> AssertOp {
+    AssertOp::Eq
+}
+
+/// Type names understood by the `type` operator
+const TYPE_NAMES: &[&str] = &["null", "bool", "boolean", "number", "integer", "string", "array", "object"];
+
+/// Split `OP VALUE` into the operator and its unparsed operand; `length N` is `length == N`
+fn split_shorthand(s: &str) -> Option<(AssertOp, &str)> {
+    let s = s.trim();
+    let (token, rest) = s.split_once(char::is_whitespace).unwrap_or((s, ""));
+    let rest = rest.trim();
+
+    if token != "length" {
+        return Some((AssertOp::parse(token)?, rest));
+    }
+    // `length 3` or `length >= 3`
+    let (cmp, value) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+    let op = match cmp {
+        "==" => AssertOp::Length,
+        ">" => AssertOp::LengthGt,
+        ">=" => AssertOp::LengthGte,
+        "<" => AssertOp::LengthLt,
+        "<=" => AssertOp::LengthLte,
+        _ if rest.is_empty() => return None,
+        _ => return Some((AssertOp::Length, rest)),
+    };
+    Some((op, value.trim()))
+}
+
+/// Parse `OP VALUE` (e.g. `> 0`, `length >= 3`, `matches ^a`, `type array`, `exists`)
+fn parse_shorthand(path: &str, s: &str) -> Option<BodyAssertion> {
+    let (op, rest) = split_shorthand(s)?;
+
+    if !op.takes_value() {
+        return rest.is_empty().then(|| BodyAssertion::new(path, op, None));
+    }
+    if rest.is_empty() {
+        return None;
+    }
+    let value = match op {
+        // Patterns and type names are taken verbatim
+        AssertOp::Matches | AssertOp::Type => serde_json::Value::String(rest.to_string()),
+        _ => parse_literal(rest),
+    };
+    Some(BodyAssertion::new(path, op, Some(value)))
+}
+
+/// Parse `OP VALUE` given as an expected value, only if the operand suits the
+/// operator: JSON of the right kind, a valid regex or a type name. Operators
+/// without a value need the structured form there (`{op: exists}`).
+fn parse_value_shorthand(path: &str, s: &str) -> Option<BodyAssertion> {
+    let (op, rest) = split_shorthand(s)?;
+    if !op.takes_value() || rest.is_empty() {
+        return None;
+    }
+
+    let value = match op {
+        AssertOp::Matches => {
+            regex::Regex::new(rest).ok()?;
+            serde_json::Value::String(rest.to_string())
+        }
+        AssertOp::Type => TYPE_NAMES.contains(&rest).then(|| serde_json::Value::String(rest.to_string()))?,
+        _ => serde_json::from_str(rest).ok()?,
+    };
+    let valid = match op {
+        AssertOp::Gt | AssertOp::Gte | AssertOp::Lt | AssertOp::Lte => value.is_number() || value.is_string(),
+        AssertOp::Approx => value.is_number(),
+        AssertOp::In | AssertOp::NotIn => value.is_array(),
+        AssertOp::Length | AssertOp::LengthGt | AssertOp::LengthGte | AssertOp::LengthLt | AssertOp::LengthLte => {
+            value.is_u64()
+        }
+        _ => true,
+    };
+    valid.then(|| BodyAssertion::new(path, op, Some(value)))
+}
+
+/// JSON if it parses, otherwise the raw string
+fn parse_literal(s: &str) -> serde_json::Value {
+    serde_json::from_str(s).unwrap_or_else(|_| serde_json::Value::String(s.to_string()))
+}
+
+fn deserialize_body_assertions<'de, D>(deserializer: D) -> Result<Vec<BodyAssertion>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    use serde::de::Error;
+
+    match serde_json::Value::deserialize(deserializer)? {
+        serde_json::Value::Null => Ok(Vec::new()),
+        serde_json::Value::Object(map) => map.into_iter()
+            .map(|(path, expected)| BodyAssertion::from_entry(&path, expected))
+            .collect::<Result<_, _>>()
+            .map_err(D::Error::custom),
+        serde_json::Value::Array(items) => items.into_iter()
+            .map(|item| match item {
+                serde_json::Value::String(s) => BodyAssertion::parse(&s),
+                other => serde_json::from_value(other).map_err(|e| format!("body assertion: {}", e)),
+            })
+            .collect::<Result<_, _>>()
+            .map_err(D::Error::custom),
+        other => Err(D::Error::custom(format!("body assertions must be a map or a list, got {}", other))),
+    }
+}
+
 fn default_method() -> String {
     "GET".to_string()
 }
@@ -820,16 +1225,75 @@ fn validate_workflow_structure(workflow: &Workflow) -> Result<(), QuicpulseError
         return Err(QuicpulseError::Argument("Workflow must have at least one step".to_string()));
     }
 
-    // Validate each step has required fields
-    for (i, step) in workflow.steps.iter().enumerate() {
+    if workflow.max_parallel == Some(0) {
+        return Err(QuicpulseError::Argument("Workflow max_parallel must be at least 1".to_string()));
+    }
+
+    if let Some(ref load) = workflow.load {
+        if load.users == 0 {
+            return Err(QuicpulseError::Argument("Workflow load.users must be at least 1".to_string()));
+        }
+        if load.iterations == Some(0) {
+            return Err(QuicpulseError::Argument("Workflow load.iterations must be at least 1".to_string()));
+        }
+        for (field, value) in [("duration", &load.duration), ("ramp_up", &load.ramp_up), ("think_time", &load.think_time)] {
+            if let Some(value) = value {
+                humantime::parse_duration(value).map_err(|e| QuicpulseError::Argument(format!(
+                    "Workflow load.{} '{}' is not a duration: {}", field, value, e
+                )))?;
+            }
+        }
+    }
+
+    validate_steps(&workflow.steps, "")?;
+    validate_snapshot_files(&workflow.steps, &mut HashMap::new())
+}
+
+/// Check no two snapshotted steps, branches included, share a snapshot file
+fn validate_snapshot_files<'a>(
+    steps: &'a [WorkflowStep],
+    files: &mut HashMap<String, &'a str>,
+) -> Result<(), QuicpulseError> {
+    for step in steps {
+        if let Some(config) = step.assert.snapshot() {
+            let file = super::snapshot::snapshot_file_name(&step.name, &config);
+            if let Some(other) = files.insert(file.clone(), &step.name) {
+                return Err(QuicpulseError::Argument(format!(
+                    "Steps '{}' and '{}' would share the snapshot file {}; give one a snapshot name",
+                    other, step.name, file
+                )));
+            }
+        }
+        validate_snapshot_files(&step.then_steps, files)?;
+        validate_snapshot_files(&step.else_steps, files)?;
+    }
+    Ok(())
+}
+
+/// Check each step, and the steps of `if` branches, has the required fields
+fn validate_steps(steps: &[WorkflowStep], prefix: &str) -> Result<(), QuicpulseError> {
+    for (i, step) in steps.iter().enumerate() {
+        let number = format!("{}{}", prefix, i + 1);
         if step.name.is_empty() {
             return Err(QuicpulseError::Argument(format!(
-                "Step {} must have a name", i + 1
+                "Step {} must have a name", number
             )));
         }
-        if step.url.is_empty() {
+        if step.if_condition.is_some() {
+            if step.then_steps.is_empty() && step.else_steps.is_empty() {
+                return Err(QuicpulseError::Argument(format!(
+                    "Step {} ({}) has an 'if' without 'then' or 'else' steps", number, step.name
+                )));
+            }
+            validate_steps(&step.then_steps, &format!("{}.then.", number))?;
+            validate_steps(&step.else_steps, &format!("{}.else.", number))?;
+        } else if !step.then_steps.is_empty() || !step.else_steps.is_empty() {
+            return Err(QuicpulseError::Argument(format!(
+                "Step {} ({}) has 'then'/'else' steps without an 'if'", number, step.name
+            )));
+        } else if step.url.is_empty() {
             return Err(QuicpulseError::Argument(format!(
-                "Step {} ({}) must have a URL", i + 1, step.name
+                "Step {} ({}) must have a URL", number, step.name
             )));
         }
     }
@@ -943,4 +1407,193 @@ steps:
         apply_cli_variables(&mut workflow, &["token=secret123".to_string()]).unwrap();
         assert_eq!(workflow.variables["token"], "secret123");
     }
+
+    #[test]
+    fn test_parse_parallel_steps() {
+        let yaml = r#"
+name: "Smoke"
+max_parallel: 4
+steps:
+  - name: "Users"
+    url: "/users"
+    parallel: true
+  - name: "Orders"
+    url: "/orders"
+    parallel: true
+"#;
+
+        let workflow: Workflow = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(workflow.max_parallel, Some(4));
+        assert!(workflow.steps.iter().all(|s| s.parallel));
+        assert!(validate_workflow_structure(&workflow).is_ok());
+    }
+
+    #[test]
+    fn test_zero_max_parallel_rejected() {
+        let yaml = r#"
+name: "Smoke"
+max_parallel: 0
+steps:
+  - name: "Users"
+    url: "/users"
+"#;
+
+        let workflow: Workflow = serde_yaml::from_str(yaml).unwrap();
+        assert!(validate_workflow_structure(&workflow).is_err());
+    }
+
+    #[test]
+    fn test_parse_load_config() {
+        let yaml = r#"
+name: "Journey"
+load:
+  users: 20
+  duration: 1m
+  ramp_up: 10s
+  think_time: 200ms
+steps:
+  - name: "Users"
+    url: "/users"
+"#;
+
+        let workflow: Workflow = serde_yaml::from_str(yaml).unwrap();
+        let load = workflow.load.as_ref().unwrap();
+        assert_eq!(load.users, 20);
+        assert_eq!(load.iterations, None);
+        assert_eq!(load.duration.as_deref(), Some("1m"));
+        assert!(validate_workflow_structure(&workflow).is_ok());
+
+        let mut invalid = workflow.clone();
+        invalid.load.as_mut().unwrap().users = 0;
+        assert!(validate_workflow_structure(&invalid).is_err());
+
+        let mut invalid = workflow;
+        invalid.load.as_mut().unwrap().think_time = Some("a while".to_string());
+        assert!(validate_workflow_structure(&invalid).is_err());
+    }
+
+    #[test]
+    fn test_snapshot_file_collisions() {
+        let yaml = r#"
+name: "Snapshots"
+steps:
+  - name: "Get user"
+    url: "/users/1"
+    assert:
+      snapshot: true
+  - name: "get_user"
+    url: "/users/2"
+    assert:
+      snapshot:
+        name: "second user"
+"#;
+
+        let workflow: Workflow = serde_yaml::from_str(yaml).unwrap();
+        assert!(validate_workflow_structure(&workflow).is_ok());
+
+        let mut invalid = workflow;
+        invalid.steps[1].assert.snapshot = Some(SnapshotAssertion::Enabled(true));
+        let err = validate_workflow_structure(&invalid).unwrap_err();
+        assert!(err.to_string().contains("'Get user' and 'get_user'"), "{}", err);
+    }
+
+    #[test]
+    fn test_parse_branches_and_loops() {
+        let yaml = r#"
+name: "Control Flow"
+steps:
+  - name: "Poll"
+    url: "/jobs/1"
+    while: "state != 'done'"
+  - name: "Branch"
+    if: "state == 'done'"
+    then:
+      - name: "Done"
+        url: "/done"
+    else:
+      - name: "Retry"
+        url: "/retry"
+        run_if: "{{ attempts }} < 3"
+"#;
+
+        let workflow: Workflow = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(workflow.steps[0].while_condition.as_deref(), Some("state != 'done'"));
+        let branch = &workflow.steps[1];
+        assert_eq!(branch.if_condition.as_deref(), Some("state == 'done'"));
+        assert_eq!(branch.then_steps[0].name, "Done");
+        assert_eq!(branch.else_steps[0].run_if.as_deref(), Some("{{ attempts }} < 3"));
+        assert!(validate_workflow_structure(&workflow).is_ok());
+
+        let mut invalid = workflow.clone();
+        invalid.steps[1].else_steps[0].url.clear();
+        assert!(validate_workflow_structure(&invalid).is_err());
+
+        let mut invalid = workflow;
+        invalid.steps[1].if_condition = None;
+        assert!(validate_workflow_structure(&invalid).is_err());
+    }
+
+    #[test]
+    fn test_parse_body_assertions() {
+        let yaml = r#"
+name: "Typed"
+steps:
+  - name: "Map form"
+    url: "/items"
+    assert:
+      body:
+        success: true
+        count: "> 0"
+        items: "length >= 3"
+        name: "matches ^w"
+        id: { op: exists }
+        price: { op: approx, value: 9.99, tolerance: 0.01 }
+        label: "plain text"
+        status: "in progress"
+        state: exists
+        note: "contains x"
+        kind: "type unknown"
+        size: "length >= many"
+        tags: 'contains "beta"'
+  - name: "List form"
+    url: "/items"
+    assert:
+      body:
+        - path: .items
+          op: length_gte
+          value: 1
+        - ".tags contains admin"
+        - ".id"
+"#;
+
+        let workflow: Workflow = serde_yaml::from_str(yaml).unwrap();
+        let body = &workflow.steps[0].assert.body;
+        assert_eq!(body[0], BodyAssertion::new("success", AssertOp::Eq, Some(true.into())));
+        assert_eq!(body[1], BodyAssertion::new("count", AssertOp::Gt, Some(0.into())));
+        assert_eq!(body[2], BodyAssertion::new("items", AssertOp::LengthGte, Some(3.into())));
+        assert_eq!(body[3], BodyAssertion::new("name", AssertOp::Matches, Some("^w".into())));
+        assert_eq!(body[4], BodyAssertion::new("id", AssertOp::Exists, None));
+        assert_eq!(body[5].op, AssertOp::Approx);
+        assert_eq!(body[5].tolerance, Some(0.01));
+        assert_eq!(body[6], BodyAssertion::new("label", AssertOp::Eq, Some("plain text".into())));
+        // Strings that are not a complete operator with a suitable operand stay expected values
+        for (assertion, (path, expected)) in body[7..12].iter().zip([
+            ("status", "in progress"),
+            ("state", "exists"),
+            ("note", "contains x"),
+            ("kind", "type unknown"),
+            ("size", "length >= many"),
+        ]) {
+            assert_eq!(*assertion, BodyAssertion::new(path, AssertOp::Eq, Some(expected.into())));
+        }
+        assert_eq!(body[12], BodyAssertion::new("tags", AssertOp::Contains, Some("beta".into())));
+
+        let body = &workflow.steps[1].assert.body;
+        assert_eq!(body[0], BodyAssertion::new(".items", AssertOp::LengthGte, Some(1.into())));
+        assert_eq!(body[1], BodyAssertion::new(".tags", AssertOp::Contains, Some("admin".into())));
+        assert_eq!(body[2], BodyAssertion::new(".id", AssertOp::Exists, None));
+
+        let invalid = "name: x\nsteps:\n  - name: s\n    url: /\n    assert:\n      body:\n        - \".id bogus 1\"\n";
+        assert!(serde_yaml::from_str::<Workflow>(invalid).is_err());
+    }
 }
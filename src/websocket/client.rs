@@ -289,7 +289,7 @@ impl WsClient {
 
 /// Certificate verifier that accepts all certificates (insecure)
 #[derive(Debug)]
-struct NoVerifier;
+pub(crate) struct NoVerifier;
 
 impl rustls::client::danger::ServerCertVerifier for NoVerifier {
     fn verify_server_cert(
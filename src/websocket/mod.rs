@@ -12,6 +12,7 @@ use crate::cli::Args;
 use crate::cli::parser::ProcessedArgs;
 use crate::context::Environment;
 use crate::errors::QuicpulseError;
+use crate::input::InputItem;
 use crate::status::ExitStatus;
 
 /// Check if request should be treated as WebSocket
@@ -124,19 +125,10 @@ fn determine_mode(args: &Args, env: &Environment) -> WsMode {
     }
 }
 
-/// Main entry point for WebSocket requests
-pub async fn run_websocket(
-    args: &Args,
-    processed: &ProcessedArgs,
-    env: &Environment,
-) -> Result<ExitStatus, QuicpulseError> {
-    use crate::input::InputItem;
-
-    let endpoint = parse_ws_endpoint(&processed.url, args)?;
-    let mode = determine_mode(args, env);
-
+/// Connection options from the arguments and the header items
+pub fn ws_options(args: &Args, items: &[InputItem]) -> Result<WsOptions, QuicpulseError> {
     // Collect headers from request items
-    let headers: Vec<(String, String)> = processed.items.iter()
+    let headers: Vec<(String, String)> = items.iter()
         .filter_map(|item| {
             match item {
                 InputItem::Header { name, value } => Some((name.clone(), value.clone())),
@@ -149,35 +141,6 @@ pub async fn run_websocket(
         })
         .collect();
 
-    // Build JSON message from request items if present
-    let json_body = if processed.items.iter().any(|item| item.is_data()) {
-        let mut obj = serde_json::Map::new();
-        for item in &processed.items {
-            let (key, value) = match item {
-                InputItem::DataField { key, value } => {
-                    (key.clone(), serde_json::json!(value))
-                }
-                InputItem::DataFieldFile { key, path } => {
-                    let content = std::fs::read_to_string(path).unwrap_or_default();
-                    (key.clone(), serde_json::json!(content.trim()))
-                }
-                InputItem::JsonField { key, value } => {
-                    (key.clone(), value.clone())
-                }
-                InputItem::JsonFieldFile { key, path } => {
-                    let content = std::fs::read_to_string(path).unwrap_or_default();
-                    let json_val = serde_json::from_str(&content).unwrap_or(serde_json::json!(content));
-                    (key.clone(), json_val)
-                }
-                _ => continue,
-            };
-            obj.insert(key, value);
-        }
-        Some(serde_json::Value::Object(obj))
-    } else {
-        None
-    };
-
     // Parse binary mode
     let binary_mode = if let Some(ref mode_str) = args.ws_binary {
         Some(mode_str.parse::<BinaryMode>()
@@ -186,14 +149,57 @@ pub async fn run_websocket(
         None
     };
 
-    let options = WsOptions {
+    Ok(WsOptions {
         timeout: args.timeout.map(|t| std::time::Duration::from_secs_f64(t)),
         compress: args.ws_compress,
         binary_mode,
         ping_interval: args.ws_ping_interval.map(std::time::Duration::from_secs),
         max_messages: args.ws_max_messages,
         headers,
-    };
+    })
+}
+
+/// JSON message built from the data items, if there are any
+pub fn json_message(items: &[InputItem]) -> Option<serde_json::Value> {
+    if !items.iter().any(|item| item.is_data()) {
+        return None;
+    }
+
+    let mut obj = serde_json::Map::new();
+    for item in items {
+        let (key, value) = match item {
+            InputItem::DataField { key, value } => {
+                (key.clone(), serde_json::json!(value))
+            }
+            InputItem::DataFieldFile { key, path } => {
+                let content = std::fs::read_to_string(path).unwrap_or_default();
+                (key.clone(), serde_json::json!(content.trim()))
+            }
+            InputItem::JsonField { key, value } => {
+                (key.clone(), value.clone())
+            }
+            InputItem::JsonFieldFile { key, path } => {
+                let content = std::fs::read_to_string(path).unwrap_or_default();
+                let json_val = serde_json::from_str(&content).unwrap_or(serde_json::json!(content));
+                (key.clone(), json_val)
+            }
+            _ => continue,
+        };
+        obj.insert(key, value);
+    }
+    Some(serde_json::Value::Object(obj))
+}
+
+/// Main entry point for WebSocket requests
+pub async fn run_websocket(
+    args: &Args,
+    processed: &ProcessedArgs,
+    env: &Environment,
+) -> Result<ExitStatus, QuicpulseError> {
+    let endpoint = parse_ws_endpoint(&processed.url, args)?;
+    let mode = determine_mode(args, env);
+    let json_body = json_message(&processed.items);
+    let options = ws_options(args, &processed.items)?;
 
     if args.verbose > 0 {
         eprintln!("WebSocket: {}", endpoint.url());
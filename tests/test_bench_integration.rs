@@ -500,3 +500,460 @@ async fn test_bench_with_follow_redirects() {
 
     assert_eq!(response.exit_status, ExitStatus::Success);
 }
+
+// =============================================================================
+// Open-Loop Rate Tests
+// =============================================================================
+
+#[tokio::test]
+async fn test_bench_constant_rate() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/test"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(20)
+        .mount(&mock_server)
+        .await;
+
+    // 20 requests at 50/s take roughly 400ms regardless of server speed
+    let response = http(&[
+        "--bench",
+        "--requests", "20",
+        "--rate", "50/s",
+        "GET",
+        &format!("{}/test", mock_server.uri())
+    ]);
+
+    assert_eq!(response.exit_status, ExitStatus::Success);
+    let output = format!("{}{}", response.stdout, response.stderr);
+    assert!(output.contains("Target rate"), "output: {}", output);
+    assert!(output.contains("Dropped"), "output: {}", output);
+}
+
+#[tokio::test]
+async fn test_bench_rate_stages() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/test"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&mock_server)
+        .await;
+
+    // Ramp to 40/s over 500ms, hold 500ms, ramp down over 500ms: 10 + 20 + 10 requests
+    let response = http(&[
+        "--bench",
+        "--bench-stages", "500ms:40/s,500ms:40/s,500ms:0",
+        "GET",
+        &format!("{}/test", mock_server.uri())
+    ]);
+
+    assert_eq!(response.exit_status, ExitStatus::Success);
+    assert_eq!(mock_server.received_requests().await.unwrap().len(), 40);
+}
+
+#[tokio::test]
+async fn test_bench_invalid_rate() {
+    let response = http_error(&[
+        "--bench",
+        "--rate", "fast",
+        "GET",
+        "http://127.0.0.1:59999/test"
+    ]);
+
+    assert_ne!(response.exit_status, ExitStatus::Success);
+    assert!(response.stderr.contains("Invalid rate"), "stderr: {}", response.stderr);
+}
+
+// =============================================================================
+// Duration and Time-Series Tests
+// =============================================================================
+
+#[tokio::test]
+async fn test_bench_duration_with_timeseries() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/test"))
+        .respond_with(ResponseTemplate::new(200).set_delay(Duration::from_millis(5)))
+        .mount(&mock_server)
+        .await;
+
+    let dir = tempfile::tempdir().unwrap();
+    let series = dir.path().join("series.csv");
+
+    let response = http(&[
+        "--bench",
+        "--bench-duration", "2s",
+        "--concurrency", "2",
+        "--bench-timeseries", series.to_str().unwrap(),
+        "GET",
+        &format!("{}/test", mock_server.uri())
+    ]);
+
+    assert_eq!(response.exit_status, ExitStatus::Success);
+    assert!(response.stderr.contains("duration:"), "stderr: {}", response.stderr);
+
+    // Far more than the default 100 requests fit in two seconds
+    let received = mock_server.received_requests().await.unwrap().len();
+    assert!(received > 100, "received {}", received);
+
+    let content = std::fs::read_to_string(&series).unwrap();
+    let mut lines = content.lines();
+    assert!(lines.next().unwrap().starts_with("interval,elapsed_s,requests"));
+    assert!(lines.count() >= 2, "series: {}", content);
+}
+
+#[tokio::test]
+async fn test_bench_duration_rejects_stages() {
+    let response = http_error(&[
+        "--bench",
+        "--bench-duration", "10s",
+        "--bench-stages", "5s:10/s",
+        "GET",
+        "http://127.0.0.1:59999/test"
+    ]);
+
+    assert_ne!(response.exit_status, ExitStatus::Success);
+    assert!(response.stderr.contains("--bench-stages"), "stderr: {}", response.stderr);
+}
+
+// =============================================================================
+// JSON Output and Baseline Tests
+// =============================================================================
+
+#[tokio::test]
+async fn test_bench_json_output_and_baseline() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/test"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("OK"))
+        .mount(&mock_server)
+        .await;
+    let url = format!("{}/test", mock_server.uri());
+
+    let response = http(&["--bench", "--requests", "20", "--bench-output", "json", "GET", &url]);
+    assert_eq!(response.exit_status, ExitStatus::Success);
+
+    let report: serde_json::Value = serde_json::from_str(&response.stdout)
+        .unwrap_or_else(|e| panic!("stdout is not JSON ({}): {}", e, response.stdout));
+    assert_eq!(report["version"], 1);
+    assert_eq!(report["total_requests"], 20);
+    assert_eq!(report["stats"]["successful_requests"], 20);
+    assert_eq!(report["stats"]["status_codes"]["200"], 20);
+    assert!(report["stats"]["histogram"].as_str().is_some_and(|h| !h.is_empty()));
+    assert!(report.get("baseline").is_none());
+
+    let dir = tempfile::tempdir().unwrap();
+    let baseline_path = dir.path().join("baseline.json");
+
+    // Against a much slower baseline the run is an improvement
+    let mut slow = report.clone();
+    slow["stats"]["requests_per_second"] = serde_json::json!(1.0);
+    for p in ["mean_ms", "p50_ms", "p75_ms", "p90_ms", "p95_ms", "p99_ms"] {
+        slow["stats"]["latency"][p] = serde_json::json!(60000.0);
+    }
+    std::fs::write(&baseline_path, slow.to_string()).unwrap();
+    let response = http(&["--bench", "--requests", "20", "--bench-baseline", baseline_path.to_str().unwrap(), "GET", &url]);
+    assert_eq!(response.exit_status, ExitStatus::Success);
+    assert!(response.stdout.contains("BASELINE COMPARISON"), "stdout: {}", response.stdout);
+
+    // Against an impossibly fast baseline it regresses; its latencies stay generous so only rps does
+    let mut fast = slow.clone();
+    fast["stats"]["requests_per_second"] = serde_json::json!(1.0e9);
+    std::fs::write(&baseline_path, fast.to_string()).unwrap();
+    let response = http_error(&[
+        "--bench", "--requests", "20",
+        "--bench-output", "json",
+        "--bench-baseline", baseline_path.to_str().unwrap(),
+        "GET", &url
+    ]);
+    assert_eq!(response.exit_status, ExitStatus::Error);
+    assert!(response.stderr.contains("Regression: rps"), "stderr: {}", response.stderr);
+    let report: serde_json::Value = serde_json::from_str(&response.stdout).unwrap();
+    assert_eq!(report["baseline"]["regressed"], true);
+
+    // A generous enough threshold lets it pass
+    let response = http(&[
+        "--bench", "--requests", "20",
+        "--bench-baseline", baseline_path.to_str().unwrap(),
+        "--bench-threshold", "rps=100%",
+        "GET", &url
+    ]);
+    assert_eq!(response.exit_status, ExitStatus::Success);
+}
+
+// =============================================================================
+// Data-Driven Benchmark Tests
+// =============================================================================
+
+#[tokio::test]
+async fn test_bench_data_round_robin() {
+    let mock_server = MockServer::start().await;
+
+    for id in ["1", "2", "3"] {
+        Mock::given(method("POST"))
+            .and(path(format!("/users/{}", id)))
+            .and(wiremock::matchers::header("X-User", format!("user{}", id).as_str()))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(4)
+            .mount(&mock_server)
+            .await;
+    }
+
+    let dir = tempfile::tempdir().unwrap();
+    let data = dir.path().join("users.csv");
+    std::fs::write(&data, "id,name\n1,user1\n2,user2\n3,user3\n").unwrap();
+
+    let response = http(&[
+        "--bench",
+        "--requests", "12",
+        "--concurrency", "2",
+        "--bench-data", data.to_str().unwrap(),
+        "POST",
+        &format!("{}/users/{{{{id}}}}", mock_server.uri()),
+        "X-User:{{name}}",
+        "request_id={uuid}",
+    ]);
+
+    assert_eq!(response.exit_status, ExitStatus::Success, "stderr: {}", response.stderr);
+    assert!(response.stderr.contains("round-robin"), "stderr: {}", response.stderr);
+}
+
+#[tokio::test]
+async fn test_bench_magic_values_per_request() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(wiremock::matchers::path_regex(r"^/items/\d+$"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(5)
+        .mount(&mock_server)
+        .await;
+
+    let response = http(&[
+        "--bench",
+        "--requests", "5",
+        "--concurrency", "1",
+        "GET",
+        &format!("{}/items/{{seq:100}}", mock_server.uri()),
+    ]);
+
+    assert_eq!(response.exit_status, ExitStatus::Success);
+    let requests = mock_server.received_requests().await.unwrap();
+    let mut paths: Vec<String> = requests.iter().map(|r| r.url.path().to_string()).collect();
+    paths.sort();
+    assert_eq!(paths, vec!["/items/100", "/items/101", "/items/102", "/items/103", "/items/104"]);
+}
+
+#[tokio::test]
+async fn test_bench_data_missing_file() {
+    let response = http_error(&[
+        "--bench",
+        "--bench-data", "/nonexistent/users.csv",
+        "GET",
+        "http://localhost:1/test",
+    ]);
+
+    assert_ne!(response.exit_status, ExitStatus::Success);
+    assert!(response.stderr.contains("bench data"), "stderr: {}", response.stderr);
+}
+
+// =============================================================================
+// Other Protocols
+// =============================================================================
+
+#[test]
+fn test_bench_http3_requires_https() {
+    let response = http_error(&[
+        "--bench",
+        "--http3",
+        "GET",
+        "http://localhost:1/test",
+    ]);
+
+    assert_ne!(response.exit_status, ExitStatus::Success);
+    assert!(response.stderr.contains("https://"), "stderr: {}", response.stderr);
+}
+
+#[test]
+fn test_bench_grpc_requires_method() {
+    let response = http_error(&[
+        "--bench",
+        "--grpc",
+        "grpc://localhost:1",
+    ]);
+
+    assert_ne!(response.exit_status, ExitStatus::Success);
+    assert!(response.stderr.contains("service and method"), "stderr: {}", response.stderr);
+}
+
+#[test]
+fn test_bench_ws_requires_message() {
+    let response = http_error(&[
+        "--bench",
+        "ws://localhost:1/socket",
+    ]);
+
+    assert_ne!(response.exit_status, ExitStatus::Success);
+    assert!(response.stderr.contains("--ws-send"), "stderr: {}", response.stderr);
+}
+
+// =============================================================================
+// Connection Metrics Tests
+// =============================================================================
+
+#[tokio::test]
+async fn test_bench_no_keepalive_opens_every_connection() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/test"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("OK"))
+        .mount(&mock_server)
+        .await;
+    let url = format!("{}/test", mock_server.uri());
+
+    let response = http(&["--bench", "--requests", "10", "--concurrency", "2", "--bench-output", "json", "GET", &url]);
+    assert_eq!(response.exit_status, ExitStatus::Success);
+    let report: serde_json::Value = serde_json::from_str(&response.stdout).unwrap();
+    let connections = &report["stats"]["connections"];
+    assert_eq!(connections["opened"].as_u64().unwrap() + connections["reused"].as_u64().unwrap(), 10);
+    assert!(connections["reused"].as_u64().unwrap() >= 8, "connections: {}", connections);
+    assert_eq!(connections["ttfb"]["count"], 10);
+
+    let response = http(&[
+        "--bench", "--requests", "10", "--concurrency", "2",
+        "--bench-no-keepalive", "--bench-output", "json",
+        "GET", &url
+    ]);
+    assert_eq!(response.exit_status, ExitStatus::Success);
+    let report: serde_json::Value = serde_json::from_str(&response.stdout).unwrap();
+    assert_eq!(report["stats"]["connections"]["opened"], 10);
+    assert_eq!(report["stats"]["connections"]["reused"], 0);
+    assert_eq!(report["stats"]["connections"]["connect"]["count"], 10);
+}
+
+// =============================================================================
+// Distributed Benchmark Tests
+// =============================================================================
+
+/// Worker process, killed when dropped
+struct Worker {
+    child: std::process::Child,
+    addr: String,
+    /// Kept open so the worker can keep logging
+    _stderr: std::io::BufReader<std::process::ChildStderr>,
+    _env: common::MockEnvironment,
+}
+
+impl Worker {
+    fn start() -> Self {
+        use std::io::BufRead;
+
+        let env = common::MockEnvironment::new();
+        let mut child = std::process::Command::new(env!("CARGO_BIN_EXE_quicpulse"))
+            .args(["--bench-worker", "--listen", "127.0.0.1:0", "--bench-token", "test-token"])
+            .env("QUICPULSE_CONFIG_DIR", env.config_path())
+            .stdin(std::process::Stdio::null())
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::piped())
+            .spawn()
+            .expect("Failed to start worker");
+
+        let mut stderr = std::io::BufReader::new(child.stderr.take().unwrap());
+        let mut line = String::new();
+        stderr.read_line(&mut line).unwrap();
+        let line = common::strip_colors(&line);
+        let addr = line.trim().rsplit(' ').next().unwrap().to_string();
+        assert!(line.contains("listening on"), "worker said: {}", line);
+
+        Self { child, addr, _stderr: stderr, _env: env }
+    }
+}
+
+impl Drop for Worker {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+#[tokio::test]
+async fn test_bench_distributed_across_local_workers() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/test"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("OK"))
+        .mount(&mock_server)
+        .await;
+    let url = format!("{}/test", mock_server.uri());
+
+    let workers = [Worker::start(), Worker::start(), Worker::start()];
+    let addrs: Vec<&str> = workers.iter().map(|worker| worker.addr.as_str()).collect();
+    let addrs = addrs.join(",");
+
+    let response = http(&[
+        "--bench", "--requests", "31", "--concurrency", "2",
+        "--bench-workers", &addrs, "--bench-token", "test-token", "--bench-output", "json",
+        "GET", &url
+    ]);
+    assert_eq!(response.exit_status, ExitStatus::Success, "stderr: {}", response.stderr);
+
+    let report: serde_json::Value = serde_json::from_str(&response.stdout).unwrap();
+    assert_eq!(report["total_requests"], 31);
+    assert_eq!(report["concurrency"], 6);
+    assert_eq!(report["stats"]["successful_requests"], 31);
+    assert!(report["stats"]["histogram"].as_str().is_some_and(|h| !h.is_empty()));
+
+    let received = mock_server.received_requests().await.unwrap();
+    assert_eq!(received.len(), 31);
+}
+
+#[tokio::test]
+async fn test_bench_distributed_sends_expanded_url() {
+    use std::io::Write;
+
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/test"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("OK"))
+        .mount(&mock_server)
+        .await;
+
+    // The workers never see the env file, only the URL it expands to
+    let mut env_file = tempfile::NamedTempFile::new().unwrap();
+    writeln!(env_file, "BASE_URL={}", mock_server.uri()).unwrap();
+    let env_file = env_file.path().to_str().unwrap().to_string();
+
+    let worker = Worker::start();
+    let response = http(&[
+        "--bench", "--requests", "4", "--concurrency", "1", "--env-file", &env_file,
+        "--bench-workers", &worker.addr, "--bench-token", "test-token", "--bench-output", "json",
+        "GET", "{{BASE_URL}}/test"
+    ]);
+    assert_eq!(response.exit_status, ExitStatus::Success, "stderr: {}", response.stderr);
+
+    let received = mock_server.received_requests().await.unwrap();
+    assert_eq!(received.len(), 4);
+}
+
+#[test]
+fn test_bench_unreachable_worker() {
+    let response = http_error(&["--bench", "--bench-workers", "127.0.0.1:1", "--bench-token", "t", "http://localhost:1/"]);
+
+    assert_ne!(response.exit_status, ExitStatus::Success);
+    assert!(response.stderr.contains("Cannot reach worker"), "stderr: {}", response.stderr);
+}
+
+#[test]
+fn test_bench_worker_requires_token() {
+    let response = http_error(&["--bench-worker", "--listen", "127.0.0.1:0"]);
+
+    assert_ne!(response.exit_status, ExitStatus::Success);
+    assert!(response.stderr.contains("--bench-token"), "stderr: {}", response.stderr);
+}
@@ -21,6 +21,7 @@ fn test_route_exact_path() {
         priority: 0,
         enabled: true,
         name: None,
+        ..Default::default()
     };
 
     let route = Route::new(config).unwrap();
@@ -48,6 +49,7 @@ fn test_route_with_parameter() {
         priority: 0,
         enabled: true,
         name: None,
+        ..Default::default()
     };
 
     let route = Route::new(config).unwrap();
@@ -78,6 +80,7 @@ fn test_route_with_multiple_parameters() {
         priority: 0,
         enabled: true,
         name: None,
+        ..Default::default()
     };
 
     let route = Route::new(config).unwrap();
@@ -98,6 +101,7 @@ fn test_route_wildcard_single() {
         priority: 0,
         enabled: true,
         name: None,
+        ..Default::default()
     };
 
     let route = Route::new(config).unwrap();
@@ -121,6 +125,7 @@ fn test_route_wildcard_double() {
         priority: 0,
         enabled: true,
         name: None,
+        ..Default::default()
     };
 
     let route = Route::new(config).unwrap();
@@ -142,6 +147,7 @@ fn test_route_method_any() {
         priority: 0,
         enabled: true,
         name: None,
+        ..Default::default()
     };
 
     let route = Route::new(config).unwrap();
@@ -165,6 +171,7 @@ fn test_route_disabled() {
         priority: 0,
         enabled: false,
         name: None,
+        ..Default::default()
     };
 
     let route = Route::new(config).unwrap();
@@ -369,6 +376,7 @@ fn test_mock_server_config_validate_invalid_route() {
             priority: 0,
             enabled: true,
             name: None,
+            ..Default::default()
         }],
         ..Default::default()
     };
@@ -551,6 +559,7 @@ async fn test_mock_server_json_response() {
             priority: 0,
             enabled: true,
             name: None,
+            ..Default::default()
         });
 
     let server = MockServer::new(config).unwrap();
@@ -616,3 +625,783 @@ async fn test_mock_server_cors_headers() {
 
     handle.abort();
 }
+
+#[tokio::test]
+async fn test_mock_server_records_requests() {
+    use quicpulse::mock::{MockServer, MockServerConfig};
+    use quicpulse::mock::routes::RouteConfig;
+    use quicpulse::har::parser::load_har;
+    use std::net::TcpListener;
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    drop(listener);
+
+    let dir = tempfile::tempdir().unwrap();
+    let record_path = dir.path().join("recorded.har");
+
+    let mut config = MockServerConfig::new()
+        .with_port(port)
+        .add_route(RouteConfig::get("/health", "OK"));
+    config.record_to = Some(record_path.to_string_lossy().to_string());
+
+    let server = MockServer::new(config).unwrap();
+
+    let handle = tokio::spawn(async move {
+        let _ = server.run().await;
+    });
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+    for path in ["/health?check=1", "/missing"] {
+        let response = timeout(
+            Duration::from_secs(2),
+            client.get(format!("http://127.0.0.1:{}{}", port, path)).send()
+        ).await;
+        assert!(matches!(response, Ok(Ok(_))));
+    }
+    let h2c = reqwest::Client::builder().http2_prior_knowledge().build().unwrap();
+    h2c.get(format!("http://127.0.0.1:{}/health", port)).send().await.unwrap();
+
+    // Recording happens after the response is flushed
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let har = load_har(&record_path).unwrap();
+    assert_eq!(har.log.entries.len(), 3);
+    assert!(har.log.entries[0].request.url.ends_with("/health?check=1"));
+    assert_eq!(har.log.entries[0].request.http_version, "HTTP/1.1");
+    assert_eq!(har.log.entries[0].response.status, 200);
+    assert_eq!(har.log.entries[0].response.content.text.as_deref(), Some("OK"));
+    assert_eq!(har.log.entries[1].response.status, 404);
+    assert_eq!(har.log.entries[2].request.http_version, "HTTP/2");
+    assert_eq!(har.log.entries[2].response.http_version, "HTTP/2");
+
+    handle.abort();
+}
+
+#[tokio::test]
+async fn test_mock_server_proxies_unmatched_and_records_routes() {
+    use quicpulse::mock::{MockServer, MockServerConfig};
+    use quicpulse::mock::routes::RouteConfig;
+    use std::net::TcpListener;
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, ResponseTemplate};
+
+    let upstream = wiremock::MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/users"))
+        .and(query_param("page", "2"))
+        .respond_with(ResponseTemplate::new(200)
+            .insert_header("X-Upstream", "yes")
+            .set_body_json(serde_json::json!([{"id": 7}])))
+        .mount(&upstream)
+        .await;
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    drop(listener);
+
+    let dir = tempfile::tempdir().unwrap();
+    let routes_path = dir.path().join("recorded.yaml");
+
+    let mut config = MockServerConfig::new()
+        .with_port(port)
+        .add_route(RouteConfig::get("/local", "mocked"));
+    config.proxy_to = Some(upstream.uri());
+    config.record_to = Some(routes_path.to_string_lossy().to_string());
+
+    let server = MockServer::new(config).unwrap();
+
+    let handle = tokio::spawn(async move {
+        let _ = server.run().await;
+    });
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+
+    // Matched routes are still served locally
+    let resp = timeout(
+        Duration::from_secs(2),
+        client.get(format!("http://127.0.0.1:{}/local", port)).send()
+    ).await.unwrap().unwrap();
+    assert_eq!(resp.text().await.unwrap(), "mocked");
+
+    // Unmatched requests reach the upstream, query string included
+    let resp = timeout(
+        Duration::from_secs(2),
+        client.get(format!("http://127.0.0.1:{}/api/users?page=2", port)).send()
+    ).await.unwrap().unwrap();
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.headers().get("x-upstream").unwrap(), "yes");
+    let json: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(json[0]["id"], 7);
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    handle.abort();
+
+    // Only the proxied exchange becomes a route, and the file loads as a mock config
+    let recorded = MockServerConfig::load(&routes_path).unwrap();
+    assert_eq!(recorded.routes.len(), 1);
+    assert_eq!(recorded.routes[0].path, "/api/users");
+    assert_eq!(recorded.routes[0].response.json, Some(serde_json::json!([{"id": 7}])));
+    assert!(MockServer::new(recorded).is_ok());
+}
+
+#[tokio::test]
+async fn test_mock_server_https_self_signed() {
+    use quicpulse::mock::{MockServer, MockServerConfig};
+    use quicpulse::mock::config::TlsConfig;
+    use quicpulse::mock::routes::RouteConfig;
+    use std::net::TcpListener;
+
+    for http2 in [false, true] {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        drop(listener);
+
+        let mut config = MockServerConfig::new()
+            .with_port(port)
+            .add_route(RouteConfig::get("/secure", "secret"));
+        config.tls = Some(TlsConfig {
+            self_signed: true,
+            http2,
+            ..Default::default()
+        });
+
+        let server = MockServer::new(config).unwrap();
+        assert_eq!(server.scheme(), "https");
+        let ca_pem = std::fs::read(server.ca_cert_path().unwrap()).unwrap();
+
+        let handle = tokio::spawn(async move {
+            let _ = server.run().await;
+        });
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        let client = reqwest::Client::builder()
+            .add_root_certificate(reqwest::Certificate::from_pem(&ca_pem).unwrap())
+            .build()
+            .unwrap();
+        let resp = timeout(
+            Duration::from_secs(2),
+            client.get(format!("https://localhost:{}/secure", port)).send()
+        ).await.unwrap().unwrap();
+
+        let expected_version = if http2 { reqwest::Version::HTTP_2 } else { reqwest::Version::HTTP_11 };
+        assert_eq!(resp.version(), expected_version);
+        assert_eq!(resp.status(), 200);
+        assert_eq!(resp.text().await.unwrap(), "secret");
+
+        handle.abort();
+    }
+}
+
+/// Start a mock server with an echo route on a free port
+async fn start_echo_server() -> (u16, tokio::task::JoinHandle<()>) {
+    use quicpulse::mock::{MockServer, MockServerConfig};
+    use quicpulse::mock::routes::{RouteConfig, HttpMethod, ResponseConfig};
+    use std::net::TcpListener;
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    drop(listener);
+
+    let mut config = MockServerConfig::new()
+        .with_port(port)
+        .add_route(RouteConfig::get("/health", "OK"))
+        .add_route(RouteConfig {
+            method: HttpMethod::Post,
+            path: "/echo".to_string(),
+            response: ResponseConfig {
+                body: Some("{{body}}".to_string()),
+                template: true,
+                ..Default::default()
+            },
+            priority: 0,
+            enabled: true,
+            name: None,
+            ..Default::default()
+        });
+    config.log_requests = false;
+
+    let server = MockServer::new(config).unwrap();
+    let handle = tokio::spawn(async move {
+        let _ = server.run().await;
+    });
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    (port, handle)
+}
+
+#[tokio::test]
+async fn test_mock_server_large_chunked_body() {
+    let (port, handle) = start_echo_server().await;
+
+    // A streamed body is sent with Transfer-Encoding: chunked
+    let payload = "x".repeat(1024 * 1024);
+    let chunks: Vec<Result<String, std::io::Error>> = payload
+        .as_bytes()
+        .chunks(64 * 1024)
+        .map(|c| Ok(String::from_utf8(c.to_vec()).unwrap()))
+        .collect();
+    let body = reqwest::Body::wrap_stream(futures::stream::iter(chunks));
+
+    let client = reqwest::Client::new();
+    let resp = timeout(
+        Duration::from_secs(5),
+        client.post(format!("http://127.0.0.1:{}/echo", port)).body(body).send()
+    ).await.unwrap().unwrap();
+
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.text().await.unwrap().len(), payload.len());
+
+    handle.abort();
+}
+
+#[tokio::test]
+async fn test_mock_server_keepalive_pipelining() {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let (port, handle) = start_echo_server().await;
+
+    let mut stream = tokio::net::TcpStream::connect(("127.0.0.1", port)).await.unwrap();
+    stream.write_all(
+        b"GET /health HTTP/1.1\r\nHost: localhost\r\n\r\n\
+          POST /echo HTTP/1.1\r\nHost: localhost\r\nContent-Length: 4\r\n\r\nping\
+          GET /health HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n"
+    ).await.unwrap();
+
+    let mut response = Vec::new();
+    timeout(Duration::from_secs(2), stream.read_to_end(&mut response)).await.unwrap().unwrap();
+    let response = String::from_utf8_lossy(&response);
+
+    // Three responses on one connection, in request order
+    assert_eq!(response.matches("HTTP/1.1 200 OK").count(), 3);
+    let ok = response.find("\r\n\r\nOK").unwrap();
+    let ping = response.find("ping").unwrap();
+    assert!(ok < ping);
+
+    handle.abort();
+}
+
+#[tokio::test]
+async fn test_mock_server_expect_continue() {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let (port, handle) = start_echo_server().await;
+
+    let mut stream = tokio::net::TcpStream::connect(("127.0.0.1", port)).await.unwrap();
+    stream.write_all(
+        b"POST /echo HTTP/1.1\r\nHost: localhost\r\nContent-Length: 5\r\nExpect: 100-continue\r\nConnection: close\r\n\r\n"
+    ).await.unwrap();
+
+    let mut interim = [0u8; 64];
+    let n = timeout(Duration::from_secs(2), stream.read(&mut interim)).await.unwrap().unwrap();
+    assert!(String::from_utf8_lossy(&interim[..n]).starts_with("HTTP/1.1 100 Continue"));
+
+    stream.write_all(b"hello").await.unwrap();
+    let mut response = Vec::new();
+    timeout(Duration::from_secs(2), stream.read_to_end(&mut response)).await.unwrap().unwrap();
+    let response = String::from_utf8_lossy(&response);
+    assert!(response.contains("200 OK"));
+    assert!(response.ends_with("hello"));
+
+    handle.abort();
+}
+
+#[tokio::test]
+async fn test_mock_server_h2c_prior_knowledge() {
+    let (port, handle) = start_echo_server().await;
+
+    let client = reqwest::Client::builder()
+        .http2_prior_knowledge()
+        .build()
+        .unwrap();
+    let resp = timeout(
+        Duration::from_secs(2),
+        client.get(format!("http://127.0.0.1:{}/health", port)).send()
+    ).await.unwrap().unwrap();
+
+    assert_eq!(resp.version(), reqwest::Version::HTTP_2);
+    assert_eq!(resp.text().await.unwrap(), "OK");
+
+    handle.abort();
+}
+
+#[tokio::test]
+async fn test_mock_server_request_matchers() {
+    use quicpulse::mock::{MockServer, MockServerConfig};
+    use quicpulse::mock::routes::RouteConfig;
+    use std::net::TcpListener;
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    drop(listener);
+
+    let mut get_user = RouteConfig::post_json("/graphql", serde_json::json!({"data": {"user": {"id": 1}}}));
+    get_user.matcher.body_json_partial = Some(serde_json::json!({"operationName": "GetUser"}));
+
+    let mut list_users = RouteConfig::post_json("/graphql", serde_json::json!({"data": {"users": []}}));
+    list_users.matcher.body_jsonpath = Some("$.query".to_string());
+    list_users.matcher.body_regex = Some("users".to_string());
+
+    let mut beta = RouteConfig::get("/items", "beta");
+    beta.matcher.headers.insert("X-Beta".to_string(), "1".to_string());
+    beta.matcher.query.insert("page".to_string(), "2".to_string());
+
+    let mut config = MockServerConfig::new()
+        .with_port(port)
+        .add_route(RouteConfig::post_json("/graphql", serde_json::json!({"errors": []})))
+        .add_route(get_user)
+        .add_route(list_users)
+        .add_route(RouteConfig::get("/items", "stable"))
+        .add_route(beta);
+    config.log_requests = false;
+
+    let server = MockServer::new(config).unwrap();
+    let handle = tokio::spawn(async move {
+        let _ = server.run().await;
+    });
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+    let url = format!("http://127.0.0.1:{}", port);
+
+    let body: serde_json::Value = client.post(format!("{}/graphql", url))
+        .json(&serde_json::json!({"operationName": "GetUser", "variables": {"id": 1}}))
+        .send().await.unwrap().json().await.unwrap();
+    assert_eq!(body["data"]["user"]["id"], 1);
+
+    let body: serde_json::Value = client.post(format!("{}/graphql", url))
+        .json(&serde_json::json!({"query": "{ users { id } }"}))
+        .send().await.unwrap().json().await.unwrap();
+    assert!(body["data"]["users"].is_array());
+
+    let body: serde_json::Value = client.post(format!("{}/graphql", url))
+        .json(&serde_json::json!({"query": "{ orders { id } }"}))
+        .send().await.unwrap().json().await.unwrap();
+    assert!(body["errors"].is_array());
+
+    let text = client.get(format!("{}/items?page=2", url))
+        .header("x-beta", "1")
+        .send().await.unwrap().text().await.unwrap();
+    assert_eq!(text, "beta");
+
+    let text = client.get(format!("{}/items?page=2", url))
+        .send().await.unwrap().text().await.unwrap();
+    assert_eq!(text, "stable");
+
+    handle.abort();
+}
+
+#[tokio::test]
+async fn test_mock_server_scenarios_and_sequences() {
+    use quicpulse::mock::{MockServer, MockServerConfig};
+    use quicpulse::mock::routes::{RouteConfig, ResponseConfig, HttpMethod, SequenceMode};
+    use std::net::TcpListener;
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    drop(listener);
+
+    let yaml = r#"
+routes:
+  - method: POST
+    path: /jobs
+    scenario: job
+    new_state: Created
+    response:
+      status: 201
+      body: created
+  - method: GET
+    path: /jobs/1
+    scenario: job
+    required_state: Created
+    responses:
+      - body: pending
+      - body: pending
+      - body: done
+  - method: GET
+    path: /jobs/1
+    response:
+      status: 404
+      body: no job
+"#;
+    let mut config = MockServerConfig::from_yaml(yaml).unwrap();
+    config.port = port;
+    config.log_requests = false;
+    config.admin = true;
+    config.routes.push(RouteConfig {
+        method: HttpMethod::Get,
+        path: "/flaky".to_string(),
+        responses: vec![ResponseConfig::error(503, "retry"), ResponseConfig::text("ok")],
+        sequence: SequenceMode::Cycle,
+        ..Default::default()
+    });
+
+    let server = MockServer::new(config).unwrap();
+    let handle = tokio::spawn(async move {
+        let _ = server.run().await;
+    });
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+    let url = format!("http://127.0.0.1:{}", port);
+    let get = |path: &'static str| {
+        let client = client.clone();
+        let url = url.clone();
+        async move {
+            let resp = client.get(format!("{}{}", url, path)).send().await.unwrap();
+            (resp.status().as_u16(), resp.text().await.unwrap())
+        }
+    };
+
+    assert_eq!(get("/jobs/1").await, (404, "no job".to_string()));
+
+    let resp = client.post(format!("{}/jobs", url)).send().await.unwrap();
+    assert_eq!(resp.status().as_u16(), 201);
+
+    assert_eq!(get("/jobs/1").await.1, "pending");
+    assert_eq!(get("/jobs/1").await.1, "pending");
+    assert_eq!(get("/jobs/1").await.1, "done");
+    assert_eq!(get("/jobs/1").await.1, "done");
+
+    assert_eq!(get("/flaky").await.0, 503);
+    assert_eq!(get("/flaky").await.0, 200);
+    assert_eq!(get("/flaky").await.0, 503);
+
+    let scenarios: serde_json::Value = client.get(format!("{}/__admin/scenarios", url))
+        .send().await.unwrap().json().await.unwrap();
+    assert_eq!(scenarios["scenarios"]["job"], "Created");
+
+    let resp = client.post(format!("{}/__admin/scenarios/reset", url)).send().await.unwrap();
+    assert_eq!(resp.status().as_u16(), 200);
+    assert_eq!(get("/jobs/1").await.0, 404);
+
+    client.post(format!("{}/jobs", url)).send().await.unwrap();
+    assert_eq!(get("/jobs/1").await.1, "pending");
+
+    handle.abort();
+}
+
+#[tokio::test]
+async fn test_mock_server_admin_api() {
+    use quicpulse::mock::{MockServer, MockServerConfig};
+    use quicpulse::mock::routes::RouteConfig;
+    use std::net::TcpListener;
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    drop(listener);
+
+    let mut config = MockServerConfig::new()
+        .with_port(port)
+        .add_route(RouteConfig::get("/health", "OK"));
+    config.log_requests = false;
+    config.admin = true;
+
+    let server = MockServer::new(config).unwrap();
+    let handle = tokio::spawn(async move {
+        let _ = server.run().await;
+    });
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+    let url = format!("http://127.0.0.1:{}", port);
+    let admin = format!("{}/__admin", url);
+
+    // Add a route at runtime
+    let resp = client.post(format!("{}/routes", admin))
+        .json(&serde_json::json!({
+            "method": "POST",
+            "path": "/orders",
+            "response": {"status": 201, "json": {"id": 7}}
+        }))
+        .send().await.unwrap();
+    assert_eq!(resp.status().as_u16(), 201);
+    let added: serde_json::Value = resp.json().await.unwrap();
+    let id = added["id"].as_str().unwrap().to_string();
+
+    let routes: serde_json::Value = client.get(format!("{}/routes", admin))
+        .send().await.unwrap().json().await.unwrap();
+    assert_eq!(routes["routes"].as_array().unwrap().len(), 2);
+
+    let resp = client.post(format!("{}/orders", url))
+        .json(&serde_json::json!({"sku": "A1"}))
+        .send().await.unwrap();
+    assert_eq!(resp.status().as_u16(), 201);
+    client.post(format!("{}/orders", url))
+        .json(&serde_json::json!({"sku": "B2"}))
+        .send().await.unwrap();
+    client.get(format!("{}/health", url)).send().await.unwrap();
+
+    // Admin requests are not journaled
+    let journal: serde_json::Value = client.get(format!("{}/requests", admin))
+        .send().await.unwrap().json().await.unwrap();
+    assert_eq!(journal["total"], 3);
+
+    let verified: serde_json::Value = client.post(format!("{}/requests/verify", admin))
+        .json(&serde_json::json!({
+            "method": "POST",
+            "path": "/orders",
+            "body_json_partial": {"sku": "A1"},
+            "count": 1
+        }))
+        .send().await.unwrap().json().await.unwrap();
+    assert_eq!(verified["count"], 1);
+    assert_eq!(verified["verified"], true);
+
+    let verified: serde_json::Value = client.post(format!("{}/requests/verify", admin))
+        .json(&serde_json::json!({"path": "/orders", "count": 1}))
+        .send().await.unwrap().json().await.unwrap();
+    assert_eq!(verified["count"], 2);
+    assert_eq!(verified["verified"], false);
+
+    // Remove the route again
+    let resp = client.delete(format!("{}/routes/{}", admin, id)).send().await.unwrap();
+    assert_eq!(resp.status().as_u16(), 200);
+    let resp = client.post(format!("{}/orders", url)).send().await.unwrap();
+    assert_eq!(resp.status().as_u16(), 404);
+    let resp = client.delete(format!("{}/routes/{}", admin, id)).send().await.unwrap();
+    assert_eq!(resp.status().as_u16(), 404);
+
+    let resp = client.delete(format!("{}/requests", admin)).send().await.unwrap();
+    assert_eq!(resp.status().as_u16(), 200);
+    let journal: serde_json::Value = client.get(format!("{}/requests", admin))
+        .send().await.unwrap().json().await.unwrap();
+    assert_eq!(journal["total"], 0);
+
+    let resp = client.post(format!("{}/routes", admin))
+        .body(r#"{"path": "/bad", "match": {"body_regex": "("}}"#)
+        .send().await.unwrap();
+    assert_eq!(resp.status().as_u16(), 400);
+
+    // Routes added at runtime cannot serve files
+    let resp = client.post(format!("{}/routes", admin))
+        .body(r#"{"path": "/leak", "response": {"body_file": "/etc/passwd"}}"#)
+        .send().await.unwrap();
+    assert_eq!(resp.status().as_u16(), 400);
+
+    handle.abort();
+}
+
+#[tokio::test]
+async fn test_mock_server_admin_api_opt_in() {
+    use quicpulse::mock::{MockServer, MockServerConfig};
+    use std::net::TcpListener;
+
+    let start = |admin: bool, token: Option<&str>| {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        drop(listener);
+
+        let mut config = MockServerConfig::new().with_port(port);
+        config.log_requests = false;
+        config.admin = admin;
+        config.admin_token = token.map(String::from);
+        let server = MockServer::new(config).unwrap();
+        let handle = tokio::spawn(async move {
+            let _ = server.run().await;
+        });
+        (format!("http://127.0.0.1:{}/__admin/routes", port), handle)
+    };
+    let (disabled, disabled_handle) = start(false, None);
+    let (protected, protected_handle) = start(true, Some("secret"));
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+
+    // Without --mock-admin the prefix is just another unmatched path
+    let resp = client.get(&disabled).send().await.unwrap();
+    assert_eq!(resp.status().as_u16(), 404);
+
+    let resp = client.get(&protected).send().await.unwrap();
+    assert_eq!(resp.status().as_u16(), 401);
+    let resp = client.get(&protected).bearer_auth("wrong").send().await.unwrap();
+    assert_eq!(resp.status().as_u16(), 401);
+    let resp = client.get(&protected).bearer_auth("secret").send().await.unwrap();
+    assert_eq!(resp.status().as_u16(), 200);
+
+    disabled_handle.abort();
+    protected_handle.abort();
+}
+
+#[tokio::test]
+async fn test_mock_server_body_limit() {
+    use quicpulse::mock::routes::RouteConfig;
+    use quicpulse::mock::{MockServer, MockServerConfig};
+    use std::net::TcpListener;
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    drop(listener);
+
+    let mut config = MockServerConfig::new()
+        .with_port(port)
+        .add_route(RouteConfig::post_json("/upload", serde_json::json!({"stored": true})));
+    config.log_requests = false;
+    config.max_body_bytes = 16;
+    let server = MockServer::new(config).unwrap();
+    let handle = tokio::spawn(async move {
+        let _ = server.run().await;
+    });
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+    let url = format!("http://127.0.0.1:{}/upload", port);
+
+    let resp = client.post(&url).body("x".repeat(16)).send().await.unwrap();
+    assert_eq!(resp.status().as_u16(), 200);
+
+    let resp = client.post(&url).body("x".repeat(17)).send().await.unwrap();
+    assert_eq!(resp.status().as_u16(), 413);
+
+    handle.abort();
+}
+
+#[tokio::test]
+async fn test_mock_server_from_openapi_with_validation() {
+    use quicpulse::mock::{MockServer, MockServerConfig};
+    use std::io::Write;
+    use std::net::TcpListener;
+
+    let spec = r##"
+openapi: 3.0.0
+info:
+  title: Users
+  version: "1.0"
+paths:
+  /users/{id}:
+    get:
+      responses:
+        "200":
+          description: ok
+          content:
+            application/json:
+              schema:
+                $ref: "#/components/schemas/User"
+  /users:
+    post:
+      requestBody:
+        required: true
+        content:
+          application/json:
+            schema:
+              $ref: "#/components/schemas/User"
+      responses:
+        "201":
+          description: created
+          content:
+            application/json:
+              example: {"id": 42, "name": "Ada"}
+components:
+  schemas:
+    User:
+      type: object
+      required: [name]
+      properties:
+        id:
+          type: integer
+        name:
+          type: string
+"##;
+    let mut file = tempfile::Builder::new().suffix(".yaml").tempfile().unwrap();
+    file.write_all(spec.as_bytes()).unwrap();
+    let spec = quicpulse::openapi::parse_spec(file.path()).unwrap();
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    drop(listener);
+
+    let mut config = MockServerConfig::new().with_port(port);
+    config.log_requests = false;
+    config.routes = quicpulse::mock::openapi::routes_from_spec(&spec, true);
+
+    let server = MockServer::new(config).unwrap();
+    let handle = tokio::spawn(async move {
+        let _ = server.run().await;
+    });
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+    let url = format!("http://127.0.0.1:{}", port);
+
+    let user: serde_json::Value = client.get(format!("{}/users/7", url))
+        .send().await.unwrap().json().await.unwrap();
+    assert!(user["id"].is_i64());
+    assert!(user["name"].is_string());
+
+    let resp = client.post(format!("{}/users", url))
+        .json(&serde_json::json!({"name": "Ada"}))
+        .send().await.unwrap();
+    assert_eq!(resp.status().as_u16(), 201);
+    let created: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(created["id"], 42);
+
+    let resp = client.post(format!("{}/users", url))
+        .json(&serde_json::json!({"id": "seven"}))
+        .send().await.unwrap();
+    assert_eq!(resp.status().as_u16(), 400);
+    let error: serde_json::Value = resp.json().await.unwrap();
+    let violations = error["violations"].as_array().unwrap();
+    assert_eq!(violations.len(), 2);
+    assert!(violations.iter().any(|v| v.as_str().unwrap().starts_with("/id: ")));
+
+    handle.abort();
+}
+
+#[tokio::test]
+async fn test_mock_server_hot_reloads_config() {
+    use quicpulse::mock::{MockServer, MockServerConfig};
+    use std::net::TcpListener;
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    drop(listener);
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("mock.yaml");
+    let body_path = dir.path().join("body.json");
+    std::fs::write(&body_path, r#"{"v": 1}"#).unwrap();
+    std::fs::write(&path, format!(
+        "log_requests: false\nroutes:\n  - path: /greeting\n    response:\n      body: hello\n  - path: /data\n    response:\n      body_file: {}\n",
+        body_path.display()
+    )).unwrap();
+
+    let mut config = MockServerConfig::load(&path).unwrap();
+    config.port = port;
+
+    let server = MockServer::new(config).unwrap();
+    let handle = tokio::spawn(async move {
+        let _ = server.run().await;
+    });
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+    let get = |path: &'static str| {
+        let client = client.clone();
+        async move {
+            let resp = client.get(format!("http://127.0.0.1:{}{}", port, path)).send().await.unwrap();
+            (resp.status().as_u16(), resp.text().await.unwrap())
+        }
+    };
+
+    assert_eq!(get("/greeting").await.1, "hello");
+
+    // Change the route
+    std::fs::write(&path, format!(
+        "log_requests: false\nroutes:\n  - path: /greeting\n    response:\n      body: hello again\n  - path: /data\n    response:\n      body_file: {}\n",
+        body_path.display()
+    )).unwrap();
+    tokio::time::sleep(Duration::from_millis(1200)).await;
+    assert_eq!(get("/greeting").await.1, "hello again");
+
+    // A broken config keeps the current routes
+    std::fs::write(&path, "routes:\n  - path: /greeting/:\n    response: {}\n").unwrap();
+    tokio::time::sleep(Duration::from_millis(1200)).await;
+    assert_eq!(get("/greeting").await.1, "hello again");
+
+    handle.abort();
+}
@@ -284,3 +284,27 @@ fn test_mock_multiple_options_combined() {
 
     assert_eq!(response.exit_status, ExitStatus::Success);
 }
+
+#[test]
+fn test_mock_tls_options() {
+    let response = http(&[
+        "--mock",
+        "--mock-tls-self-signed",
+        "--mock-tls-h2",
+        "--help"
+    ]);
+
+    assert_eq!(response.exit_status, ExitStatus::Success);
+}
+
+#[test]
+fn test_mock_openapi_options() {
+    let response = http(&[
+        "--mock",
+        "--mock-openapi", "spec.yaml",
+        "--mock-validate",
+        "--help"
+    ]);
+
+    assert_eq!(response.exit_status, ExitStatus::Success);
+}
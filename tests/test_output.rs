@@ -450,3 +450,78 @@ async fn test_quiet_check_status_warning() {
     // With -q and --check-status on error, stderr should have warning
     assert!(r.stderr.contains("500") || r.stderr.contains("warning") || r.exit_code != 0);
 }
+
+// ============================================================================
+// Metadata Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_meta_prints_timings() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/get"))
+        .respond_with(ResponseTemplate::new(200)
+            .set_body_json(serde_json::json!({"status": "ok"})))
+        .mount(&server)
+        .await;
+
+    let url = format!("{}/get", server.uri());
+    let r = http(&["--meta", "GET", &url]);
+
+    assert!(r.stdout.contains("Elapsed time:"));
+    assert!(r.stdout.contains("TCP connect:"));
+    assert!(r.stdout.contains("Waiting (TTFB):"));
+    assert!(r.stdout.contains("Connection: new"));
+    assert!(r.stdout.contains("Remote address: 127.0.0.1"));
+    assert!(!r.stdout.contains("\"status\""));
+}
+
+// ============================================================================
+// Write-Out Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_write_out_with_quiet() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/items/7"))
+        .respond_with(ResponseTemplate::new(200)
+            .set_body_json(serde_json::json!({"id": 7, "name": "widget"})))
+        .mount(&server)
+        .await;
+
+    let url = format!("{}/items/7", server.uri());
+    let r = http(&["-qq", "--write-out", "{status} {header.content-type} {json:.name}\\n", "GET", &url]);
+
+    assert_eq!(r.stdout, "200 application/json widget\n");
+}
+
+#[tokio::test]
+async fn test_write_out_file() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/get"))
+        .respond_with(ResponseTemplate::new(204))
+        .mount(&server)
+        .await;
+
+    let dir = tempfile::tempdir().unwrap();
+    let out = dir.path().join("write-out.txt");
+    let url = format!("{}/get", server.uri());
+    for _ in 0..2 {
+        let r = http(&["-qq", "-w", "{status} {num_connects}\\n", "--write-out-file", out.to_str().unwrap(), &url]);
+        assert!(r.stdout.is_empty());
+    }
+
+    assert_eq!(std::fs::read_to_string(&out).unwrap(), "204 1\n204 1\n");
+}
+
+#[test]
+fn test_write_out_unknown_variable() {
+    let r = http_error(&["--write-out", "{nope}", "http://127.0.0.1:1/"]);
+    assert!(r.exit_code != 0);
+    assert!(r.stderr.contains("Unknown --write-out variable"));
+}
@@ -335,3 +335,43 @@ async fn test_303_redirect_converts_to_get() {
     
     assert!(r.contains(HTTP_OK) || r.contains("Result"));
 }
+
+// ============================================================================
+// Write-Out Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_write_out_counts_followed_redirects() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/redirect/2"))
+        .respond_with(ResponseTemplate::new(302)
+            .insert_header("Location", "/redirect/1"))
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/redirect/1"))
+        .respond_with(ResponseTemplate::new(302)
+            .insert_header("Location", "/get"))
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/get"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&server)
+        .await;
+
+    let url = format!("{}/redirect/2", server.uri());
+
+    // Followed by the client itself, and manually with --all
+    let r = http(&["-qq", "--follow", "--write-out", "{num_redirects}", &url]);
+    assert_eq!(r.stdout.trim(), "2");
+    let r = http(&["-qq", "--follow", "--all", "--write-out", "{num_redirects}", &url]);
+    assert_eq!(r.stdout.trim(), "2");
+
+    let r = http(&["-qq", "--write-out", "{num_redirects}", &url]);
+    assert_eq!(r.stdout.trim(), "0");
+}
@@ -220,6 +220,391 @@ async fn test_workflow_assertion_failure() {
             "Assertion should have failed");
 }
 
+#[tokio::test]
+async fn test_workflow_typed_body_assertions() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/items"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "items": [{"id": 1}, {"id": 2}],
+            "total": 2,
+            "price": 19.999,
+            "state": "active",
+            "phase": "in progress"
+        })))
+        .mount(&server)
+        .await;
+
+    let dir = tempfile::tempdir().unwrap();
+    let workflow_path = dir.path().join("typed.yaml");
+
+    std::fs::write(&workflow_path, format!(r#"
+name: Typed Assertions
+base_url: "{}"
+
+steps:
+  - name: Shorthand and structured
+    url: /items
+    assert:
+      body:
+        total: "> 0"
+        .items: {{ op: length_gte, value: 1 }}
+        .price: {{ op: approx, value: 20, tolerance: 0.01 }}
+        state: "in [\"active\", \"pending\"]"
+        .items[0].id: "type number"
+        .deleted_at: {{ op: not_exists }}
+        phase: "in progress"
+
+  - name: List form
+    url: /items
+    assert:
+      body:
+        - path: .items
+          op: length_gte
+          value: 5
+        - ".state matches ^act"
+"#, server.uri())).unwrap();
+
+    let r = http(&["--run", workflow_path.to_str().unwrap()]);
+    let output = format!("{}{}", r.stdout, r.stderr);
+
+    assert!(r.exit_code != 0, "List form step should fail: {}", output);
+    assert!(output.contains(".items: expected length_gte 5, got"), "{}", output);
+    assert!(!output.contains("total: expected"), "{}", output);
+    assert!(!output.contains("phase: expected"), "{}", output);
+}
+
+#[tokio::test]
+async fn test_workflow_schema_assertions() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/users/1"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"id": 1, "name": "Ada"})))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/users/2"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"id": "2", "tags": [1]})))
+        .mount(&server)
+        .await;
+
+    let dir = tempfile::tempdir().unwrap();
+    let schema_path = dir.path().join("user.schema.json");
+    std::fs::write(&schema_path, json!({
+        "type": "object",
+        "required": ["id", "name"],
+        "properties": {"id": {"type": "integer"}, "tags": {"type": "array", "items": {"type": "string"}}}
+    }).to_string()).unwrap();
+
+    // Relative schema paths start from the workflow file
+    let spec_path = dir.path().join("openapi.yaml");
+    std::fs::write(&spec_path, r##"
+openapi: 3.0.0
+info: {title: Users, version: "1"}
+paths:
+  /users/{id}:
+    get:
+      operationId: getUser
+      responses:
+        "200":
+          content:
+            application/json:
+              schema: {$ref: "#/components/schemas/User"}
+components:
+  schemas:
+    User:
+      type: object
+      required: [id]
+      properties:
+        id: {type: integer}
+"##).unwrap();
+
+    let workflow_path = dir.path().join("schema.yaml");
+    std::fs::write(&workflow_path, format!(r#"
+name: Schema Assertions
+base_url: "{}"
+
+steps:
+  - name: Valid user
+    url: /users/1
+    assert:
+      schema: user.schema.json
+  - name: OpenAPI user
+    url: /users/1
+    assert:
+      schema: {{ openapi: openapi.yaml, operationId: getUser }}
+  - name: Inline schema
+    url: /users/1
+    assert:
+      schema: {{ type: object, required: [name] }}
+  - name: Invalid user
+    url: /users/2
+    assert:
+      schema: {}
+"#, server.uri(), schema_path.display())).unwrap();
+
+    let junit_path = dir.path().join("report.xml");
+    let json_path = dir.path().join("report.json");
+    let r = http(&[
+        "--run", workflow_path.to_str().unwrap(),
+        "--report-junit", junit_path.to_str().unwrap(),
+        "--report-json", json_path.to_str().unwrap(),
+    ]);
+    assert!(r.exit_code != 0, "Invalid user should fail: {} {}", r.stdout, r.stderr);
+
+    let report: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&json_path).unwrap()).unwrap();
+    let steps = report["steps"].as_array().unwrap();
+    assert!(steps[..3].iter().all(|s| s["passed"] == true), "{}", report);
+
+    let failed: Vec<_> = steps[3]["assertions"].as_array().unwrap().iter()
+        .filter(|a| a["passed"] == false)
+        .map(|a| a["assertion"].as_str().unwrap().to_string())
+        .collect();
+    let source = schema_path.display().to_string();
+    assert!(failed.contains(&format!("schema {} at /id", source)), "{:?}", failed);
+    assert!(failed.contains(&format!("schema {} at /tags/0", source)), "{:?}", failed);
+    assert!(failed.contains(&format!("schema {} at /", source)), "{:?}", failed);
+
+    let junit = std::fs::read_to_string(&junit_path).unwrap();
+    assert!(junit.contains("at /tags/0"), "{}", junit);
+}
+
+#[tokio::test]
+async fn test_workflow_snapshot_assertions() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/v1/items"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"id": 1, "items": ["a", "b"]})))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/v2/items"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"id": 2, "items": ["a", "c"]})))
+        .mount(&server)
+        .await;
+
+    let dir = tempfile::tempdir().unwrap();
+    let workflow_path = dir.path().join("items.yaml");
+    std::fs::write(&workflow_path, format!(r#"
+name: Snapshots
+base_url: "{}"
+
+steps:
+  - name: List items
+    url: "/{{{{ version }}}}/items"
+    assert:
+      snapshot:
+        ignore: [.id]
+"#, server.uri())).unwrap();
+    let workflow = workflow_path.to_str().unwrap();
+    let junit_path = dir.path().join("report.xml");
+    let junit = junit_path.to_str().unwrap();
+
+    let r = http(&["--run", workflow, "--var", "version=v1"]);
+    assert!(r.exit_code == 0, "First run should write the snapshot: {} {}", r.stdout, r.stderr);
+    let snapshot_path = dir.path().join("__snapshots__").join("items").join("list_items.json");
+    let stored: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&snapshot_path).unwrap()).unwrap();
+    assert_eq!(stored["body"], json!({"items": ["a", "b"]}));
+
+    let r = http(&["--run", workflow, "--var", "version=v1"]);
+    assert!(r.exit_code == 0, "Unchanged response should match: {} {}", r.stdout, r.stderr);
+
+    let r = http(&["--run", workflow, "--var", "version=v2", "--report-junit", junit]);
+    assert!(r.exit_code != 0, "Changed response should fail: {} {}", r.stdout, r.stderr);
+    let report = std::fs::read_to_string(&junit_path).unwrap();
+    assert!(report.contains("/body/items/1"), "{}", report);
+
+    let r = http(&["--run", workflow, "--var", "version=v2", "--update-snapshots"]);
+    assert!(r.exit_code == 0, "Update should accept the change: {} {}", r.stdout, r.stderr);
+    let r = http(&["--run", workflow, "--var", "version=v2"]);
+    assert!(r.exit_code == 0, "Updated snapshot should match: {} {}", r.stdout, r.stderr);
+}
+
+#[tokio::test]
+async fn test_workflow_extraction_sources() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/login"))
+        .respond_with(ResponseTemplate::new(302)
+            .insert_header("Location", "/home")
+            .insert_header("Set-Cookie", "session_id=s3cr3t; HttpOnly")
+            .set_body_string("csrf=tok-42"))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/order"))
+        .respond_with(ResponseTemplate::new(200)
+            .set_body_raw(r#"<order id="17"><status>open</status></order>"#, "application/xml"))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/check"))
+        .and(query_param("location", "/home"))
+        .and(query_param("session", "s3cr3t"))
+        .and(query_param("status", "302"))
+        .and(query_param("csrf", "tok-42"))
+        .and(query_param("order", "17"))
+        .and(query_param("state", "open"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&server)
+        .await;
+
+    let dir = tempfile::tempdir().unwrap();
+    let workflow_path = dir.path().join("extract.yaml");
+    std::fs::write(&workflow_path, format!(r#"
+name: Extraction Sources
+base_url: "{}"
+
+steps:
+  - name: Login
+    method: POST
+    url: /login
+    follow_redirects: false
+    extract:
+      location: "header:Location"
+      session: {{ from: "cookie:session_id", required: true }}
+      login_status: status
+      csrf: 'regex:csrf=([\w-]+)'
+      elapsed: duration_ms
+  - name: Order
+    url: /order
+    extract:
+      order_id: "xpath:/order/@id"
+      state: "xpath://status"
+  - name: Check
+    url: /check
+    query:
+      location: "{{{{ location }}}}"
+      session: "{{{{ session }}}}"
+      status: "{{{{ login_status }}}}"
+      csrf: "{{{{ csrf }}}}"
+      order: "{{{{ order_id }}}}"
+      state: "{{{{ state }}}}"
+    assert:
+      status: 200
+  - name: Required
+    url: /order
+    extract:
+      missing: {{ from: "header:X-Missing", required: true }}
+"#, server.uri())).unwrap();
+
+    let json_path = dir.path().join("report.json");
+    let r = http(&["--run", workflow_path.to_str().unwrap(), "--continue-on-failure", "--report-json", json_path.to_str().unwrap()]);
+    assert!(r.exit_code != 0, "Required extraction should fail: {} {}", r.stdout, r.stderr);
+
+    let report: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&json_path).unwrap()).unwrap();
+    let steps = report["steps"].as_array().unwrap();
+    assert!(steps[..3].iter().all(|s| s["passed"] == true), "{}", report);
+    assert!(steps[0]["extracted"]["elapsed"].is_u64(), "{}", report);
+    let failed = &steps[3]["assertions"][0];
+    assert_eq!(failed["assertion"], "extract missing", "{}", report);
+    assert_eq!(failed["message"], "header:X-Missing matched nothing", "{}", report);
+}
+
+#[tokio::test]
+async fn test_workflow_conditions_branches_and_polling() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/jobs"))
+        .respond_with(ResponseTemplate::new(201).set_body_json(json!({"id": 7, "items": []})))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/jobs/7"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"state": "running"})))
+        .up_to_n_times(2)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/jobs/7"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"state": "done"})))
+        .mount(&server)
+        .await;
+    for route in ["/done", "/failed", "/items"] {
+        Mock::given(method("GET"))
+            .and(path(route))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&server)
+            .await;
+    }
+
+    let dir = tempfile::tempdir().unwrap();
+    let workflow_path = dir.path().join("conditions.yaml");
+    std::fs::write(&workflow_path, format!(r#"
+name: Conditions
+base_url: "{}"
+
+steps:
+  - name: Submit
+    method: POST
+    url: /jobs
+    extract:
+      job_id: body.id
+      items: body.items
+      submit_status: status
+  - name: Poll
+    url: "/jobs/{{{{ job_id }}}}"
+    until: "job_state == 'done'"
+    max_iterations: 5
+    extract:
+      job_state: body.state
+  - name: Outcome
+    if: "job_state == 'done' && {{{{ submit_status }}}} < 300"
+    then:
+      - name: Fetch Done
+        url: /done
+    else:
+      - name: Fetch Failed
+        url: /failed
+  - name: Items
+    url: /items
+    skip_if: "{{{{ items | length }}}} == 0"
+  - name: Items Run If
+    url: /items
+    run_if: "length(items) > 0 || upper(job_state) != 'DONE'"
+"#, server.uri())).unwrap();
+
+    let json_path = dir.path().join("report.json");
+    let r = http(&["--run", workflow_path.to_str().unwrap(), "--report-json", json_path.to_str().unwrap()]);
+    assert!(r.exit_code == 0, "Workflow failed: {} {}", r.stdout, r.stderr);
+
+    let report: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&json_path).unwrap()).unwrap();
+    let steps: Vec<(&str, bool)> = report["steps"].as_array().unwrap().iter()
+        .map(|s| (s["name"].as_str().unwrap(), s["skipped"].as_bool().unwrap()))
+        .collect();
+    assert_eq!(steps, vec![
+        ("Submit", false),
+        ("Poll", false),
+        ("Poll", false),
+        ("Poll", false),
+        ("Fetch Done", false),
+        ("Items", true),
+        ("Items Run If", true),
+    ], "{}", report);
+}
+
+#[test]
+fn test_workflow_invalid_condition_fails_validation() {
+    let dir = tempfile::tempdir().unwrap();
+    let workflow_path = dir.path().join("invalid.yaml");
+    std::fs::write(&workflow_path, r#"
+name: Invalid Condition
+steps:
+  - name: Broken
+    url: http://localhost/
+    skip_if: "{{ status }} >= "
+"#).unwrap();
+
+    let r = http(&["--run", workflow_path.to_str().unwrap(), "--validate"]);
+    assert!(r.exit_code != 0, "{} {}", r.stdout, r.stderr);
+    assert!(format!("{}{}", r.stdout, r.stderr).contains("Invalid condition"), "{} {}", r.stdout, r.stderr);
+}
+
 // ============================================================================
 // Environment Tests
 // ============================================================================
@@ -893,3 +1278,222 @@ steps:
 
     assert!(r.exit_code == 0, "Multi-step chain failed: {} {}", r.stdout, r.stderr);
 }
+
+// ============================================================================
+// Parallel Execution Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_workflow_parallel_steps_run_concurrently() {
+    let server = MockServer::start().await;
+
+    for name in ["users", "orders", "products", "invoices"] {
+        Mock::given(method("GET"))
+            .and(path(format!("/{}", name)))
+            .respond_with(ResponseTemplate::new(200)
+                .set_body_json(json!({"resource": name}))
+                .set_delay(std::time::Duration::from_millis(500)))
+            .mount(&server)
+            .await;
+    }
+
+    let dir = tempfile::tempdir().unwrap();
+    let workflow_path = dir.path().join("parallel.yaml");
+
+    std::fs::write(&workflow_path, format!(r#"
+name: Parallel Test
+base_url: "{}"
+max_parallel: 4
+
+steps:
+  - name: Users
+    url: /users
+    parallel: true
+    extract:
+      last: resource
+  - name: Orders
+    url: /orders
+    parallel: true
+    extract:
+      last: resource
+  - name: Products
+    url: /products
+    parallel: true
+  - name: Invoices
+    url: /invoices
+    parallel: true
+    assert:
+      status: 200
+"#, server.uri())).unwrap();
+
+    let start = std::time::Instant::now();
+    let r = http(&[
+        "--run", workflow_path.to_str().unwrap(),
+    ]);
+    let elapsed = start.elapsed();
+
+    assert!(r.exit_code == 0, "Parallel workflow failed: {} {}", r.stdout, r.stderr);
+    // Sequential execution would take at least 2s
+    assert!(elapsed.as_millis() < 1800, "Parallel steps took {:?}", elapsed);
+
+    // Results are reported in workflow order
+    let users = r.stdout.find("Step 1: Users").expect("missing Users result");
+    let orders = r.stdout.find("Step 2: Orders").expect("missing Orders result");
+    let invoices = r.stdout.find("Step 4: Invoices").expect("missing Invoices result");
+    assert!(users < orders && orders < invoices);
+}
+
+#[tokio::test]
+async fn test_workflow_dependency_levels_run_concurrently() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/users"))
+        .respond_with(ResponseTemplate::new(201).set_body_json(json!({"id": 7})))
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/users/7/profile"))
+        .respond_with(ResponseTemplate::new(200)
+            .set_delay(std::time::Duration::from_millis(600)))
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/users/7/settings"))
+        .respond_with(ResponseTemplate::new(200)
+            .set_delay(std::time::Duration::from_millis(600)))
+        .mount(&server)
+        .await;
+
+    let dir = tempfile::tempdir().unwrap();
+    let workflow_path = dir.path().join("levels.yaml");
+
+    std::fs::write(&workflow_path, format!(r#"
+name: Dependency Levels
+base_url: "{}"
+
+steps:
+  - name: Create User
+    method: POST
+    url: /users
+    extract:
+      user_id: id
+  - name: Profile
+    url: /users/{{{{ user_id }}}}/profile
+    depends_on: [Create User]
+    assert:
+      status: 200
+  - name: Settings
+    url: /users/{{{{ user_id }}}}/settings
+    depends_on: [Create User]
+    assert:
+      status: 200
+"#, server.uri())).unwrap();
+
+    let start = std::time::Instant::now();
+    let r = http(&[
+        "--run", workflow_path.to_str().unwrap(),
+    ]);
+    let elapsed = start.elapsed();
+
+    assert!(r.exit_code == 0, "Dependency workflow failed: {} {}", r.stdout, r.stderr);
+    assert!(elapsed.as_millis() < 1150, "Dependency level took {:?}", elapsed);
+}
+
+// ============================================================================
+// Load Mode Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_workflow_load_mode() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/login"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"token": "abc"})))
+        .expect(6)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/items"))
+        .and(header("Authorization", "Bearer abc"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([1, 2, 3])))
+        .expect(6)
+        .mount(&server)
+        .await;
+
+    let dir = tempfile::tempdir().unwrap();
+    let workflow_path = dir.path().join("journey.yaml");
+
+    std::fs::write(&workflow_path, format!(r#"
+name: Journey
+base_url: "{}"
+load:
+  users: 3
+  iterations: 2
+
+steps:
+  - name: Login
+    method: POST
+    url: /login
+    extract:
+      token: token
+  - name: Items
+    url: /items
+    headers:
+      Authorization: "Bearer {{{{ token }}}}"
+    assert:
+      status: 200
+"#, server.uri())).unwrap();
+
+    let r = http(&[
+        "--run", workflow_path.to_str().unwrap(),
+        "--log-format", "json",
+    ]);
+
+    assert!(r.exit_code == 0, "Load workflow failed: {} {}", r.stdout, r.stderr);
+    let result: serde_json::Value = serde_json::from_str(&r.stdout).unwrap();
+    assert_eq!(result["users"], 3);
+    assert_eq!(result["iterations"], 6);
+    assert_eq!(result["failed_iterations"], 0);
+    assert_eq!(result["steps"][0]["name"], "Login");
+    assert_eq!(result["steps"][1]["stats"]["successful_requests"], 6);
+}
+
+#[tokio::test]
+async fn test_workflow_load_mode_failures() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/flaky"))
+        .respond_with(ResponseTemplate::new(503))
+        .mount(&server)
+        .await;
+
+    let dir = tempfile::tempdir().unwrap();
+    let workflow_path = dir.path().join("flaky.yaml");
+
+    std::fs::write(&workflow_path, format!(r#"
+name: Flaky
+base_url: "{}"
+load:
+  users: 2
+
+steps:
+  - name: Flaky
+    url: /flaky
+    assert:
+      status: 200
+"#, server.uri())).unwrap();
+
+    let r = http(&[
+        "--run", workflow_path.to_str().unwrap(),
+    ]);
+
+    // Assertion failures exit like any other error
+    assert_eq!(r.exit_code, 1, "Failing load run should fail: {} {}", r.stdout, r.stderr);
+    assert!(r.stdout.contains("LOAD TEST RESULTS"), "stdout: {}", r.stdout);
+}